@@ -1,5 +1,10 @@
 pub use clap::{Args, Parser, Subcommand};
 
+use std::path::PathBuf;
+
+use rubin::store::mem::{EvictionPolicy, DEFAULT_DATABASES};
+use rubin::store::persistence::{Compression, FsyncPolicy, StoreFormat};
+
 #[derive(Parser, Debug)]
 #[command(author, version)]
 #[command(propagate_version = true)]
@@ -12,7 +17,7 @@ pub struct CliParser {
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Start a Rubin server on a given address / port
-    Server(ConnectArgs),
+    Server(ServerArgs),
 
     /// Start the CLI to interact with a Rubin server on a given address / port
     Cli(ConnectArgs),
@@ -28,3 +33,43 @@ pub struct ConnectArgs {
     #[arg(short, long, default_value_t = 9876)]
     pub port: usize,
 }
+
+#[derive(Args, Debug)]
+pub struct ServerArgs {
+    /// Server address to use
+    #[arg(short, long, default_value_t = String::from("127.0.0.1"))]
+    pub address: String,
+
+    /// Server port to use
+    #[arg(short, long, default_value_t = 9876)]
+    pub port: usize,
+
+    /// Maximum memory in bytes (unlimited if not set)
+    #[arg(short, long)]
+    pub max_memory: Option<usize>,
+
+    /// Eviction policy (noeviction, allkeys-lru, allkeys-lfu, volatile-ttl)
+    #[arg(short, long, default_value_t = EvictionPolicy::NoEviction)]
+    pub eviction_policy: EvictionPolicy,
+
+    /// Number of logical databases clients can select between
+    #[arg(long, default_value_t = DEFAULT_DATABASES)]
+    pub databases: usize,
+
+    /// Directory to load the databases from and save them to (kept in memory only if not set)
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+
+    /// Format the databases are saved in (json, binary)
+    #[arg(long, default_value_t = StoreFormat::Json)]
+    pub format: StoreFormat,
+
+    /// Compression applied to the saved databases (none, zstd)
+    #[arg(long, default_value_t = Compression::None)]
+    pub compression: Compression,
+
+    /// Log each update to an append-only log in the data directory, synced with the given
+    /// policy (always, everysec, no)
+    #[arg(long)]
+    pub appendfsync: Option<FsyncPolicy>,
+}
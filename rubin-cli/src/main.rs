@@ -1,10 +1,18 @@
 mod cli;
 use cli::*;
 
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Write};
 
 use rubin::net::parser::Operation;
-use rubin::net::{client::RubinClient, server::start};
+use rubin::net::{
+    client::RubinClient,
+    server::{start_with_shutdown, ServerConfig},
+};
+use rubin::store::mem::{
+    GeoMatch, GeoSearch, GeoUnit, PendingEntry, PendingSummary, StreamEntry, StreamId, StreamTrim,
+    DEFAULT_SCAN_COUNT,
+};
 
 #[derive(Debug, PartialEq)]
 enum Comparitor {
@@ -12,7 +20,7 @@ enum Comparitor {
     Gte,
 }
 
-fn validate_cmd_length(cmds: &Vec<&str>, size: usize, comparitor: Comparitor) -> bool {
+fn validate_cmd_length(cmds: &[&str], size: usize, comparitor: Comparitor) -> bool {
     let command_length = cmds.len();
     match comparitor {
         Comparitor::Eq => {
@@ -28,7 +36,303 @@ fn validate_cmd_length(cmds: &Vec<&str>, size: usize, comparitor: Comparitor) ->
     }
 
     println!("incorrect argument length for operation.\n");
-    return false;
+    false
+}
+
+fn parse_number(arg: &str) -> Option<isize> {
+    match arg.parse::<isize>() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            println!("invalid number: {}\n", arg);
+            None
+        }
+    }
+}
+
+fn parse_float(arg: &str) -> Option<f64> {
+    match arg.parse::<f64>() {
+        Ok(value) if value.is_finite() => Some(value),
+        _ => {
+            println!("invalid number: {}\n", arg);
+            None
+        }
+    }
+}
+
+fn parse_duration(arg: &str) -> Option<u64> {
+    match arg.parse::<u64>() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            println!("invalid duration: {}\n", arg);
+            None
+        }
+    }
+}
+
+fn parse_cursor(arg: &str) -> Option<u64> {
+    match arg.parse::<u64>() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            println!("invalid cursor: {}\n", arg);
+            None
+        }
+    }
+}
+
+fn parse_bit(arg: &str) -> Option<bool> {
+    match arg {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => {
+            println!("bit is not 0 or 1: {}\n", arg);
+            None
+        }
+    }
+}
+
+fn parse_offset(arg: &str) -> Option<usize> {
+    match arg.parse::<usize>() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            println!("invalid offset: {}\n", arg);
+            None
+        }
+    }
+}
+
+fn parse_score(arg: &str) -> Option<f64> {
+    match arg.parse::<f64>() {
+        Ok(value) if !value.is_nan() => Some(value),
+        _ => {
+            println!("invalid score: {}\n", arg);
+            None
+        }
+    }
+}
+
+fn parse_scan_options<'a>(options: &[&'a str]) -> Option<(Option<&'a str>, usize)> {
+    if !options.len().is_multiple_of(2) {
+        println!("incorrect argument length for operation.\n");
+        return None;
+    }
+
+    let mut pattern = None;
+    let mut count = DEFAULT_SCAN_COUNT;
+    for option in options.chunks(2) {
+        match option[0].to_lowercase().as_str() {
+            "match" => pattern = Some(option[1]),
+            "count" => match option[1].parse::<usize>() {
+                Ok(value) if value > 0 => count = value,
+                _ => {
+                    println!("invalid count: {}\n", option[1]);
+                    return None;
+                }
+            },
+            _ => {
+                println!("invalid scan option: {}\n", option[0]);
+                return None;
+            }
+        }
+    }
+
+    Some((pattern, count))
+}
+
+fn parse_count(arg: &str) -> Option<usize> {
+    match arg.parse::<usize>() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            println!("invalid count: {}\n", arg);
+            None
+        }
+    }
+}
+
+fn parse_stream_count(options: &[&str]) -> Option<Option<usize>> {
+    match options {
+        [] => Some(None),
+        [option, count] if option.eq_ignore_ascii_case("count") => parse_count(count).map(Some),
+        _ => {
+            println!("invalid stream option: {}\n", options[0]);
+            None
+        }
+    }
+}
+
+fn parse_stream_trim(strategy: &str, threshold: &str) -> Option<StreamTrim> {
+    match strategy.to_lowercase().as_str() {
+        "maxlen" => match threshold.parse::<usize>() {
+            Ok(len) => Some(StreamTrim::MaxLen(len)),
+            Err(_) => {
+                println!("invalid length: {}\n", threshold);
+                None
+            }
+        },
+        "minid" => match StreamId::parse_start(threshold) {
+            Ok(id) => Some(StreamTrim::MinId(id)),
+            Err(_) => {
+                println!("invalid stream ID: {}\n", threshold);
+                None
+            }
+        },
+        _ => {
+            println!("invalid trim strategy: {}\n", strategy);
+            None
+        }
+    }
+}
+
+fn format_optional(value: Option<String>) -> String {
+    value.unwrap_or_else(|| String::from("(nil)"))
+}
+
+fn format_list(values: Vec<String>) -> String {
+    if values.is_empty() {
+        return String::from("(empty)");
+    }
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| format!("{}) {}", idx + 1, value))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn format_map(values: HashMap<String, String>) -> String {
+    if values.is_empty() {
+        return String::from("(empty)");
+    }
+
+    values
+        .iter()
+        .map(|(field, value)| format!("{}: {}", field, value))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn format_set(values: HashSet<String>) -> String {
+    let mut values: Vec<String> = values.into_iter().collect();
+    values.sort();
+
+    format_list(values)
+}
+
+fn format_page(cursor: u64, values: Vec<String>) -> String {
+    format!("cursor: {}\n{}", cursor, format_list(values))
+}
+
+fn format_scores(values: Vec<(String, f64)>) -> String {
+    format_list(
+        values
+            .into_iter()
+            .map(|(member, score)| format!("{} ({})", member, score))
+            .collect(),
+    )
+}
+
+fn format_positions(positions: Vec<Option<(f64, f64)>>) -> String {
+    format_list(
+        positions
+            .into_iter()
+            .map(|position| format_optional(position.map(|(lon, lat)| format!("{}, {}", lon, lat))))
+            .collect(),
+    )
+}
+
+fn format_lengths(lengths: Vec<Option<usize>>) -> String {
+    format_list(
+        lengths
+            .into_iter()
+            .map(|length| format_optional(length.map(|len| len.to_string())))
+            .collect(),
+    )
+}
+
+fn format_geo_matches(matches: Vec<GeoMatch>) -> String {
+    format_list(
+        matches
+            .into_iter()
+            .map(|found| {
+                format!(
+                    "{} ({:.4}) [{}, {}]",
+                    found.member, found.distance, found.longitude, found.latitude
+                )
+            })
+            .collect(),
+    )
+}
+
+fn format_entries(entries: Vec<StreamEntry>) -> String {
+    format_list(
+        entries
+            .into_iter()
+            .map(|entry| {
+                let fields: Vec<String> = entry
+                    .fields
+                    .iter()
+                    .map(|(field, value)| format!("{}: {}", field, value))
+                    .collect();
+                format!("{} ({})", entry.id, fields.join(", "))
+            })
+            .collect(),
+    )
+}
+
+fn format_pending_summary(summary: PendingSummary) -> String {
+    let format_id = |id: Option<StreamId>| format_optional(id.map(|id| id.to_string()));
+    let consumers = summary
+        .consumers
+        .into_iter()
+        .map(|(consumer, count)| format!("{} ({})", consumer, count))
+        .collect();
+
+    format!(
+        "count: {}\nfirst: {}\nlast: {}\n{}",
+        summary.count,
+        format_id(summary.first),
+        format_id(summary.last),
+        format_list(consumers)
+    )
+}
+
+fn format_pending(entries: Vec<PendingEntry>) -> String {
+    format_list(
+        entries
+            .into_iter()
+            .map(|entry| {
+                format!(
+                    "{} {} (idle: {}ms, deliveries: {})",
+                    entry.id, entry.consumer, entry.idle, entry.deliveries
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Waits for Ctrl-C, or for SIGTERM on unix as sent by service managers and containers
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("unable to listen for shutdown signal");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("unable to listen for shutdown signal")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
 }
 
 #[tokio::main]
@@ -37,7 +341,18 @@ async fn main() -> io::Result<()> {
 
     match &cli.commands {
         Commands::Server(args) => {
-            start(&args.address, args.port).await?;
+            let config = ServerConfig {
+                max_memory: args.max_memory,
+                eviction_policy: args.eviction_policy,
+                databases: args.databases,
+                data_dir: args.data_dir.clone(),
+                format: args.format,
+                compression: args.compression,
+                append_fsync: args.appendfsync,
+                ..ServerConfig::default()
+            };
+
+            start_with_shutdown(&args.address, args.port, config, shutdown_signal()).await?;
         }
         Commands::Cli(args) => {
             let client = RubinClient::new(&args.address, args.port);
@@ -46,7 +361,10 @@ async fn main() -> io::Result<()> {
                 // The overheads of creating a new string each loop are insignificant
                 let mut cmd = String::new();
 
-                print!("rubin-cli > ");
+                match client.database() {
+                    0 => print!("rubin-cli > "),
+                    db => print!("rubin-cli[{}] > ", db),
+                }
                 io::stdout().flush()?;
                 io::stdin().read_line(&mut cmd)?;
 
@@ -58,7 +376,7 @@ async fn main() -> io::Result<()> {
                     continue;
                 }
 
-                let mut cmd_split: Vec<&str> = cmd.split(' ').collect();
+                let mut cmd_split: Vec<&str> = trimmed_cmd.split(' ').collect();
                 let raw_op = &cmd_split.remove(0).trim();
                 let op = Operation::from_string(raw_op);
 
@@ -68,7 +386,7 @@ async fn main() -> io::Result<()> {
                             continue;
                         }
                         let key = &cmd_split[0];
-                        client.get_string(key).await
+                        client.get_string(key).await.map(format_optional)
                     }
                     Operation::StringSet => {
                         if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
@@ -83,9 +401,93 @@ async fn main() -> io::Result<()> {
                             continue;
                         }
                         let key = &cmd_split[0];
-                        client.remove_string(key).await
+                        client.remove_string(key).await.map(format_optional)
                     }
                     Operation::StringClear => client.clear_strings().await,
+                    Operation::StringAppend => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let value = cmd_split[1..].join(" ");
+                        client.append(cmd_split[0], &value).await
+                    }
+                    Operation::StringGetRange => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let (Some(start), Some(end)) =
+                            (parse_number(cmd_split[1]), parse_number(cmd_split[2]))
+                        else {
+                            continue;
+                        };
+
+                        client.getrange(cmd_split[0], start, end).await
+                    }
+                    Operation::StringSetRange => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let Some(offset) = parse_offset(cmd_split[1]) else {
+                            continue;
+                        };
+
+                        let value = cmd_split[2..].join(" ");
+                        client.setrange(cmd_split[0], offset, &value).await
+                    }
+                    Operation::StringLength => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.strlen(cmd_split[0]).await
+                    }
+                    Operation::StringSetNx => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let value = cmd_split[1..].join(" ");
+                        client.setnx(cmd_split[0], &value).await
+                    }
+                    Operation::StringGetDelete => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.getdel(cmd_split[0]).await.map(format_optional)
+                    }
+                    Operation::StringMultiGet => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.mget(&cmd_split).await.map(|values| {
+                            format_list(values.into_iter().map(format_optional).collect())
+                        })
+                    }
+                    Operation::StringMultiSet => {
+                        if cmd_split.is_empty() || !cmd_split.len().is_multiple_of(2) {
+                            println!("incorrect argument length for operation.\n");
+                            continue;
+                        }
+
+                        let pairs: Vec<(&str, &str)> =
+                            cmd_split.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+                        client.mset(&pairs).await
+                    }
+                    Operation::StringMultiSetNx => {
+                        if cmd_split.is_empty() || !cmd_split.len().is_multiple_of(2) {
+                            println!("incorrect argument length for operation.\n");
+                            continue;
+                        }
+
+                        let pairs: Vec<(&str, &str)> =
+                            cmd_split.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+                        client.msetnx(&pairs).await
+                    }
                     Operation::Incr => {
                         if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
                             continue;
@@ -102,6 +504,955 @@ async fn main() -> io::Result<()> {
                         let key = &cmd_split[0];
                         client.decr(key).await
                     }
+                    Operation::IncrBy | Operation::DecrBy => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let Some(amount) = parse_number(cmd_split[1]) else {
+                            continue;
+                        };
+                        if op == Operation::IncrBy {
+                            client.incrby(key, amount).await
+                        } else {
+                            client.decrby(key, amount).await
+                        }
+                    }
+                    Operation::IncrByFloat => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let Some(increment) = parse_float(cmd_split[1]) else {
+                            continue;
+                        };
+                        client.incrbyfloat(key, increment).await
+                    }
+                    Operation::GetSet => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let Some(value) = parse_number(cmd_split[1]) else {
+                            continue;
+                        };
+                        client.getset(key, value).await.map(format_optional)
+                    }
+                    Operation::ListLeftPush | Operation::ListRightPush => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let values = &cmd_split[1..];
+                        if op == Operation::ListLeftPush {
+                            client.lpush(key, values).await
+                        } else {
+                            client.rpush(key, values).await
+                        }
+                    }
+                    Operation::ListLeftPop => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        client.lpop(key).await.map(format_optional)
+                    }
+                    Operation::ListRightPop => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        client.rpop(key).await.map(format_optional)
+                    }
+                    Operation::ListRange => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let (Some(start), Some(stop)) =
+                            (parse_number(cmd_split[1]), parse_number(cmd_split[2]))
+                        else {
+                            continue;
+                        };
+                        client.lrange(key, start, stop).await.map(format_list)
+                    }
+                    Operation::ListLength => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        client.llen(key).await
+                    }
+                    Operation::ListIndex => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let Some(index) = parse_number(cmd_split[1]) else {
+                            continue;
+                        };
+                        client.lindex(key, index).await.map(format_optional)
+                    }
+                    Operation::ListSet => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let Some(index) = parse_number(cmd_split[1]) else {
+                            continue;
+                        };
+                        let value = &cmd_split[2..].join(" ");
+                        client.lset(key, index, value).await
+                    }
+                    Operation::ListTrim => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let (Some(start), Some(stop)) =
+                            (parse_number(cmd_split[1]), parse_number(cmd_split[2]))
+                        else {
+                            continue;
+                        };
+                        client.ltrim(key, start, stop).await
+                    }
+                    Operation::ListRemove => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let Some(count) = parse_number(cmd_split[1]) else {
+                            continue;
+                        };
+                        let value = &cmd_split[2..].join(" ");
+                        client.lrem(key, count, value).await
+                    }
+                    Operation::HashSet => {
+                        if cmd_split.len() < 3 || cmd_split.len().is_multiple_of(2) {
+                            println!("incorrect argument length for operation.\n");
+                            continue;
+                        }
+
+                        let key = &cmd_split[0];
+                        let fields: Vec<(&str, &str)> = cmd_split[1..]
+                            .chunks(2)
+                            .map(|pair| (pair[0], pair[1]))
+                            .collect();
+                        client.hset(key, &fields).await
+                    }
+                    Operation::HashGet => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client
+                            .hget(cmd_split[0], cmd_split[1])
+                            .await
+                            .map(format_optional)
+                    }
+                    Operation::HashDelete => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.hdel(cmd_split[0], &cmd_split[1..]).await
+                    }
+                    Operation::HashGetAll => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.hgetall(cmd_split[0]).await.map(format_map)
+                    }
+                    Operation::HashKeys => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.hkeys(cmd_split[0]).await.map(format_list)
+                    }
+                    Operation::HashValues => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.hvals(cmd_split[0]).await.map(format_list)
+                    }
+                    Operation::HashLength => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.hlen(cmd_split[0]).await
+                    }
+                    Operation::HashExists => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.hexists(cmd_split[0], cmd_split[1]).await
+                    }
+                    Operation::HashIncrBy => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(increment) = parse_number(cmd_split[2]) else {
+                            continue;
+                        };
+                        client.hincrby(cmd_split[0], cmd_split[1], increment).await
+                    }
+                    Operation::SetAdd => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.sadd(cmd_split[0], &cmd_split[1..]).await
+                    }
+                    Operation::SetRemove => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.srem(cmd_split[0], &cmd_split[1..]).await
+                    }
+                    Operation::SetIsMember => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.sismember(cmd_split[0], cmd_split[1]).await
+                    }
+                    Operation::SetMembers => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.smembers(cmd_split[0]).await.map(format_set)
+                    }
+                    Operation::SetCardinality => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.scard(cmd_split[0]).await
+                    }
+                    Operation::SetUnion => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.sunion(&cmd_split).await.map(format_set)
+                    }
+                    Operation::SetIntersection => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.sinter(&cmd_split).await.map(format_set)
+                    }
+                    Operation::SetDifference => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.sdiff(&cmd_split).await.map(format_set)
+                    }
+                    Operation::SetUnionStore => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.sunionstore(cmd_split[0], &cmd_split[1..]).await
+                    }
+                    Operation::SetIntersectionStore => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.sinterstore(cmd_split[0], &cmd_split[1..]).await
+                    }
+                    Operation::SetDifferenceStore => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.sdiffstore(cmd_split[0], &cmd_split[1..]).await
+                    }
+                    Operation::SortedSetAdd => {
+                        if cmd_split.len() < 3 || cmd_split.len().is_multiple_of(2) {
+                            println!("incorrect argument length for operation.\n");
+                            continue;
+                        }
+
+                        let mut members = vec![];
+                        for pair in cmd_split[1..].chunks(2) {
+                            let Some(score) = parse_score(pair[0]) else {
+                                break;
+                            };
+                            members.push((score, pair[1]));
+                        }
+
+                        if members.len() * 2 != cmd_split.len() - 1 {
+                            continue;
+                        }
+                        client.zadd(cmd_split[0], &members).await
+                    }
+                    Operation::SortedSetRemove => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.zrem(cmd_split[0], &cmd_split[1..]).await
+                    }
+                    Operation::SortedSetScore => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client
+                            .zscore(cmd_split[0], cmd_split[1])
+                            .await
+                            .map(format_optional)
+                    }
+                    Operation::SortedSetIncrBy => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(increment) = parse_score(cmd_split[1]) else {
+                            continue;
+                        };
+                        client.zincrby(cmd_split[0], increment, cmd_split[2]).await
+                    }
+                    Operation::SortedSetRank => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client
+                            .zrank(cmd_split[0], cmd_split[1])
+                            .await
+                            .map(format_optional)
+                    }
+                    Operation::SortedSetRange => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let (Some(start), Some(stop)) =
+                            (parse_number(cmd_split[1]), parse_number(cmd_split[2]))
+                        else {
+                            continue;
+                        };
+                        client
+                            .zrange(cmd_split[0], start, stop)
+                            .await
+                            .map(format_scores)
+                    }
+                    Operation::SortedSetReverseRange => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let (Some(start), Some(stop)) =
+                            (parse_number(cmd_split[1]), parse_number(cmd_split[2]))
+                        else {
+                            continue;
+                        };
+                        client
+                            .zrevrange(cmd_split[0], start, stop)
+                            .await
+                            .map(format_scores)
+                    }
+                    Operation::SortedSetRangeByScore => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let (Some(min), Some(max)) =
+                            (parse_score(cmd_split[1]), parse_score(cmd_split[2]))
+                        else {
+                            continue;
+                        };
+                        client
+                            .zrangebyscore(cmd_split[0], min, max)
+                            .await
+                            .map(format_scores)
+                    }
+                    Operation::SortedSetCount => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let (Some(min), Some(max)) =
+                            (parse_score(cmd_split[1]), parse_score(cmd_split[2]))
+                        else {
+                            continue;
+                        };
+                        client.zcount(cmd_split[0], min, max).await
+                    }
+                    Operation::Expire | Operation::PExpire => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(duration) = parse_duration(cmd_split[1]) else {
+                            continue;
+                        };
+
+                        if op == Operation::Expire {
+                            client.expire(cmd_split[0], duration).await
+                        } else {
+                            client.pexpire(cmd_split[0], duration).await
+                        }
+                    }
+                    Operation::Ttl => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.ttl(cmd_split[0]).await
+                    }
+                    Operation::PTtl => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.pttl(cmd_split[0]).await
+                    }
+                    Operation::Persist => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.persist(cmd_split[0]).await
+                    }
+                    Operation::Exists | Operation::Delete => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        if op == Operation::Exists {
+                            client.exists(&cmd_split).await
+                        } else {
+                            client.del(&cmd_split).await
+                        }
+                    }
+                    Operation::Type => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.key_type(cmd_split[0]).await
+                    }
+                    Operation::Rename | Operation::RenameNx => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        if op == Operation::Rename {
+                            client.rename(cmd_split[0], cmd_split[1]).await
+                        } else {
+                            client.renamenx(cmd_split[0], cmd_split[1]).await
+                        }
+                    }
+                    Operation::Keys => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.keys(cmd_split[0]).await.map(format_list)
+                    }
+                    Operation::Scan => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let Some(cursor) = parse_cursor(cmd_split[0]) else {
+                            continue;
+                        };
+                        let Some((pattern, count)) = parse_scan_options(&cmd_split[1..]) else {
+                            continue;
+                        };
+
+                        client
+                            .scan(cursor, pattern, count)
+                            .await
+                            .map(|(cursor, keys)| format_page(cursor, keys))
+                    }
+                    Operation::HashScan | Operation::SetScan => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let key = cmd_split[0];
+                        let Some(cursor) = parse_cursor(cmd_split[1]) else {
+                            continue;
+                        };
+                        let Some((pattern, count)) = parse_scan_options(&cmd_split[2..]) else {
+                            continue;
+                        };
+
+                        if op == Operation::HashScan {
+                            client.hscan(key, cursor, pattern, count).await.map(
+                                |(cursor, fields)| {
+                                    let fields = fields
+                                        .into_iter()
+                                        .map(|(field, value)| format!("{}: {}", field, value))
+                                        .collect();
+                                    format_page(cursor, fields)
+                                },
+                            )
+                        } else {
+                            client
+                                .sscan(key, cursor, pattern, count)
+                                .await
+                                .map(|(cursor, members)| format_page(cursor, members))
+                        }
+                    }
+                    Operation::SetBit => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(offset) = parse_offset(cmd_split[1]) else {
+                            continue;
+                        };
+                        let Some(value) = parse_bit(cmd_split[2]) else {
+                            continue;
+                        };
+
+                        client.setbit(cmd_split[0], offset, value).await
+                    }
+                    Operation::GetBit => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(offset) = parse_offset(cmd_split[1]) else {
+                            continue;
+                        };
+
+                        client.getbit(cmd_split[0], offset).await
+                    }
+                    Operation::BitCount => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let range = match cmd_split.len() {
+                            1 => None,
+                            3 => {
+                                let (Some(start), Some(end)) =
+                                    (parse_number(cmd_split[1]), parse_number(cmd_split[2]))
+                                else {
+                                    continue;
+                                };
+                                Some((start, end))
+                            }
+                            _ => {
+                                println!("incorrect argument length for operation.\n");
+                                continue;
+                            }
+                        };
+
+                        client.bitcount(cmd_split[0], range).await
+                    }
+                    Operation::BitPos => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let Some(bit) = parse_bit(cmd_split[1]) else {
+                            continue;
+                        };
+                        let Some(range) = cmd_split[2..]
+                            .iter()
+                            .map(|arg| parse_number(arg))
+                            .collect::<Option<Vec<isize>>>()
+                        else {
+                            continue;
+                        };
+
+                        client
+                            .bitpos(
+                                cmd_split[0],
+                                bit,
+                                range.first().copied(),
+                                range.get(1).copied(),
+                            )
+                            .await
+                    }
+                    Operation::BitOp => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let bit_op = match cmd_split[0].parse() {
+                            Ok(bit_op) => bit_op,
+                            Err(e) => {
+                                println!("{}\n", e);
+                                continue;
+                            }
+                        };
+
+                        client.bitop(bit_op, cmd_split[1], &cmd_split[2..]).await
+                    }
+                    Operation::BitField => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client
+                            .bitfield(cmd_split[0], &cmd_split[1..])
+                            .await
+                            .map(|values| {
+                                format_list(
+                                    values
+                                        .into_iter()
+                                        .map(|value| format_optional(value.map(|v| v.to_string())))
+                                        .collect(),
+                                )
+                            })
+                    }
+                    Operation::PfAdd | Operation::PfMerge => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        if op == Operation::PfAdd {
+                            client.pfadd(cmd_split[0], &cmd_split[1..]).await
+                        } else {
+                            client.pfmerge(cmd_split[0], &cmd_split[1..]).await
+                        }
+                    }
+                    Operation::PfCount => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client.pfcount(&cmd_split).await
+                    }
+                    Operation::GeoAdd => {
+                        if !validate_cmd_length(&cmd_split, 4, Comparitor::Gte) {
+                            continue;
+                        }
+                        if !(cmd_split.len() - 1).is_multiple_of(3) {
+                            println!("incorrect argument length for operation.\n");
+                            continue;
+                        }
+
+                        let members: Option<Vec<(f64, f64, &str)>> = cmd_split[1..]
+                            .chunks(3)
+                            .map(|member| {
+                                Some((parse_float(member[0])?, parse_float(member[1])?, member[2]))
+                            })
+                            .collect();
+                        let Some(members) = members else {
+                            continue;
+                        };
+
+                        client.geoadd(cmd_split[0], &members).await
+                    }
+                    Operation::GeoPosition => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client
+                            .geopos(cmd_split[0], &cmd_split[1..])
+                            .await
+                            .map(format_positions)
+                    }
+                    Operation::GeoDistance => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let unit = match cmd_split.get(3).map(|unit| unit.parse::<GeoUnit>()) {
+                            None => GeoUnit::default(),
+                            Some(Ok(unit)) => unit,
+                            Some(Err(e)) => {
+                                println!("{}\n", e);
+                                continue;
+                            }
+                        };
+
+                        client
+                            .geodist(cmd_split[0], cmd_split[1], cmd_split[2], unit)
+                            .await
+                            .map(format_optional)
+                    }
+                    Operation::GeoSearch => {
+                        if !validate_cmd_length(&cmd_split, 5, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let search = match GeoSearch::parse(&cmd_split[1..]) {
+                            Ok(search) => search,
+                            Err(e) => {
+                                println!("{}\n", e);
+                                continue;
+                            }
+                        };
+
+                        client
+                            .geosearch(cmd_split[0], &search)
+                            .await
+                            .map(format_geo_matches)
+                    }
+                    Operation::StreamAdd => {
+                        if !validate_cmd_length(&cmd_split, 4, Comparitor::Gte) {
+                            continue;
+                        }
+                        if !cmd_split.len().is_multiple_of(2) {
+                            println!("incorrect argument length for operation.\n");
+                            continue;
+                        }
+
+                        let fields: Vec<(&str, &str)> = cmd_split[2..]
+                            .chunks(2)
+                            .map(|pair| (pair[0], pair[1]))
+                            .collect();
+
+                        client.xadd(cmd_split[0], cmd_split[1], &fields).await
+                    }
+                    Operation::StreamRange | Operation::StreamReverseRange => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let Some(count) = parse_stream_count(&cmd_split[3..]) else {
+                            continue;
+                        };
+
+                        let (key, from, to) = (cmd_split[0], cmd_split[1], cmd_split[2]);
+                        if op == Operation::StreamRange {
+                            client
+                                .xrange(key, from, to, count)
+                                .await
+                                .map(format_entries)
+                        } else {
+                            client
+                                .xrevrange(key, from, to, count)
+                                .await
+                                .map(format_entries)
+                        }
+                    }
+                    Operation::StreamLength => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        client.xlen(cmd_split[0]).await
+                    }
+                    Operation::StreamTrim => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(trim) = parse_stream_trim(cmd_split[1], cmd_split[2]) else {
+                            continue;
+                        };
+
+                        client.xtrim(cmd_split[0], trim).await
+                    }
+                    Operation::StreamGroup => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        match (cmd_split[0].to_lowercase().as_str(), cmd_split.len()) {
+                            ("create", 4) => {
+                                client
+                                    .xgroup_create(cmd_split[1], cmd_split[2], cmd_split[3])
+                                    .await
+                            }
+                            ("destroy", 3) => {
+                                client.xgroup_destroy(cmd_split[1], cmd_split[2]).await
+                            }
+                            _ => {
+                                println!("invalid group subcommand: {}\n", cmd_split[0]);
+                                continue;
+                            }
+                        }
+                    }
+                    Operation::StreamReadGroup => {
+                        if !validate_cmd_length(&cmd_split, 4, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let Some(count) = parse_stream_count(&cmd_split[4..]) else {
+                            continue;
+                        };
+
+                        client
+                            .xreadgroup(
+                                cmd_split[0],
+                                cmd_split[1],
+                                cmd_split[2],
+                                cmd_split[3],
+                                count,
+                            )
+                            .await
+                            .map(format_entries)
+                    }
+                    Operation::StreamAck => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client
+                            .xack(cmd_split[0], cmd_split[1], &cmd_split[2..])
+                            .await
+                    }
+                    Operation::StreamPending => match cmd_split.len() {
+                        2 => client
+                            .xpending(cmd_split[0], cmd_split[1])
+                            .await
+                            .map(format_pending_summary),
+                        5 | 6 => {
+                            let Some(count) = parse_count(cmd_split[4]) else {
+                                continue;
+                            };
+
+                            client
+                                .xpending_range(
+                                    cmd_split[0],
+                                    cmd_split[1],
+                                    cmd_split[2],
+                                    cmd_split[3],
+                                    count,
+                                    cmd_split.get(5).copied(),
+                                )
+                                .await
+                                .map(format_pending)
+                        }
+                        _ => {
+                            println!("incorrect argument length for operation.\n");
+                            continue;
+                        }
+                    },
+                    Operation::StreamClaim => {
+                        if !validate_cmd_length(&cmd_split, 5, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let Some(min_idle) = parse_duration(cmd_split[3]) else {
+                            continue;
+                        };
+
+                        client
+                            .xclaim(
+                                cmd_split[0],
+                                cmd_split[1],
+                                cmd_split[2],
+                                min_idle,
+                                &cmd_split[4..],
+                            )
+                            .await
+                            .map(format_entries)
+                    }
+                    Operation::JsonSet => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let value = cmd_split[2..].join(" ");
+                        client.json_set(cmd_split[0], cmd_split[1], &value).await
+                    }
+                    Operation::JsonGet => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let path = cmd_split.get(1).copied().unwrap_or("$");
+                        client
+                            .json_get(cmd_split[0], path)
+                            .await
+                            .map(format_optional)
+                    }
+                    Operation::JsonDelete => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        let path = cmd_split.get(1).copied().unwrap_or("$");
+                        client.json_del(cmd_split[0], path).await
+                    }
+                    Operation::JsonArrAppend => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Gte) {
+                            continue;
+                        }
+
+                        client
+                            .json_arrappend(cmd_split[0], cmd_split[1], &cmd_split[2..])
+                            .await
+                            .map(format_lengths)
+                    }
+                    Operation::JsonNumIncrBy => {
+                        if !validate_cmd_length(&cmd_split, 3, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(increment) = parse_float(cmd_split[2]) else {
+                            continue;
+                        };
+
+                        client
+                            .json_numincrby(cmd_split[0], cmd_split[1], increment)
+                            .await
+                    }
+                    Operation::Select => {
+                        if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(db) = parse_count(cmd_split[0]) else {
+                            continue;
+                        };
+
+                        client.select(db).await
+                    }
+                    Operation::SwapDb => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let (Some(first), Some(second)) =
+                            (parse_count(cmd_split[0]), parse_count(cmd_split[1]))
+                        else {
+                            continue;
+                        };
+
+                        client.swapdb(first, second).await
+                    }
+                    Operation::Move => {
+                        if !validate_cmd_length(&cmd_split, 2, Comparitor::Eq) {
+                            continue;
+                        }
+
+                        let Some(db) = parse_count(cmd_split[1]) else {
+                            continue;
+                        };
+
+                        client.move_key(cmd_split[0], db).await
+                    }
+                    Operation::FlushDb => client.flushdb().await,
+                    Operation::FlushAll => client.flushall().await,
                     Operation::Dump => {
                         if !validate_cmd_length(&cmd_split, 1, Comparitor::Eq) {
                             continue;
@@ -110,6 +1461,9 @@ async fn main() -> io::Result<()> {
                         let path = &cmd_split[0];
                         client.dump_store(path).await
                     }
+                    Operation::Save => client.save().await,
+                    Operation::BackgroundSave => client.bgsave().await,
+                    Operation::LastSave => client.lastsave().await,
                     Operation::Error => {
                         println!("invalid operation: {}\n", raw_op);
                         continue;
@@ -8,7 +8,7 @@
 //! * `InvalidFormat`: The request / response format received is not recognised
 //!   * Can occur if the data received is malformed in some way.
 //! * `InvalidMessage`: The parsed message failed the validation checks for the type
-//! of operation used
+//!   of operation used
 
 /// Errors for messages sent using the client / server protocol
 #[derive(Debug, Clone, PartialEq)]
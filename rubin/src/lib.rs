@@ -89,8 +89,10 @@
 //! connections. As there are many ways to facilitate this, the implementation of how the server
 //! operates is up to the end-user.
 //!
-//! Currently, the server only acts as an in-memory store with no option for persistence but this
-//! is a planned feature upgrade in the future.
+//! By default, the server only acts as an in-memory store. Setting a data directory in the
+//! [`net::server::ServerConfig`] loads the databases from it when the server starts and saves
+//! them back periodically, on request and when the server is shut down (see
+//! [`net::server::start_with_shutdown`]).
 //!
 //! ### Basic Example
 //!
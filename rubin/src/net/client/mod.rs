@@ -18,7 +18,7 @@
 //!
 //!     let value = client.get_string("user:1000").await?;
 //!
-//!     assert_eq!(&result, "value");
+//!     assert_eq!(value.as_deref(), Some("value"));
 //!
 //!     Ok(())
 //! }
@@ -29,14 +29,31 @@ use tokio::{
     net::TcpStream,
 };
 
-use crate::net::parser::{create_request, parse_response, Operation};
+use serde::{de::DeserializeOwned, Serialize};
 
+use crate::net::parser::{
+    create_request, parse_optional_response, parse_response, select_database, Operation,
+};
+use crate::store::mem::{
+    type_tag, BitOp, GeoMatch, GeoSearch, GeoUnit, PendingEntry, PendingSummary, StreamEntry,
+    StreamTrim,
+};
+
+use std::collections::{HashMap, HashSet};
 use std::io::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 /// Client protocol for interacting with the Rubin Server
+///
+/// Requests are performed against database `0` until another database is selected with
+/// [`RubinClient::select`].
 pub struct RubinClient {
     /// Address of the server
     pub address: String,
+
+    /// Index of the database requests are performed against
+    database: AtomicUsize,
 }
 
 impl RubinClient {
@@ -56,7 +73,10 @@ impl RubinClient {
     /// ```
     pub fn new(addr: &str, port: usize) -> Self {
         let address = format!("{}:{}", addr, port);
-        Self { address }
+        Self {
+            address,
+            database: AtomicUsize::new(0),
+        }
     }
 
     /// Sends a request to the server to insert a key-value pair into the string store
@@ -85,6 +105,8 @@ impl RubinClient {
     /// Sends a request to the server to retrieve a value from the string store
     /// with the given key
     ///
+    /// Returns `None` if the key is not present
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -98,9 +120,9 @@ impl RubinClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn get_string(&self, key: &str) -> Result<String> {
+    pub async fn get_string(&self, key: &str) -> Result<Option<String>> {
         let msg = create_request(Operation::StringGet, vec![key.to_string()]);
-        self.request(&msg).await
+        self.request_optional(&msg).await
     }
 
     /// Sends a request to the server to remove a value form the string store denoted by the given
@@ -119,12 +141,83 @@ impl RubinClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn remove_string(&self, key: &str) -> Result<String> {
+    pub async fn remove_string(&self, key: &str) -> Result<Option<String>> {
         let msg = create_request(Operation::StringRemove, vec![key.to_string()]);
 
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to insert a serialized value into the typed value store
+    ///
+    /// The value is serialized to JSON and tagged with the name of its type, see
+    /// [`crate::store::mem::MemStore::insert_typed`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct User {
+    ///     name: String,
+    ///     age: u32,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let user = User { name: "rubin".to_string(), age: 3 };
+    ///     client.insert_typed("user:1000", &user).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn insert_typed<T: Serialize>(&self, key: &str, value: &T) -> Result<String> {
+        let value = serde_json::to_string(value)?;
+        let msg = create_request(
+            Operation::TypedSet,
+            vec![key.to_string(), type_tag::<T>(), value],
+        );
+
         self.request(&msg).await
     }
 
+    /// Sends a request to the server to retrieve and deserialize a value from the typed
+    /// value store
+    ///
+    /// Returns `None` if the key is not present, or an [`std::io::ErrorKind::InvalidData`]
+    /// error if the value was stored as a different type.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct User {
+    ///     name: String,
+    ///     age: u32,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let user: Option<User> = client.get_typed("user:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_typed<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let msg = create_request(Operation::TypedGet, vec![key.to_string(), type_tag::<T>()]);
+
+        match self.request_optional(&msg).await? {
+            Some(response) => parse_json_response(&response).map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Sends a request to the server to increment a value in the counter store
     ///
     /// # Example
@@ -167,7 +260,7 @@ impl RubinClient {
         self.request(&msg).await
     }
 
-    /// Sends a request to the server to clear all keys and values from the string store
+    /// Sends a request to the server to increment a value in the counter store by an amount
     ///
     /// # Example
     ///
@@ -177,19 +270,21 @@ impl RubinClient {
     /// #[tokio::main]
     /// async fn main() -> std::io::Result<()> {
     ///     let client = RubinClient::new("127.0.0.1", 9876);
-    ///     let result = client.clear_strings().await?;
+    ///     let result = client.incrby("requests", 50).await?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn clear_strings(&self) -> Result<String> {
-        let msg = create_request(Operation::StringClear, vec!["noop".to_string()]);
+    pub async fn incrby(&self, key: &str, increment: isize) -> Result<String> {
+        let msg = create_request(
+            Operation::IncrBy,
+            vec![key.to_string(), increment.to_string()],
+        );
 
         self.request(&msg).await
     }
 
-    /// Sends a request to the server to dump the store out to disk.
-    /// The folder MUST exist on the host.
+    /// Sends a request to the server to decrement a value in the counter store by an amount
     ///
     /// # Example
     ///
@@ -199,38 +294,2703 @@ impl RubinClient {
     /// #[tokio::main]
     /// async fn main() -> std::io::Result<()> {
     ///     let client = RubinClient::new("127.0.0.1", 9876);
-    ///     let result = client.dump_store("/path/on/server/to/dump.json").await?;
+    ///     let result = client.decrby("stock", 30).await?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn dump_store(&self, filepath: &str) -> Result<String> {
-        let msg = create_request(Operation::Dump, vec![filepath.to_string()]);
+    pub async fn decrby(&self, key: &str, decrement: isize) -> Result<String> {
+        let msg = create_request(
+            Operation::DecrBy,
+            vec![key.to_string(), decrement.to_string()],
+        );
 
         self.request(&msg).await
     }
 
-    /// Sends a request to server and parses the response
-    pub async fn request(&self, msg: &str) -> Result<String> {
-        let response = self.send(msg).await?;
-        let contents = parse_response(&response);
+    /// Sends a request to the server to increment the floating point value of a string by an amount
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let result = client.incrbyfloat("temperature", 0.5).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn incrbyfloat(&self, key: &str, increment: f64) -> Result<String> {
+        let msg = create_request(
+            Operation::IncrByFloat,
+            vec![key.to_string(), increment.to_string()],
+        );
 
-        Ok(contents)
+        self.request(&msg).await
     }
 
-    /// Sends a request to the server, returning the raw response
-    async fn send(&self, msg: &str) -> Result<String> {
-        let mut client = TcpStream::connect(&self.address).await?;
-        client.write_all(msg.as_bytes()).await?;
+    /// Sends a request to the server to set the value of a counter, retrieving the previous value
+    ///
+    /// Returns `None` if the key was not present
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let previous = client.getset("requests", 0).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn getset(&self, key: &str, value: isize) -> Result<Option<String>> {
+        let msg = create_request(Operation::GetSet, vec![key.to_string(), value.to_string()]);
 
-        let mut buffer = [0; 4096];
-        let n_bytes = client.read(&mut buffer).await?;
-        if n_bytes == 0 {
-            return Ok(String::from(""));
-        }
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to push values onto the head of a list
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let length = client.lpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lpush(&self, key: &str, values: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::ListLeftPush, key_with_values(key, values));
 
-        let response = String::from_utf8_lossy(&buffer[..n_bytes]);
+        self.request(&msg).await
+    }
 
-        Ok(response.to_string())
+    /// Sends a request to the server to push values onto the tail of a list
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let length = client.rpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rpush(&self, key: &str, values: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::ListRightPush, key_with_values(key, values));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to pop a value from the head of a list
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let job = client.lpop("queue").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lpop(&self, key: &str) -> Result<Option<String>> {
+        let msg = create_request(Operation::ListLeftPop, vec![key.to_string()]);
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to pop a value from the tail of a list
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let job = client.rpop("queue").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rpop(&self, key: &str) -> Result<Option<String>> {
+        let msg = create_request(Operation::ListRightPop, vec![key.to_string()]);
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the values of a list between
+    /// `start` and `stop` (inclusive)
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let jobs = client.lrange("queue", 0, -1).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lrange(&self, key: &str, start: isize, stop: isize) -> Result<Vec<String>> {
+        let msg = create_request(
+            Operation::ListRange,
+            vec![key.to_string(), start.to_string(), stop.to_string()],
+        );
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve the length of a list
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let length = client.llen("queue").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn llen(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::ListLength, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the value at an index of a list
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let job = client.lindex("queue", -1).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lindex(&self, key: &str, index: isize) -> Result<Option<String>> {
+        let msg = create_request(
+            Operation::ListIndex,
+            vec![key.to_string(), index.to_string()],
+        );
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to set the value at an index of a list
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let result = client.lset("queue", 0, "job-3").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lset(&self, key: &str, index: isize, value: &str) -> Result<String> {
+        let msg = create_request(
+            Operation::ListSet,
+            vec![key.to_string(), index.to_string(), value.to_string()],
+        );
+
+        self.request(&msg).await
     }
+
+    /// Sends a request to the server to trim a list to the values between
+    /// `start` and `stop` (inclusive)
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let result = client.ltrim("queue", 0, 99).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ltrim(&self, key: &str, start: isize, stop: isize) -> Result<String> {
+        let msg = create_request(
+            Operation::ListTrim,
+            vec![key.to_string(), start.to_string(), stop.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove occurrences of a value from a list
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let removed = client.lrem("queue", 0, "job-1").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lrem(&self, key: &str, count: isize, value: &str) -> Result<String> {
+        let msg = create_request(
+            Operation::ListRemove,
+            vec![key.to_string(), count.to_string(), value.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to set one or more fields in a hash
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let added = client.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hset(&self, key: &str, fields: &[(&str, &str)]) -> Result<String> {
+        let mut args = vec![key.to_string()];
+        for (field, value) in fields {
+            args.push(field.to_string());
+            args.push(value.to_string());
+        }
+
+        let msg = create_request(Operation::HashSet, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the value of a field in a hash
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let name = client.hget("user:1000", "name").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hget(&self, key: &str, field: &str) -> Result<Option<String>> {
+        let msg = create_request(Operation::HashGet, vec![key.to_string(), field.to_string()]);
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to remove one or more fields from a hash
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let removed = client.hdel("user:1000", &["name"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hdel(&self, key: &str, fields: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::HashDelete, key_with_values(key, fields));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve all field-value pairs of a hash
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let user = client.hgetall("user:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hgetall(&self, key: &str) -> Result<HashMap<String, String>> {
+        let msg = create_request(Operation::HashGetAll, vec![key.to_string()]);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve all field names of a hash
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let fields = client.hkeys("user:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hkeys(&self, key: &str) -> Result<Vec<String>> {
+        let msg = create_request(Operation::HashKeys, vec![key.to_string()]);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve all values of a hash
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let values = client.hvals("user:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hvals(&self, key: &str) -> Result<Vec<String>> {
+        let msg = create_request(Operation::HashValues, vec![key.to_string()]);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve the number of fields in a hash
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let length = client.hlen("user:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hlen(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::HashLength, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to check if a field exists in a hash.
+    ///
+    /// The server responds with `1` if the field exists, `0` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let exists = client.hexists("user:1000", "name").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hexists(&self, key: &str, field: &str) -> Result<String> {
+        let msg = create_request(
+            Operation::HashExists,
+            vec![key.to_string(), field.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to increment the integer value of a field in a hash
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let visits = client.hincrby("user:1000", "visits", 1).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hincrby(&self, key: &str, field: &str, increment: isize) -> Result<String> {
+        let msg = create_request(
+            Operation::HashIncrBy,
+            vec![key.to_string(), field.to_string(), increment.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to add one or more members to a set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let added = client.sadd("flags", &["dark-mode", "beta"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sadd(&self, key: &str, members: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::SetAdd, key_with_values(key, members));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove one or more members from a set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let removed = client.srem("flags", &["beta"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn srem(&self, key: &str, members: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::SetRemove, key_with_values(key, members));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to check if a value is a member of a set.
+    ///
+    /// The server responds with `1` if the value is a member, `0` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let enabled = client.sismember("flags", "dark-mode").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sismember(&self, key: &str, member: &str) -> Result<String> {
+        let msg = create_request(
+            Operation::SetIsMember,
+            vec![key.to_string(), member.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve all members of a set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let flags = client.smembers("flags").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn smembers(&self, key: &str) -> Result<HashSet<String>> {
+        let msg = create_request(Operation::SetMembers, vec![key.to_string()]);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve the number of members in a set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let count = client.scard("flags").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn scard(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::SetCardinality, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the union of the given sets
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let union = client.sunion(&["flags:a", "flags:b"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sunion(&self, keys: &[&str]) -> Result<HashSet<String>> {
+        let msg = create_request(Operation::SetUnion, to_args(keys));
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve the intersection of the given sets
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let common = client.sinter(&["flags:a", "flags:b"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sinter(&self, keys: &[&str]) -> Result<HashSet<String>> {
+        let msg = create_request(Operation::SetIntersection, to_args(keys));
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve the difference of the given sets
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let only_a = client.sdiff(&["flags:a", "flags:b"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sdiff(&self, keys: &[&str]) -> Result<HashSet<String>> {
+        let msg = create_request(Operation::SetDifference, to_args(keys));
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to store the union of the given sets in the
+    /// `destination` set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let count = client.sunionstore("flags:all", &["flags:a", "flags:b"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sunionstore(&self, destination: &str, keys: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::SetUnionStore, key_with_values(destination, keys));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to store the intersection of the given sets in the
+    /// `destination` set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let count = client.sinterstore("flags:common", &["flags:a", "flags:b"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sinterstore(&self, destination: &str, keys: &[&str]) -> Result<String> {
+        let msg = create_request(
+            Operation::SetIntersectionStore,
+            key_with_values(destination, keys),
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to store the difference of the given sets in the
+    /// `destination` set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let count = client.sdiffstore("flags:only-a", &["flags:a", "flags:b"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sdiffstore(&self, destination: &str, keys: &[&str]) -> Result<String> {
+        let msg = create_request(
+            Operation::SetDifferenceStore,
+            key_with_values(destination, keys),
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to add one or more members with scores to a sorted set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let added = client.zadd("leaderboard", &[(100.0, "alice")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zadd(&self, key: &str, members: &[(f64, &str)]) -> Result<String> {
+        let mut args = vec![key.to_string()];
+        for (score, member) in members {
+            args.push(score.to_string());
+            args.push(member.to_string());
+        }
+
+        let msg = create_request(Operation::SortedSetAdd, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove one or more members from a sorted set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let removed = client.zrem("leaderboard", &["alice"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zrem(&self, key: &str, members: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::SortedSetRemove, key_with_values(key, members));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the score of a member in a sorted set.
+    ///
+    /// Returns `None` if the member is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let score = client.zscore("leaderboard", "alice").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zscore(&self, key: &str, member: &str) -> Result<Option<String>> {
+        let msg = create_request(
+            Operation::SortedSetScore,
+            vec![key.to_string(), member.to_string()],
+        );
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to increment the score of a member in a sorted set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let score = client.zincrby("leaderboard", 10.0, "alice").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zincrby(&self, key: &str, increment: f64, member: &str) -> Result<String> {
+        let msg = create_request(
+            Operation::SortedSetIncrBy,
+            vec![key.to_string(), increment.to_string(), member.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the rank of a member in a sorted set.
+    ///
+    /// Returns `None` if the member is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let rank = client.zrank("leaderboard", "alice").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zrank(&self, key: &str, member: &str) -> Result<Option<String>> {
+        let msg = create_request(
+            Operation::SortedSetRank,
+            vec![key.to_string(), member.to_string()],
+        );
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the members and scores of a sorted set between
+    /// `start` and `stop` (inclusive), ordered from lowest to highest score
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let members = client.zrange("leaderboard", 0, -1).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zrange(&self, key: &str, start: isize, stop: isize) -> Result<Vec<(String, f64)>> {
+        let msg = create_request(
+            Operation::SortedSetRange,
+            vec![key.to_string(), start.to_string(), stop.to_string()],
+        );
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve the members and scores of a sorted set between
+    /// `start` and `stop` (inclusive), ordered from highest to lowest score
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let top_ten = client.zrevrange("leaderboard", 0, 9).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zrevrange(
+        &self,
+        key: &str,
+        start: isize,
+        stop: isize,
+    ) -> Result<Vec<(String, f64)>> {
+        let msg = create_request(
+            Operation::SortedSetReverseRange,
+            vec![key.to_string(), start.to_string(), stop.to_string()],
+        );
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to retrieve the members and scores of a sorted set with a
+    /// score between `min` and `max` (inclusive)
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let members = client.zrangebyscore("leaderboard", 50.0, f64::INFINITY).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zrangebyscore(&self, key: &str, min: f64, max: f64) -> Result<Vec<(String, f64)>> {
+        let msg = create_request(
+            Operation::SortedSetRangeByScore,
+            vec![key.to_string(), min.to_string(), max.to_string()],
+        );
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to count the members of a sorted set with a score between
+    /// `min` and `max` (inclusive)
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let count = client.zcount("leaderboard", 50.0, 100.0).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zcount(&self, key: &str, min: f64, max: f64) -> Result<String> {
+        let msg = create_request(
+            Operation::SortedSetCount,
+            vec![key.to_string(), min.to_string(), max.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to insert a key-value pair into the string store which expires after the given duration
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.insert_string_with_expiry("session:1000", "token", std::time::Duration::from_secs(60)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn insert_string_with_expiry(
+        &self,
+        key: &str,
+        value: &str,
+        expiry: Duration,
+    ) -> Result<String> {
+        let msg = create_request(
+            Operation::StringSet,
+            vec![
+                key.to_string(),
+                value.to_string(),
+                "PX".to_string(),
+                expiry.as_millis().to_string(),
+            ],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to set a key to expire after the given number of seconds
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let updated = client.expire("session:1000", 60).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn expire(&self, key: &str, seconds: u64) -> Result<String> {
+        let msg = create_request(
+            Operation::Expire,
+            vec![key.to_string(), seconds.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to set a key to expire after the given number of milliseconds
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let updated = client.pexpire("session:1000", 1500).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pexpire(&self, key: &str, milliseconds: u64) -> Result<String> {
+        let msg = create_request(
+            Operation::PExpire,
+            vec![key.to_string(), milliseconds.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the remaining time-to-live of a key in seconds
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let ttl = client.ttl("session:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ttl(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::Ttl, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the remaining time-to-live of a key in milliseconds
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let ttl = client.pttl("session:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pttl(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::PTtl, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove the expiry from a key
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let removed = client.persist("session:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn persist(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::Persist, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to count how many of the given keys are present
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let count = client.exists(&["user:1000", "user:2000"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn exists(&self, keys: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::Exists, to_args(keys));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove keys regardless of the type of value they hold
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let removed = client.del(&["user:1000", "user:2000"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn del(&self, keys: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::Delete, to_args(keys));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the type of value held by a key
+    ///
+    /// Returns `none` if the key is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let key_type = client.key_type("user:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn key_type(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::Type, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to rename a key, replacing any value held by the new key
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.rename("jobs:pending", "jobs:active").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rename(&self, key: &str, new_key: &str) -> Result<String> {
+        let msg = create_request(
+            Operation::Rename,
+            vec![key.to_string(), new_key.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to rename a key if the new key is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let renamed = client.renamenx("jobs:pending", "jobs:active").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn renamenx(&self, key: &str, new_key: &str) -> Result<String> {
+        let msg = create_request(
+            Operation::RenameNx,
+            vec![key.to_string(), new_key.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve all keys matching a glob-style pattern
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let keys = client.keys("user:*").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn keys(&self, pattern: &str) -> Result<Vec<String>> {
+        let msg = create_request(Operation::Keys, vec![pattern.to_string()]);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to iterate over the keys in the store, a page at a time
+    ///
+    /// Start a scan with a cursor of `0` and pass the returned cursor to the next call, the
+    /// scan is complete once `0` is returned. Up to `count` keys are inspected by each call
+    /// and only those matching the pattern are returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///
+    ///     let mut cursor = 0;
+    ///     loop {
+    ///         let (next, keys) = client.scan(cursor, Some("user:*"), 100).await?;
+    ///         println!("{:?}", keys);
+    ///
+    ///         cursor = next;
+    ///         if cursor == 0 {
+    ///             break;
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn scan(
+        &self,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> Result<(u64, Vec<String>)> {
+        let mut args = vec![cursor.to_string()];
+        args.extend(scan_options(pattern, count));
+        let msg = create_request(Operation::Scan, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to iterate over the field-value pairs of a hash, a page
+    /// at a time
+    ///
+    /// See [`RubinClient::scan`] for details of the cursor and arguments.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let (cursor, fields) = client.hscan("user:1000", 0, Some("n*"), 10).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hscan(
+        &self,
+        key: &str,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> Result<(u64, Vec<(String, String)>)> {
+        let mut args = vec![key.to_string(), cursor.to_string()];
+        args.extend(scan_options(pattern, count));
+        let msg = create_request(Operation::HashScan, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to iterate over the members of a set, a page at a time
+    ///
+    /// See [`RubinClient::scan`] for details of the cursor and arguments.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let (cursor, members) = client.sscan("tags", 0, None, 10).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sscan(
+        &self,
+        key: &str,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> Result<(u64, Vec<String>)> {
+        let mut args = vec![key.to_string(), cursor.to_string()];
+        args.extend(scan_options(pattern, count));
+        let msg = create_request(Operation::SetScan, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to set or clear the bit at an offset in a string,
+    /// returning the previous bit
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let previous = client.setbit("active:2024-01-01", 1000, true).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn setbit(&self, key: &str, offset: usize, value: bool) -> Result<String> {
+        let msg = create_request(
+            Operation::SetBit,
+            vec![
+                key.to_string(),
+                offset.to_string(),
+                (value as u8).to_string(),
+            ],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to retrieve the bit at an offset in a string
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let bit = client.getbit("active:2024-01-01", 1000).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn getbit(&self, key: &str, offset: usize) -> Result<String> {
+        let msg = create_request(Operation::GetBit, vec![key.to_string(), offset.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to count the set bits in a string, optionally within an
+    /// inclusive range of bytes
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let active = client.bitcount("active:2024-01-01", None).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bitcount(&self, key: &str, range: Option<(isize, isize)>) -> Result<String> {
+        let mut args = vec![key.to_string()];
+        if let Some((start, end)) = range {
+            args.extend([start.to_string(), end.to_string()]);
+        }
+        let msg = create_request(Operation::BitCount, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to find the position of the first bit set to `bit` in a
+    /// string, optionally within an inclusive range of bytes
+    ///
+    /// Returns `-1` if there is no matching bit
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let first = client.bitpos("active:2024-01-01", true, None, None).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bitpos(
+        &self,
+        key: &str,
+        bit: bool,
+        start: Option<isize>,
+        end: Option<isize>,
+    ) -> Result<String> {
+        let mut args = vec![key.to_string(), (bit as u8).to_string()];
+        args.extend(start.into_iter().chain(end).map(|idx| idx.to_string()));
+        let msg = create_request(Operation::BitPos, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to perform a bitwise operation between strings, storing
+    /// the result in `destination`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    /// use rubin::store::mem::BitOp;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.bitop(BitOp::And, "active:both", &["active:monday", "active:tuesday"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bitop(&self, op: BitOp, destination: &str, keys: &[&str]) -> Result<String> {
+        let mut args = vec![op.to_string(), destination.to_string()];
+        args.extend(to_args(keys));
+        let msg = create_request(Operation::BitOp, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to read, write and increment integer fields within a
+    /// string
+    ///
+    /// Subcommands are sent as they would be to `BITFIELD`, see
+    /// [`crate::store::mem::BitFieldOp::parse`]. Returns one value per `GET`, `SET` and `INCRBY`,
+    /// `None` for writes which overflowed with `OVERFLOW FAIL`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let values = client.bitfield("stats", &["INCRBY", "u16", "#0", "1", "GET", "u16", "#1"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bitfield(&self, key: &str, subcommands: &[&str]) -> Result<Vec<Option<i64>>> {
+        let mut args = vec![key.to_string()];
+        args.extend(to_args(subcommands));
+        let msg = create_request(Operation::BitField, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to add elements to a HyperLogLog
+    ///
+    /// Returns `1` if the HyperLogLog was created or its estimate may have changed
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.pfadd("visitors", &["user:1", "user:2"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pfadd(&self, key: &str, elements: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::PfAdd, key_with_values(key, elements));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to estimate the number of unique elements added to the
+    /// HyperLogLogs at the given keys
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let visitors = client.pfcount(&["visitors"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pfcount(&self, keys: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::PfCount, to_args(keys));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to merge the HyperLogLogs at the source keys into the
+    /// HyperLogLog at `destination`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.pfmerge("visitors:week", &["visitors:monday", "visitors:tuesday"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pfmerge(&self, destination: &str, sources: &[&str]) -> Result<String> {
+        let msg = create_request(Operation::PfMerge, key_with_values(destination, sources));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to append an entry to a stream
+    ///
+    /// Pass `*` as the ID to have one generated. Returns the ID of the new entry
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let id = client.xadd("events", "*", &[("type", "signup")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xadd(&self, key: &str, id: &str, fields: &[(&str, &str)]) -> Result<String> {
+        let mut args = vec![key.to_string(), id.to_string()];
+        for (field, value) in fields {
+            args.push(field.to_string());
+            args.push(value.to_string());
+        }
+
+        let msg = create_request(Operation::StreamAdd, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to get the entries of a stream with IDs between `start`
+    /// and `end` inclusive
+    ///
+    /// `-` and `+` can be used for the lowest and highest IDs
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let entries = client.xrange("events", "-", "+", Some(10)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xrange(
+        &self,
+        key: &str,
+        start: &str,
+        end: &str,
+        count: Option<usize>,
+    ) -> Result<Vec<StreamEntry>> {
+        let mut args = to_args(&[key, start, end]);
+        args.extend(stream_count(count));
+        let msg = create_request(Operation::StreamRange, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to get the entries of a stream with IDs between `end`
+    /// and `start` inclusive, highest first
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let latest = client.xrevrange("events", "+", "-", Some(1)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xrevrange(
+        &self,
+        key: &str,
+        end: &str,
+        start: &str,
+        count: Option<usize>,
+    ) -> Result<Vec<StreamEntry>> {
+        let mut args = to_args(&[key, end, start]);
+        args.extend(stream_count(count));
+        let msg = create_request(Operation::StreamReverseRange, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to get the number of entries in a stream
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let len = client.xlen("events").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xlen(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::StreamLength, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove the oldest entries from a stream
+    ///
+    /// Returns the number of entries removed
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.xtrim("events", rubin::store::mem::StreamTrim::MaxLen(1000)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xtrim(&self, key: &str, trim: StreamTrim) -> Result<String> {
+        let args = match trim {
+            StreamTrim::MaxLen(len) => to_args(&[key, "MAXLEN", &len.to_string()]),
+            StreamTrim::MinId(id) => to_args(&[key, "MINID", &id.to_string()]),
+        };
+        let msg = create_request(Operation::StreamTrim, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to create a consumer group for a stream
+    ///
+    /// The group reads entries after `id`, pass `$` to only read entries added from now on
+    /// or `0` to read the whole stream
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.xgroup_create("jobs", "workers", "$").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xgroup_create(&self, key: &str, group: &str, id: &str) -> Result<String> {
+        let msg = create_request(Operation::StreamGroup, to_args(&["CREATE", key, group, id]));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove a consumer group from a stream
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.xgroup_destroy("jobs", "workers").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xgroup_destroy(&self, key: &str, group: &str) -> Result<String> {
+        let msg = create_request(Operation::StreamGroup, to_args(&["DESTROY", key, group]));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to read entries from a stream as a consumer in a group
+    ///
+    /// Pass `>` as the ID to read entries not yet delivered to the group, otherwise the
+    /// consumer's pending entries after the ID are returned
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let jobs = client.xreadgroup("jobs", "workers", "worker-1", ">", Some(10)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xreadgroup(
+        &self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        id: &str,
+        count: Option<usize>,
+    ) -> Result<Vec<StreamEntry>> {
+        let mut args = to_args(&[key, group, consumer, id]);
+        args.extend(stream_count(count));
+        let msg = create_request(Operation::StreamReadGroup, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to acknowledge entries delivered to a consumer group
+    ///
+    /// Returns the number of entries acknowledged
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.xack("jobs", "workers", &["1526919030474-0"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xack(&self, key: &str, group: &str, ids: &[&str]) -> Result<String> {
+        let mut args = to_args(&[key, group]);
+        args.extend(to_args(ids));
+        let msg = create_request(Operation::StreamAck, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to summarise the pending entries of a consumer group
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let summary = client.xpending("jobs", "workers").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xpending(&self, key: &str, group: &str) -> Result<PendingSummary> {
+        let msg = create_request(Operation::StreamPending, to_args(&[key, group]));
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to get the pending entries of a consumer group with IDs
+    /// between `start` and `end` inclusive, optionally only those held by `consumer`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let pending = client.xpending_range("jobs", "workers", "-", "+", 10, None).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xpending_range(
+        &self,
+        key: &str,
+        group: &str,
+        start: &str,
+        end: &str,
+        count: usize,
+        consumer: Option<&str>,
+    ) -> Result<Vec<PendingEntry>> {
+        let mut args = to_args(&[key, group, start, end, &count.to_string()]);
+        args.extend(consumer.map(|consumer| consumer.to_string()));
+        let msg = create_request(Operation::StreamPending, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to transfer pending entries which have been idle for at
+    /// least `min_idle` milliseconds to `consumer`
+    ///
+    /// Returns the entries claimed
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let claimed = client.xclaim("jobs", "workers", "worker-2", 60000, &["1526919030474-0"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xclaim(
+        &self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        min_idle: u64,
+        ids: &[&str],
+    ) -> Result<Vec<StreamEntry>> {
+        let mut args = to_args(&[key, group, consumer, &min_idle.to_string()]);
+        args.extend(to_args(ids));
+        let msg = create_request(Operation::StreamClaim, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to add members with positions to a geo set
+    ///
+    /// Each member is given as a longitude, latitude and name
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let added = client.geoadd("drivers", &[(-0.1276, 51.5072, "driver:1")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn geoadd(&self, key: &str, members: &[(f64, f64, &str)]) -> Result<String> {
+        let mut args = vec![key.to_string()];
+        for (lon, lat, member) in members {
+            args.extend([lon.to_string(), lat.to_string(), member.to_string()]);
+        }
+
+        let msg = create_request(Operation::GeoAdd, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to get the positions of members of a geo set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let positions = client.geopos("drivers", &["driver:1"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn geopos(&self, key: &str, members: &[&str]) -> Result<Vec<Option<(f64, f64)>>> {
+        let msg = create_request(Operation::GeoPosition, key_with_values(key, members));
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to get the distance between two members of a geo set
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let distance = client.geodist("drivers", "driver:1", "driver:2", rubin::store::mem::GeoUnit::Kilometers).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn geodist(
+        &self,
+        key: &str,
+        member1: &str,
+        member2: &str,
+        unit: GeoUnit,
+    ) -> Result<Option<String>> {
+        let msg = create_request(
+            Operation::GeoDistance,
+            to_args(&[key, member1, member2, unit.as_str()]),
+        );
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to find the members of a geo set within an area, nearest
+    /// first
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    /// use rubin::store::mem::{GeoOrigin, GeoSearch, GeoShape, GeoUnit};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let search = GeoSearch {
+    ///         origin: GeoOrigin::LonLat(-0.1276, 51.5072),
+    ///         shape: GeoShape::Radius(5.0),
+    ///         unit: GeoUnit::Kilometers,
+    ///         count: Some(10),
+    ///     };
+    ///     let nearest = client.geosearch("drivers", &search).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn geosearch(&self, key: &str, search: &GeoSearch) -> Result<Vec<GeoMatch>> {
+        let mut args = vec![key.to_string()];
+        args.extend(search.to_args());
+        let msg = create_request(Operation::GeoSearch, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to set the value at a path in a JSON document
+    ///
+    /// The document is created if the path is the root `$`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.json_set("user:1000", "$", r#"{"name": "rubin"}"#).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_set(&self, key: &str, path: &str, value: &str) -> Result<String> {
+        let msg = create_request(Operation::JsonSet, to_args(&[key, path, value]));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to get the values at a path in a JSON document
+    ///
+    /// Returns a JSON array of every matched value
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let name = client.json_get("user:1000", "$.name").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_get(&self, key: &str, path: &str) -> Result<Option<String>> {
+        let msg = create_request(Operation::JsonGet, to_args(&[key, path]));
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to remove the values at a path in a JSON document
+    ///
+    /// Returns the number of values removed
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.json_del("user:1000", "$.address").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_del(&self, key: &str, path: &str) -> Result<String> {
+        let msg = create_request(Operation::JsonDelete, to_args(&[key, path]));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to append JSON values to the arrays at a path in a JSON
+    /// document
+    ///
+    /// Returns the new length of each matched array, `None` for matches which are not arrays
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let lengths = client.json_arrappend("user:1000", "$.tags", &[r#""rust""#]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_arrappend(
+        &self,
+        key: &str,
+        path: &str,
+        values: &[&str],
+    ) -> Result<Vec<Option<usize>>> {
+        let mut args = to_args(&[key, path]);
+        args.extend(to_args(values));
+        let msg = create_request(Operation::JsonArrAppend, args);
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to increment the numbers at a path in a JSON document
+    ///
+    /// Returns a JSON array of the new values, `null` for matches which are not numbers
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let visits = client.json_numincrby("user:1000", "$.visits", 1.0).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_numincrby(&self, key: &str, path: &str, increment: f64) -> Result<String> {
+        let msg = create_request(
+            Operation::JsonNumIncrBy,
+            to_args(&[key, path, &increment.to_string()]),
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to append a value to a string
+    ///
+    /// Returns the length of the string after the append
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.append("log", "started;").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn append(&self, key: &str, value: &str) -> Result<String> {
+        let msg = create_request(Operation::StringAppend, to_args(&[key, value]));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to get part of a string between two indices (inclusive)
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let prefix = client.getrange("greeting", 0, 4).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn getrange(&self, key: &str, start: isize, end: isize) -> Result<String> {
+        let msg = create_request(
+            Operation::StringGetRange,
+            to_args(&[key, &start.to_string(), &end.to_string()]),
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to overwrite part of a string from an offset
+    ///
+    /// Returns the length of the string after it was overwritten
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.setrange("greeting", 7, "Rubin").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn setrange(&self, key: &str, offset: usize, value: &str) -> Result<String> {
+        let msg = create_request(
+            Operation::StringSetRange,
+            to_args(&[key, &offset.to_string(), value]),
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to get the length of a string
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let len = client.strlen("greeting").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn strlen(&self, key: &str) -> Result<String> {
+        let msg = create_request(Operation::StringLength, vec![key.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to insert a string if the key is not present
+    ///
+    /// Returns `1` if the string was inserted, `0` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.setnx("lock:orders", "worker-1").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn setnx(&self, key: &str, value: &str) -> Result<String> {
+        let msg = create_request(Operation::StringSetNx, to_args(&[key, value]));
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove a string, returning the value it held
+    ///
+    /// Returns `None` if the key is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let token = client.getdel("token:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn getdel(&self, key: &str) -> Result<Option<String>> {
+        let msg = create_request(Operation::StringGetDelete, vec![key.to_string()]);
+
+        self.request_optional(&msg).await
+    }
+
+    /// Sends a request to the server to get the strings held by many keys in one request
+    ///
+    /// Returns the value of each key in order, `None` for missing keys
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let users = client.mget(&["user:1", "user:2"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn mget(&self, keys: &[&str]) -> Result<Vec<Option<String>>> {
+        let msg = create_request(Operation::StringMultiGet, to_args(keys));
+
+        let response = self.request(&msg).await?;
+        parse_json_response(&response)
+    }
+
+    /// Sends a request to the server to insert many strings in one request
+    ///
+    /// Keys and values cannot contain spaces as they are sent as separate arguments
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.mset(&[("user:1", "alice"), ("user:2", "bob")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn mset(&self, pairs: &[(&str, &str)]) -> Result<String> {
+        let mut args = vec![];
+        for (key, value) in pairs {
+            args.push(key.to_string());
+            args.push(value.to_string());
+        }
+
+        let msg = create_request(Operation::StringMultiSet, args);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to insert many strings in one request, only if none of
+    /// the keys are present
+    ///
+    /// Returns `1` if the strings were inserted, `0` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.msetnx(&[("user:1", "alice"), ("user:2", "bob")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn msetnx(&self, pairs: &[(&str, &str)]) -> Result<String> {
+        let mut args = vec![];
+        for (key, value) in pairs {
+            args.push(key.to_string());
+            args.push(value.to_string());
+        }
+
+        let msg = create_request(Operation::StringMultiSetNx, args);
+
+        self.request(&msg).await
+    }
+
+    /// Selects the database later requests from this client are performed against
+    ///
+    /// The server checks that the database exists, the selection is only changed if it
+    /// responds with `OK`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.select(1).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn select(&self, db: usize) -> Result<String> {
+        let msg = create_request(Operation::Select, vec![db.to_string()]);
+
+        let response = self.request(&msg).await?;
+        if response == "OK" {
+            self.database.store(db, Ordering::Relaxed);
+        }
+
+        Ok(response)
+    }
+
+    /// Gets the index of the database requests are performed against
+    pub fn database(&self) -> usize {
+        self.database.load(Ordering::Relaxed)
+    }
+
+    /// Sends a request to the server to swap the contents of two databases
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.swapdb(0, 1).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn swapdb(&self, first: usize, second: usize) -> Result<String> {
+        let msg = create_request(
+            Operation::SwapDb,
+            vec![first.to_string(), second.to_string()],
+        );
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to move a key from the selected database to another
+    ///
+    /// Returns `1` if the key was moved, `0` if it is not present or the other database
+    /// already holds the key
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.move_key("user:1000", 1).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn move_key(&self, key: &str, db: usize) -> Result<String> {
+        let msg = create_request(Operation::Move, vec![key.to_string(), db.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove every key from the selected database
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.flushdb().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn flushdb(&self) -> Result<String> {
+        let msg = create_request(Operation::FlushDb, vec![]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to remove every key from every database
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     client.flushall().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn flushall(&self) -> Result<String> {
+        let msg = create_request(Operation::FlushAll, vec![]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to clear all keys and values from the string store
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let result = client.clear_strings().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn clear_strings(&self) -> Result<String> {
+        let msg = create_request(Operation::StringClear, vec!["noop".to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to dump the store out to disk.
+    /// The folder MUST exist on the host.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let result = client.dump_store("/path/on/server/to/dump.json").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn dump_store(&self, filepath: &str) -> Result<String> {
+        let msg = create_request(Operation::Dump, vec![filepath.to_string()]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to write every database to its data directory
+    ///
+    /// The server responds with an error if it has no data directory.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let result = client.save().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn save(&self) -> Result<String> {
+        let msg = create_request(Operation::Save, vec![]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server to write every database to its data directory in the
+    /// background
+    ///
+    /// The server responds with an error if it has no data directory. See [`RubinClient::lastsave`] to check when it has finished.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let result = client.bgsave().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bgsave(&self) -> Result<String> {
+        let msg = create_request(Operation::BackgroundSave, vec![]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to the server for the time the databases were last written to
+    /// disk, as seconds since the Unix epoch
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::net::client::RubinClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let client = RubinClient::new("127.0.0.1", 9876);
+    ///     let timestamp = client.lastsave().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lastsave(&self) -> Result<String> {
+        let msg = create_request(Operation::LastSave, vec![]);
+
+        self.request(&msg).await
+    }
+
+    /// Sends a request to server and parses the response
+    pub async fn request(&self, msg: &str) -> Result<String> {
+        let response = self.send(msg).await?;
+        let contents = parse_response(&response);
+
+        Ok(contents)
+    }
+
+    /// Sends a request to the server which may not return a value
+    ///
+    /// Returns `None` if the server responds that the value is not present, or an error
+    /// holding the message of the server if the operation failed
+    pub async fn request_optional(&self, msg: &str) -> Result<Option<String>> {
+        let response = self.send(msg).await?;
+
+        parse_optional_response(&response)
+    }
+
+    /// Sends a request to the server, returning the raw response
+    async fn send(&self, msg: &str) -> Result<String> {
+        let msg = select_database(self.database(), msg);
+        let mut client = TcpStream::connect(&self.address).await?;
+        client.write_all(msg.as_bytes()).await?;
+        client.write_all(b"\n").await?;
+
+        // The server closes the connection once the response is sent
+        let mut buffer = Vec::new();
+        client.read_to_end(&mut buffer).await?;
+        if buffer.is_empty() {
+            return Ok(String::from(""));
+        }
+
+        let response = String::from_utf8_lossy(&buffer);
+
+        Ok(response.to_string())
+    }
+}
+
+/// Builds the arguments for a request consisting of a key followed by multiple values
+fn key_with_values(key: &str, values: &[&str]) -> Vec<String> {
+    std::iter::once(key)
+        .chain(values.iter().copied())
+        .map(|value| value.to_string())
+        .collect()
+}
+
+/// Converts a slice of string slices into request arguments
+fn to_args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+/// Builds the `MATCH` / `COUNT` option-value pairs for a scan request
+fn scan_options(pattern: Option<&str>, count: usize) -> Vec<String> {
+    let mut options = vec![];
+    if let Some(pattern) = pattern {
+        options.extend(["MATCH".to_string(), pattern.to_string()]);
+    }
+    options.extend(["COUNT".to_string(), count.to_string()]);
+
+    options
+}
+
+/// Builds the optional `COUNT` option-value pair for a stream request
+fn stream_count(count: Option<usize>) -> Vec<String> {
+    match count {
+        Some(count) => vec!["COUNT".to_string(), count.to_string()],
+        None => vec![],
+    }
+}
+
+/// Parses a response containing a JSON encoded collection of values
+fn parse_json_response<T: DeserializeOwned>(response: &str) -> Result<T> {
+    serde_json::from_str(response)
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, response.to_string()))
 }
@@ -7,6 +7,8 @@
 
 use crate::errors::MessageError;
 
+use std::io;
+
 /// Operation type denoting the type of Operation to perform
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operation {
@@ -28,12 +30,327 @@ pub enum Operation {
     /// Decrement a key in the counter store
     Decr,
 
+    /// Push values onto the head of a list
+    ListLeftPush,
+
+    /// Push values onto the tail of a list
+    ListRightPush,
+
+    /// Pop a value from the head of a list
+    ListLeftPop,
+
+    /// Pop a value from the tail of a list
+    ListRightPop,
+
+    /// Retrieve a range of values from a list
+    ListRange,
+
+    /// Retrieve the length of a list
+    ListLength,
+
+    /// Retrieve the value at an index of a list
+    ListIndex,
+
+    /// Set the value at an index of a list
+    ListSet,
+
+    /// Trim a list to a given range
+    ListTrim,
+
+    /// Remove occurrences of a value from a list
+    ListRemove,
+
+    /// Set one or more fields in a hash
+    HashSet,
+
+    /// Retrieve the value of a field in a hash
+    HashGet,
+
+    /// Remove one or more fields from a hash
+    HashDelete,
+
+    /// Retrieve all field-value pairs of a hash
+    HashGetAll,
+
+    /// Retrieve all field names of a hash
+    HashKeys,
+
+    /// Retrieve all values of a hash
+    HashValues,
+
+    /// Retrieve the number of fields in a hash
+    HashLength,
+
+    /// Check if a field exists in a hash
+    HashExists,
+
+    /// Increment the integer value of a field in a hash
+    HashIncrBy,
+
+    /// Add one or more members to a set
+    SetAdd,
+
+    /// Remove one or more members from a set
+    SetRemove,
+
+    /// Check if a value is a member of a set
+    SetIsMember,
+
+    /// Retrieve all members of a set
+    SetMembers,
+
+    /// Retrieve the number of members in a set
+    SetCardinality,
+
+    /// Retrieve the union of sets
+    SetUnion,
+
+    /// Retrieve the intersection of sets
+    SetIntersection,
+
+    /// Retrieve the difference of sets
+    SetDifference,
+
+    /// Store the union of sets in a destination set
+    SetUnionStore,
+
+    /// Store the intersection of sets in a destination set
+    SetIntersectionStore,
+
+    /// Store the difference of sets in a destination set
+    SetDifferenceStore,
+
+    /// Add one or more members with scores to a sorted set
+    SortedSetAdd,
+
+    /// Remove one or more members from a sorted set
+    SortedSetRemove,
+
+    /// Retrieve the score of a member in a sorted set
+    SortedSetScore,
+
+    /// Increment the score of a member in a sorted set
+    SortedSetIncrBy,
+
+    /// Retrieve the rank of a member in a sorted set
+    SortedSetRank,
+
+    /// Retrieve a range of members from a sorted set by index
+    SortedSetRange,
+
+    /// Retrieve a range of members from a sorted set by score
+    SortedSetRangeByScore,
+
+    /// Retrieve a range of members from a sorted set by index, highest score first
+    SortedSetReverseRange,
+
+    /// Count the members of a sorted set within a score range
+    SortedSetCount,
+
+    /// Set a key to expire after a number of seconds
+    Expire,
+
+    /// Set a key to expire after a number of milliseconds
+    PExpire,
+
+    /// Retrieve the remaining time-to-live of a key in seconds
+    Ttl,
+
+    /// Retrieve the remaining time-to-live of a key in milliseconds
+    PTtl,
+
+    /// Remove the expiry from a key
+    Persist,
+
+    /// Add a serialized value tagged with its type to the typed value store
+    TypedSet,
+
+    /// Retrieve a serialized value of a given type from the typed value store
+    TypedGet,
+
+    /// Increment a key in the counter store by an amount
+    IncrBy,
+
+    /// Decrement a key in the counter store by an amount
+    DecrBy,
+
+    /// Increment the floating point value of a string by an amount
+    IncrByFloat,
+
+    /// Set the value of a counter, retrieving the previous value
+    GetSet,
+
+    /// Count how many of the given keys are present
+    Exists,
+
+    /// Remove keys regardless of the type of value they hold
+    Delete,
+
+    /// Retrieve the type of value held by a key
+    Type,
+
+    /// Rename a key
+    Rename,
+
+    /// Rename a key if the new key is not present
+    RenameNx,
+
+    /// Retrieve all keys matching a pattern
+    Keys,
+
+    /// Iterate over the keys in the store, a page at a time
+    Scan,
+
+    /// Iterate over the field-value pairs of a hash, a page at a time
+    HashScan,
+
+    /// Iterate over the members of a set, a page at a time
+    SetScan,
+
+    /// Set or clear the bit at an offset in a string
+    SetBit,
+
+    /// Retrieve the bit at an offset in a string
+    GetBit,
+
+    /// Count the set bits in a string
+    BitCount,
+
+    /// Find the first set or clear bit in a string
+    BitPos,
+
+    /// Perform a bitwise operation between strings
+    BitOp,
+
+    /// Read, write and increment integer fields within a string
+    BitField,
+
+    /// Add elements to a HyperLogLog
+    PfAdd,
+
+    /// Estimate the number of unique elements in HyperLogLogs
+    PfCount,
+
+    /// Merge HyperLogLogs into a destination
+    PfMerge,
+
+    /// Append an entry to a stream
+    StreamAdd,
+
+    /// Get the entries of a stream within a range of IDs
+    StreamRange,
+
+    /// Get the entries of a stream within a range of IDs, highest first
+    StreamReverseRange,
+
+    /// Get the number of entries in a stream
+    StreamLength,
+
+    /// Remove the oldest entries from a stream
+    StreamTrim,
+
+    /// Create or destroy a consumer group of a stream
+    StreamGroup,
+
+    /// Read entries from a stream as a consumer in a group
+    StreamReadGroup,
+
+    /// Acknowledge entries delivered to a consumer group
+    StreamAck,
+
+    /// Get the pending entries of a consumer group
+    StreamPending,
+
+    /// Transfer pending entries to another consumer
+    StreamClaim,
+
+    /// Add members with positions to a geo set
+    GeoAdd,
+
+    /// Get the positions of members of a geo set
+    GeoPosition,
+
+    /// Get the distance between two members of a geo set
+    GeoDistance,
+
+    /// Find the members of a geo set within a radius or box
+    GeoSearch,
+
+    /// Set a value at a path in a JSON document
+    JsonSet,
+
+    /// Get the values at a path in a JSON document
+    JsonGet,
+
+    /// Remove the values at a path in a JSON document
+    JsonDelete,
+
+    /// Append values to the arrays at a path in a JSON document
+    JsonArrAppend,
+
+    /// Increment the numbers at a path in a JSON document
+    JsonNumIncrBy,
+
+    /// Append a value to a string
+    StringAppend,
+
+    /// Get part of a string between two indices
+    StringGetRange,
+
+    /// Overwrite part of a string from an offset
+    StringSetRange,
+
+    /// Get the length of a string
+    StringLength,
+
+    /// Set a string if the key is not present
+    StringSetNx,
+
+    /// Remove a string, returning its value
+    StringGetDelete,
+
+    /// Get the strings of many keys
+    StringMultiGet,
+
+    /// Set the strings of many keys
+    StringMultiSet,
+
+    /// Set the strings of many keys if none are present
+    StringMultiSetNx,
+
+    /// Select the database used by later requests
+    Select,
+
+    /// Swap the contents of two databases
+    SwapDb,
+
+    /// Move a key to another database
+    Move,
+
+    /// Remove every key from the selected database
+    FlushDb,
+
+    /// Remove every key from every database
+    FlushAll,
+
     /// Dump the store out to disk
     Dump,
 
+    /// Write every database out to disk
+    Save,
+
+    /// Write every database out to disk in the background
+    BackgroundSave,
+
+    /// Get the time every database was last written to disk
+    LastSave,
+
     /// No operation
     Noop,
 
+    /// Response denoting that no value is present (i.e. a missing key)
+    Nil,
+
     /// Parsing error
     Error,
 }
@@ -48,11 +365,185 @@ impl Operation {
             "RM" => Self::StringRemove,
             "INCR" => Self::Incr,
             "DECR" => Self::Decr,
+            "LPUSH" => Self::ListLeftPush,
+            "RPUSH" => Self::ListRightPush,
+            "LPOP" => Self::ListLeftPop,
+            "RPOP" => Self::ListRightPop,
+            "LRANGE" => Self::ListRange,
+            "LLEN" => Self::ListLength,
+            "LINDEX" => Self::ListIndex,
+            "LSET" => Self::ListSet,
+            "LTRIM" => Self::ListTrim,
+            "LREM" => Self::ListRemove,
+            "HSET" => Self::HashSet,
+            "HGET" => Self::HashGet,
+            "HDEL" => Self::HashDelete,
+            "HGETALL" => Self::HashGetAll,
+            "HKEYS" => Self::HashKeys,
+            "HVALS" => Self::HashValues,
+            "HLEN" => Self::HashLength,
+            "HEXISTS" => Self::HashExists,
+            "HINCRBY" => Self::HashIncrBy,
+            "SADD" => Self::SetAdd,
+            "SREM" => Self::SetRemove,
+            "SISMEMBER" => Self::SetIsMember,
+            "SMEMBERS" => Self::SetMembers,
+            "SCARD" => Self::SetCardinality,
+            "SUNION" => Self::SetUnion,
+            "SINTER" => Self::SetIntersection,
+            "SDIFF" => Self::SetDifference,
+            "SUNIONSTORE" => Self::SetUnionStore,
+            "SINTERSTORE" => Self::SetIntersectionStore,
+            "SDIFFSTORE" => Self::SetDifferenceStore,
+            "ZADD" => Self::SortedSetAdd,
+            "ZREM" => Self::SortedSetRemove,
+            "ZSCORE" => Self::SortedSetScore,
+            "ZINCRBY" => Self::SortedSetIncrBy,
+            "ZRANK" => Self::SortedSetRank,
+            "ZRANGE" => Self::SortedSetRange,
+            "ZRANGEBYSCORE" => Self::SortedSetRangeByScore,
+            "ZREVRANGE" => Self::SortedSetReverseRange,
+            "ZCOUNT" => Self::SortedSetCount,
+            "EXPIRE" => Self::Expire,
+            "PEXPIRE" => Self::PExpire,
+            "TTL" => Self::Ttl,
+            "PTTL" => Self::PTtl,
+            "PERSIST" => Self::Persist,
+            "TSET" => Self::TypedSet,
+            "TGET" => Self::TypedGet,
+            "INCRBY" => Self::IncrBy,
+            "DECRBY" => Self::DecrBy,
+            "INCRBYFLOAT" => Self::IncrByFloat,
+            "GETSET" => Self::GetSet,
+            "EXISTS" => Self::Exists,
+            "DEL" => Self::Delete,
+            "TYPE" => Self::Type,
+            "RENAME" => Self::Rename,
+            "RENAMENX" => Self::RenameNx,
+            "KEYS" => Self::Keys,
+            "SCAN" => Self::Scan,
+            "HSCAN" => Self::HashScan,
+            "SSCAN" => Self::SetScan,
+            "SETBIT" => Self::SetBit,
+            "GETBIT" => Self::GetBit,
+            "BITCOUNT" => Self::BitCount,
+            "BITPOS" => Self::BitPos,
+            "BITOP" => Self::BitOp,
+            "BITFIELD" => Self::BitField,
+            "PFADD" => Self::PfAdd,
+            "PFCOUNT" => Self::PfCount,
+            "PFMERGE" => Self::PfMerge,
+            "XADD" => Self::StreamAdd,
+            "XRANGE" => Self::StreamRange,
+            "XREVRANGE" => Self::StreamReverseRange,
+            "XLEN" => Self::StreamLength,
+            "XTRIM" => Self::StreamTrim,
+            "XGROUP" => Self::StreamGroup,
+            "XREADGROUP" => Self::StreamReadGroup,
+            "XACK" => Self::StreamAck,
+            "XPENDING" => Self::StreamPending,
+            "XCLAIM" => Self::StreamClaim,
+            "GEOADD" => Self::GeoAdd,
+            "GEOPOS" => Self::GeoPosition,
+            "GEODIST" => Self::GeoDistance,
+            "GEOSEARCH" => Self::GeoSearch,
+            "JSON.SET" => Self::JsonSet,
+            "JSON.GET" => Self::JsonGet,
+            "JSON.DEL" => Self::JsonDelete,
+            "JSON.ARRAPPEND" => Self::JsonArrAppend,
+            "JSON.NUMINCRBY" => Self::JsonNumIncrBy,
+            "APPEND" => Self::StringAppend,
+            "GETRANGE" => Self::StringGetRange,
+            "SETRANGE" => Self::StringSetRange,
+            "STRLEN" => Self::StringLength,
+            "SETNX" => Self::StringSetNx,
+            "GETDEL" => Self::StringGetDelete,
+            "MGET" => Self::StringMultiGet,
+            "MSET" => Self::StringMultiSet,
+            "MSETNX" => Self::StringMultiSetNx,
+            "SELECT" => Self::Select,
+            "SWAPDB" => Self::SwapDb,
+            "MOVE" => Self::Move,
+            "FLUSHDB" => Self::FlushDb,
+            "FLUSHALL" => Self::FlushAll,
             "NOOP" => Self::Noop,
+            "NIL" => Self::Nil,
             "DUMP" => Self::Dump,
+            "SAVE" => Self::Save,
+            "BGSAVE" => Self::BackgroundSave,
+            "LASTSAVE" => Self::LastSave,
             _ => Self::Error,
         }
     }
+
+    /// Checks if the operation can change the contents of the store
+    ///
+    /// Used by the server to count the changes made since the store was last saved.
+    pub fn is_update(&self) -> bool {
+        matches!(
+            self,
+            Self::StringSet
+                | Self::StringClear
+                | Self::StringRemove
+                | Self::Incr
+                | Self::Decr
+                | Self::ListLeftPush
+                | Self::ListRightPush
+                | Self::ListLeftPop
+                | Self::ListRightPop
+                | Self::ListSet
+                | Self::ListTrim
+                | Self::ListRemove
+                | Self::HashSet
+                | Self::HashDelete
+                | Self::HashIncrBy
+                | Self::SetAdd
+                | Self::SetRemove
+                | Self::SetUnionStore
+                | Self::SetIntersectionStore
+                | Self::SetDifferenceStore
+                | Self::SortedSetAdd
+                | Self::SortedSetRemove
+                | Self::SortedSetIncrBy
+                | Self::Expire
+                | Self::PExpire
+                | Self::Persist
+                | Self::TypedSet
+                | Self::IncrBy
+                | Self::DecrBy
+                | Self::IncrByFloat
+                | Self::GetSet
+                | Self::Delete
+                | Self::Rename
+                | Self::RenameNx
+                | Self::SetBit
+                | Self::BitOp
+                | Self::BitField
+                | Self::PfAdd
+                | Self::PfMerge
+                | Self::StreamAdd
+                | Self::StreamTrim
+                | Self::StreamGroup
+                | Self::StreamReadGroup
+                | Self::StreamAck
+                | Self::StreamClaim
+                | Self::GeoAdd
+                | Self::JsonSet
+                | Self::JsonDelete
+                | Self::JsonArrAppend
+                | Self::JsonNumIncrBy
+                | Self::StringAppend
+                | Self::StringSetRange
+                | Self::StringSetNx
+                | Self::StringGetDelete
+                | Self::StringMultiSet
+                | Self::StringMultiSetNx
+                | Self::SwapDb
+                | Self::Move
+                | Self::FlushDb
+                | Self::FlushAll
+        )
+    }
 }
 
 impl std::fmt::Display for Operation {
@@ -64,9 +555,114 @@ impl std::fmt::Display for Operation {
             Self::StringClear => write!(f, "CLR"),
             Self::Incr => write!(f, "INCR"),
             Self::Decr => write!(f, "DECR"),
+            Self::ListLeftPush => write!(f, "LPUSH"),
+            Self::ListRightPush => write!(f, "RPUSH"),
+            Self::ListLeftPop => write!(f, "LPOP"),
+            Self::ListRightPop => write!(f, "RPOP"),
+            Self::ListRange => write!(f, "LRANGE"),
+            Self::ListLength => write!(f, "LLEN"),
+            Self::ListIndex => write!(f, "LINDEX"),
+            Self::ListSet => write!(f, "LSET"),
+            Self::ListTrim => write!(f, "LTRIM"),
+            Self::ListRemove => write!(f, "LREM"),
+            Self::HashSet => write!(f, "HSET"),
+            Self::HashGet => write!(f, "HGET"),
+            Self::HashDelete => write!(f, "HDEL"),
+            Self::HashGetAll => write!(f, "HGETALL"),
+            Self::HashKeys => write!(f, "HKEYS"),
+            Self::HashValues => write!(f, "HVALS"),
+            Self::HashLength => write!(f, "HLEN"),
+            Self::HashExists => write!(f, "HEXISTS"),
+            Self::HashIncrBy => write!(f, "HINCRBY"),
+            Self::SetAdd => write!(f, "SADD"),
+            Self::SetRemove => write!(f, "SREM"),
+            Self::SetIsMember => write!(f, "SISMEMBER"),
+            Self::SetMembers => write!(f, "SMEMBERS"),
+            Self::SetCardinality => write!(f, "SCARD"),
+            Self::SetUnion => write!(f, "SUNION"),
+            Self::SetIntersection => write!(f, "SINTER"),
+            Self::SetDifference => write!(f, "SDIFF"),
+            Self::SetUnionStore => write!(f, "SUNIONSTORE"),
+            Self::SetIntersectionStore => write!(f, "SINTERSTORE"),
+            Self::SetDifferenceStore => write!(f, "SDIFFSTORE"),
+            Self::SortedSetAdd => write!(f, "ZADD"),
+            Self::SortedSetRemove => write!(f, "ZREM"),
+            Self::SortedSetScore => write!(f, "ZSCORE"),
+            Self::SortedSetIncrBy => write!(f, "ZINCRBY"),
+            Self::SortedSetRank => write!(f, "ZRANK"),
+            Self::SortedSetRange => write!(f, "ZRANGE"),
+            Self::SortedSetRangeByScore => write!(f, "ZRANGEBYSCORE"),
+            Self::SortedSetReverseRange => write!(f, "ZREVRANGE"),
+            Self::SortedSetCount => write!(f, "ZCOUNT"),
+            Self::Expire => write!(f, "EXPIRE"),
+            Self::PExpire => write!(f, "PEXPIRE"),
+            Self::Ttl => write!(f, "TTL"),
+            Self::PTtl => write!(f, "PTTL"),
+            Self::Persist => write!(f, "PERSIST"),
+            Self::TypedSet => write!(f, "TSET"),
+            Self::TypedGet => write!(f, "TGET"),
+            Self::IncrBy => write!(f, "INCRBY"),
+            Self::DecrBy => write!(f, "DECRBY"),
+            Self::IncrByFloat => write!(f, "INCRBYFLOAT"),
+            Self::GetSet => write!(f, "GETSET"),
+            Self::Exists => write!(f, "EXISTS"),
+            Self::Delete => write!(f, "DEL"),
+            Self::Type => write!(f, "TYPE"),
+            Self::Rename => write!(f, "RENAME"),
+            Self::RenameNx => write!(f, "RENAMENX"),
+            Self::Keys => write!(f, "KEYS"),
+            Self::Scan => write!(f, "SCAN"),
+            Self::HashScan => write!(f, "HSCAN"),
+            Self::SetScan => write!(f, "SSCAN"),
+            Self::SetBit => write!(f, "SETBIT"),
+            Self::GetBit => write!(f, "GETBIT"),
+            Self::BitCount => write!(f, "BITCOUNT"),
+            Self::BitPos => write!(f, "BITPOS"),
+            Self::BitOp => write!(f, "BITOP"),
+            Self::BitField => write!(f, "BITFIELD"),
+            Self::PfAdd => write!(f, "PFADD"),
+            Self::PfCount => write!(f, "PFCOUNT"),
+            Self::PfMerge => write!(f, "PFMERGE"),
+            Self::StreamAdd => write!(f, "XADD"),
+            Self::StreamRange => write!(f, "XRANGE"),
+            Self::StreamReverseRange => write!(f, "XREVRANGE"),
+            Self::StreamLength => write!(f, "XLEN"),
+            Self::StreamTrim => write!(f, "XTRIM"),
+            Self::StreamGroup => write!(f, "XGROUP"),
+            Self::StreamReadGroup => write!(f, "XREADGROUP"),
+            Self::StreamAck => write!(f, "XACK"),
+            Self::StreamPending => write!(f, "XPENDING"),
+            Self::StreamClaim => write!(f, "XCLAIM"),
+            Self::GeoAdd => write!(f, "GEOADD"),
+            Self::GeoPosition => write!(f, "GEOPOS"),
+            Self::GeoDistance => write!(f, "GEODIST"),
+            Self::GeoSearch => write!(f, "GEOSEARCH"),
+            Self::JsonSet => write!(f, "JSON.SET"),
+            Self::JsonGet => write!(f, "JSON.GET"),
+            Self::JsonDelete => write!(f, "JSON.DEL"),
+            Self::JsonArrAppend => write!(f, "JSON.ARRAPPEND"),
+            Self::JsonNumIncrBy => write!(f, "JSON.NUMINCRBY"),
+            Self::StringAppend => write!(f, "APPEND"),
+            Self::StringGetRange => write!(f, "GETRANGE"),
+            Self::StringSetRange => write!(f, "SETRANGE"),
+            Self::StringLength => write!(f, "STRLEN"),
+            Self::StringSetNx => write!(f, "SETNX"),
+            Self::StringGetDelete => write!(f, "GETDEL"),
+            Self::StringMultiGet => write!(f, "MGET"),
+            Self::StringMultiSet => write!(f, "MSET"),
+            Self::StringMultiSetNx => write!(f, "MSETNX"),
+            Self::Select => write!(f, "SELECT"),
+            Self::SwapDb => write!(f, "SWAPDB"),
+            Self::Move => write!(f, "MOVE"),
+            Self::FlushDb => write!(f, "FLUSHDB"),
+            Self::FlushAll => write!(f, "FLUSHALL"),
             Self::Error => write!(f, "ERR"),
             Self::Noop => write!(f, "NOOP"),
+            Self::Nil => write!(f, "NIL"),
             Self::Dump => write!(f, "DUMP"),
+            Self::Save => write!(f, "SAVE"),
+            Self::BackgroundSave => write!(f, "BGSAVE"),
+            Self::LastSave => write!(f, "LASTSAVE"),
         }
     }
 }
@@ -86,47 +682,380 @@ impl Message {
     ///
     /// # Validation Parameters
     ///
-    /// * [`Operation::StringSet`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** values)
+    /// * [`Operation::StringSet`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** values),
+    ///   optionally followed by `EX seconds` or `PX milliseconds`
     /// * [`Operation::StringGet`] - Should have **ONE** argument (a key)
     /// * [`Operation::StringRemove`] - Should have **ONE** argument (a key)
     /// * [`Operation::Incr`] - Should have **ONE** argument (a key)
     /// * [`Operation::Decr`] - Should have **ONE** argument (a key)
+    /// * [`Operation::IncrBy`] - Should have **TWO** arguments (a key and an increment)
+    /// * [`Operation::DecrBy`] - Should have **TWO** arguments (a key and a decrement)
+    /// * [`Operation::IncrByFloat`] - Should have **TWO** arguments (a key and an increment)
+    /// * [`Operation::GetSet`] - Should have **TWO** arguments (a key and a value)
+    /// * [`Operation::ListLeftPush`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** values)
+    /// * [`Operation::ListRightPush`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** values)
+    /// * [`Operation::ListLeftPop`] - Should have **ONE** argument (a key)
+    /// * [`Operation::ListRightPop`] - Should have **ONE** argument (a key)
+    /// * [`Operation::ListLength`] - Should have **ONE** argument (a key)
+    /// * [`Operation::ListIndex`] - Should have **TWO** arguments (a key and an index)
+    /// * [`Operation::ListRange`] - Should have **THREE** arguments (a key, a start and a stop index)
+    /// * [`Operation::ListTrim`] - Should have **THREE** arguments (a key, a start and a stop index)
+    /// * [`Operation::ListSet`] - Should have **AT LEAST THREE** arguments (a key, an index and a value)
+    /// * [`Operation::ListRemove`] - Should have **AT LEAST THREE** arguments (a key, a count and a value)
+    /// * [`Operation::HashSet`] - Should have a key followed by **ONE OR MORE** field-value pairs
+    /// * [`Operation::HashGet`] - Should have **TWO** arguments (a key and a field)
+    /// * [`Operation::HashExists`] - Should have **TWO** arguments (a key and a field)
+    /// * [`Operation::HashDelete`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** fields)
+    /// * [`Operation::HashGetAll`] - Should have **ONE** argument (a key)
+    /// * [`Operation::HashKeys`] - Should have **ONE** argument (a key)
+    /// * [`Operation::HashValues`] - Should have **ONE** argument (a key)
+    /// * [`Operation::HashLength`] - Should have **ONE** argument (a key)
+    /// * [`Operation::HashIncrBy`] - Should have **THREE** arguments (a key, a field and an increment)
+    /// * [`Operation::SetAdd`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** members)
+    /// * [`Operation::SetRemove`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** members)
+    /// * [`Operation::SetIsMember`] - Should have **TWO** arguments (a key and a member)
+    /// * [`Operation::SetMembers`] - Should have **ONE** argument (a key)
+    /// * [`Operation::SetCardinality`] - Should have **ONE** argument (a key)
+    /// * [`Operation::SetUnion`] - Should have **AT LEAST ONE** argument (the keys)
+    /// * [`Operation::SetIntersection`] - Should have **AT LEAST ONE** argument (the keys)
+    /// * [`Operation::SetDifference`] - Should have **AT LEAST ONE** argument (the keys)
+    /// * [`Operation::SetUnionStore`] - Should have **AT LEAST TWO** arguments (a destination and **ONE OR MORE** keys)
+    /// * [`Operation::SetIntersectionStore`] - Should have **AT LEAST TWO** arguments (a destination and **ONE OR MORE** keys)
+    /// * [`Operation::SetDifferenceStore`] - Should have **AT LEAST TWO** arguments (a destination and **ONE OR MORE** keys)
+    /// * [`Operation::SortedSetAdd`] - Should have a key followed by **ONE OR MORE** score-member pairs
+    /// * [`Operation::SortedSetRemove`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** members)
+    /// * [`Operation::SortedSetScore`] - Should have **TWO** arguments (a key and a member)
+    /// * [`Operation::SortedSetRank`] - Should have **TWO** arguments (a key and a member)
+    /// * [`Operation::SortedSetIncrBy`] - Should have **THREE** arguments (a key, an increment and a member)
+    /// * [`Operation::SortedSetRange`] - Should have **THREE** arguments (a key, a start and a stop index)
+    /// * [`Operation::SortedSetReverseRange`] - Should have **THREE** arguments (a key, a start and a stop index)
+    /// * [`Operation::SortedSetRangeByScore`] - Should have **THREE** arguments (a key, a min and a max score)
+    /// * [`Operation::SortedSetCount`] - Should have **THREE** arguments (a key, a min and a max score)
+    /// * [`Operation::Expire`] - Should have **TWO** arguments (a key and a number of seconds)
+    /// * [`Operation::PExpire`] - Should have **TWO** arguments (a key and a number of milliseconds)
+    /// * [`Operation::Ttl`] - Should have **ONE** argument (a key)
+    /// * [`Operation::PTtl`] - Should have **ONE** argument (a key)
+    /// * [`Operation::Persist`] - Should have **ONE** argument (a key)
+    /// * [`Operation::TypedSet`] - Should have **AT LEAST THREE** arguments (a key, a type name and a value)
+    /// * [`Operation::TypedGet`] - Should have **TWO** arguments (a key and a type name)
+    /// * [`Operation::Exists`] - Should have **AT LEAST ONE** argument (the keys)
+    /// * [`Operation::Delete`] - Should have **AT LEAST ONE** argument (the keys)
+    /// * [`Operation::Type`] - Should have **ONE** argument (a key)
+    /// * [`Operation::Rename`] - Should have **TWO** arguments (a key and a new key)
+    /// * [`Operation::RenameNx`] - Should have **TWO** arguments (a key and a new key)
+    /// * [`Operation::Keys`] - Should have **ONE** argument (a pattern)
+    /// * [`Operation::Scan`] - Should have **ONE** argument (a cursor) followed by option-value pairs
+    /// * [`Operation::HashScan`] - Should have **TWO** arguments (a key and a cursor) followed by option-value pairs
+    /// * [`Operation::SetScan`] - Should have **TWO** arguments (a key and a cursor) followed by option-value pairs
+    /// * [`Operation::SetBit`] - Should have **THREE** arguments (a key, an offset and a bit)
+    /// * [`Operation::GetBit`] - Should have **TWO** arguments (a key and an offset)
+    /// * [`Operation::BitCount`] - Should have **ONE** argument (a key) or **THREE** arguments (a key, a start and an end)
+    /// * [`Operation::BitPos`] - Should have **TWO TO FOUR** arguments (a key, a bit and an optional start and end)
+    /// * [`Operation::BitOp`] - Should have **AT LEAST THREE** arguments (an operation, a destination and the keys)
+    /// * [`Operation::BitField`] - Should have **AT LEAST ONE** argument (a key followed by the subcommands)
+    /// * [`Operation::PfAdd`] - Should have **AT LEAST ONE** argument (a key followed by the elements)
+    /// * [`Operation::PfCount`] - Should have **AT LEAST ONE** argument (the keys)
+    /// * [`Operation::PfMerge`] - Should have **AT LEAST ONE** argument (a destination followed by the source keys)
+    /// * [`Operation::StreamAdd`] - Should have a key and an ID followed by **ONE OR MORE** field-value pairs
+    /// * [`Operation::StreamRange`] - Should have **THREE** arguments (a key, a start and an end) and an optional count
+    /// * [`Operation::StreamReverseRange`] - Should have **THREE** arguments (a key, an end and a start) and an optional count
+    /// * [`Operation::StreamLength`] - Should have **ONE** argument (a key)
+    /// * [`Operation::StreamTrim`] - Should have **THREE** arguments (a key, a strategy and a threshold)
+    /// * [`Operation::StreamGroup`] - Should have **AT LEAST THREE** arguments (a subcommand, a key and a group)
+    /// * [`Operation::StreamReadGroup`] - Should have **FOUR** arguments (a key, a group, a consumer and an ID) and an optional count
+    /// * [`Operation::StreamAck`] - Should have **AT LEAST THREE** arguments (a key, a group and the IDs)
+    /// * [`Operation::StreamPending`] - Should have **TWO** arguments (a key and a group) and an optional start, end, count and consumer
+    /// * [`Operation::StreamClaim`] - Should have **AT LEAST FIVE** arguments (a key, a group, a consumer, a minimum idle time and the IDs)
+    /// * [`Operation::GeoAdd`] - Should have a key followed by **ONE OR MORE** longitude-latitude-member triples
+    /// * [`Operation::GeoPosition`] - Should have **AT LEAST TWO** arguments (**ONE** key and **ONE OR MORE** members)
+    /// * [`Operation::GeoDistance`] - Should have **THREE** arguments (a key and two members) and an optional unit
+    /// * [`Operation::GeoSearch`] - Should have **AT LEAST FIVE** arguments (a key, an origin and an area)
+    /// * [`Operation::StringAppend`] - Should have **AT LEAST TWO** arguments (a key and a value)
+    /// * [`Operation::StringGetRange`] - Should have **THREE** arguments (a key, a start and an end)
+    /// * [`Operation::StringSetRange`] - Should have **AT LEAST THREE** arguments (a key, an offset and a value)
+    /// * [`Operation::StringLength`] - Should have **ONE** argument (a key)
+    /// * [`Operation::StringSetNx`] - Should have **AT LEAST TWO** arguments (a key and a value)
+    /// * [`Operation::StringGetDelete`] - Should have **ONE** argument (a key)
+    /// * [`Operation::StringMultiGet`] - Should have **ONE OR MORE** arguments (the keys)
+    /// * [`Operation::StringMultiSet`] - Should have **ONE OR MORE** key-value pairs
+    /// * [`Operation::StringMultiSetNx`] - Should have **ONE OR MORE** key-value pairs
+    /// * [`Operation::JsonSet`] - Should have **AT LEAST THREE** arguments (a key, a path and a value)
+    /// * [`Operation::JsonGet`] - Should have **ONE** argument (a key) and an optional path
+    /// * [`Operation::JsonDelete`] - Should have **ONE** argument (a key) and an optional path
+    /// * [`Operation::JsonArrAppend`] - Should have **AT LEAST THREE** arguments (a key, a path and **ONE OR MORE** values)
+    /// * [`Operation::JsonNumIncrBy`] - Should have **THREE** arguments (a key, a path and an increment)
     /// * [`Operation::Dump`] - Should have **ONE** argument (a path)
+    /// * [`Operation::Save`] - No validation required
+    /// * [`Operation::BackgroundSave`] - No validation required
+    /// * [`Operation::LastSave`] - No validation required
+    /// * [`Operation::Select`] - Should have **ONE** argument (a database index)
+    /// * [`Operation::SwapDb`] - Should have **TWO** arguments (two database indices)
+    /// * [`Operation::Move`] - Should have **TWO** arguments (a key and a database index)
+    /// * [`Operation::FlushDb`] - No validation required
+    /// * [`Operation::FlushAll`] - No validation required
     /// * [`Operation::StringClear`] - No validation required
     /// * [`Operation::Noop`] - No validation required
     pub fn validate(&self) -> bool {
-        let mut valid = false;
-
         match self.op {
             // Should have AT LEAST TWO entries - ONE key and ONE OR MORE values
-            Operation::StringSet => {
-                if self.args.len() >= 2 {
-                    valid = true;
-                }
+            Operation::StringSet
+            | Operation::ListLeftPush
+            | Operation::ListRightPush
+            | Operation::HashDelete
+            | Operation::SetAdd
+            | Operation::SetRemove
+            | Operation::SetUnionStore
+            | Operation::SetIntersectionStore
+            | Operation::SetDifferenceStore
+            | Operation::SortedSetRemove
+            | Operation::GeoPosition
+            | Operation::StringAppend
+            | Operation::StringSetNx => self.args.len() >= 2,
+            // Should have ONE OR MORE entries - the keys
+            Operation::SetUnion
+            | Operation::SetIntersection
+            | Operation::SetDifference
+            | Operation::Exists
+            | Operation::Delete
+            | Operation::BitField
+            | Operation::PfAdd
+            | Operation::PfCount
+            | Operation::PfMerge
+            | Operation::StringMultiGet => !self.args.is_empty(),
+            // Should have ONE key followed by ONE OR MORE field-value / score-member pairs
+            Operation::HashSet | Operation::SortedSetAdd => {
+                self.args.len() >= 3 && self.args.len() % 2 == 1
+            }
+            // Should have ONE OR MORE key-value pairs
+            Operation::StringMultiSet | Operation::StringMultiSetNx => {
+                !self.args.is_empty() && self.args.len().is_multiple_of(2)
             }
             // Should have ONE entry - a key
             Operation::StringGet
             | Operation::StringRemove
             | Operation::Dump
             | Operation::Incr
-            | Operation::Decr => {
-                if self.args.len() == 1 {
-                    valid = true;
-                }
+            | Operation::Decr
+            | Operation::ListLeftPop
+            | Operation::ListRightPop
+            | Operation::ListLength
+            | Operation::HashGetAll
+            | Operation::HashKeys
+            | Operation::HashValues
+            | Operation::HashLength
+            | Operation::SetMembers
+            | Operation::SetCardinality
+            | Operation::Ttl
+            | Operation::PTtl
+            | Operation::Persist
+            | Operation::Type
+            | Operation::Keys
+            | Operation::StreamLength
+            | Operation::StringLength
+            | Operation::StringGetDelete
+            | Operation::Select => self.args.len() == 1,
+            // Should have TWO entries - a key and an index / field / member
+            Operation::ListIndex
+            | Operation::HashGet
+            | Operation::HashExists
+            | Operation::SetIsMember
+            | Operation::SortedSetScore
+            | Operation::SortedSetRank
+            | Operation::Expire
+            | Operation::PExpire
+            | Operation::TypedGet
+            | Operation::IncrBy
+            | Operation::DecrBy
+            | Operation::IncrByFloat
+            | Operation::GetSet
+            | Operation::Rename
+            | Operation::RenameNx
+            | Operation::GetBit
+            | Operation::SwapDb
+            | Operation::Move => self.args.len() == 2,
+            // Should have THREE entries - a key, a start and a stop / a key, a field and an increment
+            Operation::ListRange
+            | Operation::ListTrim
+            | Operation::HashIncrBy
+            | Operation::SortedSetIncrBy
+            | Operation::SortedSetRange
+            | Operation::SortedSetReverseRange
+            | Operation::SortedSetRangeByScore
+            | Operation::SortedSetCount
+            | Operation::SetBit
+            | Operation::StreamTrim
+            | Operation::JsonNumIncrBy
+            | Operation::StringGetRange => self.args.len() == 3,
+            // Should have AT LEAST THREE entries - a key, an index / count and a value
+            Operation::ListSet
+            | Operation::ListRemove
+            | Operation::TypedSet
+            | Operation::BitOp
+            | Operation::StreamGroup
+            | Operation::StreamAck
+            | Operation::JsonSet
+            | Operation::JsonArrAppend
+            | Operation::StringSetRange => self.args.len() >= 3,
+            // Should have a key and OPTIONALLY a start and an end
+            Operation::BitCount => self.args.len() == 1 || self.args.len() == 3,
+            // Should have a key and OPTIONALLY a path
+            Operation::JsonGet | Operation::JsonDelete => {
+                self.args.len() == 1 || self.args.len() == 2
             }
-            Operation::StringClear | Operation::Noop => valid = true,
-            _ => {}
+            // Should have a key, a bit and OPTIONALLY a start and an end
+            Operation::BitPos => (2..=4).contains(&self.args.len()),
+            // Should have a cursor followed by ZERO OR MORE option-value pairs
+            Operation::Scan => self.args.len() % 2 == 1,
+            // Should have a key and a cursor followed by ZERO OR MORE option-value pairs
+            Operation::HashScan | Operation::SetScan => {
+                self.args.len() >= 2 && self.args.len().is_multiple_of(2)
+            }
+            // Should have a key followed by ONE OR MORE longitude-latitude-member triples
+            Operation::GeoAdd => self.args.len() >= 4 && (self.args.len() - 1).is_multiple_of(3),
+            // Should have a key and two members and OPTIONALLY a unit
+            Operation::GeoDistance => self.args.len() == 3 || self.args.len() == 4,
+            // Should have a key, an origin and an area followed by ZERO OR MORE options
+            Operation::GeoSearch => self.args.len() >= 5,
+            // Should have a key and an ID followed by ONE OR MORE field-value pairs
+            Operation::StreamAdd => self.args.len() >= 4 && self.args.len().is_multiple_of(2),
+            // Should have a key, a start and an end and OPTIONALLY a count
+            Operation::StreamRange | Operation::StreamReverseRange => {
+                self.args.len() == 3 || self.args.len() == 5
+            }
+            // Should have a key, a group, a consumer and an ID and OPTIONALLY a count
+            Operation::StreamReadGroup => self.args.len() == 4 || self.args.len() == 6,
+            // Should have a key and a group and OPTIONALLY a start, an end, a count and a consumer
+            Operation::StreamPending => [2, 5, 6].contains(&self.args.len()),
+            // Should have a key, a group, a consumer and a minimum idle time followed by the IDs
+            Operation::StreamClaim => self.args.len() >= 5,
+            Operation::StringClear
+            | Operation::FlushDb
+            | Operation::FlushAll
+            | Operation::Save
+            | Operation::BackgroundSave
+            | Operation::LastSave
+            | Operation::Noop => true,
+            _ => false,
         }
-
-        valid
     }
 }
 
 /// Create a request string from an [`Operation`] and an array of [`String`]
+///
+/// Arguments are separated by spaces, so any whitespace or backslashes within an argument
+/// are escaped with [`escape_arg`] to keep it as a single argument.
+///
+/// # Example
+///
+/// ```
+/// use rubin::net::parser::{create_request, parse_request, Operation};
+///
+/// let args = vec!["user:1000".to_string(), "John Q Smith".to_string()];
+/// let request = create_request(Operation::StringSet, args.clone());
+///
+/// assert_eq!(request, r"SET::user:1000 John\sQ\sSmith");
+/// assert_eq!(parse_request(&request).unwrap().args, args);
+/// ```
 pub fn create_request(op_code: Operation, args: Vec<String>) -> String {
+    let args = args.iter().map(|arg| escape_arg(arg)).collect::<Vec<_>>();
     format!("{}::{}", op_code, args.join(" "))
 }
 
+/// Escapes the separators of a request within an argument
+///
+/// Backslashes are doubled and spaces, tabs, carriage returns and newlines are written as
+/// `\s`, `\t`, `\r` and `\n` respectively.
+pub fn escape_arg(arg: &str) -> String {
+    let mut escaped = String::with_capacity(arg.len());
+    for c in arg.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ' ' => escaped.push_str("\\s"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Reverses the escaping of an argument by [`escape_arg`]
+///
+/// Backslashes which do not start a known escape are kept as is, so requests written by hand
+/// without escaping are still read as they were sent.
+pub fn unescape_arg(arg: &str) -> String {
+    if !arg.contains('\\') {
+        return arg.to_string();
+    }
+
+    let mut unescaped = String::with_capacity(arg.len());
+    let mut chars = arg.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        let escaped = match chars.peek() {
+            Some('\\') => '\\',
+            Some('s') => ' ',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('n') => '\n',
+            _ => {
+                unescaped.push(c);
+                continue;
+            }
+        };
+
+        chars.next();
+        unescaped.push(escaped);
+    }
+
+    unescaped
+}
+
+/// Prefixes a request with the database it should be performed against
+///
+/// Requests against database `0` are left unchanged.
+///
+/// # Example
+///
+/// ```
+/// use rubin::net::parser::{create_request, select_database, Operation};
+///
+/// let request = create_request(Operation::StringGet, vec!["user:1000".to_string()]);
+///
+/// assert_eq!(select_database(0, &request), "GET::user:1000");
+/// assert_eq!(select_database(3, &request), "@3 GET::user:1000");
+/// ```
+pub fn select_database(db: usize, request: &str) -> String {
+    match db {
+        0 => request.to_string(),
+        db => format!("@{} {}", db, request),
+    }
+}
+
+/// Splits the database prefix added by [`select_database`] off a request
+///
+/// Returns database `0` if the request has no prefix.
+///
+/// # Errors
+///
+/// * [`MessageError::InvalidFormat`] - The prefix is not a valid database index
+pub fn split_database(req: &str) -> Result<(usize, &str), MessageError> {
+    let Some(prefixed) = req.strip_prefix('@') else {
+        return Ok((0, req));
+    };
+
+    prefixed
+        .split_once(' ')
+        .and_then(|(db, req)| Some((db.parse().ok()?, req)))
+        .ok_or_else(|| MessageError::InvalidFormat(format!("invalid database: {}", req)))
+}
+
 /// Parse a request, extracting out the [`Operation`] and arguments.
 ///
 /// These are then used to construct a [`Message`]
@@ -136,26 +1065,24 @@ pub fn create_request(op_code: Operation, args: Vec<String>) -> String {
 /// * [`MessageError::InvalidFormat`] - The format of the message is incorrect
 /// * [`MessageError::InvalidMessage`] - Message failed the validation checks
 pub fn parse_request(req: &str) -> Result<Message, MessageError> {
-    let r_split = req
-        .split("::")
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
+    // Only the first delimiter separates the operation, arguments may contain it
+    let (code, args) = match req.split_once("::") {
+        Some((code, args)) => (code, Some(args)),
+        None => (req, None),
+    };
 
-    let op = Operation::from_string(&r_split[0]);
+    let op = Operation::from_string(code);
     if op == Operation::Error {
-        let err = format!("invalid operation: {}", &r_split[0]);
+        let err = format!("invalid operation: {}", code);
         return Err(MessageError::InvalidFormat(err));
     }
 
-    if r_split.len() < 2 {
+    let Some(args) = args else {
         let error = MessageError::InvalidFormat("not enough arguments for operation".to_string());
         return Err(error);
-    }
+    };
 
-    let args = r_split[1]
-        .split(' ')
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
+    let args = args.split(' ').map(unescape_arg).collect::<Vec<String>>();
 
     let msg = Message { op, args };
 
@@ -170,7 +1097,7 @@ pub fn parse_request(req: &str) -> Result<Message, MessageError> {
 
 /// Parse a string to extract out the response value
 ///
-/// Trims the message based on the `::` delimiter
+/// Trims the message based on the first `::` delimiter
 ///
 /// # Example
 ///
@@ -183,12 +1110,39 @@ pub fn parse_request(req: &str) -> Result<Message, MessageError> {
 /// assert_eq!(&response, "value");
 /// ```
 pub fn parse_response(msg: &str) -> String {
-    let resp = msg.split("::").collect::<Vec<&str>>();
-    if resp.len() < 2 {
-        return String::from("");
+    match msg.split_once("::") {
+        Some((_, value)) => value.trim().to_string(),
+        None => String::from(""),
     }
+}
 
-    return resp[1].trim().to_string();
+/// Parse a response which may not contain a value, extracting the value if present
+///
+/// Responses tagged with [`Operation::Nil`] denote that the value is not present and
+/// are returned as `None`.
+///
+/// # Errors
+///
+/// * [`io::ErrorKind::Other`] holding the message of a response tagged with
+///   [`Operation::Error`], sent when the operation failed
+///
+/// ```rust
+/// use rubin::net::parser::parse_optional_response;
+///
+/// assert_eq!(parse_optional_response("GET::value").unwrap().as_deref(), Some("value"));
+/// assert_eq!(parse_optional_response("GET::").unwrap().as_deref(), Some(""));
+/// assert_eq!(parse_optional_response("NIL::").unwrap(), None);
+/// assert!(parse_optional_response("ERR::invalid argument: one").is_err());
+/// ```
+pub fn parse_optional_response(msg: &str) -> io::Result<Option<String>> {
+    match msg.split_once("::") {
+        Some((op, _)) if Operation::from_string(op) == Operation::Nil => Ok(None),
+        // Unknown tags also parse as `Operation::Error`, so the tag is compared directly
+        Some((op, _)) if op == Operation::Error.to_string() => {
+            Err(io::Error::other(parse_response(msg)))
+        }
+        _ => Ok(Some(parse_response(msg))),
+    }
 }
 
 #[cfg(test)]
@@ -206,6 +1160,111 @@ mod tests {
             "INCR",
             "DECR",
             "DUMP",
+            "LPUSH",
+            "RPUSH",
+            "LPOP",
+            "RPOP",
+            "LRANGE",
+            "LLEN",
+            "LINDEX",
+            "LSET",
+            "LTRIM",
+            "LREM",
+            "HSET",
+            "HGET",
+            "HDEL",
+            "HGETALL",
+            "HKEYS",
+            "HVALS",
+            "HLEN",
+            "HEXISTS",
+            "HINCRBY",
+            "SADD",
+            "SREM",
+            "SISMEMBER",
+            "SMEMBERS",
+            "SCARD",
+            "SUNION",
+            "SINTER",
+            "SDIFF",
+            "SUNIONSTORE",
+            "SINTERSTORE",
+            "SDIFFSTORE",
+            "ZADD",
+            "ZREM",
+            "ZSCORE",
+            "ZINCRBY",
+            "ZRANK",
+            "ZRANGE",
+            "ZRANGEBYSCORE",
+            "ZREVRANGE",
+            "ZCOUNT",
+            "EXPIRE",
+            "PEXPIRE",
+            "TTL",
+            "PTTL",
+            "PERSIST",
+            "NIL",
+            "TSET",
+            "TGET",
+            "INCRBY",
+            "DECRBY",
+            "INCRBYFLOAT",
+            "GETSET",
+            "EXISTS",
+            "DEL",
+            "TYPE",
+            "RENAME",
+            "RENAMENX",
+            "KEYS",
+            "SCAN",
+            "HSCAN",
+            "SSCAN",
+            "SETBIT",
+            "GETBIT",
+            "BITCOUNT",
+            "BITPOS",
+            "BITOP",
+            "BITFIELD",
+            "PFADD",
+            "PFCOUNT",
+            "PFMERGE",
+            "XADD",
+            "XRANGE",
+            "XREVRANGE",
+            "XLEN",
+            "XTRIM",
+            "XGROUP",
+            "XREADGROUP",
+            "XACK",
+            "XPENDING",
+            "XCLAIM",
+            "GEOADD",
+            "GEOPOS",
+            "GEODIST",
+            "GEOSEARCH",
+            "JSON.SET",
+            "JSON.GET",
+            "JSON.DEL",
+            "JSON.ARRAPPEND",
+            "JSON.NUMINCRBY",
+            "APPEND",
+            "GETRANGE",
+            "SETRANGE",
+            "STRLEN",
+            "SETNX",
+            "GETDEL",
+            "MGET",
+            "MSET",
+            "MSETNX",
+            "SELECT",
+            "SWAPDB",
+            "MOVE",
+            "FLUSHDB",
+            "FLUSHALL",
+            "SAVE",
+            "BGSAVE",
+            "LASTSAVE",
             "SOMETHING",
         ];
         for op in op_codes {
@@ -220,6 +1279,111 @@ mod tests {
                 "DECR" => assert!(code == Operation::Decr),
                 "NOOP" => assert!(code == Operation::Noop),
                 "DUMP" => assert!(code == Operation::Dump),
+                "LPUSH" => assert!(code == Operation::ListLeftPush),
+                "RPUSH" => assert!(code == Operation::ListRightPush),
+                "LPOP" => assert!(code == Operation::ListLeftPop),
+                "RPOP" => assert!(code == Operation::ListRightPop),
+                "LRANGE" => assert!(code == Operation::ListRange),
+                "LLEN" => assert!(code == Operation::ListLength),
+                "LINDEX" => assert!(code == Operation::ListIndex),
+                "LSET" => assert!(code == Operation::ListSet),
+                "LTRIM" => assert!(code == Operation::ListTrim),
+                "LREM" => assert!(code == Operation::ListRemove),
+                "HSET" => assert!(code == Operation::HashSet),
+                "HGET" => assert!(code == Operation::HashGet),
+                "HDEL" => assert!(code == Operation::HashDelete),
+                "HGETALL" => assert!(code == Operation::HashGetAll),
+                "HKEYS" => assert!(code == Operation::HashKeys),
+                "HVALS" => assert!(code == Operation::HashValues),
+                "HLEN" => assert!(code == Operation::HashLength),
+                "HEXISTS" => assert!(code == Operation::HashExists),
+                "HINCRBY" => assert!(code == Operation::HashIncrBy),
+                "SADD" => assert!(code == Operation::SetAdd),
+                "SREM" => assert!(code == Operation::SetRemove),
+                "SISMEMBER" => assert!(code == Operation::SetIsMember),
+                "SMEMBERS" => assert!(code == Operation::SetMembers),
+                "SCARD" => assert!(code == Operation::SetCardinality),
+                "SUNION" => assert!(code == Operation::SetUnion),
+                "SINTER" => assert!(code == Operation::SetIntersection),
+                "SDIFF" => assert!(code == Operation::SetDifference),
+                "SUNIONSTORE" => assert!(code == Operation::SetUnionStore),
+                "SINTERSTORE" => assert!(code == Operation::SetIntersectionStore),
+                "SDIFFSTORE" => assert!(code == Operation::SetDifferenceStore),
+                "ZADD" => assert!(code == Operation::SortedSetAdd),
+                "ZREM" => assert!(code == Operation::SortedSetRemove),
+                "ZSCORE" => assert!(code == Operation::SortedSetScore),
+                "ZINCRBY" => assert!(code == Operation::SortedSetIncrBy),
+                "ZRANK" => assert!(code == Operation::SortedSetRank),
+                "ZRANGE" => assert!(code == Operation::SortedSetRange),
+                "ZRANGEBYSCORE" => assert!(code == Operation::SortedSetRangeByScore),
+                "ZREVRANGE" => assert!(code == Operation::SortedSetReverseRange),
+                "ZCOUNT" => assert!(code == Operation::SortedSetCount),
+                "EXPIRE" => assert!(code == Operation::Expire),
+                "PEXPIRE" => assert!(code == Operation::PExpire),
+                "TTL" => assert!(code == Operation::Ttl),
+                "PTTL" => assert!(code == Operation::PTtl),
+                "PERSIST" => assert!(code == Operation::Persist),
+                "NIL" => assert!(code == Operation::Nil),
+                "TSET" => assert!(code == Operation::TypedSet),
+                "TGET" => assert!(code == Operation::TypedGet),
+                "INCRBY" => assert!(code == Operation::IncrBy),
+                "DECRBY" => assert!(code == Operation::DecrBy),
+                "INCRBYFLOAT" => assert!(code == Operation::IncrByFloat),
+                "GETSET" => assert!(code == Operation::GetSet),
+                "EXISTS" => assert!(code == Operation::Exists),
+                "DEL" => assert!(code == Operation::Delete),
+                "TYPE" => assert!(code == Operation::Type),
+                "RENAME" => assert!(code == Operation::Rename),
+                "RENAMENX" => assert!(code == Operation::RenameNx),
+                "KEYS" => assert!(code == Operation::Keys),
+                "SCAN" => assert!(code == Operation::Scan),
+                "HSCAN" => assert!(code == Operation::HashScan),
+                "SSCAN" => assert!(code == Operation::SetScan),
+                "SETBIT" => assert!(code == Operation::SetBit),
+                "GETBIT" => assert!(code == Operation::GetBit),
+                "BITCOUNT" => assert!(code == Operation::BitCount),
+                "BITPOS" => assert!(code == Operation::BitPos),
+                "BITOP" => assert!(code == Operation::BitOp),
+                "BITFIELD" => assert!(code == Operation::BitField),
+                "PFADD" => assert!(code == Operation::PfAdd),
+                "PFCOUNT" => assert!(code == Operation::PfCount),
+                "PFMERGE" => assert!(code == Operation::PfMerge),
+                "XADD" => assert!(code == Operation::StreamAdd),
+                "XRANGE" => assert!(code == Operation::StreamRange),
+                "XREVRANGE" => assert!(code == Operation::StreamReverseRange),
+                "XLEN" => assert!(code == Operation::StreamLength),
+                "XTRIM" => assert!(code == Operation::StreamTrim),
+                "XGROUP" => assert!(code == Operation::StreamGroup),
+                "XREADGROUP" => assert!(code == Operation::StreamReadGroup),
+                "XACK" => assert!(code == Operation::StreamAck),
+                "XPENDING" => assert!(code == Operation::StreamPending),
+                "XCLAIM" => assert!(code == Operation::StreamClaim),
+                "GEOADD" => assert!(code == Operation::GeoAdd),
+                "GEOPOS" => assert!(code == Operation::GeoPosition),
+                "GEODIST" => assert!(code == Operation::GeoDistance),
+                "GEOSEARCH" => assert!(code == Operation::GeoSearch),
+                "JSON.SET" => assert!(code == Operation::JsonSet),
+                "JSON.GET" => assert!(code == Operation::JsonGet),
+                "JSON.DEL" => assert!(code == Operation::JsonDelete),
+                "JSON.ARRAPPEND" => assert!(code == Operation::JsonArrAppend),
+                "JSON.NUMINCRBY" => assert!(code == Operation::JsonNumIncrBy),
+                "APPEND" => assert!(code == Operation::StringAppend),
+                "GETRANGE" => assert!(code == Operation::StringGetRange),
+                "SETRANGE" => assert!(code == Operation::StringSetRange),
+                "STRLEN" => assert!(code == Operation::StringLength),
+                "SETNX" => assert!(code == Operation::StringSetNx),
+                "GETDEL" => assert!(code == Operation::StringGetDelete),
+                "MGET" => assert!(code == Operation::StringMultiGet),
+                "MSET" => assert!(code == Operation::StringMultiSet),
+                "MSETNX" => assert!(code == Operation::StringMultiSetNx),
+                "SELECT" => assert!(code == Operation::Select),
+                "SWAPDB" => assert!(code == Operation::SwapDb),
+                "MOVE" => assert!(code == Operation::Move),
+                "FLUSHDB" => assert!(code == Operation::FlushDb),
+                "FLUSHALL" => assert!(code == Operation::FlushAll),
+                "SAVE" => assert!(code == Operation::Save),
+                "BGSAVE" => assert!(code == Operation::BackgroundSave),
+                "LASTSAVE" => assert!(code == Operation::LastSave),
                 _ => assert!(code == Operation::Error),
             }
         }
@@ -294,6 +1458,25 @@ mod tests {
         assert!(m.validate());
     }
 
+    #[test]
+    fn validate_counter_arithmetic() {
+        for op in [
+            Operation::IncrBy,
+            Operation::DecrBy,
+            Operation::IncrByFloat,
+            Operation::GetSet,
+        ] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string(), "10".to_string()],
+            };
+            assert!(m.validate());
+
+            m.args.pop();
+            assert!(!m.validate());
+        }
+    }
+
     #[test]
     fn validation_noop_message() {
         let m = Message {
@@ -314,6 +1497,397 @@ mod tests {
         assert!(m.validate());
     }
 
+    #[test]
+    fn classifies_updates() {
+        for op in ["SET", "LPOP", "XREADGROUP", "FLUSHALL", "MOVE"] {
+            assert!(Operation::from_string(op).is_update(), "{}", op);
+        }
+
+        for op in ["GET", "LRANGE", "SCAN", "SELECT", "SAVE", "DUMP"] {
+            assert!(!Operation::from_string(op).is_update(), "{}", op);
+        }
+    }
+
+    #[test]
+    fn validation_save_messages() {
+        for op in [
+            Operation::Save,
+            Operation::BackgroundSave,
+            Operation::LastSave,
+        ] {
+            let m = Message { op, args: vec![] };
+            assert!(m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_list_push_messages() {
+        for op in [Operation::ListLeftPush, Operation::ListRightPush] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string(), "value1".to_string()],
+            };
+
+            assert!(m.validate());
+
+            m.args.push("value2".to_string());
+            assert!(m.validate());
+
+            m.args.truncate(1);
+            assert!(!m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_list_range_messages() {
+        for op in [Operation::ListRange, Operation::ListTrim] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string(), "0".to_string(), "-1".to_string()],
+            };
+
+            assert!(m.validate());
+
+            m.args.pop();
+            assert!(!m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_list_set_message() {
+        let mut m = Message {
+            op: Operation::ListSet,
+            args: vec!["key".to_string(), "0".to_string(), "value".to_string()],
+        };
+
+        assert!(m.validate());
+
+        m.args.pop();
+        assert!(!m.validate());
+    }
+
+    #[test]
+    fn validation_hash_set_message() {
+        let mut m = Message {
+            op: Operation::HashSet,
+            args: vec!["key".to_string(), "field".to_string(), "value".to_string()],
+        };
+
+        assert!(m.validate());
+
+        m.args.push("field2".to_string());
+        assert!(!m.validate());
+
+        m.args.push("value2".to_string());
+        assert!(m.validate());
+
+        m.args.truncate(2);
+        assert!(!m.validate());
+    }
+
+    #[test]
+    fn validation_hash_field_messages() {
+        for op in [Operation::HashGet, Operation::HashExists] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string(), "field".to_string()],
+            };
+
+            assert!(m.validate());
+
+            m.args.pop();
+            assert!(!m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_set_store_messages() {
+        for op in [
+            Operation::SetUnionStore,
+            Operation::SetIntersectionStore,
+            Operation::SetDifferenceStore,
+        ] {
+            let mut m = Message {
+                op,
+                args: vec!["dest".to_string(), "key".to_string()],
+            };
+
+            assert!(m.validate());
+
+            m.args.pop();
+            assert!(!m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_sorted_set_add_message() {
+        let mut m = Message {
+            op: Operation::SortedSetAdd,
+            args: vec!["key".to_string(), "1.5".to_string(), "member".to_string()],
+        };
+
+        assert!(m.validate());
+
+        m.args.push("2".to_string());
+        assert!(!m.validate());
+    }
+
+    #[test]
+    fn validation_expiry_messages() {
+        for op in [Operation::Expire, Operation::PExpire] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string(), "10".to_string()],
+            };
+
+            assert!(m.validate());
+
+            m.args.pop();
+            assert!(!m.validate());
+        }
+
+        for op in [Operation::Ttl, Operation::PTtl, Operation::Persist] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string()],
+            };
+
+            assert!(m.validate());
+
+            m.args.push("10".to_string());
+            assert!(!m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_keyspace_messages() {
+        for op in [Operation::Exists, Operation::Delete] {
+            let mut m = Message {
+                op,
+                args: vec!["key1".to_string(), "key2".to_string()],
+            };
+            assert!(m.validate());
+
+            m.args.clear();
+            assert!(!m.validate());
+        }
+
+        for op in [Operation::Type, Operation::Keys] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string()],
+            };
+            assert!(m.validate());
+
+            m.args.push("other".to_string());
+            assert!(!m.validate());
+        }
+
+        for op in [Operation::Rename, Operation::RenameNx] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string(), "new".to_string()],
+            };
+            assert!(m.validate());
+
+            m.args.pop();
+            assert!(!m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_scan_messages() {
+        let mut m = Message {
+            op: Operation::Scan,
+            args: vec!["0".to_string()],
+        };
+        assert!(m.validate());
+
+        m.args.push("MATCH".to_string());
+        assert!(!m.validate());
+
+        m.args.push("user:*".to_string());
+        assert!(m.validate());
+
+        m.args.clear();
+        assert!(!m.validate());
+
+        for op in [Operation::HashScan, Operation::SetScan] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string(), "0".to_string()],
+            };
+            assert!(m.validate());
+
+            m.args.push("COUNT".to_string());
+            assert!(!m.validate());
+
+            m.args.push("100".to_string());
+            assert!(m.validate());
+
+            m.args.truncate(1);
+            assert!(!m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_bitmap_messages() {
+        let args = |count: usize| (0..count).map(|idx| idx.to_string()).collect();
+        let cases = [
+            (Operation::SetBit, vec![3]),
+            (Operation::GetBit, vec![2]),
+            (Operation::BitCount, vec![1, 3]),
+            (Operation::BitPos, vec![2, 3, 4]),
+            (Operation::BitOp, vec![3, 4, 5]),
+            (Operation::BitField, vec![1, 2, 3, 4, 5]),
+        ];
+
+        for (op, valid) in cases {
+            for count in 0..6 {
+                let m = Message {
+                    op: op.clone(),
+                    args: args(count),
+                };
+                assert_eq!(m.validate(), valid.contains(&count), "{} {}", op, count);
+            }
+        }
+    }
+
+    #[test]
+    fn validation_hyperloglog_messages() {
+        for op in [Operation::PfAdd, Operation::PfCount, Operation::PfMerge] {
+            let mut m = Message {
+                op,
+                args: vec!["key".to_string(), "element".to_string()],
+            };
+            assert!(m.validate());
+
+            m.args.pop();
+            assert!(m.validate());
+
+            m.args.clear();
+            assert!(!m.validate());
+        }
+    }
+
+    #[test]
+    fn validation_geo_messages() {
+        let args = |count: usize| (0..count).map(|idx| idx.to_string()).collect();
+        let cases = [
+            (Operation::GeoAdd, vec![4, 7]),
+            (Operation::GeoPosition, vec![2, 3, 4, 5, 6, 7]),
+            (Operation::GeoDistance, vec![3, 4]),
+            (Operation::GeoSearch, vec![5, 6, 7]),
+        ];
+
+        for (op, valid) in cases {
+            for count in 0..8 {
+                let m = Message {
+                    op: op.clone(),
+                    args: args(count),
+                };
+                assert_eq!(m.validate(), valid.contains(&count), "{} {}", op, count);
+            }
+        }
+    }
+
+    #[test]
+    fn validation_string_messages() {
+        let args = |count: usize| (0..count).map(|idx| idx.to_string()).collect();
+        let cases = [
+            (Operation::StringAppend, vec![2, 3, 4, 5]),
+            (Operation::StringGetRange, vec![3]),
+            (Operation::StringSetRange, vec![3, 4, 5]),
+            (Operation::StringLength, vec![1]),
+            (Operation::StringSetNx, vec![2, 3, 4, 5]),
+            (Operation::StringGetDelete, vec![1]),
+            (Operation::StringMultiGet, vec![1, 2, 3, 4, 5]),
+            (Operation::StringMultiSet, vec![2, 4]),
+            (Operation::StringMultiSetNx, vec![2, 4]),
+        ];
+
+        for (op, valid) in cases {
+            for count in 0..6 {
+                let m = Message {
+                    op: op.clone(),
+                    args: args(count),
+                };
+                assert_eq!(m.validate(), valid.contains(&count), "{} {}", op, count);
+            }
+        }
+    }
+
+    #[test]
+    fn validation_json_messages() {
+        let args = |count: usize| (0..count).map(|idx| idx.to_string()).collect();
+        let cases = [
+            (Operation::JsonSet, vec![3, 4, 5]),
+            (Operation::JsonGet, vec![1, 2]),
+            (Operation::JsonDelete, vec![1, 2]),
+            (Operation::JsonArrAppend, vec![3, 4, 5]),
+            (Operation::JsonNumIncrBy, vec![3]),
+        ];
+
+        for (op, valid) in cases {
+            for count in 0..6 {
+                let m = Message {
+                    op: op.clone(),
+                    args: args(count),
+                };
+                assert_eq!(m.validate(), valid.contains(&count), "{} {}", op, count);
+            }
+        }
+    }
+
+    #[test]
+    fn validation_stream_messages() {
+        let args = |count: usize| (0..count).map(|idx| idx.to_string()).collect();
+        let cases = [
+            (Operation::StreamAdd, vec![4, 6]),
+            (Operation::StreamRange, vec![3, 5]),
+            (Operation::StreamReverseRange, vec![3, 5]),
+            (Operation::StreamLength, vec![1]),
+            (Operation::StreamTrim, vec![3]),
+            (Operation::StreamGroup, vec![3, 4, 5, 6]),
+            (Operation::StreamReadGroup, vec![4, 6]),
+            (Operation::StreamAck, vec![3, 4, 5, 6]),
+            (Operation::StreamPending, vec![2, 5, 6]),
+            (Operation::StreamClaim, vec![5, 6]),
+        ];
+
+        for (op, valid) in cases {
+            for count in 0..7 {
+                let m = Message {
+                    op: op.clone(),
+                    args: args(count),
+                };
+                assert_eq!(m.validate(), valid.contains(&count), "{} {}", op, count);
+            }
+        }
+    }
+
+    #[test]
+    fn validation_typed_messages() {
+        let mut m = Message {
+            op: Operation::TypedSet,
+            args: vec!["key".to_string(), "app::User".to_string()],
+        };
+        assert!(!m.validate());
+
+        m.args.push("{\"name\":".to_string());
+        m.args.push("\"rubin\"}".to_string());
+        assert!(m.validate());
+
+        let mut m = Message {
+            op: Operation::TypedGet,
+            args: vec!["key".to_string(), "app::User".to_string()],
+        };
+        assert!(m.validate());
+
+        m.args.pop();
+        assert!(!m.validate());
+    }
+
     #[test]
     fn create_appropriate_request() {
         let ops = vec![Operation::StringSet, Operation::StringGet];
@@ -342,6 +1916,40 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn parse_requests_containing_the_delimiter() {
+        let request = "TGET::user:1000 app::User";
+        let result = parse_request(request).unwrap();
+
+        assert_eq!(result.op, Operation::TypedGet);
+        assert_eq!(result.args, vec!["user:1000", "app::User"]);
+    }
+
+    #[test]
+    fn round_trips_arguments_containing_separators() {
+        let args = vec![
+            "user:1000".to_string(),
+            "John Q Smith".to_string(),
+            "line one\nline\ttwo\r".to_string(),
+            r"C:\data\s db".to_string(),
+            String::new(),
+        ];
+
+        let request = create_request(Operation::ListRightPush, args.clone());
+        assert!(!request.contains('\n'));
+
+        let result = parse_request(&request).unwrap();
+        assert_eq!(result.op, Operation::ListRightPush);
+        assert_eq!(result.args, args);
+    }
+
+    #[test]
+    fn keeps_unknown_escapes() {
+        assert_eq!(unescape_arg(r"C:\dir\"), r"C:\dir\");
+        assert_eq!(unescape_arg(r"a\\sb"), r"a\sb");
+        assert_eq!(unescape_arg(r"a\sb"), "a b");
+    }
+
     #[test]
     fn detects_an_invalid_message() {
         let request = "SET::arg1";
@@ -362,6 +1970,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn splits_the_database_from_requests() {
+        assert_eq!(split_database("GET::key").unwrap(), (0, "GET::key"));
+        assert_eq!(split_database("@12 GET::key").unwrap(), (12, "GET::key"));
+        assert_eq!(
+            split_database(&select_database(2, "SET::key a b")).unwrap(),
+            (2, "SET::key a b")
+        );
+
+        for request in ["@GET::key", "@x GET::key", "@-1 GET::key"] {
+            assert!(split_database(request).is_err(), "{}", request);
+        }
+    }
+
+    #[test]
+    fn validation_database_messages() {
+        let args = |count: usize| (0..count).map(|idx| idx.to_string()).collect();
+        let cases = [
+            (Operation::Select, vec![1]),
+            (Operation::SwapDb, vec![2]),
+            (Operation::Move, vec![2]),
+        ];
+
+        for (op, valid) in cases {
+            for count in 0..4 {
+                let m = Message {
+                    op: op.clone(),
+                    args: args(count),
+                };
+                assert_eq!(m.validate(), valid.contains(&count), "{} {}", op, count);
+            }
+        }
+
+        for op in [Operation::FlushDb, Operation::FlushAll] {
+            assert!(Message { op, args: vec![] }.validate());
+        }
+    }
+
     #[test]
     fn parses_a_valid_response() {
         let response = "SET::OK";
@@ -370,6 +2016,14 @@ mod tests {
         assert_eq!(&result, "OK");
     }
 
+    #[test]
+    fn parses_a_response_containing_the_delimiter() {
+        let response = "GET::user::1000\n";
+        let result = parse_response(response);
+
+        assert_eq!(&result, "user::1000");
+    }
+
     #[test]
     fn parses_an_invalid_response_correctly() {
         let response = "GET:";
@@ -377,4 +2031,21 @@ mod tests {
 
         assert_eq!(&result, "");
     }
+
+    #[test]
+    fn parses_a_nil_response() -> io::Result<()> {
+        assert_eq!(parse_optional_response("NIL::\n")?, None);
+        assert_eq!(
+            parse_optional_response("GET::value\n")?.as_deref(),
+            Some("value")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_an_error_response() {
+        let err = parse_optional_response("ERR::invalid argument: one\n").unwrap_err();
+        assert_eq!(err.to_string(), "invalid argument: one");
+    }
 }
@@ -1,27 +1,164 @@
 //! Server protocol for operating a store on a network
 //!
-//! Creates a [`MemStore`] which operates over a network, accepting requests from clients
-//! to interact with the store.
+//! Creates a [`PersistentStore`] for each database which operates over a network, accepting
+//! requests from clients to interact with the store.
 //!
 //! Can be run as an asynchronus task or as a background process, usage depends on end-user wants
 //! and needs.
 
-use std::sync::Arc;
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use crate::{
     errors::MessageError,
-    net::parser::{parse_request, Operation},
-    store::mem::MemStore,
+    net::parser::{parse_request, split_database, Message, Operation},
+    store::mem::{
+        BitFieldOp, EvictionPolicy, GeoSearch, StreamId, StreamTrim, TypedValue, DEFAULT_DATABASES,
+        DEFAULT_SCAN_COUNT,
+    },
+    store::persistence::{
+        Compression, FsyncPolicy, PersistentStore, SaveRule, StoreFormat, DEFAULT_SAVE_RULES,
+    },
 };
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::{TcpListener, TcpStream},
-    sync::Mutex,
+    sync::{Mutex, MutexGuard},
+    time::interval,
 };
 
 use tracing::{debug, error, info};
 use tracing_subscriber::FmtSubscriber;
 
+/// Configuration options for the server
+///
+/// # Example
+///
+/// ```
+/// use rubin::net::server::ServerConfig;
+/// use rubin::store::mem::EvictionPolicy;
+///
+/// let config = ServerConfig {
+///     max_memory: Some(64 * 1024 * 1024),
+///     eviction_policy: EvictionPolicy::AllKeysLru,
+///     ..ServerConfig::default()
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Maximum number of bytes each database can use, unlimited if not set
+    pub max_memory: Option<usize>,
+
+    /// Policy used to evict keys when a database reaches `max_memory`
+    pub eviction_policy: EvictionPolicy,
+
+    /// Number of logical databases clients can select between
+    pub databases: usize,
+
+    /// Directory the databases are loaded from and saved to, kept in memory only if not set
+    pub data_dir: Option<PathBuf>,
+
+    /// Rules for saving each database to `data_dir` in the background
+    pub save_rules: Vec<SaveRule>,
+
+    /// Format the databases are saved to `data_dir` in
+    pub format: StoreFormat,
+
+    /// Compression applied to the databases saved to `data_dir`
+    pub compression: Compression,
+
+    /// Fsync policy of the append-only log each database keeps in `data_dir`, no log is kept
+    /// if not set
+    pub append_fsync: Option<FsyncPolicy>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_memory: None,
+            eviction_policy: EvictionPolicy::default(),
+            databases: DEFAULT_DATABASES,
+            data_dir: None,
+            save_rules: DEFAULT_SAVE_RULES.to_vec(),
+            format: StoreFormat::default(),
+            compression: Compression::default(),
+            append_fsync: None,
+        }
+    }
+}
+
+/// State shared between every client of the server
+struct ServerState {
+    /// Databases clients operate on, each locked on its own so clients using different
+    /// databases do not hold each other up
+    databases: Vec<Arc<Mutex<PersistentStore>>>,
+
+    /// Directory the databases are saved to, saving is disabled if not set
+    data_dir: Option<PathBuf>,
+}
+
+impl ServerState {
+    /// Gets a database by index
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if there is no database with the index
+    fn database(&self, index: usize) -> io::Result<&Arc<Mutex<PersistentStore>>> {
+        self.databases.get(index).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "database index is out of range",
+            )
+        })
+    }
+
+    /// Locks two different databases, always in index order so two clients locking the
+    /// same pair cannot wait on each other
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if either database does not exist or they are the
+    ///   same database
+    async fn lock_pair(
+        &self,
+        first: usize,
+        second: usize,
+    ) -> io::Result<(
+        MutexGuard<'_, PersistentStore>,
+        MutexGuard<'_, PersistentStore>,
+    )> {
+        let (first_db, second_db) = (self.database(first)?, self.database(second)?);
+        if first == second {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "source and target databases are the same",
+            ));
+        }
+
+        if first < second {
+            let first_db = first_db.lock().await;
+            Ok((first_db, second_db.lock().await))
+        } else {
+            let second_db = second_db.lock().await;
+            Ok((first_db.lock().await, second_db))
+        }
+    }
+}
+
+/// How often the background task removes expired keys from the store
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Most expired keys removed from each database per sweep, so a sweep never holds the store
+/// for long, expired keys beyond this are removed by later sweeps or when accessed
+const EXPIRY_SWEEP_LIMIT: usize = 200;
+
+/// Largest request accepted from a client, in bytes
+const MAX_REQUEST_SIZE: usize = 512 * 1024 * 1024;
+
 static INIT_TRACING: std::sync::Once = std::sync::Once::new();
 /// Sets up a global logger
 fn init_logger() {
@@ -40,36 +177,65 @@ async fn send_response(client: &mut TcpStream, code: Operation, msg: &str) {
 }
 
 /// Reads a message from a client and converts it to a string
-async fn read_from_client(client: &mut TcpStream) -> String {
+///
+/// Reads until the end of the first line or until the client stops sending, so requests are
+/// not limited to the size of a single read.
+///
+/// # Errors
+///
+/// * [`io::ErrorKind::InvalidData`] if the request is larger than [`MAX_REQUEST_SIZE`]
+async fn read_from_client(client: &mut TcpStream) -> io::Result<String> {
+    let mut msg = Vec::new();
     let mut buffer = vec![0; 4096];
-    let n_bytes = client
-        .read(&mut buffer)
-        .await
-        .expect("unable to read from client");
+    loop {
+        let n_bytes = client.read(&mut buffer).await?;
+        if n_bytes == 0 {
+            break;
+        }
+
+        // Arguments are escaped, so the first newline always ends the request
+        if let Some(end) = buffer[..n_bytes].iter().position(|&b| b == b'\n') {
+            msg.extend_from_slice(&buffer[..end]);
+            break;
+        }
 
-    if n_bytes == 0 {
-        return String::from("");
+        msg.extend_from_slice(&buffer[..n_bytes]);
+        if msg.len() > MAX_REQUEST_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("request is larger than {} bytes", MAX_REQUEST_SIZE),
+            ));
+        }
     }
 
-    let msg = String::from_utf8_lossy(&buffer[..n_bytes]);
-    msg.trim_end().to_string()
+    let msg = String::from_utf8_lossy(&msg);
+    Ok(msg.trim_end().to_string())
 }
 
 /// Main handler for the server
 ///
-/// Processes incoming requests from the client and performs the requested operation.
-/// If the operation cannot be processed, an error is returned.
-async fn handler(mut client: TcpStream, store: Arc<Mutex<MemStore>>) {
+/// Processes incoming requests from the client and performs the requested operation against
+/// the database selected by the request. If the operation cannot be processed, an error is
+/// returned.
+async fn handler(mut client: TcpStream, state: Arc<ServerState>) {
     let client_address = client
         .peer_addr()
         .expect("unable to get client address")
         .to_string();
 
-    let msg = read_from_client(&mut client).await;
+    let msg = match read_from_client(&mut client).await {
+        Ok(msg) => msg,
+        Err(error) => {
+            error!("failed to read request - {}", error);
+            send_response(&mut client, Operation::Error, &error.to_string()).await;
+            return;
+        }
+    };
     info!("{} -> {}", client_address, msg);
 
-    let message = match parse_request(&msg) {
-        Ok(msg) => msg,
+    let request = split_database(&msg).and_then(|(db, req)| Ok((db, parse_request(req)?)));
+    let (db, message) = match request {
+        Ok(request) => request,
         Err(error) => {
             match error {
                 MessageError::InvalidMessage(msg) | MessageError::InvalidFormat(msg) => {
@@ -81,73 +247,887 @@ async fn handler(mut client: TcpStream, store: Arc<Mutex<MemStore>>) {
         }
     };
 
-    let mut vault = store.lock().await;
+    let response = process_server_request(&state, db, &message).await;
+
+    match response {
+        Ok(Some(value)) => {
+            send_response(&mut client, message.op, &value).await;
+            info!("{} <- {}", client_address, &value);
+        }
+        Ok(None) => {
+            send_response(&mut client, Operation::Nil, "").await;
+            info!("{} <- {}", client_address, Operation::Nil);
+        }
+        Err(e) => {
+            let err_message = e.to_string();
+            send_response(&mut client, Operation::Error, &err_message).await;
+            error!("{} <- {}", client_address, &err_message);
+        }
+    }
+}
+
+/// Performs a requested operation against the server, sending operations on the database
+/// selected by the request to [`process_store_request`]
+async fn process_server_request(
+    state: &Arc<ServerState>,
+    db: usize,
+    message: &Message,
+) -> io::Result<Option<String>> {
     match message.op {
-        Operation::StringSet => {
-            let key = &message.args[0];
-            let value = &message.args[1..].join(" ");
+        Operation::Select | Operation::SwapDb | Operation::Move | Operation::FlushAll => {
+            process_database_request(state, db, message).await.map(Some)
+        }
+        Operation::Save => save(state).await.map(Some),
+        Operation::BackgroundSave => background_save(state).map(Some),
+        Operation::LastSave => Ok(Some(last_save(state).await.to_string())),
+        _ => {
+            let mut vault = state.database(db)?.lock().await;
+            process_store_request(&mut vault, message).await
+        }
+    }
+}
+
+/// Performs a requested operation against a single database, sending operations which may
+/// not return a value to [`process_optional_request`]
+async fn process_store_request(
+    vault: &mut PersistentStore,
+    message: &Message,
+) -> io::Result<Option<String>> {
+    match message.op {
+        Operation::StringGet
+        | Operation::StringRemove
+        | Operation::ListLeftPop
+        | Operation::ListRightPop
+        | Operation::ListIndex
+        | Operation::HashGet
+        | Operation::SortedSetScore
+        | Operation::SortedSetRank
+        | Operation::TypedGet
+        | Operation::GetSet
+        | Operation::GeoDistance
+        | Operation::JsonGet
+        | Operation::StringGetDelete => process_optional_request(vault, message).await,
+        _ => process_request(vault, message).await.map(Some),
+    }
+}
 
-            let _ = vault.insert_string(key, value);
-            send_response(&mut client, message.op, "OK").await;
-            info!("{} <- {}", client_address, "OK");
+/// Performs a requested operation which works across databases
+///
+/// `db` is the database selected by the request, which keys are moved out of.
+async fn process_database_request(
+    state: &ServerState,
+    db: usize,
+    message: &Message,
+) -> io::Result<String> {
+    let args = &message.args;
+
+    match message.op {
+        Operation::Select => {
+            state.database(parse_arg(&args[0])?)?;
+            Ok("OK".to_string())
         }
-        Operation::StringGet => {
-            let key = &message.args[0];
+        Operation::SwapDb => {
+            let (first, second) = (parse_arg(&args[0])?, parse_arg(&args[1])?);
+            if first == second {
+                state.database(first)?;
+                return Ok("OK".to_string());
+            }
 
-            if let Ok(value) = vault.get_string(key) {
-                send_response(&mut client, message.op, &value).await;
-                info!("{} <- {}", client_address, &value);
+            let (mut first, mut second) = state.lock_pair(first, second).await?;
+            first.swap(&mut second).await?;
+            Ok("OK".to_string())
+        }
+        Operation::Move => {
+            let (mut source, mut target) = state.lock_pair(db, parse_arg(&args[1])?).await?;
+            source
+                .move_key(&args[0], &mut target)
+                .await
+                .map(|moved| (moved as u8).to_string())
+        }
+        Operation::FlushAll => {
+            for database in &state.databases {
+                database.lock().await.flush().await?;
             }
+            Ok("OK".to_string())
         }
-        Operation::StringRemove => {
-            let key = &message.args[0];
+        _ => Ok("nothing to do".to_string()),
+    }
+}
 
-            if let Ok(value) = vault.remove_string(key) {
-                send_response(&mut client, message.op, &value).await;
-                info!("{} <- {}", client_address, &value);
-            }
+/// Gets the data directory the databases are saved to
+///
+/// Clients cannot choose where the databases are written, so saving is only possible when
+/// the server has a data directory.
+///
+/// # Errors
+///
+/// * [`io::ErrorKind::InvalidInput`] if no data directory is configured
+fn save_directory(state: &ServerState) -> io::Result<&Path> {
+    state.data_dir.as_deref().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no data directory is configured",
+        )
+    })
+}
+
+/// Path of the file a database is saved to within the data directory
+///
+/// The append-only log of the database, if enabled, is kept alongside it with an `.aof`
+/// extension.
+fn database_file(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("db-{}.rubin", index))
+}
+
+/// Writes every database to the data directory, responding once the files are written
+///
+/// Like [`background_save`] each database is encoded while it is locked and its file is
+/// written once it is released, so only the client asking for the save waits on it.
+async fn save(state: &ServerState) -> io::Result<String> {
+    save_directory(state)?;
+    save_databases(state).await?;
+
+    Ok("OK".to_string())
+}
+
+/// Starts writing every database to the data directory in the background
+///
+/// The databases are saved by a separate task, so no client is held up while the files are
+/// written.
+fn background_save(state: &Arc<ServerState>) -> io::Result<String> {
+    save_directory(state)?;
+
+    let state = Arc::clone(state);
+    tokio::spawn(async move {
+        match save_databases(&state).await {
+            Ok(()) => info!("Background save complete"),
+            Err(e) => error!("unable to save databases in the background - {}", e),
         }
-        Operation::StringClear => {
-            if vault.clear_strings().is_ok() {
-                send_response(&mut client, message.op, "OK").await;
-                info!("{} <- {}", client_address, "OK");
+    });
+
+    Ok("Background saving started".to_string())
+}
+
+/// Writes each database in turn, see [`PersistentStore::background_save()`]
+///
+/// A database which is already being saved, for example by one of its save rules, is saved
+/// again once that save finishes so no update made before this save is missed.
+async fn save_databases(state: &ServerState) -> io::Result<()> {
+    for database in &state.databases {
+        loop {
+            match PersistentStore::background_save(database).await {
+                Ok(saving) => {
+                    saving.await.unwrap_or_else(|e| Err(e.into()))?;
+                    break;
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                Err(e) => return Err(e),
             }
         }
-        Operation::Incr => {
-            let key = &message.args[0];
+    }
+
+    Ok(())
+}
+
+/// Gets the time any database was last saved as seconds since the Unix epoch
+///
+/// This is the time the server started if no database has been saved since.
+async fn last_save(state: &ServerState) -> u64 {
+    let mut last_save = 0;
+    for database in &state.databases {
+        last_save = last_save.max(database.lock().await.last_save());
+    }
+
+    last_save
+}
+
+/// Parses an argument from a request into the required type
+fn parse_arg<T: FromStr>(arg: &str) -> io::Result<T> {
+    arg.parse::<T>().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid argument: {}", arg),
+        )
+    })
+}
+
+/// Parses a bit argument, which must be `0` or `1`
+fn parse_bit(arg: &str) -> io::Result<bool> {
+    match arg {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("bit is not 0 or 1: {}", arg),
+        )),
+    }
+}
+
+/// Converts a slice of request arguments into string slices
+fn as_strs(args: &[String]) -> Vec<&str> {
+    args.iter().map(|arg| arg.as_str()).collect()
+}
+
+/// Groups arguments into key-value pairs
+fn as_pairs(args: &[String]) -> Vec<(&str, &str)> {
+    args.chunks(2)
+        .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+        .collect()
+}
 
-            if let Ok(value) = vault.incr(key) {
-                send_response(&mut client, message.op, &value.to_string()).await;
-                info!("{} <- {}", client_address, &value);
+/// Parses the optional `MATCH pattern` / `COUNT count` pairs following the cursor of a
+/// [`Operation::Scan`], [`Operation::HashScan`] or [`Operation::SetScan`] request
+fn parse_scan_options(options: &[String]) -> io::Result<(Option<&str>, usize)> {
+    let mut pattern = None;
+    let mut count = DEFAULT_SCAN_COUNT;
+
+    for option in options.chunks(2) {
+        match option[0].to_uppercase().as_str() {
+            "MATCH" => pattern = Some(option[1].as_str()),
+            "COUNT" => count = parse_arg(&option[1])?,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid scan option: {}", option[0]),
+                ))
             }
         }
-        Operation::Decr => {
-            let key = &message.args[0];
+    }
+
+    Ok((pattern, count))
+}
+
+/// Parses an optional `COUNT n` option from the end of a stream request
+fn parse_stream_count(options: &[String]) -> io::Result<Option<usize>> {
+    match options {
+        [] => Ok(None),
+        [option, count] if option.eq_ignore_ascii_case("COUNT") => Ok(Some(parse_arg(count)?)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid stream option: {}", options[0]),
+        )),
+    }
+}
+
+/// Parses a list of stream IDs
+fn parse_stream_ids(args: &[String]) -> io::Result<Vec<StreamId>> {
+    args.iter().map(|arg| arg.parse()).collect()
+}
+
+/// Splits the text of a [`Operation::JsonArrAppend`] request into its separate JSON values
+///
+/// Arguments are separated by spaces, which may also appear within a value, so the values
+/// are found by parsing the rejoined text.
+fn split_json_values(args: &[String]) -> io::Result<Vec<String>> {
+    serde_json::Deserializer::from_str(&args.join(" "))
+        .into_iter::<serde_json::Value>()
+        .map(|value| {
+            value.map(|v| v.to_string()).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid JSON value: {}", e),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Splits an optional trailing `EX seconds` / `PX milliseconds` expiry off the end of
+/// the arguments to a [`Operation::StringSet`] request
+///
+/// An expiry is only read from requests of exactly `key value EX|PX n`. Values are sent as a
+/// single escaped argument, so any other number of arguments is a value which happens to
+/// end in `EX` or `PX` and is stored as is.
+fn split_expiry(args: &[String]) -> io::Result<(&[String], Option<Duration>)> {
+    if args.len() != 4 {
+        return Ok((args, None));
+    }
+
+    let (values, option) = args.split_at(args.len() - 2);
+    let expiry = match option[0].to_uppercase().as_str() {
+        "EX" => Duration::from_secs(parse_arg(&option[1])?),
+        "PX" => Duration::from_millis(parse_arg(&option[1])?),
+        _ => return Ok((args, None)),
+    };
+
+    Ok((values, Some(expiry)))
+}
+
+/// Performs the requested operation on the store
+///
+/// Returns the value to send back to the client or an error if the operation failed.
+async fn process_request(vault: &mut PersistentStore, message: &Message) -> io::Result<String> {
+    let args = &message.args;
 
-            if let Ok(value) = vault.decr(key) {
-                send_response(&mut client, message.op, &value.to_string()).await;
-                info!("{} <- {}", client_address, &value);
+    match message.op {
+        Operation::StringSet => {
+            let (args, expiry) = split_expiry(args)?;
+            let key = &args[0];
+            let value = &args[1..].join(" ");
+
+            match expiry {
+                Some(expiry) => vault.insert_string_with_expiry(key, value, expiry).await?,
+                None => vault.insert_string(key, value).await?,
             }
+            Ok("OK".to_string())
         }
-        Operation::Dump => {
-            let filepath = &message.args[0];
+        Operation::StringClear => {
+            vault.clear_strings().await?;
+            Ok("OK".to_string())
+        }
+        Operation::FlushDb => {
+            vault.flush().await?;
+            Ok("OK".to_string())
+        }
+        Operation::StringAppend => vault
+            .append(&args[0], &args[1..].join(" "))
+            .await
+            .map(|len| len.to_string()),
+        Operation::StringGetRange => {
+            vault.getrange(&args[0], parse_arg(&args[1])?, parse_arg(&args[2])?)
+        }
+        Operation::StringSetRange => vault
+            .setrange(&args[0], parse_arg(&args[1])?, &args[2..].join(" "))
+            .await
+            .map(|len| len.to_string()),
+        Operation::StringLength => vault.strlen(&args[0]).map(|len| len.to_string()),
+        Operation::StringSetNx => vault
+            .setnx(&args[0], &args[1..].join(" "))
+            .await
+            .map(|set| (set as u8).to_string()),
+        Operation::StringMultiGet => Ok(serde_json::to_string(&vault.mget(&as_strs(args))?)?),
+        Operation::StringMultiSet => {
+            vault.mset(&as_pairs(args)).await?;
+            Ok("OK".to_string())
+        }
+        Operation::StringMultiSetNx => vault
+            .msetnx(&as_pairs(args))
+            .await
+            .map(|set| (set as u8).to_string()),
+        Operation::Incr => vault.incr(&args[0]).await.map(|value| value.to_string()),
+        Operation::Decr => vault.decr(&args[0]).await.map(|value| value.to_string()),
+        Operation::IncrBy => vault
+            .incrby(&args[0], parse_arg(&args[1])?)
+            .await
+            .map(|value| value.to_string()),
+        Operation::DecrBy => vault
+            .decrby(&args[0], parse_arg(&args[1])?)
+            .await
+            .map(|value| value.to_string()),
+        Operation::IncrByFloat => vault
+            .incrbyfloat(&args[0], parse_arg(&args[1])?)
+            .await
+            .map(|value| value.to_string()),
+        Operation::ListLeftPush | Operation::ListRightPush => {
+            let key = &args[0];
+            let values = as_strs(&args[1..]);
 
-            if let Err(e) = vault.dump_store(filepath) {
-                let err_message = format!("unable to save store: {}", e);
-                send_response(&mut client, message.op, &err_message).await;
-                error!("{} <- {}", client_address, &err_message);
+            let length = if message.op == Operation::ListLeftPush {
+                vault.lpush(key, &values).await?
             } else {
-                send_response(&mut client, message.op, "OK").await;
-                info!("{} <- {}", client_address, "OK");
+                vault.rpush(key, &values).await?
+            };
+
+            Ok(length.to_string())
+        }
+        Operation::ListRange => {
+            let start = parse_arg(&args[1])?;
+            let stop = parse_arg(&args[2])?;
+            let values = vault.lrange(&args[0], start, stop)?;
+
+            Ok(serde_json::to_string(&values)?)
+        }
+        Operation::ListLength => vault.llen(&args[0]).map(|length| length.to_string()),
+        Operation::ListSet => {
+            let index = parse_arg(&args[1])?;
+            let value = &args[2..].join(" ");
+
+            vault.lset(&args[0], index, value).await?;
+            Ok("OK".to_string())
+        }
+        Operation::ListTrim => {
+            let start = parse_arg(&args[1])?;
+            let stop = parse_arg(&args[2])?;
+
+            vault.ltrim(&args[0], start, stop).await?;
+            Ok("OK".to_string())
+        }
+        Operation::ListRemove => {
+            let count = parse_arg(&args[1])?;
+            let value = &args[2..].join(" ");
+
+            vault
+                .lrem(&args[0], count, value)
+                .await
+                .map(|removed| removed.to_string())
+        }
+        Operation::HashSet => {
+            let fields: Vec<(&str, &str)> = args[1..]
+                .chunks(2)
+                .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+                .collect();
+
+            vault
+                .hset(&args[0], &fields)
+                .await
+                .map(|added| added.to_string())
+        }
+        Operation::HashDelete => vault
+            .hdel(&args[0], &as_strs(&args[1..]))
+            .await
+            .map(|removed| removed.to_string()),
+        Operation::HashGetAll => Ok(serde_json::to_string(&vault.hgetall(&args[0])?)?),
+        Operation::HashKeys => Ok(serde_json::to_string(&vault.hkeys(&args[0])?)?),
+        Operation::HashValues => Ok(serde_json::to_string(&vault.hvals(&args[0])?)?),
+        Operation::HashLength => vault.hlen(&args[0]).map(|length| length.to_string()),
+        Operation::HashExists => vault
+            .hexists(&args[0], &args[1])
+            .map(|exists| (exists as u8).to_string()),
+        Operation::HashIncrBy => vault
+            .hincrby(&args[0], &args[1], parse_arg(&args[2])?)
+            .await
+            .map(|value| value.to_string()),
+        Operation::SetAdd => vault
+            .sadd(&args[0], &as_strs(&args[1..]))
+            .await
+            .map(|added| added.to_string()),
+        Operation::SetRemove => vault
+            .srem(&args[0], &as_strs(&args[1..]))
+            .await
+            .map(|removed| removed.to_string()),
+        Operation::SetIsMember => vault
+            .sismember(&args[0], &args[1])
+            .map(|member| (member as u8).to_string()),
+        Operation::SetMembers => Ok(serde_json::to_string(&vault.smembers(&args[0])?)?),
+        Operation::SetCardinality => vault.scard(&args[0]).map(|count| count.to_string()),
+        Operation::SetUnion => Ok(serde_json::to_string(&vault.sunion(&as_strs(args))?)?),
+        Operation::SetIntersection => Ok(serde_json::to_string(&vault.sinter(&as_strs(args))?)?),
+        Operation::SetDifference => Ok(serde_json::to_string(&vault.sdiff(&as_strs(args))?)?),
+        Operation::SetUnionStore => vault
+            .sunionstore(&args[0], &as_strs(&args[1..]))
+            .await
+            .map(|count| count.to_string()),
+        Operation::SetIntersectionStore => vault
+            .sinterstore(&args[0], &as_strs(&args[1..]))
+            .await
+            .map(|count| count.to_string()),
+        Operation::SetDifferenceStore => vault
+            .sdiffstore(&args[0], &as_strs(&args[1..]))
+            .await
+            .map(|count| count.to_string()),
+        Operation::SortedSetAdd => {
+            let mut members = vec![];
+            for pair in args[1..].chunks(2) {
+                members.push((parse_arg(&pair[0])?, pair[1].as_str()));
             }
+
+            vault
+                .zadd(&args[0], &members)
+                .await
+                .map(|added| added.to_string())
         }
-        _ => {
-            send_response(&mut client, Operation::Noop, "nothing to do").await;
-            info!("{} <- noop", client_address);
+        Operation::SortedSetRemove => vault
+            .zrem(&args[0], &as_strs(&args[1..]))
+            .await
+            .map(|removed| removed.to_string()),
+        Operation::SortedSetIncrBy => vault
+            .zincrby(&args[0], parse_arg(&args[1])?, &args[2])
+            .await
+            .map(|score| score.to_string()),
+        Operation::SortedSetRange => {
+            let members = vault.zrange(&args[0], parse_arg(&args[1])?, parse_arg(&args[2])?)?;
+            Ok(serde_json::to_string(&members)?)
+        }
+        Operation::SortedSetReverseRange => {
+            let members = vault.zrevrange(&args[0], parse_arg(&args[1])?, parse_arg(&args[2])?)?;
+            Ok(serde_json::to_string(&members)?)
+        }
+        Operation::SortedSetRangeByScore => {
+            let members =
+                vault.zrangebyscore(&args[0], parse_arg(&args[1])?, parse_arg(&args[2])?)?;
+            Ok(serde_json::to_string(&members)?)
+        }
+        Operation::SortedSetCount => vault
+            .zcount(&args[0], parse_arg(&args[1])?, parse_arg(&args[2])?)
+            .map(|count| count.to_string()),
+        Operation::Expire => vault
+            .expire(&args[0], parse_arg(&args[1])?)
+            .await
+            .map(|updated| (updated as u8).to_string()),
+        Operation::PExpire => vault
+            .pexpire(&args[0], parse_arg(&args[1])?)
+            .await
+            .map(|updated| (updated as u8).to_string()),
+        Operation::Ttl => vault.ttl(&args[0]).map(|ttl| ttl.to_string()),
+        Operation::PTtl => vault.pttl(&args[0]).map(|ttl| ttl.to_string()),
+        Operation::Persist => vault
+            .persist(&args[0])
+            .await
+            .map(|removed| (removed as u8).to_string()),
+        Operation::TypedSet => {
+            let typed = TypedValue {
+                type_name: args[1].clone(),
+                value: args[2..].join(" "),
+            };
+
+            vault.insert_tagged(&args[0], typed).await?;
+            Ok("OK".to_string())
+        }
+        Operation::Exists => vault.exists(&as_strs(args)).map(|count| count.to_string()),
+        Operation::Delete => vault
+            .del(&as_strs(args))
+            .await
+            .map(|count| count.to_string()),
+        Operation::Type => vault.key_type(&args[0]).map(|key_type| match key_type {
+            Some(key_type) => key_type.to_string(),
+            None => "none".to_string(),
+        }),
+        Operation::Rename => {
+            vault.rename(&args[0], &args[1]).await?;
+            Ok("OK".to_string())
+        }
+        Operation::RenameNx => vault
+            .renamenx(&args[0], &args[1])
+            .await
+            .map(|renamed| (renamed as u8).to_string()),
+        Operation::Keys => Ok(serde_json::to_string(&vault.keys(&args[0])?)?),
+        Operation::SetBit => vault
+            .setbit(&args[0], parse_arg(&args[1])?, parse_bit(&args[2])?)
+            .await
+            .map(|previous| (previous as u8).to_string()),
+        Operation::GetBit => vault
+            .getbit(&args[0], parse_arg(&args[1])?)
+            .map(|bit| (bit as u8).to_string()),
+        Operation::BitCount => {
+            let range = match args.len() {
+                3 => Some((parse_arg(&args[1])?, parse_arg(&args[2])?)),
+                _ => None,
+            };
+
+            vault
+                .bitcount(&args[0], range)
+                .map(|count| count.to_string())
+        }
+        Operation::BitPos => {
+            let start = args.get(2).map(|arg| parse_arg(arg)).transpose()?;
+            let end = args.get(3).map(|arg| parse_arg(arg)).transpose()?;
+
+            vault
+                .bitpos(&args[0], parse_bit(&args[1])?, start, end)
+                .map(|position| match position {
+                    Some(position) => position.to_string(),
+                    None => "-1".to_string(),
+                })
+        }
+        Operation::BitOp => vault
+            .bitop(args[0].parse()?, &args[1], &as_strs(&args[2..]))
+            .await
+            .map(|len| len.to_string()),
+        Operation::BitField => {
+            let ops = BitFieldOp::parse(&args[1..])?;
+            Ok(serde_json::to_string(
+                &vault.bitfield(&args[0], &ops).await?,
+            )?)
+        }
+        Operation::PfAdd => vault
+            .pfadd(&args[0], &as_strs(&args[1..]))
+            .await
+            .map(|updated| (updated as u8).to_string()),
+        Operation::PfCount => vault.pfcount(&as_strs(args)).map(|count| count.to_string()),
+        Operation::PfMerge => {
+            vault.pfmerge(&args[0], &as_strs(&args[1..])).await?;
+            Ok("OK".to_string())
+        }
+        Operation::GeoAdd => {
+            let members = args[1..]
+                .chunks(3)
+                .map(|member| {
+                    Ok((
+                        parse_arg(&member[0])?,
+                        parse_arg(&member[1])?,
+                        member[2].as_str(),
+                    ))
+                })
+                .collect::<io::Result<Vec<(f64, f64, &str)>>>()?;
+
+            vault
+                .geoadd(&args[0], &members)
+                .await
+                .map(|added| added.to_string())
+        }
+        Operation::GeoPosition => Ok(serde_json::to_string(
+            &vault.geopos(&args[0], &as_strs(&args[1..]))?,
+        )?),
+        Operation::GeoSearch => {
+            let search = GeoSearch::parse(&args[1..])?;
+            Ok(serde_json::to_string(&vault.geosearch(&args[0], &search)?)?)
+        }
+        Operation::StreamAdd => {
+            let id = match args[1].as_str() {
+                "*" => None,
+                id => Some(id.parse()?),
+            };
+            let fields: Vec<(&str, &str)> = args[2..]
+                .chunks(2)
+                .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+                .collect();
+
+            vault
+                .xadd(&args[0], id, &fields)
+                .await
+                .map(|id| id.to_string())
+        }
+        Operation::StreamRange => {
+            let entries = vault.xrange(
+                &args[0],
+                StreamId::parse_start(&args[1])?,
+                StreamId::parse_end(&args[2])?,
+                parse_stream_count(&args[3..])?,
+            )?;
+            Ok(serde_json::to_string(&entries)?)
+        }
+        Operation::StreamReverseRange => {
+            let entries = vault.xrevrange(
+                &args[0],
+                StreamId::parse_end(&args[1])?,
+                StreamId::parse_start(&args[2])?,
+                parse_stream_count(&args[3..])?,
+            )?;
+            Ok(serde_json::to_string(&entries)?)
+        }
+        Operation::StreamLength => vault.xlen(&args[0]).map(|len| len.to_string()),
+        Operation::StreamTrim => {
+            let trim = match args[1].to_uppercase().as_str() {
+                "MAXLEN" => StreamTrim::MaxLen(parse_arg(&args[2])?),
+                "MINID" => StreamTrim::MinId(StreamId::parse_start(&args[2])?),
+                strategy => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("invalid trim strategy: {}", strategy),
+                    ))
+                }
+            };
+
+            vault
+                .xtrim(&args[0], trim)
+                .await
+                .map(|removed| removed.to_string())
+        }
+        Operation::StreamGroup => match (args[0].to_uppercase().as_str(), args.len()) {
+            ("CREATE", 4) => {
+                let id = match args[3].as_str() {
+                    "$" => None,
+                    id => Some(StreamId::parse_start(id)?),
+                };
+
+                vault.xgroup_create(&args[1], &args[2], id).await?;
+                Ok("OK".to_string())
+            }
+            ("DESTROY", 3) => vault
+                .xgroup_destroy(&args[1], &args[2])
+                .await
+                .map(|destroyed| (destroyed as u8).to_string()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid group subcommand: {}", args[0]),
+            )),
+        },
+        Operation::StreamReadGroup => {
+            let id = match args[3].as_str() {
+                ">" => None,
+                id => Some(StreamId::parse_start(id)?),
+            };
+            let count = parse_stream_count(&args[4..])?;
+
+            let entries = vault
+                .xreadgroup(&args[0], &args[1], &args[2], id, count)
+                .await?;
+            Ok(serde_json::to_string(&entries)?)
+        }
+        Operation::StreamAck => vault
+            .xack(&args[0], &args[1], &parse_stream_ids(&args[2..])?)
+            .await
+            .map(|count| count.to_string()),
+        Operation::StreamPending => {
+            if args.len() == 2 {
+                return Ok(serde_json::to_string(&vault.xpending(&args[0], &args[1])?)?);
+            }
+
+            let pending = vault.xpending_range(
+                &args[0],
+                &args[1],
+                StreamId::parse_start(&args[2])?,
+                StreamId::parse_end(&args[3])?,
+                parse_arg(&args[4])?,
+                args.get(5).map(String::as_str),
+            )?;
+            Ok(serde_json::to_string(&pending)?)
+        }
+        Operation::StreamClaim => {
+            let entries = vault
+                .xclaim(
+                    &args[0],
+                    &args[1],
+                    &args[2],
+                    parse_arg(&args[3])?,
+                    &parse_stream_ids(&args[4..])?,
+                )
+                .await?;
+            Ok(serde_json::to_string(&entries)?)
+        }
+        Operation::Scan => {
+            let (pattern, count) = parse_scan_options(&args[1..])?;
+            let page = vault.scan(parse_arg(&args[0])?, pattern, count)?;
+            Ok(serde_json::to_string(&page)?)
+        }
+        Operation::HashScan => {
+            let (pattern, count) = parse_scan_options(&args[2..])?;
+            let page = vault.hscan(&args[0], parse_arg(&args[1])?, pattern, count)?;
+            Ok(serde_json::to_string(&page)?)
+        }
+        Operation::SetScan => {
+            let (pattern, count) = parse_scan_options(&args[2..])?;
+            let page = vault.sscan(&args[0], parse_arg(&args[1])?, pattern, count)?;
+            Ok(serde_json::to_string(&page)?)
+        }
+        Operation::JsonSet => vault
+            .json_set(&args[0], &args[1], &args[2..].join(" "))
+            .await
+            .map(|set| (set as u8).to_string()),
+        Operation::JsonDelete => vault
+            .json_del(&args[0], args.get(1).map_or("$", |path| path.as_str()))
+            .await
+            .map(|removed| removed.to_string()),
+        Operation::JsonArrAppend => {
+            let values = split_json_values(&args[2..])?;
+            let lengths = vault
+                .json_arrappend(&args[0], &args[1], &as_strs(&values))
+                .await?;
+            Ok(serde_json::to_string(&lengths)?)
+        }
+        Operation::JsonNumIncrBy => {
+            vault
+                .json_numincrby(&args[0], &args[1], parse_arg(&args[2])?)
+                .await
+        }
+        Operation::Dump => {
+            let filepath = &args[0];
+
+            vault
+                .store
+                .dump_store(filepath)
+                .map_err(|e| io::Error::new(e.kind(), format!("unable to save store: {}", e)))?;
+            Ok("OK".to_string())
         }
+        _ => Ok("nothing to do".to_string()),
     }
 }
 
+/// Performs a requested operation which may not return a value (i.e. a missing key)
+///
+/// Returns `None` if there is no value to send back, which is sent to the client as a
+/// [`Operation::Nil`] response.
+async fn process_optional_request(
+    vault: &mut PersistentStore,
+    message: &Message,
+) -> io::Result<Option<String>> {
+    let args = &message.args;
+
+    match message.op {
+        Operation::StringGet => vault.get_string(&args[0]),
+        Operation::StringRemove => vault.remove_string(&args[0]).await,
+        Operation::StringGetDelete => vault.getdel(&args[0]).await,
+        Operation::ListLeftPop => vault.lpop(&args[0]).await,
+        Operation::ListRightPop => vault.rpop(&args[0]).await,
+        Operation::ListIndex => vault.lindex(&args[0], parse_arg(&args[1])?),
+        Operation::HashGet => vault.hget(&args[0], &args[1]),
+        Operation::SortedSetScore => vault
+            .zscore(&args[0], &args[1])
+            .map(|score| score.map(|s| s.to_string())),
+        Operation::SortedSetRank => vault
+            .zrank(&args[0], &args[1])
+            .map(|rank| rank.map(|r| r.to_string())),
+        Operation::TypedGet => vault.store.get_tagged(&args[0], &args[1]),
+        Operation::GetSet => vault
+            .getset(&args[0], parse_arg(&args[1])?)
+            .await
+            .map(|previous| previous.map(|p| p.to_string())),
+        Operation::GeoDistance => {
+            let unit = args.get(3).map(|unit| unit.parse()).transpose()?;
+            vault
+                .geodist(&args[0], &args[1], &args[2], unit.unwrap_or_default())
+                .map(|distance| distance.map(|d| d.to_string()))
+        }
+        Operation::JsonGet => {
+            vault.json_get(&args[0], args.get(1).map_or("$", |path| path.as_str()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Periodically removes expired keys from every database
+///
+/// Expired keys are never returned to clients but would otherwise stay in memory until
+/// they are next accessed. Each sweep removes at most [`EXPIRY_SWEEP_LIMIT`] keys from each
+/// database, those which expired first, so each database is only held briefly. The task ends
+/// once the databases have been dropped.
+async fn sweep_expired(state: Weak<ServerState>) {
+    let mut ticker = interval(EXPIRY_SWEEP_INTERVAL);
+    loop {
+        ticker.tick().await;
+
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+
+        let mut removed = 0;
+        for database in &state.databases {
+            removed += database
+                .lock()
+                .await
+                .store
+                .purge_expired_batch(EXPIRY_SWEEP_LIMIT);
+        }
+        if removed > 0 {
+            debug!("Removed {} expired keys", removed);
+        }
+    }
+}
+
+/// Opens every database, loading each from its file in the data directory if there is one
+///
+/// Loaded databases keep their append-only log if the server is configured with an fsync
+/// policy, otherwise the log is written into the database file and removed.
+async fn open_databases(config: &ServerConfig) -> io::Result<Vec<Arc<Mutex<PersistentStore>>>> {
+    let mut databases = Vec::new();
+    for index in 0..config.databases.max(1) {
+        let mut vault = match &config.data_dir {
+            Some(dir) => {
+                let mut vault = PersistentStore::from_existing(database_file(dir, index)).await?;
+                vault.format = config.format;
+                vault.set_compression(config.compression);
+                vault.set_save_rules(config.save_rules.clone());
+                match config.append_fsync {
+                    Some(policy) => vault.enable_aof(policy).await?,
+                    None => vault.disable_aof().await?,
+                }
+
+                vault
+            }
+            None => PersistentStore::in_memory(),
+        };
+        vault
+            .store
+            .set_max_memory(config.max_memory, config.eviction_policy);
+
+        databases.push(Arc::new(Mutex::new(vault)));
+    }
+
+    Ok(databases)
+}
+
 /// Starts the server to accept clients
 ///
 /// This can be run as an independent task or as part of separate binary.
@@ -173,21 +1153,108 @@ async fn handler(mut client: TcpStream, store: Arc<Mutex<MemStore>>) {
 /// }
 /// ```
 pub async fn start(addr: &str, port: usize) -> std::io::Result<()> {
+    start_with_config(addr, port, ServerConfig::default()).await
+}
+
+/// Starts the server to accept clients using the given configuration
+///
+/// # Usage
+///
+/// ```no_run
+/// use rubin::net::server::{start_with_config, ServerConfig};
+/// use rubin::store::mem::EvictionPolicy;
+///
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let config = ServerConfig {
+///         max_memory: Some(64 * 1024 * 1024),
+///         eviction_policy: EvictionPolicy::AllKeysLru,
+///         databases: 4,
+///         ..ServerConfig::default()
+///     };
+///
+///     tokio::task::spawn(start_with_config("127.0.0.1", 9876, config));
+///
+///     // Rest of the workload
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn start_with_config(addr: &str, port: usize, config: ServerConfig) -> io::Result<()> {
+    start_with_shutdown(addr, port, config, std::future::pending()).await
+}
+
+/// Starts the server to accept clients until `shutdown` completes
+///
+/// If a data directory is configured, each database is loaded from its own file in it when
+/// the server starts, saved to it whenever one of its save rules is met (see [`SaveRule`])
+/// and saved once more when the server shuts down. Updates are also logged to an append-only
+/// log next to each file if `append_fsync` is set.
+///
+/// # Usage
+///
+/// ```no_run
+/// use rubin::net::server::{start_with_shutdown, ServerConfig};
+///
+/// #[tokio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let config = ServerConfig {
+///         data_dir: Some("./storage".into()),
+///         ..ServerConfig::default()
+///     };
+///
+///     let shutdown = async {
+///         tokio::signal::ctrl_c().await.expect("unable to listen for shutdown");
+///     };
+///
+///     start_with_shutdown("127.0.0.1", 9876, config, shutdown).await
+/// }
+/// ```
+pub async fn start_with_shutdown(
+    addr: &str,
+    port: usize,
+    config: ServerConfig,
+    shutdown: impl Future<Output = ()>,
+) -> io::Result<()> {
     init_logger();
-    let store = Arc::new(Mutex::new(MemStore::new()));
+
+    let state = Arc::new(ServerState {
+        databases: open_databases(&config).await?,
+        data_dir: config.data_dir.clone(),
+    });
+    if let Some(dir) = &config.data_dir {
+        info!("Loaded databases from {}", dir.display());
+        for database in &state.databases {
+            PersistentStore::schedule_saves(database);
+        }
+    }
+
     let addr = format!("{}:{}", addr, port);
     let listener = TcpListener::bind(&addr).await?;
+    tokio::spawn(sweep_expired(Arc::downgrade(&state)));
 
     info!("Started Rubin server at {}", addr);
+    tokio::pin!(shutdown);
     loop {
-        let (client, _) = listener.accept().await?;
-        let store = Arc::clone(&store);
+        let (client, _) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = &mut shutdown => break,
+        };
+        let state = Arc::clone(&state);
 
         let client_addr = client.peer_addr()?;
         debug!("Accepted new client: {}", client_addr);
 
         tokio::spawn(async move {
-            handler(client, store).await;
+            handler(client, state).await;
         });
     }
+
+    info!("Shutting down Rubin server at {}", addr);
+    if let Some(dir) = &config.data_dir {
+        save_databases(&state).await?;
+        info!("Saved databases to {}", dir.display());
+    }
+
+    Ok(())
 }
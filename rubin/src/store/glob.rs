@@ -0,0 +1,158 @@
+//! Glob-style pattern matching for keys
+//!
+//! Supports the same patterns as Redis:
+//!
+//! * `?` matches any single character
+//! * `*` matches any number of characters, including none
+//! * `[abc]` matches one of the characters in the brackets, `[a-z]` matches a range and
+//!   `[^a]` matches any character except those in the brackets
+//! * `\` escapes the next character so it is matched literally
+
+/// Checks if a string matches a glob-style pattern
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+
+    // Position to resume from if the characters after the last `*` fail to match,
+    // as the pattern index after the `*` and the text index it was matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if let Some(next) = match_single(&pattern, p, text[t]) {
+            p = next;
+            t += 1;
+            continue;
+        }
+
+        if pattern.get(p) == Some(&'*') {
+            p += 1;
+            backtrack = Some((p, t));
+            continue;
+        }
+
+        // Let the last `*` consume one more character and try again
+        match backtrack {
+            Some((star_p, star_t)) => {
+                p = star_p;
+                t = star_t + 1;
+                backtrack = Some((star_p, t));
+            }
+            None => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Matches a single character against the pattern element at `p`, returning the index of
+/// the next pattern element if it matches
+///
+/// Returns `None` for a `*`, which is handled by [`glob_match`].
+fn match_single(pattern: &[char], p: usize, c: char) -> Option<usize> {
+    match pattern.get(p)? {
+        '*' => None,
+        '?' => Some(p + 1),
+        '\\' if p + 1 < pattern.len() => (pattern[p + 1] == c).then_some(p + 2),
+        '[' => match match_class(pattern, p, c) {
+            Some((matched, next)) => matched.then_some(next),
+            // An unclosed bracket is matched literally
+            None => (c == '[').then_some(p + 1),
+        },
+        literal => (*literal == c).then_some(p + 1),
+    }
+}
+
+/// Matches a character against the bracketed class starting at `start`
+///
+/// Returns whether the character matched along with the index after the closing bracket,
+/// or `None` if the class is not closed.
+fn match_class(pattern: &[char], start: usize, c: char) -> Option<(bool, usize)> {
+    let mut i = start + 1;
+    let negate = pattern.get(i) == Some(&'^');
+    if negate {
+        i += 1;
+    }
+
+    let mut matched = false;
+    loop {
+        match *pattern.get(i)? {
+            ']' => break,
+            '\\' if i + 1 < pattern.len() => {
+                matched |= pattern[i + 1] == c;
+                i += 2;
+            }
+            low if pattern.get(i + 1) == Some(&'-')
+                && pattern.get(i + 2).is_some_and(|high| *high != ']') =>
+            {
+                let high = pattern[i + 2];
+                let (low, high) = if low <= high {
+                    (low, high)
+                } else {
+                    (high, low)
+                };
+                matched |= (low..=high).contains(&c);
+                i += 3;
+            }
+            other => {
+                matched |= other == c;
+                i += 1;
+            }
+        }
+    }
+
+    Some((matched != negate, i + 1))
+}
+
+#[cfg(test)]
+mod glob_tests {
+    use super::*;
+
+    #[test]
+    fn matches_literals_and_wildcards() {
+        assert!(glob_match("user:1000", "user:1000"));
+        assert!(!glob_match("user:1000", "user:100"));
+
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("user:*", "user:1000"));
+        assert!(glob_match("user:*", "user:"));
+        assert!(!glob_match("user:*", "session:1000"));
+        assert!(glob_match("*:1000", "user:1000"));
+        assert!(glob_match("u*r:*0", "user:1000"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+
+        assert!(glob_match("h?llo", "hello"));
+        assert!(glob_match("h?llo", "hallo"));
+        assert!(!glob_match("h?llo", "hllo"));
+    }
+
+    #[test]
+    fn matches_character_classes() {
+        assert!(glob_match("h[ae]llo", "hello"));
+        assert!(glob_match("h[ae]llo", "hallo"));
+        assert!(!glob_match("h[ae]llo", "hillo"));
+
+        assert!(glob_match("h[^e]llo", "hallo"));
+        assert!(!glob_match("h[^e]llo", "hello"));
+
+        assert!(glob_match("key-[0-9]", "key-5"));
+        assert!(glob_match("key-[9-0]", "key-5"));
+        assert!(!glob_match("key-[0-9]", "key-a"));
+        assert!(glob_match("key-[a-]", "key--"));
+    }
+
+    #[test]
+    fn matches_escaped_characters() {
+        assert!(glob_match("what\\?", "what?"));
+        assert!(!glob_match("what\\?", "whats"));
+        assert!(glob_match("\\*", "*"));
+        assert!(!glob_match("\\*", "anything"));
+        assert!(glob_match("[\\]]", "]"));
+
+        // Unclosed classes are matched literally
+        assert!(glob_match("key[", "key["));
+    }
+}
@@ -0,0 +1,827 @@
+//! Bitmap operations for the [`MemStore`]
+//!
+//! Bitmaps are not a separate type but bit-level operations on values in the string store.
+//! Bits are numbered from the most significant bit of the first byte, so bit `0` is the high
+//! bit of byte `0` and bit `8` is the high bit of byte `1`.
+//!
+//! Setting a bit past the end of a string grows it with zero bytes, while reading past the
+//! end (or reading a missing key) gives `0`. Byte ranges follow the same rules as list
+//! ranges, with negative indices counting from the end of the string.
+
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use crate::store::mem::list::normalise_range;
+use crate::store::mem::{Bytes, KeyType, MemStore};
+
+/// Highest bit offset which can be addressed, limiting a bitmap to 512MB
+const MAX_BIT_OFFSET: usize = u32::MAX as usize;
+
+/// Checks that the bits from `offset` up to `offset + bits` can be addressed
+fn check_offset(offset: usize, bits: usize) -> io::Result<()> {
+    match offset.checked_add(bits - 1) {
+        Some(last) if last <= MAX_BIT_OFFSET => Ok(()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "bit offset is out of range",
+        )),
+    }
+}
+
+/// Reads a single bit, treating bits past the end as `0`
+fn read_bit(bytes: &[u8], offset: usize) -> bool {
+    bytes
+        .get(offset / 8)
+        .is_some_and(|byte| byte & (0x80 >> (offset % 8)) != 0)
+}
+
+/// Writes a single bit, growing the bytes with zeroes if needed
+fn write_bit(bytes: &mut Vec<u8>, offset: usize, value: bool) {
+    let idx = offset / 8;
+    if idx >= bytes.len() {
+        bytes.resize(idx + 1, 0);
+    }
+
+    let mask = 0x80 >> (offset % 8);
+    if value {
+        bytes[idx] |= mask;
+    } else {
+        bytes[idx] &= !mask;
+    }
+}
+
+/// Bitwise operations used to combine strings with [`MemStore::bitop`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BitOp {
+    /// Bitwise AND of every source
+    And,
+
+    /// Bitwise OR of every source
+    Or,
+
+    /// Bitwise XOR of every source
+    Xor,
+
+    /// Bitwise NOT of a single source
+    Not,
+}
+
+impl FromStr for BitOp {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "AND" => Ok(Self::And),
+            "OR" => Ok(Self::Or),
+            "XOR" => Ok(Self::Xor),
+            "NOT" => Ok(Self::Not),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown bit operation: {}", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BitOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::And => write!(f, "AND"),
+            Self::Or => write!(f, "OR"),
+            Self::Xor => write!(f, "XOR"),
+            Self::Not => write!(f, "NOT"),
+        }
+    }
+}
+
+/// Integer type of a field operated on by [`MemStore::bitfield`]
+///
+/// Written as `i<bits>` for signed integers of up to 64 bits and `u<bits>` for unsigned
+/// integers of up to 63 bits, e.g. `i8` or `u16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BitFieldType {
+    /// Signed integer of the given number of bits
+    Signed(u8),
+
+    /// Unsigned integer of the given number of bits
+    Unsigned(u8),
+}
+
+impl BitFieldType {
+    /// Number of bits in the field
+    pub fn bits(&self) -> usize {
+        match self {
+            Self::Signed(bits) | Self::Unsigned(bits) => *bits as usize,
+        }
+    }
+
+    /// Smallest and largest values the field can hold
+    fn bounds(&self) -> (i128, i128) {
+        match self {
+            Self::Signed(bits) => (-(1 << (bits - 1)), (1 << (bits - 1)) - 1),
+            Self::Unsigned(bits) => (0, (1 << bits) - 1),
+        }
+    }
+
+    /// Reads the field at a bit offset
+    fn read(&self, bytes: &[u8], offset: usize) -> i64 {
+        let raw = (0..self.bits()).fold(0i128, |value, bit| {
+            (value << 1) | read_bit(bytes, offset + bit) as i128
+        });
+
+        let (_, max) = self.bounds();
+        match self {
+            Self::Signed(bits) if raw > max => (raw - (1 << bits)) as i64,
+            _ => raw as i64,
+        }
+    }
+
+    /// Writes the field at a bit offset, the value must be within the bounds of the field
+    fn write(&self, bytes: &mut Vec<u8>, offset: usize, value: i128) {
+        let bits = self.bits();
+        for bit in 0..bits {
+            write_bit(bytes, offset + bit, (value >> (bits - 1 - bit)) & 1 == 1);
+        }
+    }
+
+    /// Fits a value into the bounds of the field according to the overflow behaviour,
+    /// `None` if the value does not fit and overflow fails
+    fn fit(&self, value: i128, overflow: BitFieldOverflow) -> Option<i128> {
+        let (min, max) = self.bounds();
+        if (min..=max).contains(&value) {
+            return Some(value);
+        }
+
+        match overflow {
+            BitFieldOverflow::Wrap => Some(min + (value - min).rem_euclid(1 << self.bits())),
+            BitFieldOverflow::Sat => Some(value.clamp(min, max)),
+            BitFieldOverflow::Fail => None,
+        }
+    }
+}
+
+impl FromStr for BitFieldType {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid bitfield type: {}", s),
+            )
+        };
+
+        let (signed, bits) = match s.split_at_checked(1) {
+            Some(("i" | "I", bits)) => (true, bits),
+            Some(("u" | "U", bits)) => (false, bits),
+            _ => return Err(invalid()),
+        };
+
+        match (signed, bits.parse::<u8>().map_err(|_| invalid())?) {
+            (true, bits @ 1..=64) => Ok(Self::Signed(bits)),
+            (false, bits @ 1..=63) => Ok(Self::Unsigned(bits)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for BitFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Signed(bits) => write!(f, "i{}", bits),
+            Self::Unsigned(bits) => write!(f, "u{}", bits),
+        }
+    }
+}
+
+/// Behaviour of [`MemStore::bitfield`] writes which overflow their field
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BitFieldOverflow {
+    /// Wrap around, as with two's complement arithmetic
+    #[default]
+    Wrap,
+
+    /// Saturate at the smallest or largest value of the field
+    Sat,
+
+    /// Skip the write and return `None`
+    Fail,
+}
+
+impl FromStr for BitFieldOverflow {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "WRAP" => Ok(Self::Wrap),
+            "SAT" => Ok(Self::Sat),
+            "FAIL" => Ok(Self::Fail),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown overflow behaviour: {}", s),
+            )),
+        }
+    }
+}
+
+/// A subcommand of [`MemStore::bitfield`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BitFieldOp {
+    /// Read the field at a bit offset
+    Get { field: BitFieldType, offset: usize },
+
+    /// Write the field at a bit offset, returning the previous value
+    Set {
+        field: BitFieldType,
+        offset: usize,
+        value: i64,
+    },
+
+    /// Increment the field at a bit offset, returning the new value
+    IncrBy {
+        field: BitFieldType,
+        offset: usize,
+        increment: i64,
+    },
+
+    /// Set the overflow behaviour of the following writes
+    Overflow(BitFieldOverflow),
+}
+
+impl BitFieldOp {
+    /// Parses a sequence of subcommands, as sent to the `BITFIELD` command
+    ///
+    /// Offsets prefixed with `#` are multiplied by the width of the field, so `#2` addresses
+    /// the third field of that type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{BitFieldOp, BitFieldOverflow, BitFieldType};
+    ///
+    /// let ops = BitFieldOp::parse(&["OVERFLOW", "SAT", "INCRBY", "u8", "#2", "10"]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     ops,
+    ///     vec![
+    ///         BitFieldOp::Overflow(BitFieldOverflow::Sat),
+    ///         BitFieldOp::IncrBy { field: BitFieldType::Unsigned(8), offset: 16, increment: 10 },
+    ///     ]
+    /// );
+    /// ```
+    pub fn parse(args: &[impl AsRef<str>]) -> io::Result<Vec<Self>> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+
+        let mut args = args.iter().map(AsRef::as_ref);
+        let mut next = |name: &str| {
+            args.next()
+                .ok_or_else(|| invalid(format!("missing argument to {}", name)))
+        };
+
+        let mut ops = vec![];
+        while let Ok(subcommand) = next("") {
+            let op = match subcommand.to_uppercase().as_str() {
+                "OVERFLOW" => Self::Overflow(next("OVERFLOW")?.parse()?),
+                name @ ("GET" | "SET" | "INCRBY") => {
+                    let field: BitFieldType = next(name)?.parse()?;
+                    let offset = parse_offset(next(name)?, field)?;
+
+                    match name {
+                        "GET" => Self::Get { field, offset },
+                        _ => {
+                            let arg = next(name)?;
+                            let value = arg
+                                .parse::<i64>()
+                                .map_err(|_| invalid(format!("invalid argument: {}", arg)))?;
+
+                            if name == "SET" {
+                                Self::Set {
+                                    field,
+                                    offset,
+                                    value,
+                                }
+                            } else {
+                                Self::IncrBy {
+                                    field,
+                                    offset,
+                                    increment: value,
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => return Err(invalid(format!("unknown subcommand: {}", subcommand))),
+            };
+
+            ops.push(op);
+        }
+
+        Ok(ops)
+    }
+
+    /// Checks if the subcommand writes to the string
+    fn is_write(&self) -> bool {
+        matches!(self, Self::Set { .. } | Self::IncrBy { .. })
+    }
+}
+
+/// Parses a bitfield offset, multiplying offsets prefixed with `#` by the width of the field
+fn parse_offset(arg: &str, field: BitFieldType) -> io::Result<usize> {
+    let (multiplier, value) = match arg.strip_prefix('#') {
+        Some(value) => (field.bits(), value),
+        None => (1, arg),
+    };
+
+    let offset = value
+        .parse::<usize>()
+        .ok()
+        .and_then(|offset| offset.checked_mul(multiplier))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid bit offset: {}", arg),
+            )
+        })?;
+
+    check_offset(offset, field.bits())?;
+
+    Ok(offset)
+}
+
+impl MemStore {
+    /// Sets or clears the bit at an offset in a string, returning the previous bit
+    ///
+    /// The string is created if it is not present and grown with zero bytes if the offset is
+    /// past its end.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the offset is above `2^32 - 1`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// // Mark users 7 and 1000 as active today
+    /// assert!(!ms.setbit("active:2024-01-01", 7, true).unwrap());
+    /// ms.setbit("active:2024-01-01", 1000, true).unwrap();
+    ///
+    /// assert!(ms.getbit("active:2024-01-01", 7).unwrap());
+    /// assert_eq!(ms.get_bytes("active:2024-01-01").unwrap().unwrap().len(), 126);
+    /// ```
+    pub fn setbit(&mut self, key: &str, offset: usize, value: bool) -> io::Result<bool> {
+        check_offset(offset, 1)?;
+        self.check_type(key, KeyType::String)?;
+        self.reserve_memory()?;
+
+        let bytes = self.strings.entry(key);
+        let previous = read_bit(bytes, offset);
+        write_bit(bytes, offset, value);
+
+        Ok(previous)
+    }
+
+    /// Gets the bit at an offset in a string
+    ///
+    /// Bits past the end of the string, or in a string which is not present, are `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_bytes("flags", &[0b1000_0000]).unwrap();
+    ///
+    /// assert!(ms.getbit("flags", 0).unwrap());
+    /// assert!(!ms.getbit("flags", 1).unwrap());
+    /// assert!(!ms.getbit("flags", 100).unwrap());
+    /// ```
+    pub fn getbit(&self, key: &str, offset: usize) -> io::Result<bool> {
+        Ok(self
+            .strings
+            .get(key)
+            .is_some_and(|bytes| read_bit(bytes, offset)))
+    }
+
+    /// Counts the set bits in a string, optionally within an inclusive range of bytes
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("key", "foobar").unwrap();
+    ///
+    /// assert_eq!(ms.bitcount("key", None).unwrap(), 26);
+    /// assert_eq!(ms.bitcount("key", Some((1, 1))).unwrap(), 6);
+    /// assert_eq!(ms.bitcount("key", Some((-2, -1))).unwrap(), 7);
+    /// ```
+    pub fn bitcount(&self, key: &str, range: Option<(isize, isize)>) -> io::Result<usize> {
+        let Some(bytes) = self.strings.get(key) else {
+            return Ok(0);
+        };
+
+        let (start, stop) = range.unwrap_or((0, -1));
+        let count = match normalise_range(start, stop, bytes.len()) {
+            Some((start, stop)) => bytes[start..=stop]
+                .iter()
+                .map(|byte| byte.count_ones() as usize)
+                .sum(),
+            None => 0,
+        };
+
+        Ok(count)
+    }
+
+    /// Finds the position of the first bit set to `bit` in a string, optionally within an
+    /// inclusive range of bytes
+    ///
+    /// Positions are counted from the start of the string, not the start of the range.
+    ///
+    /// # Returns
+    ///
+    /// * The position of the first matching bit, `None` if there is no matching bit
+    /// * When searching for a clear bit without an `end`, bits past the end of the string
+    ///   count as clear, so the position after the last byte is returned if every bit is set
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_bytes("key", &[0xff, 0xf0, 0x00]).unwrap();
+    ///
+    /// assert_eq!(ms.bitpos("key", false, None, None).unwrap(), Some(12));
+    /// assert_eq!(ms.bitpos("key", true, Some(2), None).unwrap(), None);
+    ///
+    /// ms.insert_bytes("full", &[0xff]).unwrap();
+    /// assert_eq!(ms.bitpos("full", false, None, None).unwrap(), Some(8));
+    /// assert_eq!(ms.bitpos("full", false, Some(0), Some(-1)).unwrap(), None);
+    /// ```
+    pub fn bitpos(
+        &self,
+        key: &str,
+        bit: bool,
+        start: Option<isize>,
+        end: Option<isize>,
+    ) -> io::Result<Option<usize>> {
+        let Some(bytes) = self.strings.get(key) else {
+            return Ok((!bit).then_some(0));
+        };
+
+        let Some((start, stop)) =
+            normalise_range(start.unwrap_or(0), end.unwrap_or(-1), bytes.len())
+        else {
+            return Ok(None);
+        };
+
+        let position = bytes[start..=stop]
+            .iter()
+            .position(|byte| if bit { *byte != 0 } else { *byte != 0xff })
+            .map(|idx| {
+                let byte = bytes[start + idx];
+                let within = if bit {
+                    byte.leading_zeros()
+                } else {
+                    byte.leading_ones()
+                };
+
+                (start + idx) * 8 + within as usize
+            });
+
+        match position {
+            None if !bit && end.is_none() => Ok(Some((stop + 1) * 8)),
+            position => Ok(position),
+        }
+    }
+
+    /// Performs a bitwise operation between strings, storing the result in `destination`
+    ///
+    /// Strings of different lengths are padded with zero bytes to the length of the longest,
+    /// with missing keys treated as empty strings. If the result is empty the destination is
+    /// removed.
+    ///
+    /// # Returns
+    ///
+    /// * The length of the string stored in `destination`
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if no keys are given, or more than one key is given
+    ///   for [`BitOp::Not`]
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{BitOp, MemStore};
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.setbit("active:monday", 1, true).unwrap();
+    /// ms.setbit("active:tuesday", 1, true).unwrap();
+    /// ms.setbit("active:tuesday", 2, true).unwrap();
+    ///
+    /// // Users active on both days
+    /// ms.bitop(BitOp::And, "active:both", &["active:monday", "active:tuesday"]).unwrap();
+    /// assert_eq!(ms.bitcount("active:both", None).unwrap(), 1);
+    /// ```
+    pub fn bitop(&mut self, op: BitOp, destination: &str, keys: &[&str]) -> io::Result<usize> {
+        if keys.is_empty() || (op == BitOp::Not && keys.len() != 1) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("wrong number of keys for BITOP {}", op),
+            ));
+        }
+
+        let sources: Vec<&[u8]> = keys
+            .iter()
+            .map(|key| self.strings.get(key).map_or(&[][..], |bytes| &bytes[..]))
+            .collect();
+        let len = sources.iter().map(|bytes| bytes.len()).max().unwrap_or(0);
+
+        let result: Vec<u8> = (0..len)
+            .map(|idx| {
+                let mut bytes = sources
+                    .iter()
+                    .map(|source| source.get(idx).copied().unwrap_or(0));
+                let first = bytes.next().unwrap_or(0);
+
+                match op {
+                    BitOp::And => bytes.fold(first, |acc, byte| acc & byte),
+                    BitOp::Or => bytes.fold(first, |acc, byte| acc | byte),
+                    BitOp::Xor => bytes.fold(first, |acc, byte| acc ^ byte),
+                    BitOp::Not => !first,
+                }
+            })
+            .collect();
+
+        if result.is_empty() {
+            self.del(&[destination])?;
+            return Ok(0);
+        }
+
+        self.reserve_memory()?;
+        self.replace_type(destination, KeyType::String);
+        self.strings.insert(destination, Bytes::from(result))?;
+
+        Ok(len)
+    }
+
+    /// Reads, writes and increments integer fields of arbitrary width at bit offsets
+    /// in a string
+    ///
+    /// Subcommands are applied in order, returning one value for each
+    /// [`BitFieldOp::Get`], [`BitFieldOp::Set`] and [`BitFieldOp::IncrBy`]:
+    ///
+    /// * `Get` returns the value of the field
+    /// * `Set` returns the previous value of the field
+    /// * `IncrBy` returns the new value of the field
+    ///
+    /// Writes which overflow their field are handled according to the last
+    /// [`BitFieldOp::Overflow`], wrapping by default. A failed write returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{BitFieldOp, MemStore};
+    ///
+    /// let mut ms = MemStore::new();
+    /// let ops = BitFieldOp::parse(&["SET", "u8", "0", "200", "INCRBY", "u8", "0", "100"]).unwrap();
+    /// assert_eq!(ms.bitfield("key", &ops).unwrap(), vec![Some(0), Some(44)]);
+    ///
+    /// let ops = BitFieldOp::parse(&["OVERFLOW", "FAIL", "INCRBY", "u8", "0", "250"]).unwrap();
+    /// assert_eq!(ms.bitfield("key", &ops).unwrap(), vec![None]);
+    /// ```
+    pub fn bitfield(&mut self, key: &str, ops: &[BitFieldOp]) -> io::Result<Vec<Option<i64>>> {
+        let writes = ops.iter().any(BitFieldOp::is_write);
+        if writes {
+            self.check_type(key, KeyType::String)?;
+            self.reserve_memory()?;
+        }
+
+        let mut bytes = self
+            .strings
+            .get(key)
+            .map(|bytes| bytes.to_vec())
+            .unwrap_or_default();
+        let mut overflow = BitFieldOverflow::default();
+        let mut results = vec![];
+
+        for op in ops {
+            let result = match *op {
+                BitFieldOp::Overflow(behaviour) => {
+                    overflow = behaviour;
+                    continue;
+                }
+                BitFieldOp::Get { field, offset } => Some(field.read(&bytes, offset)),
+                BitFieldOp::Set {
+                    field,
+                    offset,
+                    value,
+                } => field.fit(value as i128, overflow).map(|value| {
+                    let previous = field.read(&bytes, offset);
+                    field.write(&mut bytes, offset, value);
+                    previous
+                }),
+                BitFieldOp::IncrBy {
+                    field,
+                    offset,
+                    increment,
+                } => {
+                    let current = field.read(&bytes, offset) as i128;
+                    field
+                        .fit(current + increment as i128, overflow)
+                        .map(|value| {
+                            field.write(&mut bytes, offset, value);
+                            value as i64
+                        })
+                }
+            };
+
+            results.push(result);
+        }
+
+        if writes {
+            *self.strings.entry(key) = Bytes::from(bytes);
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod bitmap_tests {
+    use super::*;
+
+    #[test]
+    fn sets_and_gets_bits() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        assert!(!ms.setbit("bits", 0, true)?);
+        assert!(!ms.setbit("bits", 9, true)?);
+        assert!(ms.setbit("bits", 9, false)?);
+        assert!(ms.setbit("bits", 0, true)?);
+
+        assert_eq!(ms.get_bytes("bits")?, Some(vec![0b1000_0000, 0]));
+        assert!(ms.getbit("bits", 0)?);
+        assert!(!ms.getbit("bits", 9)?);
+        assert!(!ms.getbit("bits", 1000)?);
+        assert!(!ms.getbit("missing", 0)?);
+
+        let err = ms.setbit("bits", MAX_BIT_OFFSET + 1, true).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        ms.rpush("list", &["a"])?;
+        assert!(ms.setbit("list", 0, true).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn counts_and_finds_bits() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_bytes("key", &[0x00, 0x0f, 0xff])?;
+
+        assert_eq!(ms.bitcount("key", None)?, 12);
+        assert_eq!(ms.bitcount("key", Some((0, 1)))?, 4);
+        assert_eq!(ms.bitcount("key", Some((5, 10)))?, 0);
+        assert_eq!(ms.bitcount("missing", None)?, 0);
+
+        assert_eq!(ms.bitpos("key", true, None, None)?, Some(12));
+        assert_eq!(ms.bitpos("key", true, Some(2), None)?, Some(16));
+        assert_eq!(ms.bitpos("key", false, None, None)?, Some(0));
+        assert_eq!(ms.bitpos("key", false, Some(-1), None)?, Some(24));
+        assert_eq!(ms.bitpos("key", false, Some(-1), Some(-1))?, None);
+        assert_eq!(ms.bitpos("key", true, Some(0), Some(0))?, None);
+
+        assert_eq!(ms.bitpos("missing", true, None, None)?, None);
+        assert_eq!(ms.bitpos("missing", false, None, None)?, Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn combines_strings() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_bytes("a", &[0b1100, 0xff])?;
+        ms.insert_bytes("b", &[0b1010])?;
+
+        assert_eq!(ms.bitop(BitOp::And, "and", &["a", "b"])?, 2);
+        assert_eq!(ms.get_bytes("and")?, Some(vec![0b1000, 0]));
+
+        ms.bitop(BitOp::Or, "or", &["a", "b"])?;
+        assert_eq!(ms.get_bytes("or")?, Some(vec![0b1110, 0xff]));
+
+        ms.bitop(BitOp::Xor, "xor", &["a", "b", "missing"])?;
+        assert_eq!(ms.get_bytes("xor")?, Some(vec![0b0110, 0xff]));
+
+        ms.bitop(BitOp::Not, "not", &["b"])?;
+        assert_eq!(ms.get_bytes("not")?, Some(vec![0b1111_0101]));
+
+        assert!(ms.bitop(BitOp::Not, "not", &["a", "b"]).is_err());
+        assert!(ms.bitop(BitOp::And, "and", &[]).is_err());
+
+        // An empty result removes the destination, whatever it held
+        ms.rpush("list", &["a"])?;
+        assert_eq!(ms.bitop(BitOp::Or, "list", &["missing"])?, 0);
+        assert_eq!(ms.key_type("list")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_bitfield_subcommands() -> io::Result<()> {
+        let ops = BitFieldOp::parse(&[
+            "get", "i5", "#3", "OVERFLOW", "fail", "SET", "u4", "2", "-1",
+        ])?;
+        assert_eq!(
+            ops,
+            vec![
+                BitFieldOp::Get {
+                    field: BitFieldType::Signed(5),
+                    offset: 15
+                },
+                BitFieldOp::Overflow(BitFieldOverflow::Fail),
+                BitFieldOp::Set {
+                    field: BitFieldType::Unsigned(4),
+                    offset: 2,
+                    value: -1
+                },
+            ]
+        );
+
+        assert!(BitFieldOp::parse(&["GET", "u64", "0"]).is_err());
+        assert!(BitFieldOp::parse(&["GET", "i65", "0"]).is_err());
+        assert!(BitFieldOp::parse(&["GET", "x8", "0"]).is_err());
+        assert!(BitFieldOp::parse(&["GET", "i8"]).is_err());
+        assert!(BitFieldOp::parse(&["SET", "i8", "0", "value"]).is_err());
+        assert!(BitFieldOp::parse(&["OVERFLOW", "NEVER"]).is_err());
+        assert!(BitFieldOp::parse(&["DEL", "i8", "0"]).is_err());
+        assert!(BitFieldOp::parse(&["GET", "i8", "4294967290"]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_and_writes_bitfields() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_bytes("key", &[0b1111_0000])?;
+
+        let ops = BitFieldOp::parse(&["GET", "u4", "0", "GET", "i4", "0", "GET", "u8", "4"])?;
+        assert_eq!(ms.bitfield("key", &ops)?, vec![Some(15), Some(-1), Some(0)]);
+
+        let ops = BitFieldOp::parse(&["SET", "i64", "8", "-2", "GET", "i64", "8"])?;
+        assert_eq!(ms.bitfield("key", &ops)?, vec![Some(0), Some(-2)]);
+        assert_eq!(ms.get_bytes("key")?.map(|bytes| bytes.len()), Some(9));
+
+        // Reads do not create the key
+        let ops = BitFieldOp::parse(&["GET", "u8", "0"])?;
+        assert_eq!(ms.bitfield("missing", &ops)?, vec![Some(0)]);
+        assert_eq!(ms.get_bytes("missing")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn handles_bitfield_overflow() -> io::Result<()> {
+        let mut ms = MemStore::new();
+
+        let ops = BitFieldOp::parse(&[
+            "INCRBY", "i8", "0", "130", "OVERFLOW", "SAT", "INCRBY", "i8", "0", "-200", "INCRBY",
+            "u4", "8", "20", "OVERFLOW", "FAIL", "INCRBY", "u4", "8", "1", "SET", "u4", "8", "3",
+        ])?;
+        assert_eq!(
+            ms.bitfield("key", &ops)?,
+            vec![Some(-126), Some(-128), Some(15), None, Some(15)]
+        );
+
+        let ops = BitFieldOp::parse(&["GET", "u4", "8"])?;
+        assert_eq!(ms.bitfield("key", &ops)?, vec![Some(3)]);
+
+        // Wrapping also applies to values written with SET
+        let ops = BitFieldOp::parse(&["SET", "u8", "0", "257", "GET", "u8", "0"])?;
+        assert_eq!(ms.bitfield("key", &ops)?, vec![Some(128), Some(1)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bitmaps_keep_expiry() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.setbit("key", 0, true)?;
+        ms.expire("key", 60)?;
+
+        ms.setbit("key", 10, true)?;
+        ms.bitfield("key", &BitFieldOp::parse(&["SET", "u8", "0", "1"])?)?;
+        assert_eq!(ms.ttl("key")?, 60);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+//! Binary-safe values for the string store
+//!
+//! Strings are stored as raw bytes so bitmaps and other binary data can be held alongside
+//! text. Values which are valid UTF-8 are serialized as JSON strings, keeping stores written
+//! before values were binary-safe loadable, while any other value is serialized as an array
+//! of bytes.
+
+use std::fmt;
+use std::io;
+use std::mem::size_of;
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::store::MemoryUsage;
+
+/// A binary-safe string value
+///
+/// # Example
+///
+/// ```rust
+/// use rubin::store::mem::Bytes;
+///
+/// let text = Bytes::from("value");
+/// assert_eq!(text.as_str(), Some("value"));
+///
+/// let binary = Bytes::from(vec![0xff, 0x00]);
+/// assert_eq!(binary.as_str(), None);
+/// assert_eq!(binary.len(), 2);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Bytes(Vec<u8>);
+
+impl Bytes {
+    /// Gets the value as a string slice, `None` if it is not valid UTF-8
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.0).ok()
+    }
+
+    /// Converts the value into a `String`
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the value is not valid UTF-8
+    pub fn into_string(self) -> io::Result<String> {
+        String::from_utf8(self.0)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "value is not valid UTF-8"))
+    }
+
+    /// Converts the value into its bytes
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl Deref for Bytes {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Bytes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<&str> for Bytes {
+    fn from(value: &str) -> Self {
+        Self(value.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for Bytes {
+    fn from(value: String) -> Self {
+        Self(value.into_bytes())
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(value: &[u8]) -> Self {
+        Self(value.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+/// Displays the value as text, replacing any invalid UTF-8 sequences
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.0))
+    }
+}
+
+impl MemoryUsage for Bytes {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.0.capacity()
+    }
+}
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.as_str() {
+            Some(text) => serializer.serialize_str(text),
+            None => self.0.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Text(String),
+            Binary(Vec<u8>),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Text(text) => Self::from(text),
+            Repr::Binary(bytes) => Self(bytes),
+        })
+    }
+}
+
+#[cfg(test)]
+mod bytes_tests {
+    use super::*;
+
+    #[test]
+    fn serializes_text_as_strings() -> io::Result<()> {
+        let text = Bytes::from("value");
+        assert_eq!(serde_json::to_string(&text)?, "\"value\"");
+        assert_eq!(serde_json::from_str::<Bytes>("\"value\"")?, text);
+
+        let binary = Bytes::from(vec![0xff, 0x00, 0x41]);
+        let contents = serde_json::to_string(&binary)?;
+        assert_eq!(contents, "[255,0,65]");
+        assert_eq!(serde_json::from_str::<Bytes>(&contents)?, binary);
+
+        Ok(())
+    }
+
+    #[test]
+    fn converts_to_strings() {
+        assert_eq!(Bytes::from("value").into_string().unwrap(), "value");
+
+        let err = Bytes::from(vec![0xff]).into_string().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(Bytes::from(vec![0x41, 0xff]).to_string(), "A\u{fffd}");
+    }
+}
@@ -0,0 +1,229 @@
+//! Counter arithmetic operations for the [`MemStore`]
+//!
+//! Integer counters are kept in the counter store and use checked arithmetic, so an
+//! increment which would overflow is rejected with an error and leaves the counter unchanged.
+//!
+//! Floating point increments operate on the string store, as the counter store only holds
+//! integers.
+
+use std::io;
+
+use crate::store::mem::{KeyType, MemStore};
+
+/// Error returned when an increment or decrement would overflow the counter
+fn overflow_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "increment or decrement would overflow",
+    )
+}
+
+impl MemStore {
+    /// Increments the value of a given key by an amount
+    ///
+    /// Missing keys are treated as `0` before the increment.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the increment would overflow the counter
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// assert_eq!(ms.incrby("requests", 50).unwrap(), 50);
+    /// assert_eq!(ms.incrby("requests", 25).unwrap(), 75);
+    ///
+    /// assert!(ms.incrby("requests", isize::MAX).is_err());
+    /// ```
+    pub fn incrby(&mut self, key: impl AsRef<str>, increment: isize) -> io::Result<isize> {
+        let key = key.as_ref();
+        self.check_type(key, KeyType::Counter)?;
+        self.reserve_memory()?;
+
+        let current = self.counters.get(key).copied().unwrap_or(0);
+        let count = current.checked_add(increment).ok_or_else(overflow_error)?;
+        *self.counters.entry(key) = count;
+
+        Ok(count)
+    }
+
+    /// Decrements the value of a given key by an amount
+    ///
+    /// Missing keys are treated as `0` before the decrement.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the decrement would overflow the counter
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.incrby("stock", 100).unwrap();
+    ///
+    /// assert_eq!(ms.decrby("stock", 30).unwrap(), 70);
+    /// ```
+    pub fn decrby(&mut self, key: impl AsRef<str>, decrement: isize) -> io::Result<isize> {
+        let key = key.as_ref();
+        self.check_type(key, KeyType::Counter)?;
+        self.reserve_memory()?;
+
+        let current = self.counters.get(key).copied().unwrap_or(0);
+        let count = current.checked_sub(decrement).ok_or_else(overflow_error)?;
+        *self.counters.entry(key) = count;
+
+        Ok(count)
+    }
+
+    /// Increments the floating point value of a string by an amount
+    ///
+    /// Missing keys are treated as `0` before the increment. The result is stored back into
+    /// the string store, so it can be read with [`MemStore::get_string`].
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the stored value is not a number or the result
+    ///   is not finite
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("temperature", "10.5").unwrap();
+    ///
+    /// assert_eq!(ms.incrbyfloat("temperature", 0.25).unwrap(), 10.75);
+    /// assert_eq!(ms.get_string("temperature").unwrap().as_deref(), Some("10.75"));
+    /// ```
+    pub fn incrbyfloat(&mut self, key: &str, increment: f64) -> io::Result<f64> {
+        self.check_type(key, KeyType::String)?;
+        self.reserve_memory()?;
+
+        let current = match self.strings.get(key) {
+            Some(value) => value
+                .as_str()
+                .and_then(|value| value.parse::<f64>().ok())
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "value is not a valid float")
+                })?,
+            None => 0.0,
+        };
+
+        let value = current + increment;
+        if !value.is_finite() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "increment would produce NaN or Infinity",
+            ));
+        }
+
+        *self.strings.entry(key) = value.to_string().into();
+
+        Ok(value)
+    }
+
+    /// Sets the value of a counter, returning the previous value
+    ///
+    /// Any expiry on the counter is removed.
+    ///
+    /// # Returns
+    ///
+    /// * The previous value of the counter, `None` if the key was not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.incrby("requests", 120).unwrap();
+    ///
+    /// // Read and reset the counter in a single operation
+    /// assert_eq!(ms.getset("requests", 0).unwrap(), Some(120));
+    /// assert_eq!(ms.getset("missing", 0).unwrap(), None);
+    /// ```
+    pub fn getset(&mut self, key: &str, value: isize) -> io::Result<Option<isize>> {
+        self.check_type(key, KeyType::Counter)?;
+        self.reserve_memory()?;
+
+        let previous = self.counters.get(key).copied();
+        self.counters.insert(key, value)?;
+
+        Ok(previous)
+    }
+}
+
+#[cfg(test)]
+mod counter_tests {
+    use super::*;
+
+    #[test]
+    fn increments_and_decrements_by_amounts() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        assert_eq!(ms.incrby("counter", 10)?, 10);
+        assert_eq!(ms.incrby("counter", -3)?, 7);
+        assert_eq!(ms.decrby("counter", 10)?, -3);
+        assert_eq!(ms.decrby("counter", -5)?, 2);
+        assert_eq!(ms.counters.retrieve("counter")?, Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_overflow() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.incrby("counter", isize::MAX)?;
+
+        let err = ms.incr("counter").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(ms.incrby("counter", 1).is_err());
+        assert_eq!(ms.counters.retrieve("counter")?, Some(isize::MAX));
+
+        ms.getset("counter", isize::MIN)?;
+        assert!(ms.decr("counter").is_err());
+        assert!(ms.decrby("counter", 1).is_err());
+        assert!(ms.incrby("counter", isize::MIN).is_err());
+        assert_eq!(ms.counters.retrieve("counter")?, Some(isize::MIN));
+
+        Ok(())
+    }
+
+    #[test]
+    fn increments_floats() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        assert_eq!(ms.incrbyfloat("float", 1.5)?, 1.5);
+        assert_eq!(ms.incrbyfloat("float", -0.5)?, 1.0);
+        assert_eq!(ms.get_string("float")?.as_deref(), Some("1"));
+
+        ms.insert_string("text", "value")?;
+        assert!(ms.incrbyfloat("text", 1.0).is_err());
+
+        let err = ms.incrbyfloat("float", f64::INFINITY).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(ms.get_string("float")?.as_deref(), Some("1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn increments_keep_expiry() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.incrby("counter", 5)?;
+        ms.expire("counter", 60)?;
+
+        ms.incrby("counter", 5)?;
+        assert_eq!(ms.ttl("counter")?, 60);
+
+        assert_eq!(ms.getset("counter", 0)?, Some(10));
+        assert_eq!(ms.ttl("counter")?, -1);
+
+        Ok(())
+    }
+}
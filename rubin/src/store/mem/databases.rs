@@ -0,0 +1,405 @@
+//! Numbered logical databases, each an independent [`MemStore`]
+//!
+//! Keys in one database never collide with keys in another, letting separate tenants share a
+//! single server. Every database has the same memory limit and eviction policy, applied to
+//! each database separately.
+//!
+//! Each database is written to its own file in a directory (see [`Databases::dump`]), so a
+//! single database can be inspected or restored without touching the others.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::store::mem::{EvictionPolicy, MemStore};
+use crate::store::persistence::file_handling::{backup_path, read_store_sync, write_encoded_sync};
+use crate::store::persistence::StoreFormat;
+
+/// Number of databases created by default
+pub const DEFAULT_DATABASES: usize = 16;
+
+/// A fixed number of numbered [`MemStore`]s
+///
+/// # Example
+///
+/// ```rust
+/// use rubin::store::mem::Databases;
+///
+/// let mut dbs = Databases::new(4);
+/// dbs.get_mut(0).unwrap().insert_string("user:1000", "test").unwrap();
+/// dbs.get_mut(1).unwrap().insert_string("user:1000", "staging").unwrap();
+///
+/// let value = dbs.get(1).unwrap().get_string("user:1000").unwrap();
+/// assert_eq!(value.as_deref(), Some("staging"));
+/// ```
+pub struct Databases {
+    /// Store for each database, indexed by number
+    stores: Vec<MemStore>,
+}
+
+/// Encoded contents of every database, taken by [`Databases::snapshot`]
+///
+/// A snapshot can be written out to disk after the databases have been released, so updates
+/// are not held up while the files are written.
+pub struct DatabasesSnapshot {
+    /// Encoded contents of each database, `None` for an empty database
+    files: Vec<Option<Vec<u8>>>,
+}
+
+/// Path of the file a database is written to within a directory
+fn database_file(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("db-{}.json", index))
+}
+
+/// Checks if a store holds no keys
+fn is_empty(store: &MemStore) -> bool {
+    store
+        .keyed_stores()
+        .iter()
+        .all(|inner| inner.keys().next().is_none())
+}
+
+impl Databases {
+    /// Creates the given number of empty databases, at least one is always created
+    pub fn new(count: usize) -> Self {
+        let stores = (0..count.max(1)).map(|_| MemStore::new()).collect();
+        Self { stores }
+    }
+
+    /// Gets the number of databases
+    pub fn len(&self) -> usize {
+        self.stores.len()
+    }
+
+    /// Always `false`, as there is at least one database
+    pub fn is_empty(&self) -> bool {
+        self.stores.is_empty()
+    }
+
+    /// Checks that a database index is in range
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if there is no database with the index
+    pub fn check_index(&self, index: usize) -> io::Result<()> {
+        if index >= self.stores.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "database index is out of range",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Gets a database by index
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if there is no database with the index
+    pub fn get(&self, index: usize) -> io::Result<&MemStore> {
+        self.check_index(index)?;
+        Ok(&self.stores[index])
+    }
+
+    /// Gets a database by index mutably
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if there is no database with the index
+    pub fn get_mut(&mut self, index: usize) -> io::Result<&mut MemStore> {
+        self.check_index(index)?;
+        Ok(&mut self.stores[index])
+    }
+
+    /// Sets the memory limit and eviction policy of every database
+    pub fn set_max_memory(&mut self, max_memory: Option<usize>, policy: EvictionPolicy) {
+        for store in &mut self.stores {
+            store.set_max_memory(max_memory, policy);
+        }
+    }
+
+    /// Swaps the contents of two databases
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::Databases;
+    ///
+    /// let mut dbs = Databases::new(2);
+    /// dbs.get_mut(1).unwrap().insert_string("release", "v2").unwrap();
+    ///
+    /// dbs.swap(0, 1).unwrap();
+    /// assert_eq!(dbs.get(0).unwrap().get_string("release").unwrap().as_deref(), Some("v2"));
+    /// assert_eq!(dbs.get(1).unwrap().get_string("release").unwrap(), None);
+    /// ```
+    pub fn swap(&mut self, first: usize, second: usize) -> io::Result<()> {
+        self.check_index(first)?;
+        self.check_index(second)?;
+        self.stores.swap(first, second);
+
+        Ok(())
+    }
+
+    /// Moves a key with its value and expiry from one database to another
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the key was moved, `false` if it is not present in the source database or
+    ///   is already present in the target database
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if either database does not exist or they are the
+    ///   same database
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::Databases;
+    ///
+    /// let mut dbs = Databases::new(2);
+    /// dbs.get_mut(0).unwrap().insert_string("user:1000", "value").unwrap();
+    ///
+    /// assert!(dbs.move_key("user:1000", 0, 1).unwrap());
+    /// assert_eq!(dbs.get(0).unwrap().get_string("user:1000").unwrap(), None);
+    /// assert!(!dbs.move_key("user:1000", 0, 1).unwrap());
+    /// ```
+    pub fn move_key(&mut self, key: &str, source: usize, target: usize) -> io::Result<bool> {
+        self.check_index(source)?;
+        self.check_index(target)?;
+        if source == target {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "source and target databases are the same",
+            ));
+        }
+
+        // Split the stores so both databases can be borrowed mutably at once
+        let (low, high) = self.stores.split_at_mut(source.max(target));
+        let (source, target) = if source < target {
+            (&mut low[source], &mut high[0])
+        } else {
+            (&mut high[0], &mut low[target])
+        };
+
+        source.move_key(key, target)
+    }
+
+    /// Removes every key from every database
+    pub fn flush_all(&mut self) {
+        for store in &mut self.stores {
+            store.flush();
+        }
+    }
+
+    /// Removes expired keys from every database, returning the number removed
+    pub fn purge_expired(&mut self) -> usize {
+        self.stores.iter_mut().map(MemStore::purge_expired).sum()
+    }
+
+    /// Removes up to `limit` expired keys from each database, returning the number removed
+    pub fn purge_expired_batch(&mut self, limit: usize) -> usize {
+        self.stores
+            .iter_mut()
+            .map(|store| store.purge_expired_batch(limit))
+            .sum()
+    }
+
+    /// Encodes the contents of every database so they can be written out later
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::mem::Databases;
+    ///
+    /// let mut dbs = Databases::new(16);
+    /// dbs.get_mut(3).unwrap().insert_string("user:1000", "value").unwrap();
+    ///
+    /// let snapshot = dbs.snapshot().unwrap();
+    /// dbs.get_mut(3).unwrap().insert_string("user:1000", "changed").unwrap();
+    ///
+    /// // Writes `value` rather than `changed`
+    /// snapshot.write("./storage").unwrap();
+    /// ```
+    pub fn snapshot(&self) -> io::Result<DatabasesSnapshot> {
+        let files = self
+            .stores
+            .iter()
+            .map(|store| {
+                (!is_empty(store))
+                    .then(|| StoreFormat::Json.encode(store))
+                    .transpose()
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(DatabasesSnapshot { files })
+    }
+
+    /// Writes each database holding keys to its own file in a directory
+    ///
+    /// Files are named `db-<index>.json`. The file of an empty database is removed, along
+    /// with its previous file, so that a flushed database stays empty when loaded.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::mem::Databases;
+    ///
+    /// let mut dbs = Databases::new(16);
+    /// dbs.get_mut(3).unwrap().insert_string("user:1000", "value").unwrap();
+    ///
+    /// dbs.dump("./storage").unwrap();
+    /// let loaded = Databases::load("./storage", 16).unwrap();
+    /// ```
+    pub fn dump(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        self.snapshot()?.write(dir)
+    }
+
+    /// Loads databases from the files written by [`Databases::dump`]
+    ///
+    /// Databases without a file are left empty. Files may hold JSON or binary snapshots,
+    /// compressed or not, and the previous file of a database is loaded if its file is
+    /// truncated or corrupt.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if neither a file nor its previous file hold a valid
+    ///   store
+    pub fn load(dir: impl AsRef<Path>, count: usize) -> io::Result<Self> {
+        let mut databases = Self::new(count);
+
+        for (index, store) in databases.stores.iter_mut().enumerate() {
+            let path = database_file(dir.as_ref(), index);
+            if let Some((loaded, _, _)) = read_store_sync(&path)? {
+                *store = loaded;
+            }
+        }
+
+        Ok(databases)
+    }
+}
+
+impl DatabasesSnapshot {
+    /// Writes each database holding keys to its own file in a directory, see
+    /// [`Databases::dump`]
+    pub fn write(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        for (index, contents) in self.files.iter().enumerate() {
+            let path = database_file(dir, index);
+            match contents {
+                Some(contents) => write_encoded_sync(&path, contents)?,
+                None => {
+                    for file in [backup_path(&path), path] {
+                        if file.exists() {
+                            std::fs::remove_file(&file)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Databases {
+    fn default() -> Self {
+        Self::new(DEFAULT_DATABASES)
+    }
+}
+
+#[cfg(test)]
+mod databases_tests {
+    use super::*;
+    use std::time::Duration;
+    use tempdir::TempDir;
+
+    #[test]
+    fn keeps_databases_separate() -> io::Result<()> {
+        let mut dbs = Databases::new(3);
+        dbs.get_mut(0)?.insert_string("key", "zero")?;
+        dbs.get_mut(2)?.rpush("key", &["two"])?;
+
+        assert_eq!(dbs.get(0)?.get_string("key")?.as_deref(), Some("zero"));
+        assert_eq!(dbs.get(1)?.exists(&["key"])?, 0);
+        assert_eq!(dbs.get(2)?.lrange("key", 0, -1)?, vec!["two"]);
+
+        let err = dbs.check_index(3).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(dbs.get(3).is_err());
+        assert!(dbs.swap(0, 3).is_err());
+        assert_eq!(Databases::new(0).len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn moves_keys_between_databases() -> io::Result<()> {
+        let mut dbs = Databases::new(3);
+        dbs.get_mut(2)?
+            .insert_string_with_expiry("session", "token", Duration::from_secs(60))?;
+        dbs.get_mut(0)?.sadd("taken", &["a"])?;
+        dbs.get_mut(2)?.insert_string("taken", "value")?;
+
+        assert!(dbs.move_key("session", 2, 0)?);
+        assert_eq!(dbs.get(0)?.get_string("session")?.as_deref(), Some("token"));
+        assert!(dbs.get(0)?.ttl("session")? > 0);
+        assert_eq!(dbs.get(2)?.exists(&["session"])?, 0);
+
+        assert!(!dbs.move_key("taken", 2, 0)?);
+        assert!(!dbs.move_key("missing", 2, 0)?);
+        assert!(dbs.move_key("taken", 2, 2).is_err());
+        assert!(dbs.move_key("taken", 2, 5).is_err());
+
+        dbs.flush_all();
+        assert_eq!(
+            dbs.get(0)?.keys("*")?.len() + dbs.get(2)?.keys("*")?.len(),
+            0
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn writes_each_database_to_its_own_file() -> io::Result<()> {
+        let td = TempDir::new("databases")?;
+        let mut dbs = Databases::new(4);
+        dbs.get_mut(1)?.insert_string("key", "one")?;
+        dbs.get_mut(3)?.incr("counter")?;
+        dbs.dump(td.path())?;
+
+        assert!(!td.path().join("db-0.json").exists());
+        assert!(td.path().join("db-1.json").exists());
+
+        let mut loaded = Databases::load(td.path(), 4)?;
+        assert_eq!(loaded.get(1)?.get_string("key")?.as_deref(), Some("one"));
+        assert_eq!(loaded.get(3)?.counters.retrieve("counter")?, Some(1));
+
+        loaded.get_mut(1)?.flush();
+        loaded.dump(td.path())?;
+        assert!(!td.path().join("db-1.json").exists());
+        assert!(!td.path().join("db-1.json.bak").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn snapshots_are_unaffected_by_later_updates() -> io::Result<()> {
+        let td = TempDir::new("databases")?;
+        let mut dbs = Databases::new(2);
+        dbs.get_mut(0)?.insert_string("key", "before")?;
+
+        let snapshot = dbs.snapshot()?;
+        dbs.get_mut(0)?.insert_string("key", "after")?;
+        dbs.get_mut(1)?.insert_string("key", "after")?;
+        snapshot.write(td.path())?;
+
+        let loaded = Databases::load(td.path(), 2)?;
+        assert_eq!(loaded.get(0)?.get_string("key")?.as_deref(), Some("before"));
+        assert_eq!(loaded.get(1)?.exists(&["key"])?, 0);
+
+        Ok(())
+    }
+}
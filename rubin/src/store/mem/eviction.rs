@@ -0,0 +1,354 @@
+//! Memory limits and key eviction for the [`MemStore`]
+//!
+//! A [`MemStore`] can be given a maximum amount of memory to use. Before each write the
+//! approximate memory usage of the store is checked and, if it is over the limit, keys are
+//! evicted according to the configured [`EvictionPolicy`] until it is back under.
+//!
+//! Memory usage is an estimate based on the size of the stored keys and values, see
+//! [`crate::store::MemoryUsage`].
+//!
+//! Like Redis, keys are evicted by an approximation of the policy rather than by comparing
+//! every key. Each key evicted is the best of a random sample of [`EVICTION_SAMPLES`] keys
+//! from each inner store, the exception being [`EvictionPolicy::VolatileTtl`] which always
+//! evicts the key closest to expiring.
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use crate::store::mem::MemStore;
+
+/// Number of keys sampled from each inner store when picking a key to evict, higher values
+/// are closer to the exact policy but slower
+const EVICTION_SAMPLES: usize = 16;
+
+/// Policy used to select which keys to evict when a [`MemStore`] is over its memory limit
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Do not evict any keys, writes are rejected while the store is over its memory limit
+    #[default]
+    NoEviction,
+
+    /// Evict the least recently used keys
+    AllKeysLru,
+
+    /// Evict the least frequently used keys, access counts decaying while a key is unused
+    AllKeysLfu,
+
+    /// Evict the keys with an expiry which are closest to expiring
+    VolatileTtl,
+}
+
+impl FromStr for EvictionPolicy {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "noeviction" => Ok(Self::NoEviction),
+            "allkeys-lru" => Ok(Self::AllKeysLru),
+            "allkeys-lfu" => Ok(Self::AllKeysLfu),
+            "volatile-ttl" => Ok(Self::VolatileTtl),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown eviction policy: {}", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EvictionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoEviction => write!(f, "noeviction"),
+            Self::AllKeysLru => write!(f, "allkeys-lru"),
+            Self::AllKeysLfu => write!(f, "allkeys-lfu"),
+            Self::VolatileTtl => write!(f, "volatile-ttl"),
+        }
+    }
+}
+
+impl MemStore {
+    /// Sets the maximum number of bytes the store can use and the policy used to evict keys
+    /// when the limit is reached
+    ///
+    /// Setting the maximum to `None` removes the limit.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{EvictionPolicy, MemStore};
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.set_max_memory(Some(1024), EvictionPolicy::AllKeysLru);
+    ///
+    /// for i in 0..100 {
+    ///     ms.insert_string(&format!("key-{}", i), "value").unwrap();
+    /// }
+    ///
+    /// assert!(ms.strings.len() < 100);
+    /// ```
+    pub fn set_max_memory(&mut self, max_memory: Option<usize>, policy: EvictionPolicy) {
+        self.max_memory = max_memory;
+        self.eviction_policy = policy;
+    }
+
+    /// Gets the approximate number of bytes used by the keys and values in the store
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// assert_eq!(ms.used_memory(), 0);
+    ///
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// assert!(ms.used_memory() > 0);
+    /// ```
+    pub fn used_memory(&mut self) -> usize {
+        self.keyed_stores_mut()
+            .into_iter()
+            .map(|store| store.used_memory())
+            .sum()
+    }
+
+    /// Ensures the store is under its memory limit before a write, evicting keys if required
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::OutOfMemory`] if the store is over its limit and not enough keys can
+    ///   be evicted under the current policy
+    pub(crate) fn reserve_memory(&mut self) -> io::Result<()> {
+        let Some(max_memory) = self.max_memory else {
+            return Ok(());
+        };
+
+        let mut used = self.used_memory();
+        if used <= max_memory {
+            return Ok(());
+        }
+
+        // Expired keys are free to remove regardless of the policy
+        self.purge_expired();
+        used = self.used_memory();
+
+        if used > max_memory {
+            used = self.evict(used - max_memory, used);
+        }
+
+        if used > max_memory {
+            return Err(io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                "command not allowed when used memory exceeds max memory",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Evicts keys under the current policy until at least `required` bytes have been freed
+    /// or no more keys can be evicted
+    ///
+    /// Returns the memory usage after eviction.
+    fn evict(&mut self, required: usize, mut used: usize) -> usize {
+        let policy = self.eviction_policy;
+        if policy == EvictionPolicy::NoEviction {
+            return used;
+        }
+
+        let stores = self.keyed_stores_mut();
+        let target = used - required;
+        while used > target {
+            let candidates = stores.iter().enumerate().flat_map(|(idx, store)| {
+                let usage = match policy {
+                    EvictionPolicy::VolatileTtl => store.soonest_expiring().into_iter().collect(),
+                    _ => store.sample_usage(EVICTION_SAMPLES),
+                };
+                usage.into_iter().map(move |usage| (idx, usage))
+            });
+
+            let evicted = match policy {
+                EvictionPolicy::AllKeysLru => candidates.min_by_key(|(_, usage)| usage.last_access),
+                EvictionPolicy::AllKeysLfu => {
+                    candidates.min_by_key(|(_, usage)| (usage.hits, usage.last_access))
+                }
+                EvictionPolicy::VolatileTtl => candidates.min_by_key(|(_, usage)| usage.expiry),
+                EvictionPolicy::NoEviction => unreachable!(),
+            };
+
+            let Some((idx, usage)) = evicted else {
+                break;
+            };
+
+            if stores[idx].delete_key(&usage.key) {
+                used -= usage.size;
+            }
+        }
+
+        used
+    }
+}
+
+#[cfg(test)]
+mod eviction_tests {
+    use super::*;
+
+    /// Fills a store with string keys `key-0` to `key-9` and sets the memory limit to the current usage
+    fn filled_store(policy: EvictionPolicy) -> io::Result<MemStore> {
+        let mut ms = MemStore::new();
+        for i in 0..10 {
+            ms.insert_string(&format!("key-{}", i), "value")?;
+        }
+
+        let limit = ms.used_memory();
+        ms.set_max_memory(Some(limit), policy);
+
+        Ok(ms)
+    }
+
+    #[test]
+    fn parse_policies() -> io::Result<()> {
+        for policy in [
+            EvictionPolicy::NoEviction,
+            EvictionPolicy::AllKeysLru,
+            EvictionPolicy::AllKeysLfu,
+            EvictionPolicy::VolatileTtl,
+        ] {
+            assert_eq!(policy.to_string().parse::<EvictionPolicy>()?, policy);
+        }
+
+        assert!("random".parse::<EvictionPolicy>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tracks_used_memory() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("list", &["a"])?;
+        let used = ms.used_memory();
+
+        ms.rpush("list", &["b", "c", "d"])?;
+        assert!(ms.used_memory() > used);
+
+        ms.lpop("list")?;
+        ms.lpop("list")?;
+        ms.lpop("list")?;
+        ms.lpop("list")?;
+        assert_eq!(ms.used_memory(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_eviction_rejects_writes() -> io::Result<()> {
+        let mut ms = filled_store(EvictionPolicy::NoEviction)?;
+        ms.insert_string("key-a", "value")?;
+
+        let err = ms.insert_string("key-b", "value").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::OutOfMemory);
+        assert_eq!(ms.strings.len(), 11);
+
+        // Removing keys is still allowed and frees up memory
+        ms.remove_string("key-a")?;
+        ms.insert_string("key-b", "value")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn evicts_least_recently_used() -> io::Result<()> {
+        let mut ms = filled_store(EvictionPolicy::AllKeysLru)?;
+        ms.get_string("key-0")?;
+
+        ms.insert_string("key-a", "value")?;
+        ms.insert_string("key-b", "value")?;
+
+        assert_eq!(ms.get_string("key-0")?.as_deref(), Some("value"));
+        assert_eq!(ms.get_string("key-1")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn evicts_least_frequently_used() -> io::Result<()> {
+        let mut ms = filled_store(EvictionPolicy::AllKeysLfu)?;
+        for i in 0..10 {
+            if i != 5 {
+                ms.get_string(&format!("key-{}", i))?;
+            }
+        }
+
+        ms.insert_string("key-a", "value")?;
+        ms.insert_string("key-b", "value")?;
+
+        assert_eq!(ms.get_string("key-5")?, None);
+        assert_eq!(ms.get_string("key-0")?.as_deref(), Some("value"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn evicts_closest_to_expiring() -> io::Result<()> {
+        let mut ms = filled_store(EvictionPolicy::VolatileTtl)?;
+        ms.expire("key-3", 100)?;
+        ms.expire("key-7", 10)?;
+
+        ms.insert_string("key-a", "value")?;
+        ms.insert_string("key-b", "value")?;
+
+        assert_eq!(ms.get_string("key-7")?, None);
+        assert_eq!(ms.get_string("key-3")?.as_deref(), Some("value"));
+
+        // Only keys with an expiry can be evicted
+        ms.insert_string("key-c", "value")?;
+        let err = ms.insert_string("key-d", "value").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::OutOfMemory);
+
+        Ok(())
+    }
+
+    #[test]
+    fn samples_keys_of_large_stores() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        for i in 0..1000 {
+            ms.insert_string(&format!("key-{}", i), "value")?;
+        }
+        ms.expire("key-500", 100)?;
+        ms.expire("key-900", 10)?;
+
+        let limit = ms.used_memory();
+        ms.set_max_memory(Some(limit), EvictionPolicy::AllKeysLru);
+        for i in 0..10 {
+            ms.insert_string(&format!("new-{}", i), "value")?;
+        }
+
+        assert!(ms.used_memory() <= limit + 128);
+        assert!((0..10).all(|i| ms.strings.get_ref().contains_key(&format!("new-{}", i))));
+
+        // The key closest to expiring is found without sampling
+        ms.set_max_memory(Some(limit), EvictionPolicy::VolatileTtl);
+        ms.insert_string("new-10", "value")?;
+        assert_eq!(ms.get_string("key-900")?, None);
+        assert_eq!(ms.get_string("key-500")?.as_deref(), Some("value"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn evicts_across_stores() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.set_max_memory(Some(1024), EvictionPolicy::AllKeysLru);
+
+        for i in 0..100 {
+            ms.rpush(&format!("list-{}", i), &["a", "b"])?;
+            ms.hset(&format!("hash-{}", i), &[("field", "value")])?;
+            ms.sadd(&format!("set-{}", i), &["member"])?;
+        }
+
+        assert!(ms.lists.len() < 100);
+        assert!(ms.used_memory() < 2048);
+
+        Ok(())
+    }
+}
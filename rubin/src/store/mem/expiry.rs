@@ -0,0 +1,449 @@
+//! Key expiry operations for the [`MemStore`]
+//!
+//! Keys in any of the inner stores can be given a time-to-live after which they are treated
+//! as absent. Expired keys are removed lazily when they are next accessed or actively by
+//! calling [`MemStore::purge_expired()`].
+//!
+//! Expiry deadlines are stored alongside the values so they survive being written to disk.
+
+use std::io;
+use std::time::Duration;
+
+use crate::store::mem::MemStore;
+use crate::store::{now_millis, KeyedStore};
+
+/// Gets the deadline (milliseconds since the Unix epoch) a duration from now
+///
+/// Deadlines are limited to `i64::MAX` so the remaining time-to-live can always be returned
+/// by [`MemStore::pttl()`].
+///
+/// # Errors
+///
+/// * [`io::ErrorKind::InvalidInput`] if the deadline is too far in the future to be stored
+fn deadline_after(expiry: Duration) -> io::Result<u64> {
+    u64::try_from(expiry.as_millis())
+        .ok()
+        .and_then(|milliseconds| now_millis().checked_add(milliseconds))
+        .filter(|deadline| i64::try_from(*deadline).is_ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expiry is out of range: {}ms", expiry.as_millis()),
+            )
+        })
+}
+
+impl MemStore {
+    /// Inserts a string into the string store which expires after the given duration
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the expiry is too far in the future to be stored,
+    ///   in which case the string is not inserted
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string_with_expiry("session:1000", "token", Duration::from_secs(60)).unwrap();
+    ///
+    /// assert_eq!(ms.get_string("session:1000").unwrap().as_deref(), Some("token"));
+    /// assert_eq!(ms.ttl("session:1000").unwrap(), 60);
+    /// ```
+    pub fn insert_string_with_expiry(
+        &mut self,
+        key: &str,
+        value: &str,
+        expiry: Duration,
+    ) -> io::Result<()> {
+        let deadline = deadline_after(expiry)?;
+        self.insert_string(key, value)?;
+        self.strings.set_expiry(key, deadline);
+
+        Ok(())
+    }
+
+    /// Sets a key to expire after the given number of seconds
+    ///
+    /// The expiry is applied to the key in every store it is present in.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the key was present and the expiry was set, `false` otherwise
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the expiry is too far in the future to be stored
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("session:1000", "token").unwrap();
+    ///
+    /// assert!(ms.expire("session:1000", 60).unwrap());
+    /// assert!(!ms.expire("missing", 60).unwrap());
+    /// ```
+    pub fn expire(&mut self, key: &str, seconds: u64) -> io::Result<bool> {
+        self.pexpire(key, seconds.saturating_mul(1000))
+    }
+
+    /// Sets a key to expire after the given number of milliseconds
+    ///
+    /// The expiry is applied to the key in every store it is present in.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the key was present and the expiry was set, `false` otherwise
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the expiry is too far in the future to be stored
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a"]).unwrap();
+    ///
+    /// assert!(ms.pexpire("queue", 1800).unwrap());
+    /// assert_eq!(ms.ttl("queue").unwrap(), 2);
+    /// ```
+    pub fn pexpire(&mut self, key: &str, milliseconds: u64) -> io::Result<bool> {
+        let deadline = deadline_after(Duration::from_millis(milliseconds))?;
+
+        Ok(self.expire_at(key, deadline))
+    }
+
+    /// Sets a key to expire at a deadline (milliseconds since the Unix epoch)
+    ///
+    /// Used to replay expiries exactly, rather than relative to when they are replayed.
+    pub(crate) fn expire_at(&mut self, key: &str, deadline: u64) -> bool {
+        let mut updated = false;
+        for store in self.keyed_stores_mut() {
+            updated |= store.set_expiry(key, deadline);
+        }
+
+        updated
+    }
+
+    /// Gets the expiry deadline of a key, `None` if it is not present or has no expiry
+    pub(crate) fn expiry_deadline(&self, key: &str) -> Option<u64> {
+        self.keyed_stores()
+            .iter()
+            .filter_map(|store| store.expiry(key))
+            .min()
+    }
+
+    /// Gets the remaining time-to-live of a key in seconds (rounded to the nearest second)
+    ///
+    /// # Returns
+    ///
+    /// * `-2` if the key is not present
+    /// * `-1` if the key is present but has no expiry
+    /// * The remaining time-to-live otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    ///
+    /// assert_eq!(ms.ttl("user:1000").unwrap(), -1);
+    /// assert_eq!(ms.ttl("missing").unwrap(), -2);
+    /// ```
+    pub fn ttl(&self, key: &str) -> io::Result<i64> {
+        let ttl = self.pttl(key)?;
+        if ttl < 0 {
+            return Ok(ttl);
+        }
+
+        Ok((ttl + 500) / 1000)
+    }
+
+    /// Gets the remaining time-to-live of a key in milliseconds
+    ///
+    /// # Returns
+    ///
+    /// * `-2` if the key is not present
+    /// * `-1` if the key is present but has no expiry
+    /// * The remaining time-to-live otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// ms.pexpire("user:1000", 5000).unwrap();
+    ///
+    /// let ttl = ms.pttl("user:1000").unwrap();
+    /// assert!(ttl > 0 && ttl <= 5000);
+    /// ```
+    pub fn pttl(&self, key: &str) -> io::Result<i64> {
+        let stores = self.keyed_stores();
+        if !stores.iter().any(|store| store.contains_key(key)) {
+            return Ok(-2);
+        }
+
+        let deadline = stores.iter().filter_map(|store| store.expiry(key)).min();
+        match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_sub(now_millis());
+                Ok(i64::try_from(remaining).unwrap_or(i64::MAX))
+            }
+            None => Ok(-1),
+        }
+    }
+
+    /// Removes the expiry from a key so that it no longer expires
+    ///
+    /// # Returns
+    ///
+    /// * `true` if an expiry was removed, `false` if the key is not present or had no expiry
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// ms.expire("user:1000", 60).unwrap();
+    ///
+    /// assert!(ms.persist("user:1000").unwrap());
+    /// assert_eq!(ms.ttl("user:1000").unwrap(), -1);
+    /// ```
+    pub fn persist(&mut self, key: &str) -> io::Result<bool> {
+        let mut removed = false;
+        for store in self.keyed_stores_mut() {
+            removed |= store.clear_expiry(key);
+        }
+
+        Ok(removed)
+    }
+
+    /// Removes all expired keys from every store
+    ///
+    /// Expired keys are never returned from the store but still occupy memory until they are
+    /// accessed again, this is used to reclaim that memory.
+    ///
+    /// # Returns
+    ///
+    /// * The number of keys removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// ms.pexpire("user:1000", 0).unwrap();
+    ///
+    /// assert_eq!(ms.purge_expired(), 1);
+    /// assert_eq!(ms.strings.len(), 0);
+    /// ```
+    pub fn purge_expired(&mut self) -> usize {
+        self.purge_expired_batch(usize::MAX)
+    }
+
+    /// Removes up to `limit` keys whose expiry has passed from the store.
+    ///
+    /// Keys which expired first are removed first. Used to reclaim memory a little at a time
+    /// rather than holding up the store while every expired key is removed.
+    ///
+    /// # Returns
+    ///
+    /// * The number of keys removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// for key in ["user:1000", "user:1001", "user:1002"] {
+    ///     ms.insert_string(key, "value").unwrap();
+    ///     ms.pexpire(key, 0).unwrap();
+    /// }
+    ///
+    /// assert_eq!(ms.purge_expired_batch(2), 2);
+    /// assert_eq!(ms.strings.len(), 1);
+    /// ```
+    pub fn purge_expired_batch(&mut self, limit: usize) -> usize {
+        let mut removed = 0;
+        for store in self.keyed_stores_mut() {
+            removed += store.purge_expired(limit - removed);
+        }
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod expiry_tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn expired_keys_are_absent() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_string("key", "value")?;
+        ms.rpush("list", &["a", "b"])?;
+        ms.incr("counter")?;
+
+        assert!(ms.pexpire("key", 10)?);
+        assert!(ms.pexpire("list", 10)?);
+        assert!(ms.pexpire("counter", 10)?);
+        sleep(Duration::from_millis(20));
+
+        assert_eq!(ms.get_string("key")?, None);
+        assert_eq!(ms.llen("list")?, 0);
+        assert_eq!(ms.ttl("key")?, -2);
+        assert_eq!(ms.incr("counter")?, 1);
+        assert_eq!(ms.ttl("counter")?, -1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn time_to_live() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.sadd("set", &["a"])?;
+
+        assert_eq!(ms.ttl("set")?, -1);
+        assert_eq!(ms.ttl("missing")?, -2);
+        assert!(!ms.expire("missing", 10)?);
+
+        ms.expire("set", 10)?;
+        assert_eq!(ms.ttl("set")?, 10);
+
+        assert!(ms.persist("set")?);
+        assert!(!ms.persist("set")?);
+        assert_eq!(ms.ttl("set")?, -1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_resets_expiry() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_string_with_expiry("key", "value", Duration::from_secs(10))?;
+        assert_eq!(ms.ttl("key")?, 10);
+
+        ms.insert_string("key", "other")?;
+        assert_eq!(ms.ttl("key")?, -1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_expiries_out_of_range() -> io::Result<()> {
+        let mut ms = MemStore::new();
+
+        for expiry in [
+            Duration::from_millis(u64::MAX),
+            Duration::from_secs(u64::MAX),
+        ] {
+            let err = ms
+                .insert_string_with_expiry("key", "value", expiry)
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert_eq!(ms.get_string("key")?, None);
+
+        ms.insert_string("key", "value")?;
+        for err in [
+            ms.expire("key", u64::MAX).unwrap_err(),
+            ms.pexpire("key", u64::MAX).unwrap_err(),
+            ms.pexpire("key", i64::MAX as u64).unwrap_err(),
+        ] {
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert_eq!(ms.pttl("key")?, -1);
+
+        // A deadline beyond the limit, such as one read from an older store file, is clamped
+        ms.expire_at("key", u64::MAX);
+        assert_eq!(ms.pttl("key")?, i64::MAX);
+
+        Ok(())
+    }
+
+    #[test]
+    fn purge_expired_keys() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        for i in 0..10 {
+            let key = format!("key-{}", i);
+            ms.insert_string(&key, "value")?;
+            if i % 2 == 0 {
+                ms.pexpire(&key, 0)?;
+            }
+        }
+
+        ms.hset("hash", &[("field", "value")])?;
+        ms.pexpire("hash", 0)?;
+
+        assert_eq!(ms.purge_expired(), 6);
+        assert_eq!(ms.strings.len(), 5);
+        assert_eq!(ms.hashes.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn expiry_survives_serialisation() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_string_with_expiry("session", "token", Duration::from_secs(60))?;
+        ms.insert_string_with_expiry("stale", "token", Duration::from_millis(0))?;
+
+        let contents = serde_json::to_string(&ms)?;
+        let loaded: MemStore = serde_json::from_str(&contents)?;
+
+        assert_eq!(loaded.ttl("session")?, 60);
+        assert_eq!(loaded.get_string("stale")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn purge_expired_keys_in_batches() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        let deadline = now_millis() - 1000;
+        for i in 0..5 {
+            let key = format!("key-{}", i);
+            ms.insert_string_with_expiry(&key, "value", Duration::from_secs(60))?;
+            ms.expire_at(&key, deadline + i);
+        }
+        ms.insert_string_with_expiry("session", "token", Duration::from_secs(60))?;
+
+        // Replacing a key or its expiry leaves no stale deadline behind
+        ms.insert_string("key-0", "value")?;
+        ms.insert_string_with_expiry("key-1", "value", Duration::from_secs(60))?;
+        ms.expire_at("key-1", deadline + 10);
+
+        let contents = serde_json::to_string(&ms)?;
+        let mut ms: MemStore = serde_json::from_str(&contents)?;
+
+        assert_eq!(ms.purge_expired_batch(2), 2);
+        assert!(ms.strings.get_ref().contains_key("key-1"));
+        assert!(!ms.strings.get_ref().contains_key("key-2"));
+
+        assert_eq!(ms.purge_expired_batch(10), 2);
+        assert_eq!(ms.purge_expired_batch(10), 0);
+        assert_eq!(ms.strings.len(), 2);
+        assert_eq!(ms.ttl("session")?, 60);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,794 @@
+//! Geospatial operations for the [`MemStore`]
+//!
+//! Geo sets are not a separate type but sorted sets whose scores are 52-bit geohashes of each
+//! member's position. Interleaving the bits of the longitude and latitude keeps nearby points
+//! close together in the score order, so an area search only has to read the few score ranges
+//! covering the cells around it before checking the exact distance of each candidate.
+//!
+//! Positions are stored at the precision of the geohash, so a position read back may differ
+//! from the one added by up to around half a metre. Distances use the haversine formula,
+//! treating the Earth as a sphere.
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeSet;
+use std::io;
+use std::str::FromStr;
+
+use crate::store::mem::MemStore;
+
+/// Number of bits used for each of the longitude and latitude in a geohash
+const GEO_STEP_MAX: u32 = 26;
+
+/// Lowest longitude which can be stored
+const LON_MIN: f64 = -180.0;
+
+/// Highest longitude which can be stored
+const LON_MAX: f64 = 180.0;
+
+/// Lowest latitude which can be stored, the limit of the Web Mercator projection
+const LAT_MIN: f64 = -85.051_128_78;
+
+/// Highest latitude which can be stored
+const LAT_MAX: f64 = 85.051_128_78;
+
+/// Radius of the Earth in metres
+const EARTH_RADIUS: f64 = 6_372_797.560_856;
+
+/// Unit of distance used by geo operations
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GeoUnit {
+    /// Metres
+    #[default]
+    Meters,
+
+    /// Kilometres
+    Kilometers,
+
+    /// Miles
+    Miles,
+
+    /// Feet
+    Feet,
+}
+
+impl GeoUnit {
+    /// Number of metres in one of the unit
+    fn meters(&self) -> f64 {
+        match self {
+            Self::Meters => 1.0,
+            Self::Kilometers => 1000.0,
+            Self::Miles => 1609.34,
+            Self::Feet => 0.3048,
+        }
+    }
+
+    /// Abbreviation of the unit, as accepted by [`GeoUnit::from_str`]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Meters => "m",
+            Self::Kilometers => "km",
+            Self::Miles => "mi",
+            Self::Feet => "ft",
+        }
+    }
+}
+
+impl FromStr for GeoUnit {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "m" => Ok(Self::Meters),
+            "km" => Ok(Self::Kilometers),
+            "mi" => Ok(Self::Miles),
+            "ft" => Ok(Self::Feet),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid unit: {}", s),
+            )),
+        }
+    }
+}
+
+/// Centre of a [`GeoSearch`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoOrigin {
+    /// Position of a member of the geo set
+    Member(String),
+
+    /// A longitude and latitude
+    LonLat(f64, f64),
+}
+
+/// Area covered by a [`GeoSearch`], measured in the search's unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoShape {
+    /// Circle with the given radius
+    Radius(f64),
+
+    /// Rectangle with the given width and height, aligned with the lines of longitude and
+    /// latitude
+    Box(f64, f64),
+}
+
+/// Search for the members of a geo set within an area, see [`MemStore::geosearch`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoSearch {
+    /// Centre of the area
+    pub origin: GeoOrigin,
+
+    /// Shape and size of the area
+    pub shape: GeoShape,
+
+    /// Unit of the shape's size and of the distances returned
+    pub unit: GeoUnit,
+
+    /// Maximum number of members to return, all if `None`
+    pub count: Option<usize>,
+}
+
+impl GeoSearch {
+    /// Parses the options of a search, as sent to the `GEOSEARCH` command
+    ///
+    /// The origin is given by `FROMMEMBER member` or `FROMLONLAT longitude latitude` and the
+    /// area by `BYRADIUS radius unit` or `BYBOX width height unit`, optionally followed by
+    /// `COUNT n`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{GeoOrigin, GeoSearch, GeoShape, GeoUnit};
+    ///
+    /// let search = GeoSearch::parse(&["FROMLONLAT", "-0.1276", "51.5072", "BYRADIUS", "5", "km"]).unwrap();
+    ///
+    /// assert_eq!(search.origin, GeoOrigin::LonLat(-0.1276, 51.5072));
+    /// assert_eq!(search.shape, GeoShape::Radius(5.0));
+    /// assert_eq!(search.unit, GeoUnit::Kilometers);
+    /// assert_eq!(search.count, None);
+    /// ```
+    pub fn parse(args: &[impl AsRef<str>]) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+
+        let mut args = args.iter().map(AsRef::as_ref);
+        let mut next = |name: &str| {
+            args.next()
+                .ok_or_else(|| invalid(format!("missing argument to {}", name)))
+        };
+        let number = |arg: &str| {
+            arg.parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| invalid(format!("invalid number: {}", arg)))
+        };
+
+        let mut origin = None;
+        let mut area = None;
+        let mut count = None;
+        while let Ok(option) = next("") {
+            match option.to_uppercase().as_str() {
+                "FROMMEMBER" => origin = Some(GeoOrigin::Member(next(option)?.to_string())),
+                "FROMLONLAT" => {
+                    let lon = number(next(option)?)?;
+                    let lat = number(next(option)?)?;
+                    origin = Some(GeoOrigin::LonLat(lon, lat));
+                }
+                "BYRADIUS" => {
+                    let radius = number(next(option)?)?;
+                    area = Some((GeoShape::Radius(radius), next(option)?.parse()?));
+                }
+                "BYBOX" => {
+                    let width = number(next(option)?)?;
+                    let height = number(next(option)?)?;
+                    area = Some((GeoShape::Box(width, height), next(option)?.parse()?));
+                }
+                "COUNT" => {
+                    let arg = next(option)?;
+                    let value = arg
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|value| *value > 0)
+                        .ok_or_else(|| invalid(format!("invalid count: {}", arg)))?;
+                    count = Some(value);
+                }
+                _ => return Err(invalid(format!("invalid search option: {}", option))),
+            }
+        }
+
+        let origin = origin.ok_or_else(|| invalid("missing FROMMEMBER or FROMLONLAT".into()))?;
+        let (shape, unit) = area.ok_or_else(|| invalid("missing BYRADIUS or BYBOX".into()))?;
+
+        Ok(Self {
+            origin,
+            shape,
+            unit,
+            count,
+        })
+    }
+
+    /// Converts the search back into the options parsed by [`GeoSearch::parse`]
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = match &self.origin {
+            GeoOrigin::Member(member) => vec!["FROMMEMBER".to_string(), member.clone()],
+            GeoOrigin::LonLat(lon, lat) => {
+                vec!["FROMLONLAT".to_string(), lon.to_string(), lat.to_string()]
+            }
+        };
+
+        match self.shape {
+            GeoShape::Radius(radius) => args.extend(["BYRADIUS".to_string(), radius.to_string()]),
+            GeoShape::Box(width, height) => {
+                args.extend(["BYBOX".to_string(), width.to_string(), height.to_string()])
+            }
+        }
+        args.push(self.unit.as_str().to_string());
+
+        if let Some(count) = self.count {
+            args.extend(["COUNT".to_string(), count.to_string()]);
+        }
+
+        args
+    }
+}
+
+/// A member found by [`MemStore::geosearch`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoMatch {
+    /// Name of the member
+    pub member: String,
+
+    /// Distance from the origin of the search, in the search's unit
+    pub distance: f64,
+
+    /// Longitude of the member
+    pub longitude: f64,
+
+    /// Latitude of the member
+    pub latitude: f64,
+}
+
+/// Checks that a position can be stored
+fn validate_position(lon: f64, lat: f64) -> io::Result<()> {
+    if !(LON_MIN..=LON_MAX).contains(&lon) || !(LAT_MIN..=LAT_MAX).contains(&lat) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid longitude, latitude pair: {}, {}", lon, lat),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Scales a coordinate to a cell index in the range `0..2^GEO_STEP_MAX`
+fn to_cell(value: f64, min: f64, max: f64) -> u64 {
+    let cells = (1u64 << GEO_STEP_MAX) as f64;
+    let cell = ((value - min) / (max - min) * cells) as u64;
+
+    cell.min((1 << GEO_STEP_MAX) - 1)
+}
+
+/// Interleaves the bits of two cell indices, with `odd` taking the odd bit positions
+fn interleave(even: u64, odd: u64) -> u64 {
+    (0..GEO_STEP_MAX).fold(0, |hash, bit| {
+        hash | ((even >> bit) & 1) << (2 * bit) | ((odd >> bit) & 1) << (2 * bit + 1)
+    })
+}
+
+/// Splits a hash back into the cell indices interleaved by [`interleave`]
+fn deinterleave(hash: u64) -> (u64, u64) {
+    (0..GEO_STEP_MAX).fold((0, 0), |(even, odd), bit| {
+        (
+            even | ((hash >> (2 * bit)) & 1) << bit,
+            odd | ((hash >> (2 * bit + 1)) & 1) << bit,
+        )
+    })
+}
+
+/// Encodes a position as a 52-bit geohash
+fn encode(lon: f64, lat: f64) -> u64 {
+    interleave(
+        to_cell(lat, LAT_MIN, LAT_MAX),
+        to_cell(lon, LON_MIN, LON_MAX),
+    )
+}
+
+/// Decodes a geohash into the position at the centre of its cell
+fn decode(hash: u64) -> (f64, f64) {
+    let (lat, lon) = deinterleave(hash);
+    let cells = (1u64 << GEO_STEP_MAX) as f64;
+
+    let lon = LON_MIN + (lon as f64 + 0.5) / cells * (LON_MAX - LON_MIN);
+    let lat = LAT_MIN + (lat as f64 + 0.5) / cells * (LAT_MAX - LAT_MIN);
+
+    (lon.clamp(LON_MIN, LON_MAX), lat.clamp(LAT_MIN, LAT_MAX))
+}
+
+/// Great-circle distance between two positions in metres
+fn distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let half_dlat = (lat2 - lat1) / 2.0;
+    let half_dlon = (lon2 - lon1).to_radians() / 2.0;
+
+    let a = half_dlat.sin().powi(2) + lat1.cos() * lat2.cos() * half_dlon.sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().asin()
+}
+
+/// Score ranges of the geohash cells covering the area within `dlon` / `dlat` degrees of a
+/// position
+///
+/// Cells are chosen at the finest precision where the area spans no more than two cells in
+/// each direction, giving at most four ranges to read.
+fn covering_ranges(lon: f64, lat: f64, dlon: f64, dlat: f64) -> Vec<(f64, f64)> {
+    let step = (0..=GEO_STEP_MAX)
+        .rev()
+        .find(|step| {
+            let cells = (1u64 << step) as f64;
+            (LON_MAX - LON_MIN) / cells >= 2.0 * dlon && (LAT_MAX - LAT_MIN) / cells >= 2.0 * dlat
+        })
+        .unwrap_or(0);
+
+    let cells = 1i64 << step;
+    let lon_cell = |lon: f64| {
+        let cell = ((lon - LON_MIN) / (LON_MAX - LON_MIN) * cells as f64).floor() as i64;
+        // Longitudes wrap around the antimeridian
+        cell.rem_euclid(cells) as u64
+    };
+    let lat_cell = |lat: f64| {
+        let cell = ((lat - LAT_MIN) / (LAT_MAX - LAT_MIN) * cells as f64).floor() as i64;
+        cell.clamp(0, cells - 1) as u64
+    };
+
+    let shift = 2 * (GEO_STEP_MAX - step);
+    let mut prefixes = BTreeSet::new();
+    for lon_cell in [lon_cell(lon - dlon), lon_cell(lon + dlon)] {
+        for lat_cell in [lat_cell(lat - dlat), lat_cell(lat + dlat)] {
+            prefixes.insert(interleave(lat_cell, lon_cell));
+        }
+    }
+
+    prefixes
+        .into_iter()
+        .map(|prefix| {
+            let start = prefix << shift;
+            let end = ((prefix + 1) << shift) - 1;
+            (start as f64, end as f64)
+        })
+        .collect()
+}
+
+impl MemStore {
+    /// Adds one or more members with positions to a geo set, creating the set if it does not
+    /// exist
+    ///
+    /// Existing members have their position updated. Geo sets are held in the sorted set
+    /// store, so the key has the type [`KeyType::SortedSet`].
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: Key of the geo set
+    /// * `members`: Longitude, latitude and name of each member
+    ///
+    /// # Returns
+    ///
+    /// * The number of members newly added to the set
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if a longitude is outside -180 to 180 or a latitude
+    ///   is outside -85.05112878 to 85.05112878
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// let added = ms.geoadd("drivers", &[(-0.1276, 51.5072, "driver:1"), (-0.1426, 51.5014, "driver:2")]).unwrap();
+    /// assert_eq!(added, 2);
+    ///
+    /// assert!(ms.geoadd("drivers", &[(0.0, 89.0, "driver:3")]).is_err());
+    /// ```
+    pub fn geoadd(&mut self, key: &str, members: &[(f64, f64, &str)]) -> io::Result<usize> {
+        for (lon, lat, _) in members {
+            validate_position(*lon, *lat)?;
+        }
+
+        let scored: Vec<(f64, &str)> = members
+            .iter()
+            .map(|(lon, lat, member)| (encode(*lon, *lat) as f64, *member))
+            .collect();
+
+        self.zadd(key, &scored)
+    }
+
+    /// Gets the positions of members of a geo set
+    ///
+    /// # Returns
+    ///
+    /// * The longitude and latitude of each member, `None` for members not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.geoadd("drivers", &[(-0.1276, 51.5072, "driver:1")]).unwrap();
+    ///
+    /// let positions = ms.geopos("drivers", &["driver:1", "driver:2"]).unwrap();
+    /// let (lon, lat) = positions[0].unwrap();
+    ///
+    /// assert!((lon + 0.1276).abs() < 1e-5 && (lat - 51.5072).abs() < 1e-5);
+    /// assert_eq!(positions[1], None);
+    /// ```
+    pub fn geopos(&self, key: &str, members: &[&str]) -> io::Result<Vec<Option<(f64, f64)>>> {
+        let set = self.zsets.get(key);
+
+        Ok(members
+            .iter()
+            .map(|member| {
+                set.and_then(|set| set.score(member))
+                    .map(|score| decode(score as u64))
+            })
+            .collect())
+    }
+
+    /// Gets the distance between two members of a geo set
+    ///
+    /// # Returns
+    ///
+    /// * The distance in the given unit, `None` if either member is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{GeoUnit, MemStore};
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.geoadd("cities", &[(-0.1276, 51.5072, "london"), (2.3522, 48.8566, "paris")]).unwrap();
+    ///
+    /// let distance = ms.geodist("cities", "london", "paris", GeoUnit::Kilometers).unwrap().unwrap();
+    /// assert!((distance - 343.5).abs() < 1.0);
+    /// ```
+    pub fn geodist(
+        &self,
+        key: &str,
+        member1: &str,
+        member2: &str,
+        unit: GeoUnit,
+    ) -> io::Result<Option<f64>> {
+        let positions = self.geopos(key, &[member1, member2])?;
+        let (Some((lon1, lat1)), Some((lon2, lat2))) = (positions[0], positions[1]) else {
+            return Ok(None);
+        };
+
+        Ok(Some(distance(lon1, lat1, lon2, lat2) / unit.meters()))
+    }
+
+    /// Finds the members of a geo set within an area, nearest first
+    ///
+    /// Only the parts of the set around the area are read, so searching a small area of a
+    /// large set does not visit every member.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the search starts from a member which is not present
+    /// * [`io::ErrorKind::InvalidInput`] if the search starts from an invalid position or the
+    ///   area has a negative size
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{GeoOrigin, GeoSearch, GeoShape, GeoUnit, MemStore};
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.geoadd(
+    ///     "drivers",
+    ///     &[(-0.1276, 51.5072, "driver:1"), (-0.1426, 51.5014, "driver:2"), (2.3522, 48.8566, "driver:3")],
+    /// )
+    /// .unwrap();
+    ///
+    /// // Nearest two drivers within 5km
+    /// let search = GeoSearch {
+    ///     origin: GeoOrigin::LonLat(-0.1300, 51.5050),
+    ///     shape: GeoShape::Radius(5.0),
+    ///     unit: GeoUnit::Kilometers,
+    ///     count: Some(2),
+    /// };
+    /// let nearest = ms.geosearch("drivers", &search).unwrap();
+    ///
+    /// assert_eq!(nearest[0].member, "driver:1");
+    /// assert_eq!(nearest[1].member, "driver:2");
+    /// ```
+    pub fn geosearch(&self, key: &str, search: &GeoSearch) -> io::Result<Vec<GeoMatch>> {
+        let (lon, lat) = match &search.origin {
+            GeoOrigin::LonLat(lon, lat) => {
+                validate_position(*lon, *lat)?;
+                (*lon, *lat)
+            }
+            GeoOrigin::Member(member) => self.geopos(key, &[member])?[0]
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such member"))?,
+        };
+
+        let unit = search.unit.meters();
+        let (half_width, half_height) = match search.shape {
+            GeoShape::Radius(radius) => (radius * unit, radius * unit),
+            GeoShape::Box(width, height) => (width * unit / 2.0, height * unit / 2.0),
+        };
+        if !(half_width >= 0.0 && half_height >= 0.0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "search area must not be negative",
+            ));
+        }
+
+        let Some(set) = self.zsets.get(key) else {
+            return Ok(vec![]);
+        };
+
+        // Bounding box in degrees, widest at the latitude furthest from the equator
+        let dlat = (half_height / EARTH_RADIUS).to_degrees();
+        let furthest = (lat.abs() + dlat).to_radians();
+        let dlon = match half_width / EARTH_RADIUS / furthest.cos() {
+            dlon if furthest < std::f64::consts::FRAC_PI_2 && dlon < std::f64::consts::PI => {
+                dlon.to_degrees()
+            }
+            _ => LON_MAX,
+        };
+
+        let mut matches: Vec<GeoMatch> = covering_ranges(lon, lat, dlon, dlat)
+            .into_iter()
+            .flat_map(|(start, end)| set.range_by_score(start, end))
+            .filter_map(|(member, score)| {
+                let (member_lon, member_lat) = decode(score as u64);
+                let within = match search.shape {
+                    GeoShape::Radius(_) => distance(lon, lat, member_lon, member_lat) <= half_width,
+                    GeoShape::Box(..) => {
+                        distance(member_lon, lat, member_lon, member_lat) <= half_height
+                            && distance(lon, member_lat, member_lon, member_lat) <= half_width
+                    }
+                };
+
+                within.then(|| GeoMatch {
+                    member: member.to_string(),
+                    distance: distance(lon, lat, member_lon, member_lat) / unit,
+                    longitude: member_lon,
+                    latitude: member_lat,
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            a.distance
+                .total_cmp(&b.distance)
+                .then_with(|| a.member.cmp(&b.member))
+        });
+        matches.truncate(search.count.unwrap_or(usize::MAX));
+
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod geo_tests {
+    use super::*;
+    use crate::store::mem::KeyType;
+
+    fn search(origin: GeoOrigin, shape: GeoShape, unit: GeoUnit) -> GeoSearch {
+        GeoSearch {
+            origin,
+            shape,
+            unit,
+            count: None,
+        }
+    }
+
+    fn members(matches: &[GeoMatch]) -> Vec<&str> {
+        matches.iter().map(|found| found.member.as_str()).collect()
+    }
+
+    #[test]
+    fn encodes_positions() {
+        for (lon, lat) in [
+            (0.0, 0.0),
+            (-0.1276, 51.5072),
+            (179.9999, -85.0),
+            (-180.0, 85.0),
+        ] {
+            let (decoded_lon, decoded_lat) = decode(encode(lon, lat));
+            assert!((decoded_lon - lon).abs() < 1e-5, "{} {}", lon, decoded_lon);
+            assert!((decoded_lat - lat).abs() < 1e-5, "{} {}", lat, decoded_lat);
+        }
+
+        assert_eq!(
+            deinterleave(interleave(0x2aa_aaaa, 0x155_5555)),
+            (0x2aa_aaaa, 0x155_5555)
+        );
+        assert!(encode(180.0, LAT_MAX) < 1 << 52);
+    }
+
+    #[test]
+    fn stores_positions_as_sorted_sets() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        assert_eq!(ms.geoadd("cities", &[(-0.1276, 51.5072, "london")])?, 1);
+        assert_eq!(ms.geoadd("cities", &[(2.3522, 48.8566, "london")])?, 0);
+        assert_eq!(ms.key_type("cities")?, Some(KeyType::SortedSet));
+
+        let (lon, lat) = ms.geopos("cities", &["london"])?[0].unwrap();
+        assert!((lon - 2.3522).abs() < 1e-5 && (lat - 48.8566).abs() < 1e-5);
+        assert_eq!(ms.geopos("missing", &["london"])?, vec![None]);
+
+        let err = ms.geoadd("cities", &[(181.0, 0.0, "nowhere")]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        ms.rpush("list", &["value"])?;
+        assert!(ms.geoadd("list", &[(0.0, 0.0, "member")]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn measures_distances() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.geoadd(
+            "cities",
+            &[
+                (13.361389, 38.115556, "palermo"),
+                (15.087269, 37.502669, "catania"),
+            ],
+        )?;
+
+        let meters = ms
+            .geodist("cities", "palermo", "catania", GeoUnit::Meters)?
+            .unwrap();
+        assert!((meters - 166_274.15).abs() < 1.0);
+
+        let miles = ms
+            .geodist("cities", "palermo", "catania", GeoUnit::Miles)?
+            .unwrap();
+        assert!((miles - 103.32).abs() < 0.01);
+        assert_eq!(
+            ms.geodist("cities", "palermo", "rome", GeoUnit::Meters)?,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn searches_by_radius_and_box() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.geoadd(
+            "cities",
+            &[
+                (13.361389, 38.115556, "palermo"),
+                (15.087269, 37.502669, "catania"),
+                (12.758489, 38.788135, "edge1"),
+                (17.241510, 38.788135, "edge2"),
+            ],
+        )?;
+
+        let origin = GeoOrigin::LonLat(15.0, 37.0);
+        let found = ms.geosearch(
+            "cities",
+            &search(origin.clone(), GeoShape::Radius(200.0), GeoUnit::Kilometers),
+        )?;
+        assert_eq!(members(&found), vec!["catania", "palermo"]);
+        assert!((found[0].distance - 56.4413).abs() < 0.001);
+
+        let found = ms.geosearch(
+            "cities",
+            &search(
+                origin.clone(),
+                GeoShape::Box(400.0, 400.0),
+                GeoUnit::Kilometers,
+            ),
+        )?;
+        assert_eq!(
+            members(&found),
+            vec!["catania", "palermo", "edge2", "edge1"]
+        );
+
+        let mut nearest = search(
+            GeoOrigin::Member("palermo".to_string()),
+            GeoShape::Radius(500.0),
+            GeoUnit::Kilometers,
+        );
+        nearest.count = Some(2);
+        assert_eq!(
+            members(&ms.geosearch("cities", &nearest)?),
+            vec!["palermo", "edge1"]
+        );
+
+        nearest.origin = GeoOrigin::Member("rome".to_string());
+        let err = ms.geosearch("cities", &nearest).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(ms
+            .geosearch(
+                "missing",
+                &search(origin, GeoShape::Radius(1.0), GeoUnit::Meters)
+            )?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn searches_match_a_full_scan() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        let mut points = vec![];
+        for i in 0..2000 {
+            // Spread points across the globe, including around the antimeridian and poles
+            let lon = ((i * 7919) % 36000) as f64 / 100.0 - 180.0;
+            let lat = ((i * 104_729) % 17000) as f64 / 100.0 - 85.0;
+            points.push((lon, lat, format!("point:{}", i)));
+        }
+        let members: Vec<(f64, f64, &str)> = points
+            .iter()
+            .map(|(lon, lat, member)| (*lon, *lat, member.as_str()))
+            .collect();
+        ms.geoadd("points", &members)?;
+
+        for (lon, lat) in [
+            (0.0, 0.0),
+            (179.9, 10.0),
+            (-179.9, -10.0),
+            (45.0, 84.0),
+            (-120.0, -80.0),
+        ] {
+            for radius in [10.0, 500.0, 3000.0, 20_000.0] {
+                let shape = GeoShape::Radius(radius);
+                let found = ms.geosearch(
+                    "points",
+                    &search(GeoOrigin::LonLat(lon, lat), shape, GeoUnit::Kilometers),
+                )?;
+
+                let expected = points
+                    .iter()
+                    .filter(|(_, _, member)| {
+                        let (member_lon, member_lat) =
+                            ms.geopos("points", &[member.as_str()]).unwrap()[0].unwrap();
+                        distance(lon, lat, member_lon, member_lat) <= radius * 1000.0
+                    })
+                    .count();
+                assert_eq!(found.len(), expected, "{} {} {}", lon, lat, radius);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_searches() -> io::Result<()> {
+        let parsed = GeoSearch::parse(&[
+            "frommember",
+            "palermo",
+            "bybox",
+            "10",
+            "20",
+            "mi",
+            "count",
+            "3",
+        ])?;
+        let expected = GeoSearch {
+            origin: GeoOrigin::Member("palermo".to_string()),
+            shape: GeoShape::Box(10.0, 20.0),
+            unit: GeoUnit::Miles,
+            count: Some(3),
+        };
+        assert_eq!(parsed, expected);
+        assert_eq!(GeoSearch::parse(&expected.to_args())?, expected);
+
+        assert!(GeoSearch::parse(&["FROMMEMBER", "palermo"]).is_err());
+        assert!(GeoSearch::parse(&["BYRADIUS", "10", "km"]).is_err());
+        assert!(GeoSearch::parse(&["FROMLONLAT", "0", "0", "BYRADIUS", "10", "yards"]).is_err());
+        assert!(GeoSearch::parse(&["FROMLONLAT", "0", "NaN", "BYRADIUS", "10", "km"]).is_err());
+        assert!(
+            GeoSearch::parse(&["FROMLONLAT", "0", "0", "BYRADIUS", "10", "km", "COUNT", "0"])
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+//! Hash operations for the [`MemStore`]
+//!
+//! Hashes map a key to a collection of field-value pairs, allowing individual fields
+//! of a record to be read and updated without rewriting the whole record.
+
+use std::collections::HashMap;
+use std::io;
+
+use crate::store::mem::{KeyType, MemStore};
+
+impl MemStore {
+    /// Sets one or more fields in a hash, creating the hash if it does not exist.
+    ///
+    /// Existing fields are overwritten with the new value.
+    ///
+    /// # Returns
+    ///
+    /// * The number of fields that were newly added to the hash
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// let added = ms.hset("user:1000", &[("name", "rubin"), ("age", "3")]).unwrap();
+    /// assert_eq!(added, 2);
+    ///
+    /// let added = ms.hset("user:1000", &[("age", "4")]).unwrap();
+    /// assert_eq!(added, 0);
+    /// ```
+    pub fn hset(&mut self, key: &str, fields: &[(&str, &str)]) -> io::Result<usize> {
+        self.check_type(key, KeyType::Hash)?;
+        self.reserve_memory()?;
+
+        let hash = self.hashes.entry(key);
+
+        let mut added = 0;
+        for (field, value) in fields {
+            if hash.insert(field.to_string(), value.to_string()).is_none() {
+                added += 1;
+            }
+        }
+
+        Ok(added)
+    }
+
+    /// Retrieves the value of a field in a hash.
+    ///
+    /// # Returns
+    ///
+    /// * The value of the field, `None` if the field or hash is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("name", "rubin")]).unwrap();
+    ///
+    /// let name = ms.hget("user:1000", "name").unwrap();
+    /// assert_eq!(name.as_deref(), Some("rubin"));
+    /// ```
+    pub fn hget(&self, key: &str, field: &str) -> io::Result<Option<String>> {
+        let value = self
+            .hashes
+            .get(key)
+            .and_then(|hash| hash.get(field))
+            .cloned();
+
+        Ok(value)
+    }
+
+    /// Removes one or more fields from a hash.
+    ///
+    /// The hash is removed from the store once it is empty.
+    ///
+    /// # Returns
+    ///
+    /// * The number of fields removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("name", "rubin"), ("age", "3")]).unwrap();
+    ///
+    /// let removed = ms.hdel("user:1000", &["age", "missing"]).unwrap();
+    /// assert_eq!(removed, 1);
+    /// ```
+    pub fn hdel(&mut self, key: &str, fields: &[&str]) -> io::Result<usize> {
+        let Some(hash) = self.hashes.get_mut(key) else {
+            return Ok(0);
+        };
+
+        let removed = fields
+            .iter()
+            .filter(|field| hash.remove(**field).is_some())
+            .count();
+
+        if hash.is_empty() {
+            self.hashes.delete(key);
+        }
+
+        Ok(removed)
+    }
+
+    /// Retrieves all field-value pairs of a hash, empty if the hash is not present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("name", "rubin"), ("age", "3")]).unwrap();
+    ///
+    /// let user = ms.hgetall("user:1000").unwrap();
+    /// assert_eq!(user.get("name").unwrap(), "rubin");
+    /// assert_eq!(user.get("age").unwrap(), "3");
+    /// ```
+    pub fn hgetall(&self, key: &str) -> io::Result<HashMap<String, String>> {
+        Ok(self.hashes.get(key).cloned().unwrap_or_default())
+    }
+
+    /// Retrieves all field names of a hash, empty if the hash is not present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("name", "rubin")]).unwrap();
+    ///
+    /// assert_eq!(ms.hkeys("user:1000").unwrap(), vec!["name"]);
+    /// ```
+    pub fn hkeys(&self, key: &str) -> io::Result<Vec<String>> {
+        Ok(self
+            .hashes
+            .get(key)
+            .map(|hash| hash.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Retrieves all values of a hash, empty if the hash is not present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("name", "rubin")]).unwrap();
+    ///
+    /// assert_eq!(ms.hvals("user:1000").unwrap(), vec!["rubin"]);
+    /// ```
+    pub fn hvals(&self, key: &str) -> io::Result<Vec<String>> {
+        Ok(self
+            .hashes
+            .get(key)
+            .map(|hash| hash.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// Returns the number of fields in a hash, zero if the hash is not present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("name", "rubin"), ("age", "3")]).unwrap();
+    ///
+    /// assert_eq!(ms.hlen("user:1000").unwrap(), 2);
+    /// ```
+    pub fn hlen(&self, key: &str) -> io::Result<usize> {
+        Ok(self.hashes.get(key).map_or(0, |hash| hash.len()))
+    }
+
+    /// Checks if a field exists in a hash.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("name", "rubin")]).unwrap();
+    ///
+    /// assert!(ms.hexists("user:1000", "name").unwrap());
+    /// assert!(!ms.hexists("user:1000", "age").unwrap());
+    /// ```
+    pub fn hexists(&self, key: &str, field: &str) -> io::Result<bool> {
+        Ok(self
+            .hashes
+            .get(key)
+            .is_some_and(|hash| hash.contains_key(field)))
+    }
+
+    /// Increments the integer value of a field in a hash by a given amount.
+    ///
+    /// If the field does not exist, it is set to zero before performing the operation.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the field value is not an integer or the
+    ///   operation would overflow
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("visits", "10")]).unwrap();
+    ///
+    /// let visits = ms.hincrby("user:1000", "visits", 5).unwrap();
+    /// assert_eq!(visits, 15);
+    /// ```
+    pub fn hincrby(&mut self, key: &str, field: &str, increment: isize) -> io::Result<isize> {
+        self.check_type(key, KeyType::Hash)?;
+        self.reserve_memory()?;
+
+        let hash = self.hashes.entry(key);
+
+        let current = match hash.get(field) {
+            Some(value) => value.parse::<isize>().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "hash value is not an integer")
+            })?,
+            None => 0,
+        };
+
+        let value = current.checked_add(increment).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "increment or decrement would overflow",
+            )
+        })?;
+
+        hash.insert(field.to_string(), value.to_string());
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod hash_tests {
+    use super::*;
+
+    #[test]
+    fn set_and_get_fields() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        assert_eq!(ms.hset("user", &[("name", "rubin"), ("age", "3")])?, 2);
+        assert_eq!(ms.hset("user", &[("name", "other"), ("city", "here")])?, 1);
+
+        assert_eq!(ms.hget("user", "name")?.as_deref(), Some("other"));
+        assert_eq!(ms.hget("user", "missing")?, None);
+        assert_eq!(ms.hget("missing", "name")?, None);
+        assert_eq!(ms.hlen("user")?, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn delete_fields() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.hset("user", &[("name", "rubin"), ("age", "3")])?;
+
+        assert_eq!(ms.hdel("user", &["name", "missing"])?, 1);
+        assert!(!ms.hexists("user", "name")?);
+
+        assert_eq!(ms.hdel("user", &["age"])?, 1);
+        assert_eq!(ms.hashes.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn retrieve_keys_and_values() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.hset("user", &[("name", "rubin"), ("age", "3")])?;
+
+        let mut keys = ms.hkeys("user")?;
+        keys.sort();
+        assert_eq!(keys, vec!["age", "name"]);
+
+        let mut values = ms.hvals("user")?;
+        values.sort();
+        assert_eq!(values, vec!["3", "rubin"]);
+
+        let all = ms.hgetall("user")?;
+        assert_eq!(all.len(), 2);
+        assert!(ms.hgetall("missing")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn increment_fields() -> io::Result<()> {
+        let mut ms = MemStore::new();
+
+        assert_eq!(ms.hincrby("user", "visits", 5)?, 5);
+        assert_eq!(ms.hincrby("user", "visits", -2)?, 3);
+        assert_eq!(ms.hget("user", "visits")?.as_deref(), Some("3"));
+
+        ms.hset("user", &[("name", "rubin")])?;
+        let err = ms.hincrby("user", "name", 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        ms.hset("user", &[("big", &isize::MAX.to_string())])?;
+        let err = ms.hincrby("user", "big", 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+//! HyperLogLog operations for the [`MemStore`]
+//!
+//! A HyperLogLog estimates the number of unique elements added to it using a fixed amount of
+//! memory (16KB), no matter how many elements are added. Elements are not stored, so they
+//! cannot be listed or removed, only counted.
+//!
+//! Each HyperLogLog uses `2^14` registers, giving a standard error of
+//! [`HyperLogLog::STANDARD_ERROR`] (0.81%). Estimates are usually much closer than this
+//! for small cardinalities.
+
+use std::io;
+use std::mem::size_of;
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::mem::{KeyType, MemStore};
+use crate::store::MemoryUsage;
+
+/// Number of hash bits used to select a register
+const PRECISION: u32 = 14;
+
+/// Number of registers in each HyperLogLog
+const REGISTERS: usize = 1 << PRECISION;
+
+/// Number of hash bits used to calculate the rank of an element
+const RANK_BITS: u32 = 64 - PRECISION;
+
+/// Hashes an element to a 64-bit value (FNV-1a with a MurmurHash3 finalizer)
+///
+/// The hash is fixed rather than randomly seeded so that HyperLogLogs written to disk can be
+/// merged with those created later. The finalizer spreads the FNV-1a hash across every bit,
+/// as both the register index and the rank depend on the hash being uniform.
+fn hash(element: &[u8]) -> u64 {
+    let mut hash = element.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+/// Probabilistic estimate of the number of unique elements in a collection
+///
+/// # Example
+///
+/// ```rust
+/// use rubin::store::mem::HyperLogLog;
+///
+/// let mut hll = HyperLogLog::new();
+/// for i in 0..10_000 {
+///     hll.add(format!("user:{}", i % 5_000).as_bytes());
+/// }
+///
+/// let error = (hll.count() as f64 - 5_000.0).abs() / 5_000.0;
+/// assert!(error < 3.0 * HyperLogLog::STANDARD_ERROR);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    /// Highest rank seen by each register, allocated when the first element is added
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Standard error of the estimated cardinality, `1.04 / sqrt(2^14)`
+    pub const STANDARD_ERROR: f64 = 0.0081;
+
+    /// Constructs an empty HyperLogLog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an element, returning `true` if the estimate may have changed
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let hash = hash(element);
+        let idx = (hash & (REGISTERS as u64 - 1)) as usize;
+
+        // A sentinel bit above the rank bits bounds the rank at `RANK_BITS + 1`
+        let rank = ((hash >> PRECISION) | (1 << RANK_BITS)).trailing_zeros() as u8 + 1;
+
+        let registers = self.registers_mut();
+        if rank <= registers[idx] {
+            return false;
+        }
+
+        registers[idx] = rank;
+        true
+    }
+
+    /// Merges another HyperLogLog into this one, so this estimates the size of the union
+    pub fn merge(&mut self, other: &Self) {
+        if other.registers.is_empty() {
+            return;
+        }
+
+        for (register, other) in self.registers_mut().iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+    }
+
+    /// Estimates the number of unique elements added
+    ///
+    /// Uses the estimator from Ertl, "New cardinality estimation algorithms for HyperLogLog
+    /// sketches" (2017), which does not need the empirical bias correction of earlier
+    /// estimators.
+    pub fn count(&self) -> u64 {
+        let q = RANK_BITS as usize;
+        let m = REGISTERS as f64;
+
+        // Number of registers holding each rank, unallocated registers hold zero
+        let mut histogram = [0usize; RANK_BITS as usize + 2];
+        histogram[0] = REGISTERS - self.registers.len();
+        for register in &self.registers {
+            histogram[(*register as usize).min(q + 1)] += 1;
+        }
+
+        let mut z = m * tau((m - histogram[q + 1] as f64) / m);
+        for count in histogram[1..=q].iter().rev() {
+            z += *count as f64;
+            z *= 0.5;
+        }
+        z += m * sigma(histogram[0] as f64 / m);
+
+        (m * m / (2.0 * std::f64::consts::LN_2 * z)).round() as u64
+    }
+
+    /// Gets the registers, allocating them if they have not been
+    fn registers_mut(&mut self) -> &mut [u8] {
+        if self.registers.len() != REGISTERS {
+            self.registers.resize(REGISTERS, 0);
+        }
+
+        &mut self.registers
+    }
+}
+
+/// Correction for registers which have not been set, see [`HyperLogLog::count`]
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+
+        if z == previous {
+            return z;
+        }
+    }
+}
+
+/// Correction for registers which have reached the maximum rank, see [`HyperLogLog::count`]
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let previous = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+
+        if z == previous {
+            return z / 3.0;
+        }
+    }
+}
+
+impl MemoryUsage for HyperLogLog {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.registers.capacity()
+    }
+}
+
+impl MemStore {
+    /// Adds elements to a HyperLogLog, creating it if it does not exist
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the HyperLogLog was created or its estimate may have changed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// assert!(ms.pfadd("visitors", &["user:1", "user:2"]).unwrap());
+    /// assert!(!ms.pfadd("visitors", &["user:1"]).unwrap());
+    /// ```
+    pub fn pfadd(&mut self, key: &str, elements: &[&str]) -> io::Result<bool> {
+        self.check_type(key, KeyType::HyperLogLog)?;
+        self.reserve_memory()?;
+
+        let created = self.hyperloglogs.get(key).is_none();
+        let hll = self.hyperloglogs.entry(key);
+        let updated = elements.iter().fold(false, |updated, element| {
+            hll.add(element.as_bytes()) | updated
+        });
+
+        Ok(created || updated)
+    }
+
+    /// Estimates the number of unique elements added to the HyperLogLogs at the given keys
+    ///
+    /// When more than one key is given the estimate is for the union of the HyperLogLogs.
+    /// Missing keys are treated as empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.pfadd("visitors:monday", &["user:1", "user:2"]).unwrap();
+    /// ms.pfadd("visitors:tuesday", &["user:2", "user:3"]).unwrap();
+    ///
+    /// assert_eq!(ms.pfcount(&["visitors:monday"]).unwrap(), 2);
+    /// assert_eq!(ms.pfcount(&["visitors:monday", "visitors:tuesday"]).unwrap(), 3);
+    /// ```
+    pub fn pfcount(&self, keys: &[&str]) -> io::Result<u64> {
+        if let [key] = keys {
+            return Ok(self.hyperloglogs.get(key).map_or(0, HyperLogLog::count));
+        }
+
+        Ok(self.union(keys).count())
+    }
+
+    /// Merges the HyperLogLogs at the source keys into the HyperLogLog at `destination`,
+    /// creating it if it does not exist
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.pfadd("visitors:monday", &["user:1", "user:2"]).unwrap();
+    /// ms.pfadd("visitors:tuesday", &["user:2", "user:3"]).unwrap();
+    ///
+    /// ms.pfmerge("visitors:week", &["visitors:monday", "visitors:tuesday"]).unwrap();
+    /// assert_eq!(ms.pfcount(&["visitors:week"]).unwrap(), 3);
+    /// ```
+    pub fn pfmerge(&mut self, destination: &str, sources: &[&str]) -> io::Result<()> {
+        self.check_type(destination, KeyType::HyperLogLog)?;
+
+        let merged = self.union(sources);
+
+        self.reserve_memory()?;
+        self.hyperloglogs.entry(destination).merge(&merged);
+
+        Ok(())
+    }
+
+    /// Merges the HyperLogLogs at the given keys into a new HyperLogLog
+    fn union(&self, keys: &[&str]) -> HyperLogLog {
+        let mut union = HyperLogLog::new();
+        for hll in keys.iter().filter_map(|key| self.hyperloglogs.get(key)) {
+            union.merge(hll);
+        }
+
+        union
+    }
+}
+
+#[cfg(test)]
+mod hyperloglog_tests {
+    use super::*;
+
+    /// Adds `count` unique elements to a new HyperLogLog
+    fn filled(count: usize, prefix: &str) -> HyperLogLog {
+        let mut hll = HyperLogLog::new();
+        for i in 0..count {
+            hll.add(format!("{}:{}", prefix, i).as_bytes());
+        }
+
+        hll
+    }
+
+    fn relative_error(estimate: u64, actual: usize) -> f64 {
+        (estimate as f64 - actual as f64).abs() / actual as f64
+    }
+
+    #[test]
+    fn estimates_are_within_the_standard_error_bound() {
+        assert_eq!(HyperLogLog::new().count(), 0);
+        assert_eq!(filled(1, "element").count(), 1);
+
+        // Small cardinalities are close to exact
+        for actual in [10, 100, 1_000] {
+            let estimate = filled(actual, "element").count();
+            assert!(relative_error(estimate, actual) < HyperLogLog::STANDARD_ERROR);
+        }
+
+        // Allow three standard errors, which the estimate should stay within
+        // more than 99% of the time
+        for actual in [10_000, 50_000, 250_000] {
+            let estimate = filled(actual, "element").count();
+            let error = relative_error(estimate, actual);
+            assert!(
+                error < 3.0 * HyperLogLog::STANDARD_ERROR,
+                "estimate {} for {} elements has error {}",
+                estimate,
+                actual,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn repeated_elements_are_counted_once() {
+        let mut hll = filled(1_000, "element");
+        let registers = hll.clone();
+
+        for i in 0..1_000 {
+            assert!(!hll.add(format!("element:{}", i).as_bytes()));
+        }
+
+        assert_eq!(hll, registers);
+    }
+
+    #[test]
+    fn merges_into_the_union() {
+        let mut hll = filled(20_000, "a");
+        hll.merge(&filled(20_000, "b"));
+        hll.merge(&filled(10_000, "a"));
+        hll.merge(&HyperLogLog::new());
+
+        let error = relative_error(hll.count(), 40_000);
+        assert!(error < 3.0 * HyperLogLog::STANDARD_ERROR);
+    }
+
+    #[test]
+    fn counts_and_merges_keys() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        assert!(ms.pfadd("empty", &[])?);
+        assert!(!ms.pfadd("empty", &[])?);
+        assert_eq!(ms.pfcount(&["empty", "missing"])?, 0);
+
+        assert!(ms.pfadd("a", &["1", "2", "3"])?);
+        assert!(ms.pfadd("b", &["3", "4"])?);
+        assert_eq!(ms.pfcount(&["a", "b"])?, 4);
+
+        ms.pfadd("dest", &["5"])?;
+        ms.pfmerge("dest", &["a", "b", "missing"])?;
+        assert_eq!(ms.pfcount(&["dest"])?, 5);
+        assert_eq!(ms.pfcount(&["a"])?, 3);
+
+        ms.insert_string("text", "value")?;
+        assert!(ms.pfadd("text", &["1"]).is_err());
+        assert!(ms.pfmerge("text", &["a"]).is_err());
+        assert_eq!(ms.key_type("dest")?, Some(KeyType::HyperLogLog));
+
+        Ok(())
+    }
+
+    #[test]
+    fn hyperloglogs_serialise() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        for i in 0..1_000 {
+            ms.pfadd("visitors", &[&format!("user:{}", i)])?;
+        }
+        let estimate = ms.pfcount(&["visitors"])?;
+
+        let contents = serde_json::to_string(&ms)?;
+        let loaded: MemStore = serde_json::from_str(&contents)?;
+        assert_eq!(loaded.pfcount(&["visitors"])?, estimate);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,714 @@
+//! JSON document operations for the [`MemStore`]
+//!
+//! Documents are stored parsed, so parts of a document can be read and updated in place on
+//! the server rather than fetching, modifying and rewriting the whole value.
+//!
+//! Parts of a document are selected with a JSONPath-like syntax starting from the root `$`:
+//!
+//! * `.name` or `['name']`: Member of an object
+//! * `[index]`: Element of an array, negative indices count back from the end
+//! * `.*` or `[*]`: Every member of an object or element of an array
+//! * `..name`: Member with the given name at any depth
+//!
+//! A path can match any number of values, so reads return an array of every match and
+//! updates apply to each match in turn.
+
+use serde_json::{Number, Value};
+
+use std::io;
+use std::mem::size_of;
+
+use crate::store::mem::{KeyType, MemStore};
+use crate::store::MemoryUsage;
+
+/// A single step of a parsed path
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    /// Member of an object
+    Member(String),
+
+    /// Element of an array
+    Index(isize),
+
+    /// Every member or element
+    Wildcard,
+
+    /// Member with the given name at any depth
+    Descendant(String),
+}
+
+/// Location of a single value within a document
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Step {
+    /// Member of an object
+    Member(String),
+
+    /// Element of an array
+    Index(usize),
+}
+
+/// Parses a path into its segments
+fn parse_path(path: &str) -> io::Result<Vec<Segment>> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid path: {}", path),
+        )
+    };
+
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = vec![];
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            match &after[..end] {
+                "" => return Err(invalid()),
+                "*" => segments.push(Segment::Descendant(String::new())),
+                name => segments.push(Segment::Descendant(name.to_string())),
+            }
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            match &after[..end] {
+                "" => return Err(invalid()),
+                "*" => segments.push(Segment::Wildcard),
+                name => segments.push(Segment::Member(name.to_string())),
+            }
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            if let Some(quote) = after.chars().next().filter(|c| *c == '\'' || *c == '"') {
+                let (name, after) = after[1..].split_once(quote).ok_or_else(invalid)?;
+                segments.push(Segment::Member(name.to_string()));
+                rest = after.strip_prefix(']').ok_or_else(invalid)?;
+            } else {
+                let (inner, after) = after.split_once(']').ok_or_else(invalid)?;
+                segments.push(match inner.trim() {
+                    "*" => Segment::Wildcard,
+                    index => Segment::Index(index.parse().map_err(|_| invalid())?),
+                });
+                rest = after;
+            }
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Finds the locations of every value in a document matched by a path
+fn find(root: &Value, segments: &[Segment]) -> Vec<Vec<Step>> {
+    let mut current = vec![(vec![], root)];
+    for segment in segments {
+        let mut next = vec![];
+        for (steps, value) in current {
+            match segment {
+                Segment::Descendant(name) => {
+                    descendants(steps, value, &mut |steps, value| {
+                        select(steps, value, name, &mut next);
+                    });
+                }
+                Segment::Member(name) => select(steps, value, name, &mut next),
+                Segment::Wildcard => children(steps, value, &mut next),
+                Segment::Index(index) => {
+                    let Value::Array(values) = value else {
+                        continue;
+                    };
+                    let len = values.len() as isize;
+                    let index = if *index < 0 { len + index } else { *index };
+                    if (0..len).contains(&index) {
+                        let mut steps = steps.clone();
+                        steps.push(Step::Index(index as usize));
+                        next.push((steps, &values[index as usize]));
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+
+    current.into_iter().map(|(steps, _)| steps).collect()
+}
+
+/// Selects a member of an object by name, or every child if the name is empty
+fn select<'a>(
+    steps: Vec<Step>,
+    value: &'a Value,
+    name: &str,
+    selected: &mut Vec<(Vec<Step>, &'a Value)>,
+) {
+    if name.is_empty() {
+        return children(steps, value, selected);
+    }
+
+    if let Some(member) = value.as_object().and_then(|object| object.get(name)) {
+        let mut steps = steps;
+        steps.push(Step::Member(name.to_string()));
+        selected.push((steps, member));
+    }
+}
+
+/// Selects every member of an object or element of an array
+fn children<'a>(steps: Vec<Step>, value: &'a Value, selected: &mut Vec<(Vec<Step>, &'a Value)>) {
+    let step = |step: Step| {
+        let mut steps = steps.clone();
+        steps.push(step);
+        steps
+    };
+
+    match value {
+        Value::Object(object) => selected.extend(
+            object
+                .iter()
+                .map(|(name, member)| (step(Step::Member(name.clone())), member)),
+        ),
+        Value::Array(values) => selected.extend(
+            values
+                .iter()
+                .enumerate()
+                .map(|(index, element)| (step(Step::Index(index)), element)),
+        ),
+        _ => {}
+    }
+}
+
+/// Visits a value and every value nested within it
+fn descendants<'a>(
+    steps: Vec<Step>,
+    value: &'a Value,
+    visit: &mut impl FnMut(Vec<Step>, &'a Value),
+) {
+    let mut nested = vec![];
+    children(steps.clone(), value, &mut nested);
+
+    visit(steps, value);
+    for (steps, value) in nested {
+        descendants(steps, value, visit);
+    }
+}
+
+/// Gets the value at a location within a document
+fn resolve<'a>(root: &'a Value, steps: &[Step]) -> Option<&'a Value> {
+    steps.iter().try_fold(root, |value, step| match step {
+        Step::Member(name) => value.get(name),
+        Step::Index(index) => value.get(index),
+    })
+}
+
+/// Gets the value at a location within a document mutably
+fn resolve_mut<'a>(root: &'a mut Value, steps: &[Step]) -> Option<&'a mut Value> {
+    steps.iter().try_fold(root, |value, step| match step {
+        Step::Member(name) => value.get_mut(name),
+        Step::Index(index) => value.get_mut(index),
+    })
+}
+
+/// Parses a JSON value
+fn parse_value(value: &str) -> io::Result<Value> {
+    serde_json::from_str(value).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid JSON value: {}", e),
+        )
+    })
+}
+
+/// Error returned when a document is not present
+fn no_such_key() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no such key")
+}
+
+impl MemoryUsage for Value {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + match self {
+                Value::String(value) => value.capacity(),
+                Value::Array(values) => values.iter().map(MemoryUsage::memory_usage).sum(),
+                Value::Object(object) => object
+                    .iter()
+                    .map(|(name, member)| name.memory_usage() + member.memory_usage())
+                    .sum(),
+                _ => 0,
+            }
+    }
+}
+
+/// Adds an increment to a number, keeping integers as integers where the result fits
+fn add_numbers(number: &Number, increment: f64) -> io::Result<Number> {
+    let integer = (increment.fract() == 0.0)
+        .then_some(increment as i64)
+        .and_then(|increment| number.as_i64()?.checked_add(increment));
+    if let Some(result) = integer {
+        return Ok(Number::from(result));
+    }
+
+    number
+        .as_f64()
+        .map(|number| number + increment)
+        .and_then(Number::from_f64)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "increment would produce an invalid number",
+            )
+        })
+}
+
+impl MemStore {
+    /// Sets the values matched by a path in a JSON document
+    ///
+    /// The root path `$` replaces the whole document, creating it if it does not exist. Any
+    /// other path updates the values it matches, or adds a new member if the path ends with
+    /// a member name which is missing from an existing object.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: Key of the document
+    /// * `path`: Path of the values to set
+    /// * `value`: JSON text of the new value
+    ///
+    /// # Returns
+    ///
+    /// * `true` if any value was set
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the path or value is invalid, or a document is
+    ///   created at a path other than the root
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.json_set("user:1000", "$", r#"{"name": "rubin", "address": {"city": "Glasgow"}}"#).unwrap();
+    ///
+    /// ms.json_set("user:1000", "$.address.city", r#""Edinburgh""#).unwrap();
+    /// ms.json_set("user:1000", "$.age", "3").unwrap();
+    ///
+    /// let user = ms.json_get("user:1000", "$").unwrap().unwrap();
+    /// assert_eq!(user, r#"[{"address":{"city":"Edinburgh"},"age":3,"name":"rubin"}]"#);
+    /// ```
+    pub fn json_set(&mut self, key: &str, path: &str, value: &str) -> io::Result<bool> {
+        let segments = parse_path(path)?;
+        let value = parse_value(value)?;
+
+        self.check_type(key, KeyType::Json)?;
+        if segments.is_empty() {
+            self.reserve_memory()?;
+            *self.documents.entry(key) = value;
+            return Ok(true);
+        }
+
+        let Some(document) = self.documents.get(key) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "new documents must be created at the root",
+            ));
+        };
+
+        let mut matches = find(document, &segments);
+        let mut created = None;
+        if matches.is_empty() {
+            // Add a new member to the objects matched by the rest of the path
+            let Some((Segment::Member(name), parent)) = segments.split_last() else {
+                return Ok(false);
+            };
+            matches = find(document, parent)
+                .into_iter()
+                .filter(|steps| resolve(document, steps).is_some_and(Value::is_object))
+                .collect();
+            created = Some(name);
+        }
+
+        if matches.is_empty() {
+            return Ok(false);
+        }
+
+        self.reserve_memory()?;
+        let document = self.documents.get_mut(key).ok_or_else(no_such_key)?;
+        for steps in &matches {
+            let Some(target) = resolve_mut(document, steps) else {
+                continue;
+            };
+
+            match (created, target) {
+                (Some(name), Value::Object(object)) => {
+                    object.insert(name.clone(), value.clone());
+                }
+                (None, target) => *target = value.clone(),
+                _ => {}
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Gets the values matched by a path in a JSON document
+    ///
+    /// # Returns
+    ///
+    /// * JSON text of an array of every matched value, `None` if the document is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.json_set("order:1", "$", r#"{"items": [{"sku": "a1", "qty": 2}, {"sku": "b2", "qty": 1}]}"#).unwrap();
+    ///
+    /// assert_eq!(ms.json_get("order:1", "$.items[*].sku").unwrap().unwrap(), r#"["a1","b2"]"#);
+    /// assert_eq!(ms.json_get("order:1", "$..qty").unwrap().unwrap(), "[2,1]");
+    /// assert_eq!(ms.json_get("order:1", "$.items[-1]['qty']").unwrap().unwrap(), "[1]");
+    /// assert_eq!(ms.json_get("order:2", "$").unwrap(), None);
+    /// ```
+    pub fn json_get(&self, key: &str, path: &str) -> io::Result<Option<String>> {
+        let segments = parse_path(path)?;
+        let Some(document) = self.documents.get(key) else {
+            return Ok(None);
+        };
+
+        let values: Vec<&Value> = find(document, &segments)
+            .iter()
+            .filter_map(|steps| resolve(document, steps))
+            .collect();
+
+        Ok(Some(serde_json::to_string(&values)?))
+    }
+
+    /// Removes the values matched by a path from a JSON document
+    ///
+    /// Removing the root removes the whole document.
+    ///
+    /// # Returns
+    ///
+    /// * The number of values removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.json_set("user:1000", "$", r#"{"name": "rubin", "tags": ["a", "b", "c"]}"#).unwrap();
+    ///
+    /// assert_eq!(ms.json_del("user:1000", "$.tags[0]").unwrap(), 1);
+    /// assert_eq!(ms.json_get("user:1000", "$.tags").unwrap().unwrap(), r#"[["b","c"]]"#);
+    ///
+    /// assert_eq!(ms.json_del("user:1000", "$").unwrap(), 1);
+    /// assert_eq!(ms.json_get("user:1000", "$").unwrap(), None);
+    /// ```
+    pub fn json_del(&mut self, key: &str, path: &str) -> io::Result<usize> {
+        let segments = parse_path(path)?;
+        self.check_type(key, KeyType::Json)?;
+
+        if segments.is_empty() {
+            return Ok(self.documents.delete(key) as usize);
+        }
+
+        let Some(document) = self.documents.get(key) else {
+            return Ok(0);
+        };
+
+        // Remove the last elements of each array first so earlier indices stay valid
+        let mut matches = find(document, &segments);
+        matches.sort();
+        matches.dedup();
+
+        let document = self.documents.get_mut(key).ok_or_else(no_such_key)?;
+        let mut removed = 0;
+        for steps in matches.iter().rev() {
+            let Some((last, parent)) = steps.split_last() else {
+                continue;
+            };
+
+            let found = match (resolve_mut(document, parent), last) {
+                (Some(Value::Object(object)), Step::Member(name)) => object.remove(name).is_some(),
+                (Some(Value::Array(values)), Step::Index(index)) if *index < values.len() => {
+                    values.remove(*index);
+                    true
+                }
+                _ => false,
+            };
+            removed += found as usize;
+        }
+
+        Ok(removed)
+    }
+
+    /// Appends values to the arrays matched by a path in a JSON document
+    ///
+    /// # Returns
+    ///
+    /// * The new length of each matched array, `None` for matches which are not arrays
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the document is not present
+    /// * [`io::ErrorKind::InvalidInput`] if the path or a value is invalid
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.json_set("user:1000", "$", r#"{"name": "rubin", "tags": ["cache"]}"#).unwrap();
+    ///
+    /// let lengths = ms.json_arrappend("user:1000", "$.tags", &[r#""rust""#, r#""redis""#]).unwrap();
+    /// assert_eq!(lengths, vec![Some(3)]);
+    ///
+    /// let lengths = ms.json_arrappend("user:1000", "$.name", &["1"]).unwrap();
+    /// assert_eq!(lengths, vec![None]);
+    /// ```
+    pub fn json_arrappend(
+        &mut self,
+        key: &str,
+        path: &str,
+        values: &[&str],
+    ) -> io::Result<Vec<Option<usize>>> {
+        let segments = parse_path(path)?;
+        let values = values
+            .iter()
+            .map(|value| parse_value(value))
+            .collect::<io::Result<Vec<Value>>>()?;
+
+        self.check_type(key, KeyType::Json)?;
+        let document = self.documents.get(key).ok_or_else(no_such_key)?;
+        let matches = find(document, &segments);
+
+        self.reserve_memory()?;
+        let document = self.documents.get_mut(key).ok_or_else(no_such_key)?;
+
+        Ok(matches
+            .iter()
+            .map(|steps| match resolve_mut(document, steps) {
+                Some(Value::Array(array)) => {
+                    array.extend(values.iter().cloned());
+                    Some(array.len())
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Increments the numbers matched by a path in a JSON document
+    ///
+    /// Integers stay integers when incremented by a whole number which does not overflow.
+    ///
+    /// # Returns
+    ///
+    /// * JSON text of an array of the new value of each match, `null` for matches which are
+    ///   not numbers
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the document is not present
+    /// * [`io::ErrorKind::InvalidInput`] if the path is invalid or the result is not a finite
+    ///   number
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.json_set("product:1", "$", r#"{"stock": 10, "price": 2.5, "name": "widget"}"#).unwrap();
+    ///
+    /// assert_eq!(ms.json_numincrby("product:1", "$.stock", -3.0).unwrap(), "[7]");
+    /// assert_eq!(ms.json_numincrby("product:1", "$.price", 0.25).unwrap(), "[2.75]");
+    /// assert_eq!(ms.json_numincrby("product:1", "$.name", 1.0).unwrap(), "[null]");
+    /// ```
+    pub fn json_numincrby(&mut self, key: &str, path: &str, increment: f64) -> io::Result<String> {
+        let segments = parse_path(path)?;
+
+        self.check_type(key, KeyType::Json)?;
+        let document = self.documents.get(key).ok_or_else(no_such_key)?;
+        let matches = find(document, &segments);
+
+        // Work out every result first so a failed increment leaves the document unchanged
+        let results = matches
+            .iter()
+            .map(|steps| match resolve(document, steps) {
+                Some(Value::Number(number)) => add_numbers(number, increment).map(Some),
+                _ => Ok(None),
+            })
+            .collect::<io::Result<Vec<Option<Number>>>>()?;
+
+        let document = self.documents.get_mut(key).ok_or_else(no_such_key)?;
+        let mut updated = vec![];
+        for (steps, result) in matches.iter().zip(results) {
+            match (result, resolve_mut(document, steps)) {
+                (Some(number), Some(target)) => {
+                    *target = Value::Number(number.clone());
+                    updated.push(Value::Number(number));
+                }
+                _ => updated.push(Value::Null),
+            }
+        }
+
+        Ok(serde_json::to_string(&updated)?)
+    }
+}
+
+#[cfg(test)]
+mod json_tests {
+    use super::*;
+
+    fn document() -> MemStore {
+        let mut ms = MemStore::new();
+        ms.json_set(
+            "doc",
+            "$",
+            r#"{
+                "name": "rubin",
+                "owner": {"name": "graham", "langs": ["rust", "python"]},
+                "releases": [{"version": "0.3.0", "downloads": 10}, {"version": "0.4.0", "downloads": 25}]
+            }"#,
+        )
+        .unwrap();
+
+        ms
+    }
+
+    #[test]
+    fn parses_paths() -> io::Result<()> {
+        assert_eq!(parse_path("$")?, vec![]);
+        assert_eq!(
+            parse_path("$.owner['langs'][-1]")?,
+            vec![
+                Segment::Member("owner".to_string()),
+                Segment::Member("langs".to_string()),
+                Segment::Index(-1),
+            ]
+        );
+        assert_eq!(
+            parse_path("$..name.*[*][\"a.b\"]")?,
+            vec![
+                Segment::Descendant("name".to_string()),
+                Segment::Wildcard,
+                Segment::Wildcard,
+                Segment::Member("a.b".to_string()),
+            ]
+        );
+
+        for path in ["", "name", "$.", "$[", "$[abc]", "$['name'", "$..", "$name"] {
+            let err = parse_path(path).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{}", path);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn gets_values_by_path() -> io::Result<()> {
+        let ms = document();
+
+        assert_eq!(ms.json_get("doc", "$.name")?.unwrap(), r#"["rubin"]"#);
+        assert_eq!(
+            ms.json_get("doc", "$..name")?.unwrap(),
+            r#"["rubin","graham"]"#
+        );
+        assert_eq!(
+            ms.json_get("doc", "$.owner.langs[1]")?.unwrap(),
+            r#"["python"]"#
+        );
+        assert_eq!(
+            ms.json_get("doc", "$.releases[*].version")?.unwrap(),
+            r#"["0.3.0","0.4.0"]"#
+        );
+        assert_eq!(ms.json_get("doc", "$.releases[5]")?.unwrap(), "[]");
+        assert_eq!(ms.json_get("doc", "$.missing.name")?.unwrap(), "[]");
+        assert_eq!(ms.json_get("missing", "$")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sets_values_by_path() -> io::Result<()> {
+        let mut ms = document();
+
+        assert!(ms.json_set("doc", "$.releases[*].downloads", "0")?);
+        assert_eq!(ms.json_get("doc", "$..downloads")?.unwrap(), "[0,0]");
+
+        // Missing members are added to existing objects
+        assert!(ms.json_set("doc", "$.owner.email", r#""g@example.com""#)?);
+        assert_eq!(
+            ms.json_get("doc", "$.owner.email")?.unwrap(),
+            r#"["g@example.com"]"#
+        );
+        assert!(!ms.json_set("doc", "$.missing.email", "1")?);
+        assert!(!ms.json_set("doc", "$.owner.langs[9]", "1")?);
+
+        let err = ms.json_set("new", "$.name", "1").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(ms.json_set("doc", "$.name", "{invalid").is_err());
+
+        ms.expire("doc", 60)?;
+        ms.json_set("doc", "$", "[]")?;
+        assert!(ms.ttl("doc")? > 0);
+        assert_eq!(ms.key_type("doc")?, Some(KeyType::Json));
+
+        ms.rpush("list", &["value"])?;
+        assert!(ms.json_set("list", "$", "1").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn deletes_values_by_path() -> io::Result<()> {
+        let mut ms = document();
+
+        assert_eq!(ms.json_del("doc", "$.owner.langs[*]")?, 2);
+        assert_eq!(ms.json_get("doc", "$.owner.langs")?.unwrap(), "[[]]");
+        assert_eq!(ms.json_del("doc", "$..name")?, 2);
+        assert_eq!(ms.json_del("doc", "$.missing")?, 0);
+        assert_eq!(ms.json_del("missing", "$.name")?, 0);
+
+        assert_eq!(ms.json_del("doc", "$")?, 1);
+        assert_eq!(ms.exists(&["doc"])?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn updates_arrays_and_numbers() -> io::Result<()> {
+        let mut ms = document();
+
+        assert_eq!(
+            ms.json_arrappend("doc", "$..langs", &[r#""go""#, r#"{"name": "zig"}"#])?,
+            vec![Some(4)]
+        );
+        assert_eq!(
+            ms.json_get("doc", "$.owner.langs[-1].name")?.unwrap(),
+            r#"["zig"]"#
+        );
+        assert_eq!(ms.json_arrappend("doc", "$.name", &["1"])?, vec![None]);
+
+        assert_eq!(
+            ms.json_numincrby("doc", "$.releases[*].downloads", 5.0)?,
+            "[15,30]"
+        );
+        assert_eq!(
+            ms.json_numincrby("doc", "$.releases[0].downloads", 0.5)?,
+            "[15.5]"
+        );
+        assert_eq!(ms.json_numincrby("doc", "$..version", 1.0)?, "[null,null]");
+
+        ms.json_set("big", "$", &i64::MAX.to_string())?;
+        assert_eq!(
+            ms.json_numincrby("big", "$", 1.0)?,
+            "[9.223372036854776e18]"
+        );
+        ms.json_set("big", "$", "1e308")?;
+        assert!(ms.json_numincrby("big", "$", 1e308).is_err());
+
+        let err = ms.json_arrappend("missing", "$", &["1"]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(ms.json_numincrby("missing", "$", 1.0).is_err());
+
+        Ok(())
+    }
+}
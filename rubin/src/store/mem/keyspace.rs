@@ -0,0 +1,493 @@
+//! Keyspace operations for the [`MemStore`]
+//!
+//! Each value type is held in its own inner store, but keys share a single keyspace: a key
+//! can only hold one type of value at a time. Writes which would create a key of a different
+//! type to the one already present are rejected, except for operations which replace the
+//! value outright (such as [`MemStore::insert_string`]).
+//!
+//! The operations here work on keys regardless of the type of value they hold.
+
+use std::fmt;
+use std::io;
+
+use crate::store::glob::glob_match;
+use crate::store::mem::MemStore;
+
+/// Type of value held by a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    /// Held in the string store
+    String,
+
+    /// Held in the counter store
+    Counter,
+
+    /// Held in the HyperLogLog store
+    HyperLogLog,
+
+    /// Held in the list store
+    List,
+
+    /// Held in the hash store
+    Hash,
+
+    /// Held in the set store
+    Set,
+
+    /// Held in the sorted set store
+    SortedSet,
+
+    /// Held in the stream store
+    Stream,
+
+    /// Held in the JSON document store
+    Json,
+
+    /// Held in the typed value store
+    Typed,
+}
+
+impl fmt::Display for KeyType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::String => write!(f, "string"),
+            Self::Counter => write!(f, "counter"),
+            Self::HyperLogLog => write!(f, "hyperloglog"),
+            Self::List => write!(f, "list"),
+            Self::Hash => write!(f, "hash"),
+            Self::Set => write!(f, "set"),
+            Self::SortedSet => write!(f, "zset"),
+            Self::Stream => write!(f, "stream"),
+            Self::Json => write!(f, "json"),
+            Self::Typed => write!(f, "typed"),
+        }
+    }
+}
+
+/// Type of value held by each store, in the order returned by `keyed_stores`
+const STORE_TYPES: [KeyType; 10] = [
+    KeyType::String,
+    KeyType::Counter,
+    KeyType::HyperLogLog,
+    KeyType::List,
+    KeyType::Hash,
+    KeyType::Set,
+    KeyType::SortedSet,
+    KeyType::Stream,
+    KeyType::Json,
+    KeyType::Typed,
+];
+
+impl MemStore {
+    /// Gets the type of value held by a key
+    ///
+    /// # Returns
+    ///
+    /// * The type of the value, `None` if the key is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{KeyType, MemStore};
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["job-1"]).unwrap();
+    ///
+    /// assert_eq!(ms.key_type("queue").unwrap(), Some(KeyType::List));
+    /// assert_eq!(ms.key_type("missing").unwrap(), None);
+    /// ```
+    pub fn key_type(&self, key: &str) -> io::Result<Option<KeyType>> {
+        let key_type = self
+            .keyed_stores()
+            .iter()
+            .zip(STORE_TYPES)
+            .find(|(store, _)| store.contains_key(key))
+            .map(|(_, key_type)| key_type);
+
+        Ok(key_type)
+    }
+
+    /// Counts how many of the given keys are present
+    ///
+    /// Keys given more than once are counted each time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// ms.incr("visits").unwrap();
+    ///
+    /// assert_eq!(ms.exists(&["user:1000", "visits", "missing"]).unwrap(), 2);
+    /// ```
+    pub fn exists(&self, keys: &[&str]) -> io::Result<usize> {
+        let stores = self.keyed_stores();
+        let count = keys
+            .iter()
+            .filter(|key| stores.iter().any(|store| store.contains_key(key)))
+            .count();
+
+        Ok(count)
+    }
+
+    /// Removes keys regardless of the type of value they hold
+    ///
+    /// # Returns
+    ///
+    /// * The number of keys that were removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// ms.sadd("tags", &["rust"]).unwrap();
+    ///
+    /// assert_eq!(ms.del(&["user:1000", "tags", "missing"]).unwrap(), 2);
+    /// assert_eq!(ms.exists(&["user:1000", "tags"]).unwrap(), 0);
+    /// ```
+    pub fn del(&mut self, keys: &[&str]) -> io::Result<usize> {
+        let mut removed = 0;
+        for key in keys {
+            let mut deleted = false;
+            for store in self.keyed_stores_mut() {
+                deleted |= store.delete_key(key);
+            }
+
+            if deleted {
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Renames a key, replacing any value already held by the new key
+    ///
+    /// The expiry of the key is kept.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the key is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("jobs:pending", &["job-1"]).unwrap();
+    ///
+    /// ms.rename("jobs:pending", "jobs:active").unwrap();
+    /// assert_eq!(ms.llen("jobs:active").unwrap(), 1);
+    /// assert!(ms.rename("jobs:pending", "jobs:active").is_err());
+    /// ```
+    pub fn rename(&mut self, key: &str, new_key: &str) -> io::Result<()> {
+        let Some(key_type) = self.key_type(key)? else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "no such key"));
+        };
+
+        if key == new_key {
+            return Ok(());
+        }
+
+        for (store, store_type) in self.keyed_stores_mut().into_iter().zip(STORE_TYPES) {
+            if store_type == key_type {
+                store.rename_key(key, new_key);
+            } else {
+                store.delete_key(new_key);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Renames a key only if the new key is not present
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the key was renamed, `false` if the new key is already present
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the key is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// ms.insert_string("user:2000", "value").unwrap();
+    ///
+    /// assert!(!ms.renamenx("user:1000", "user:2000").unwrap());
+    /// assert!(ms.renamenx("user:1000", "user:3000").unwrap());
+    /// ```
+    pub fn renamenx(&mut self, key: &str, new_key: &str) -> io::Result<bool> {
+        if self.key_type(key)?.is_none() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "no such key"));
+        }
+
+        if self.exists(&[new_key])? > 0 {
+            return Ok(false);
+        }
+
+        self.rename(key, new_key)?;
+
+        Ok(true)
+    }
+
+    /// Moves a key with its value and expiry into another store
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the key was moved, `false` if it is not present or the other store already
+    ///   holds the key
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut staging = MemStore::new();
+    /// let mut production = MemStore::new();
+    /// staging.hset("config", &[("timeout", "30")]).unwrap();
+    ///
+    /// assert!(staging.move_key("config", &mut production).unwrap());
+    /// assert_eq!(production.hget("config", "timeout").unwrap().as_deref(), Some("30"));
+    /// assert!(!staging.move_key("config", &mut production).unwrap());
+    /// ```
+    pub fn move_key(&mut self, key: &str, target: &mut MemStore) -> io::Result<bool> {
+        if self.exists(&[key])? == 0 || target.exists(&[key])? > 0 {
+            return Ok(false);
+        }
+
+        target.reserve_memory()?;
+        let moved = self.strings.transfer(key, &mut target.strings)
+            || self.counters.transfer(key, &mut target.counters)
+            || self.hyperloglogs.transfer(key, &mut target.hyperloglogs)
+            || self.lists.transfer(key, &mut target.lists)
+            || self.hashes.transfer(key, &mut target.hashes)
+            || self.sets.transfer(key, &mut target.sets)
+            || self.zsets.transfer(key, &mut target.zsets)
+            || self.streams.transfer(key, &mut target.streams)
+            || self.documents.transfer(key, &mut target.documents)
+            || self.typed.transfer(key, &mut target.typed);
+
+        Ok(moved)
+    }
+
+    /// Removes every key from the store, keeping its memory limit and eviction policy
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// ms.rpush("queue", &["job-1"]).unwrap();
+    ///
+    /// ms.flush();
+    /// assert_eq!(ms.keys("*").unwrap().len(), 0);
+    /// ```
+    pub fn flush(&mut self) {
+        *self = Self {
+            max_memory: self.max_memory,
+            eviction_policy: self.eviction_policy,
+            ..Self::default()
+        };
+    }
+
+    /// Gets every key matching a glob-style pattern, sorted alphabetically
+    ///
+    /// Patterns support `*`, `?`, `[...]` character classes and `\` escapes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// ms.hset("user:2000", &[("name", "rubin")]).unwrap();
+    /// ms.insert_string("session:1000", "token").unwrap();
+    ///
+    /// assert_eq!(ms.keys("user:*").unwrap(), vec!["user:1000", "user:2000"]);
+    /// assert_eq!(ms.keys("*:1000").unwrap(), vec!["session:1000", "user:1000"]);
+    /// ```
+    pub fn keys(&self, pattern: &str) -> io::Result<Vec<String>> {
+        let mut keys: Vec<String> = self
+            .keyed_stores()
+            .iter()
+            .flat_map(|store| store.keys())
+            .filter(|key| glob_match(pattern, key))
+            .map(|key| key.to_string())
+            .collect();
+
+        keys.sort();
+        keys.dedup();
+
+        Ok(keys)
+    }
+
+    /// Ensures a key is either not present or holds the expected type of value
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the key holds a different type of value
+    pub(crate) fn check_type(&self, key: &str, expected: KeyType) -> io::Result<()> {
+        match self.key_type(key)? {
+            Some(key_type) if key_type != expected => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "operation against a key holding the wrong kind of value ({})",
+                    key_type
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes a key from every store other than the one holding the given type, used
+    /// before a value of that type replaces whatever the key held
+    pub(crate) fn replace_type(&mut self, key: &str, key_type: KeyType) {
+        for (store, store_type) in self.keyed_stores_mut().into_iter().zip(STORE_TYPES) {
+            if store_type != key_type && store.contains_key(key) {
+                store.delete_key(key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod keyspace_tests {
+    use super::*;
+
+    #[test]
+    fn reports_key_types() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_string("string", "value")?;
+        ms.incr("counter")?;
+        ms.pfadd("hyperloglog", &["a"])?;
+        ms.rpush("list", &["a"])?;
+        ms.hset("hash", &[("field", "value")])?;
+        ms.sadd("set", &["a"])?;
+        ms.zadd("zset", &[(1.0, "a")])?;
+        ms.xadd("stream", None, &[("field", "value")])?;
+        ms.json_set("json", "$", "{}")?;
+        ms.insert_typed("typed", &vec![1, 2])?;
+
+        for (key, key_type) in [
+            ("string", KeyType::String),
+            ("counter", KeyType::Counter),
+            ("hyperloglog", KeyType::HyperLogLog),
+            ("list", KeyType::List),
+            ("hash", KeyType::Hash),
+            ("set", KeyType::Set),
+            ("zset", KeyType::SortedSet),
+            ("stream", KeyType::Stream),
+            ("json", KeyType::Json),
+            ("typed", KeyType::Typed),
+        ] {
+            assert_eq!(ms.key_type(key)?, Some(key_type));
+            assert_eq!(key_type.to_string(), key);
+        }
+
+        assert_eq!(ms.key_type("missing")?, None);
+        assert_eq!(ms.exists(&["string", "zset", "string", "missing"])?, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn enforces_one_type_per_key() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("key", &["a"])?;
+
+        let err = ms.sadd("key", &["a"]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(ms.incr("key").is_err());
+        assert!(ms.hset("key", &[("field", "value")]).is_err());
+        assert!(ms.zadd("key", &[(1.0, "a")]).is_err());
+        assert!(ms.incrbyfloat("key", 1.0).is_err());
+        assert_eq!(ms.key_type("key")?, Some(KeyType::List));
+
+        // Setting a string replaces the value whatever its type
+        ms.insert_string("key", "value")?;
+        assert_eq!(ms.key_type("key")?, Some(KeyType::String));
+        assert_eq!(ms.llen("key")?, 0);
+
+        // Expired keys do not hold a type
+        ms.pexpire("key", 0)?;
+        ms.sadd("key", &["a"])?;
+        assert_eq!(ms.key_type("key")?, Some(KeyType::Set));
+
+        Ok(())
+    }
+
+    #[test]
+    fn deletes_keys_of_any_type() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_string("string", "value")?;
+        ms.zadd("zset", &[(1.0, "a")])?;
+        ms.incr("counter")?;
+
+        assert_eq!(ms.del(&["string", "zset", "missing"])?, 2);
+        assert_eq!(ms.exists(&["string", "zset", "counter"])?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn renames_keys() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.hset("hash", &[("field", "value")])?;
+        ms.expire("hash", 60)?;
+        ms.insert_string("string", "value")?;
+
+        // The value and expiry move to the new key, replacing what was there
+        ms.rename("hash", "string")?;
+        assert_eq!(ms.key_type("hash")?, None);
+        assert_eq!(ms.key_type("string")?, Some(KeyType::Hash));
+        assert_eq!(ms.hget("string", "field")?.as_deref(), Some("value"));
+        assert_eq!(ms.get_string("string")?, None);
+        assert_eq!(ms.ttl("string")?, 60);
+
+        ms.rename("string", "string")?;
+        assert_eq!(ms.key_type("string")?, Some(KeyType::Hash));
+
+        let err = ms.rename("missing", "other").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        ms.incr("counter")?;
+        assert!(!ms.renamenx("counter", "string")?);
+        assert!(ms.renamenx("counter", "visits")?);
+        assert_eq!(ms.counters.retrieve("visits")?, Some(1));
+        assert!(ms.renamenx("missing", "other").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn finds_keys_matching_a_pattern() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        for i in 0..3 {
+            ms.insert_string(&format!("user:{}", i), "value")?;
+            ms.rpush(&format!("queue:{}", i), &["job"])?;
+        }
+        ms.pexpire("user:2", 0)?;
+
+        assert_eq!(ms.keys("user:*")?, vec!["user:0", "user:1"]);
+        assert_eq!(ms.keys("*:[12]")?, vec!["queue:1", "queue:2", "user:1"]);
+        assert_eq!(ms.keys("*")?.len(), 5);
+        assert!(ms.keys("session:*")?.is_empty());
+
+        Ok(())
+    }
+}
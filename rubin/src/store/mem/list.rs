@@ -0,0 +1,473 @@
+//! List operations for the [`MemStore`]
+//!
+//! Lists are stored as a [`VecDeque`] of strings so items can be pushed and popped
+//! from either end cheaply, making them suitable for use as simple queues.
+//!
+//! Indices follow the Redis conventions where negative values count backwards from
+//! the end of the list (i.e. `-1` is the last item).
+
+use std::collections::VecDeque;
+use std::io;
+
+use crate::store::mem::{KeyType, MemStore};
+
+/// Converts a (possibly negative) index into a position within a collection of `len` items.
+///
+/// Returns `None` if the index falls outside of the collection.
+pub(crate) fn normalise_index(index: isize, len: usize) -> Option<usize> {
+    let len = len as isize;
+    let index = if index < 0 { len + index } else { index };
+
+    if index < 0 || index >= len {
+        return None;
+    }
+
+    Some(index as usize)
+}
+
+/// Converts an inclusive `start` / `stop` range (with negative indices counting from the end)
+/// into a bounded range within a collection of `len` items.
+///
+/// Returns `None` if the range does not cover any items.
+pub(crate) fn normalise_range(start: isize, stop: isize, len: usize) -> Option<(usize, usize)> {
+    let len = len as isize;
+    let start = if start < 0 { len + start } else { start }.max(0);
+    let stop = if stop < 0 { len + stop } else { stop }.min(len - 1);
+
+    if start > stop || start >= len {
+        return None;
+    }
+
+    Some((start as usize, stop as usize))
+}
+
+impl MemStore {
+    /// Pushes values onto the head of a list, creating the list if it does not exist.
+    ///
+    /// Values are pushed one after the other so the last value will be at the head of the list.
+    ///
+    /// # Returns
+    ///
+    /// * The length of the list after the push
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// let length = ms.lpush("queue", &["a", "b", "c"]).unwrap();
+    /// assert_eq!(length, 3);
+    ///
+    /// let items = ms.lrange("queue", 0, -1).unwrap();
+    /// assert_eq!(items, vec!["c", "b", "a"]);
+    /// ```
+    pub fn lpush(&mut self, key: &str, values: &[&str]) -> io::Result<usize> {
+        self.check_type(key, KeyType::List)?;
+        self.reserve_memory()?;
+
+        let list = self.lists.entry(key);
+        for value in values {
+            list.push_front(value.to_string());
+        }
+
+        Ok(list.len())
+    }
+
+    /// Pushes values onto the tail of a list, creating the list if it does not exist.
+    ///
+    /// # Returns
+    ///
+    /// * The length of the list after the push
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// let length = ms.rpush("queue", &["a", "b", "c"]).unwrap();
+    /// assert_eq!(length, 3);
+    ///
+    /// let items = ms.lrange("queue", 0, -1).unwrap();
+    /// assert_eq!(items, vec!["a", "b", "c"]);
+    /// ```
+    pub fn rpush(&mut self, key: &str, values: &[&str]) -> io::Result<usize> {
+        self.check_type(key, KeyType::List)?;
+        self.reserve_memory()?;
+
+        let list = self.lists.entry(key);
+        for value in values {
+            list.push_back(value.to_string());
+        }
+
+        Ok(list.len())
+    }
+
+    /// Removes and returns the value at the head of a list.
+    ///
+    /// The list is removed from the store once it is empty.
+    ///
+    /// # Returns
+    ///
+    /// * The value at the head of the list, `None` if the list is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a", "b"]).unwrap();
+    ///
+    /// let value = ms.lpop("queue").unwrap();
+    /// assert_eq!(value.as_deref(), Some("a"));
+    /// ```
+    pub fn lpop(&mut self, key: &str) -> io::Result<Option<String>> {
+        self.pop(key, VecDeque::pop_front)
+    }
+
+    /// Removes and returns the value at the tail of a list.
+    ///
+    /// The list is removed from the store once it is empty.
+    ///
+    /// # Returns
+    ///
+    /// * The value at the tail of the list, `None` if the list is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a", "b"]).unwrap();
+    ///
+    /// let value = ms.rpop("queue").unwrap();
+    /// assert_eq!(value.as_deref(), Some("b"));
+    /// ```
+    pub fn rpop(&mut self, key: &str) -> io::Result<Option<String>> {
+        self.pop(key, VecDeque::pop_back)
+    }
+
+    /// Returns the values of a list between `start` and `stop` (inclusive).
+    ///
+    /// Negative indices count backwards from the end of the list.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a", "b", "c", "d"]).unwrap();
+    ///
+    /// let items = ms.lrange("queue", 1, -2).unwrap();
+    /// assert_eq!(items, vec!["b", "c"]);
+    /// ```
+    pub fn lrange(&self, key: &str, start: isize, stop: isize) -> io::Result<Vec<String>> {
+        let Some(list) = self.lists.get(key) else {
+            return Ok(vec![]);
+        };
+
+        let Some((start, stop)) = normalise_range(start, stop, list.len()) else {
+            return Ok(vec![]);
+        };
+
+        Ok(list.range(start..=stop).cloned().collect())
+    }
+
+    /// Returns the length of a list, zero if the list is not present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a", "b", "c"]).unwrap();
+    ///
+    /// assert_eq!(ms.llen("queue").unwrap(), 3);
+    /// assert_eq!(ms.llen("missing").unwrap(), 0);
+    /// ```
+    pub fn llen(&self, key: &str) -> io::Result<usize> {
+        Ok(self.lists.get(key).map_or(0, |list| list.len()))
+    }
+
+    /// Returns the value at a given index of a list.
+    ///
+    /// # Returns
+    ///
+    /// * The value at the index, `None` if the index is out of range or the list is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a", "b", "c"]).unwrap();
+    ///
+    /// assert_eq!(ms.lindex("queue", 0).unwrap().as_deref(), Some("a"));
+    /// assert_eq!(ms.lindex("queue", -1).unwrap().as_deref(), Some("c"));
+    /// assert_eq!(ms.lindex("queue", 5).unwrap(), None);
+    /// ```
+    pub fn lindex(&self, key: &str, index: isize) -> io::Result<Option<String>> {
+        let value = self
+            .lists
+            .get(key)
+            .and_then(|list| normalise_index(index, list.len()).map(|idx| list[idx].clone()));
+
+        Ok(value)
+    }
+
+    /// Sets the value at a given index of a list.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the list is not present
+    /// * [`io::ErrorKind::InvalidInput`] if the index is out of range
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a", "b", "c"]).unwrap();
+    ///
+    /// ms.lset("queue", 1, "z").unwrap();
+    /// assert_eq!(ms.lindex("queue", 1).unwrap().as_deref(), Some("z"));
+    /// ```
+    pub fn lset(&mut self, key: &str, index: isize, value: &str) -> io::Result<()> {
+        self.reserve_memory()?;
+
+        let Some(list) = self.lists.get_mut(key) else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "no such key"));
+        };
+
+        let Some(idx) = normalise_index(index, list.len()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "index out of range",
+            ));
+        };
+
+        list[idx] = value.to_string();
+
+        Ok(())
+    }
+
+    /// Trims a list so that it only contains the values between `start` and `stop` (inclusive).
+    ///
+    /// If the range does not cover any values, the list is removed from the store.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a", "b", "c", "d"]).unwrap();
+    ///
+    /// ms.ltrim("queue", 0, 1).unwrap();
+    /// assert_eq!(ms.lrange("queue", 0, -1).unwrap(), vec!["a", "b"]);
+    /// ```
+    pub fn ltrim(&mut self, key: &str, start: isize, stop: isize) -> io::Result<()> {
+        let Some(list) = self.lists.get_mut(key) else {
+            return Ok(());
+        };
+
+        match normalise_range(start, stop, list.len()) {
+            Some((start, stop)) => {
+                list.truncate(stop + 1);
+                list.drain(..start);
+            }
+            None => {
+                self.lists.delete(key);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes occurrences of a value from a list.
+    ///
+    /// * `count > 0`: Remove up to `count` occurrences moving from head to tail
+    /// * `count < 0`: Remove up to `count` occurrences moving from tail to head
+    /// * `count = 0`: Remove all occurrences
+    ///
+    /// # Returns
+    ///
+    /// * The number of values removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.rpush("queue", &["a", "b", "a", "c", "a"]).unwrap();
+    ///
+    /// let removed = ms.lrem("queue", -2, "a").unwrap();
+    /// assert_eq!(removed, 2);
+    /// assert_eq!(ms.lrange("queue", 0, -1).unwrap(), vec!["a", "b", "c"]);
+    /// ```
+    pub fn lrem(&mut self, key: &str, count: isize, value: &str) -> io::Result<usize> {
+        let Some(list) = self.lists.get_mut(key) else {
+            return Ok(0);
+        };
+
+        let limit = match count {
+            0 => usize::MAX,
+            _ => count.unsigned_abs(),
+        };
+
+        let mut positions: Vec<usize> = if count < 0 {
+            (0..list.len())
+                .rev()
+                .filter(|&idx| list[idx] == value)
+                .take(limit)
+                .collect()
+        } else {
+            (0..list.len())
+                .filter(|&idx| list[idx] == value)
+                .take(limit)
+                .collect()
+        };
+
+        // Remove from the back so earlier positions remain valid
+        positions.sort_unstable_by(|a, b| b.cmp(a));
+        for idx in &positions {
+            list.remove(*idx);
+        }
+
+        if list.is_empty() {
+            self.lists.delete(key);
+        }
+
+        Ok(positions.len())
+    }
+
+    /// Pops a value from a list using the given pop function, removing the list once empty
+    fn pop(
+        &mut self,
+        key: &str,
+        pop_fn: fn(&mut VecDeque<String>) -> Option<String>,
+    ) -> io::Result<Option<String>> {
+        let Some(list) = self.lists.get_mut(key) else {
+            return Ok(None);
+        };
+
+        let value = pop_fn(list);
+        if list.is_empty() {
+            self.lists.delete(key);
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod list_tests {
+    use super::*;
+
+    #[test]
+    fn push_to_both_ends() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("list", &["b", "c"])?;
+        let length = ms.lpush("list", &["a"])?;
+
+        assert_eq!(length, 3);
+        assert_eq!(ms.lrange("list", 0, -1)?, vec!["a", "b", "c"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pop_from_both_ends() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("list", &["a", "b", "c"])?;
+
+        assert_eq!(ms.lpop("list")?.as_deref(), Some("a"));
+        assert_eq!(ms.rpop("list")?.as_deref(), Some("c"));
+        assert_eq!(ms.llen("list")?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn popping_the_last_item_removes_the_list() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("list", &["a"])?;
+        ms.lpop("list")?;
+
+        assert_eq!(ms.lists.len(), 0);
+        assert_eq!(ms.lpop("list")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn range_out_of_bounds() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("list", &["a", "b", "c"])?;
+
+        assert_eq!(ms.lrange("list", -100, 100)?, vec!["a", "b", "c"]);
+        assert!(ms.lrange("list", 5, 10)?.is_empty());
+        assert!(ms.lrange("list", 2, 1)?.is_empty());
+        assert!(ms.lrange("missing", 0, -1)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn index_and_set() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("list", &["a", "b", "c"])?;
+
+        ms.lset("list", -1, "z")?;
+        assert_eq!(ms.lindex("list", 2)?.as_deref(), Some("z"));
+        assert_eq!(ms.lindex("list", 3)?, None);
+
+        let err = ms.lset("list", 3, "y").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let err = ms.lset("missing", 0, "y").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        Ok(())
+    }
+
+    #[test]
+    fn trim_list() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("list", &["a", "b", "c", "d", "e"])?;
+
+        ms.ltrim("list", 1, -2)?;
+        assert_eq!(ms.lrange("list", 0, -1)?, vec!["b", "c", "d"]);
+
+        ms.ltrim("list", 5, 10)?;
+        assert_eq!(ms.lists.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove_occurrences() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.rpush("list", &["a", "b", "a", "c", "a"])?;
+
+        assert_eq!(ms.lrem("list", 1, "a")?, 1);
+        assert_eq!(ms.lrange("list", 0, -1)?, vec!["b", "a", "c", "a"]);
+
+        assert_eq!(ms.lrem("list", 0, "a")?, 2);
+        assert_eq!(ms.lrange("list", 0, -1)?, vec!["b", "c"]);
+
+        assert_eq!(ms.lrem("list", 0, "x")?, 0);
+
+        Ok(())
+    }
+}
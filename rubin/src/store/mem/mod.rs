@@ -4,13 +4,22 @@
 //!
 //! # Supported types:
 //!
-//! * `Strings`: Store string values
+//! * `Strings`: Store binary-safe string values, which can also be used as bitmaps
+//! * `Counters`: Store values to be incremented / decremented
+//! * `HyperLogLogs`: Estimate the number of unique elements in a collection
+//! * `Lists`: Store a list of string values which can be pushed / popped from either end
+//! * `Hashes`: Store a map of field-value pairs under a single key
+//! * `Sets`: Store an unordered collection of unique string values
+//! * `Sorted Sets`: Store a collection of unique string values ordered by score
+//! * `Geo Sets`: Store positions in a sorted set for searches by radius or box
+//! * `Streams`: Store an append-only log of entries, read by consumer groups
+//! * `JSON Documents`: Store JSON values which can be queried and updated by path
 //!
-//! # Future supported types:
+//! A memory limit can be set on the store (see [`MemStore::set_max_memory()`]) with keys being
+//! evicted according to an [`EvictionPolicy`] once it is reached.
 //!
-//! * `Lists`: Store for a `Vec<T>` of items with `T` being a generic type
-//! * `HashMap`: Store a `HashMap` of values
-//! * `Set`: Store a `Set` of values
+//! Keys in any store can be given a time-to-live (see [`MemStore::expire()`]) after which they
+//! are treated as absent.
 //!
 //! As development continues, more features will be added.
 //!
@@ -26,28 +35,102 @@
 //!
 //! // Retrieve a value from the store
 //! let result = ms.get_string("key").unwrap();
-//! assert_eq!(&result, "value");
+//! assert_eq!(result.as_deref(), Some("value"));
 //! ```
 
+mod bitmap;
+mod bytes;
+mod counter;
+mod databases;
+mod eviction;
+mod expiry;
+mod geo;
+mod hash;
+mod hyperloglog;
+mod json;
+mod keyspace;
+mod list;
+mod scan;
+mod set;
+mod stream;
+mod string;
+mod typed;
+mod zset;
+
+pub use bitmap::{BitFieldOp, BitFieldOverflow, BitFieldType, BitOp};
+pub use bytes::Bytes;
+pub use databases::{Databases, DatabasesSnapshot, DEFAULT_DATABASES};
+pub use eviction::EvictionPolicy;
+pub use geo::{GeoMatch, GeoOrigin, GeoSearch, GeoShape, GeoUnit};
+pub use hyperloglog::HyperLogLog;
+pub use keyspace::KeyType;
+pub use scan::DEFAULT_SCAN_COUNT;
+pub use stream::{PendingEntry, PendingSummary, Stream, StreamEntry, StreamId, StreamTrim};
+pub use typed::TypedValue;
+pub use zset::SortedSet;
+
+pub(crate) use typed::type_tag;
+
 use serde::{Deserialize, Serialize};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
 
 use crate::store::persistence::file_handling::write_store_sync;
-use crate::store::InnerStore;
+use crate::store::persistence::{Compression, SnapshotSize, StoreFormat};
+use crate::store::{InnerStore, KeyedStore};
 
 /// In-memory store of values
 ///
 /// Used to store key-value pairs of strings with more features being added
 /// as development continues.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct MemStore {
-    /// Key-value store of `String` values
-    pub strings: InnerStore<String>,
+    /// Key-value store of binary-safe string values
+    pub strings: InnerStore<Bytes>,
 
     /// Key-value store of values to be incremented / decremented
     pub counters: InnerStore<isize>,
+
+    /// Key-value store of cardinality estimates
+    #[serde(default)]
+    pub hyperloglogs: InnerStore<HyperLogLog>,
+
+    /// Key-value store of lists of `String` values
+    #[serde(default)]
+    pub lists: InnerStore<VecDeque<String>>,
+
+    /// Key-value store of field-value maps
+    #[serde(default)]
+    pub hashes: InnerStore<HashMap<String, String>>,
+
+    /// Key-value store of unordered sets of unique `String` values
+    #[serde(default)]
+    pub sets: InnerStore<HashSet<String>>,
+
+    /// Key-value store of sets ordered by score
+    #[serde(default)]
+    pub zsets: InnerStore<SortedSet>,
+
+    /// Key-value store of append-only logs of entries
+    #[serde(default)]
+    pub streams: InnerStore<Stream>,
+
+    /// Key-value store of JSON documents
+    #[serde(default)]
+    pub documents: InnerStore<serde_json::Value>,
+
+    /// Key-value store of serialized values tagged with their type
+    #[serde(default)]
+    pub typed: InnerStore<TypedValue>,
+
+    /// Maximum number of bytes the store can use, unlimited if not set
+    #[serde(skip)]
+    pub max_memory: Option<usize>,
+
+    /// Policy used to evict keys when the store reaches `max_memory`
+    #[serde(skip)]
+    pub eviction_policy: EvictionPolicy,
 }
 
 impl MemStore {
@@ -81,7 +164,27 @@ impl MemStore {
     /// ms.insert_string("user:1000", "value").unwrap();
     /// ```
     pub fn insert_string(&mut self, key: &str, value: &str) -> io::Result<()> {
-        self.strings.insert(key, value.to_string())
+        self.reserve_memory()?;
+        self.replace_type(key, KeyType::String);
+
+        self.strings.insert(key, Bytes::from(value))
+    }
+
+    /// Inserts a binary value into the string store with a given key
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_bytes("image:1000", &[0x89, 0x50, 0x4e, 0x47]).unwrap();
+    /// ```
+    pub fn insert_bytes(&mut self, key: &str, value: &[u8]) -> io::Result<()> {
+        self.reserve_memory()?;
+        self.replace_type(key, KeyType::String);
+
+        self.strings.insert(key, Bytes::from(value))
     }
 
     /// Retrieve a value from the string store
@@ -90,6 +193,15 @@ impl MemStore {
     ///
     /// * `key`: Key of the value to retrieve
     ///
+    /// # Returns
+    ///
+    /// * value: Value stored under the key, `None` if not present
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the value is not valid UTF-8, see
+    ///   [`MemStore::get_bytes`] for binary values
+    ///
     /// # Examples
     ///
     /// ```
@@ -99,10 +211,32 @@ impl MemStore {
     /// ms.insert_string("key", "value");
     ///
     /// let result = ms.get_string("key").unwrap();
-    /// assert_eq!(&result, "value");
+    /// assert_eq!(result.as_deref(), Some("value"));
+    ///
+    /// assert!(ms.get_string("missing").unwrap().is_none());
     /// ```
-    pub fn get_string(&self, key: &str) -> io::Result<String> {
-        self.strings.retrieve(key)
+    pub fn get_string(&self, key: &str) -> io::Result<Option<String>> {
+        self.strings
+            .retrieve(key)?
+            .map(Bytes::into_string)
+            .transpose()
+    }
+
+    /// Retrieve the raw bytes of a value from the string store
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_bytes("image:1000", &[0x89, 0x50, 0x4e, 0x47]).unwrap();
+    ///
+    /// let result = ms.get_bytes("image:1000").unwrap();
+    /// assert_eq!(result.as_deref(), Some(&[0x89, 0x50, 0x4e, 0x47][..]));
+    /// ```
+    pub fn get_bytes(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.strings.retrieve(key)?.map(Bytes::into_vec))
     }
 
     /// Remove a value from the string store
@@ -113,7 +247,8 @@ impl MemStore {
     ///
     /// # Returns
     ///
-    /// * value: Value that was removed from the store, empty string if not present
+    /// * value: Value that was removed from the store, `None` if not present. Invalid UTF-8
+    ///   sequences in binary values are replaced with `U+FFFD`
     ///
     /// # Example
     ///
@@ -126,10 +261,10 @@ impl MemStore {
     ///
     /// let value = ms.remove_string("user:1000").unwrap();
     ///
-    /// assert_eq!(&value, "value");
+    /// assert_eq!(value.as_deref(), Some("value"));
     /// ```
-    pub fn remove_string(&mut self, key: &str) -> io::Result<String> {
-        self.strings.remove(key)
+    pub fn remove_string(&mut self, key: &str) -> io::Result<Option<String>> {
+        Ok(self.strings.remove(key)?.map(|value| value.to_string()))
     }
 
     /// Clears all entries out of the string store
@@ -173,12 +308,14 @@ impl MemStore {
     ///     println!("{} {}", key, value);
     /// }
     /// ```
-    pub fn get_string_store_ref(&self) -> &HashMap<String, String> {
+    pub fn get_string_store_ref(&self) -> &HashMap<String, Bytes> {
         self.strings.get_ref()
     }
 
     /// Increments the value of a given key by 1
     ///
+    /// Returns an error if the increment would overflow, see [`MemStore::incrby`].
+    ///
     /// # Example
     ///
     /// ```rust
@@ -191,22 +328,16 @@ impl MemStore {
     ///     value = ms.incr("view-count").unwrap()
     /// }
     ///
-    /// assert_eq!(ms.counters.retrieve("view-count").unwrap(), 100);
+    /// assert_eq!(ms.counters.retrieve("view-count").unwrap(), Some(100));
     /// ```
     pub fn incr(&mut self, key: impl AsRef<str>) -> io::Result<isize> {
-        self.counters
-            .inner
-            .entry(key.as_ref().to_string())
-            .and_modify(|count| *count += 1)
-            .or_insert(1);
-
-        let count = self.counters.retrieve(key.as_ref())?;
-
-        Ok(count)
+        self.incrby(key, 1)
     }
 
     /// Decrements the value of a given key by 1
     ///
+    /// Returns an error if the decrement would overflow, see [`MemStore::decrby`].
+    ///
     /// # Example
     ///
     /// ```rust
@@ -223,18 +354,10 @@ impl MemStore {
     ///     value = ms.decr("view-count").unwrap();
     /// }
     ///
-    /// assert_eq!(ms.counters.retrieve("view-count").unwrap(), 0);
+    /// assert_eq!(ms.counters.retrieve("view-count").unwrap(), Some(0));
     /// ```
     pub fn decr(&mut self, key: impl AsRef<str>) -> io::Result<isize> {
-        self.counters
-            .inner
-            .entry(key.as_ref().to_string())
-            .and_modify(|count| *count -= 1)
-            .or_insert(-1);
-
-        let count = self.counters.retrieve(key.as_ref())?;
-
-        Ok(count)
+        self.decrby(key, 1)
     }
 
     /// Writes the contents of the store out to disk.
@@ -254,7 +377,67 @@ impl MemStore {
     /// ms.dump_store("save/path/location.json");
     /// ```
     pub fn dump_store(&self, filepath: impl AsRef<std::path::Path>) -> io::Result<()> {
-        write_store_sync(filepath, self)
+        self.dump_store_as(filepath, StoreFormat::Json, Compression::None)?;
+        Ok(())
+    }
+
+    /// Writes the contents of the store out to disk in the given format and compression.
+    ///
+    /// Returns the size of the store before and after compression.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rubin::store::mem::MemStore;
+    /// use rubin::store::persistence::{Compression, StoreFormat};
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// // ...
+    ///
+    /// let size = ms
+    ///     .dump_store_as("save/path/location.bin.zst", StoreFormat::Binary, Compression::Zstd)
+    ///     .unwrap();
+    /// ```
+    pub fn dump_store_as(
+        &self,
+        filepath: impl AsRef<std::path::Path>,
+        format: StoreFormat,
+        compression: Compression,
+    ) -> io::Result<SnapshotSize> {
+        write_store_sync(filepath, self, format, compression)
+    }
+
+    /// Gets shared references to every inner store
+    fn keyed_stores(&self) -> [&dyn KeyedStore; 10] {
+        [
+            &self.strings,
+            &self.counters,
+            &self.hyperloglogs,
+            &self.lists,
+            &self.hashes,
+            &self.sets,
+            &self.zsets,
+            &self.streams,
+            &self.documents,
+            &self.typed,
+        ]
+    }
+
+    /// Gets mutable references to every inner store
+    fn keyed_stores_mut(&mut self) -> [&mut dyn KeyedStore; 10] {
+        [
+            &mut self.strings,
+            &mut self.counters,
+            &mut self.hyperloglogs,
+            &mut self.lists,
+            &mut self.hashes,
+            &mut self.sets,
+            &mut self.zsets,
+            &mut self.streams,
+            &mut self.documents,
+            &mut self.typed,
+        ]
     }
 }
 
@@ -312,9 +495,9 @@ mod memstore {
         ms.insert_string("key2", "value2")?;
 
         let mut result = ms.get_string("key2")?;
-        assert_eq!(result, "value2");
+        assert_eq!(result.as_deref(), Some("value2"));
         result = ms.get_string("key1")?;
-        assert_eq!(result, "value1");
+        assert_eq!(result.as_deref(), Some("value1"));
 
         Ok(())
     }
@@ -323,7 +506,7 @@ mod memstore {
     fn string_store_no_entries() -> io::Result<()> {
         let ms = MemStore::new();
         let result = ms.get_string("key1")?;
-        assert_eq!(result, "");
+        assert_eq!(result, None);
 
         Ok(())
     }
@@ -338,7 +521,7 @@ mod memstore {
 
         assert!(ms.strings.len() == 1000);
         ms.clear_strings()?;
-        assert!(ms.strings.len() == 0);
+        assert!(ms.strings.is_empty());
 
         Ok(())
     }
@@ -355,7 +538,7 @@ mod memstore {
         assert_eq!(value, 1000);
 
         let stored_value = ms.counters.retrieve("view-counter")?;
-        assert_eq!(value, stored_value);
+        assert_eq!(Some(value), stored_value);
 
         Ok(())
     }
@@ -375,8 +558,8 @@ mod memstore {
         let key1 = ms.counters.retrieve("key-1")?;
         let key2 = ms.counters.retrieve("key-2")?;
 
-        assert_eq!(key1, 3);
-        assert_eq!(key2, 5);
+        assert_eq!(key1, Some(3));
+        assert_eq!(key2, Some(5));
 
         Ok(())
     }
@@ -393,7 +576,7 @@ mod memstore {
         assert_eq!(value, -1000);
 
         let stored_value = ms.counters.retrieve("view-counter")?;
-        assert_eq!(value, stored_value);
+        assert_eq!(Some(value), stored_value);
 
         Ok(())
     }
@@ -413,8 +596,8 @@ mod memstore {
         let key1 = ms.counters.retrieve("key-1")?;
         let key2 = ms.counters.retrieve("key-2")?;
 
-        assert_eq!(key1, -3);
-        assert_eq!(key2, -5);
+        assert_eq!(key1, Some(-3));
+        assert_eq!(key2, Some(-5));
 
         Ok(())
     }
@@ -0,0 +1,370 @@
+//! Cursor-based iteration for the [`MemStore`]
+//!
+//! Scanning returns a page of items at a time along with a cursor to pass to the next call,
+//! so a large keyspace can be walked without holding the store for the whole pass. A scan
+//! starts with a cursor of `0` and is complete when `0` is returned.
+//!
+//! Items are visited in the order of a fixed hash of their name and the cursor is the hash to
+//! continue from. This keeps the cursor valid while the store is modified between calls:
+//!
+//! * Every item present for the whole scan is returned exactly once
+//! * Items added or removed during the scan may or may not be returned
+//!
+//! Each call inspects up to `count` items. Patterns are applied to the inspected items, so a
+//! call can return fewer items than `count` (or none) before the scan is complete.
+
+use std::io;
+
+use crate::store::glob::glob_match;
+use crate::store::mem::MemStore;
+
+/// Number of items inspected by each scan call if no count is given
+pub const DEFAULT_SCAN_COUNT: usize = 10;
+
+/// Hashes a name to its position in the scan order (64-bit FNV-1a)
+///
+/// The hash is fixed rather than randomly seeded so that cursors remain valid across calls.
+fn scan_position(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Selects the page of items following the cursor in scan order
+///
+/// Up to `count` items are inspected, along with any further items at the same position so
+/// that a hash collision cannot split items across pages. Inspected items not matching the
+/// pattern are dropped.
+///
+/// Returns the cursor for the next call, `0` once there are no more items.
+fn scan_page<'a, T>(
+    items: impl Iterator<Item = (&'a str, T)>,
+    cursor: u64,
+    pattern: Option<&str>,
+    count: usize,
+) -> io::Result<(u64, Vec<(&'a str, T)>)> {
+    if count == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "scan count must be greater than zero",
+        ));
+    }
+
+    let mut candidates: Vec<(u64, &str, T)> = items
+        .map(|(name, item)| (scan_position(name), name, item))
+        .filter(|(position, _, _)| *position >= cursor)
+        .collect();
+
+    let mut next_cursor = 0;
+    if candidates.len() > count {
+        candidates.select_nth_unstable_by_key(count - 1, |(position, _, _)| *position);
+        let last = candidates[count - 1].0;
+
+        next_cursor = candidates
+            .iter()
+            .map(|(position, _, _)| *position)
+            .filter(|position| *position > last)
+            .min()
+            .unwrap_or(0);
+
+        candidates.retain(|(position, _, _)| *position <= last);
+    }
+
+    candidates.sort_unstable_by_key(|(position, _, _)| *position);
+    let page = candidates
+        .into_iter()
+        .filter(|(_, name, _)| pattern.is_none_or(|pattern| glob_match(pattern, name)))
+        .map(|(_, name, item)| (name, item))
+        .collect();
+
+    Ok((next_cursor, page))
+}
+
+impl MemStore {
+    /// Iterates over the keys in the store, a page at a time
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor`: `0` to start a scan, otherwise the cursor returned by the previous call
+    /// * `pattern`: Optional glob-style pattern the returned keys must match
+    /// * `count`: Number of keys to inspect, see [`DEFAULT_SCAN_COUNT`]
+    ///
+    /// # Returns
+    ///
+    /// * The cursor for the next call, `0` once the scan is complete
+    /// * The keys in this page
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the count is zero
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// for i in 0..100 {
+    ///     ms.insert_string(&format!("user:{}", i), "value").unwrap();
+    /// }
+    ///
+    /// let mut keys = vec![];
+    /// let mut cursor = 0;
+    /// loop {
+    ///     let (next, page) = ms.scan(cursor, Some("user:*"), 10).unwrap();
+    ///     keys.extend(page);
+    ///
+    ///     cursor = next;
+    ///     if cursor == 0 {
+    ///         break;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(keys.len(), 100);
+    /// ```
+    pub fn scan(
+        &self,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> io::Result<(u64, Vec<String>)> {
+        let stores = self.keyed_stores();
+        let keys = stores
+            .iter()
+            .flat_map(|store| store.keys())
+            .map(|key| (key, ()));
+
+        let (next_cursor, page) = scan_page(keys, cursor, pattern, count)?;
+        let keys = page.into_iter().map(|(key, _)| key.to_string()).collect();
+
+        Ok((next_cursor, keys))
+    }
+
+    /// Iterates over the field-value pairs of a hash, a page at a time
+    ///
+    /// The pattern is matched against the field names. See [`MemStore::scan`] for details
+    /// of the cursor and arguments.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.hset("user:1000", &[("name", "rubin"), ("nickname", "rb"), ("age", "3")]).unwrap();
+    ///
+    /// let (cursor, mut fields) = ms.hscan("user:1000", 0, Some("n*"), 10).unwrap();
+    /// fields.sort();
+    ///
+    /// assert_eq!(cursor, 0);
+    /// assert_eq!(
+    ///     fields,
+    ///     vec![
+    ///         ("name".to_string(), "rubin".to_string()),
+    ///         ("nickname".to_string(), "rb".to_string())
+    ///     ]
+    /// );
+    /// ```
+    pub fn hscan(
+        &self,
+        key: &str,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> io::Result<(u64, Vec<(String, String)>)> {
+        let fields = self
+            .hashes
+            .get(key)
+            .into_iter()
+            .flatten()
+            .map(|(field, value)| (field.as_str(), value));
+
+        let (next_cursor, page) = scan_page(fields, cursor, pattern, count)?;
+        let fields = page
+            .into_iter()
+            .map(|(field, value)| (field.to_string(), value.clone()))
+            .collect();
+
+        Ok((next_cursor, fields))
+    }
+
+    /// Iterates over the members of a set, a page at a time
+    ///
+    /// See [`MemStore::scan`] for details of the cursor and arguments.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("tags", &["rust", "cache", "redis"]).unwrap();
+    ///
+    /// let (cursor, members) = ms.sscan("tags", 0, Some("r*"), 10).unwrap();
+    ///
+    /// assert_eq!(cursor, 0);
+    /// assert_eq!(members.len(), 2);
+    /// ```
+    pub fn sscan(
+        &self,
+        key: &str,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> io::Result<(u64, Vec<String>)> {
+        let members = self
+            .sets
+            .get(key)
+            .into_iter()
+            .flatten()
+            .map(|member| (member.as_str(), ()));
+
+        let (next_cursor, page) = scan_page(members, cursor, pattern, count)?;
+        let members = page
+            .into_iter()
+            .map(|(member, _)| member.to_string())
+            .collect();
+
+        Ok((next_cursor, members))
+    }
+}
+
+#[cfg(test)]
+mod scan_tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Runs a scan to completion with the given count, collecting every page
+    fn scan_all(ms: &MemStore, pattern: Option<&str>, count: usize) -> io::Result<Vec<String>> {
+        let mut keys = vec![];
+        let mut cursor = 0;
+        loop {
+            let (next, page) = ms.scan(cursor, pattern, count)?;
+            keys.extend(page);
+
+            cursor = next;
+            if cursor == 0 {
+                return Ok(keys);
+            }
+        }
+    }
+
+    #[test]
+    fn scans_every_key_once() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        for i in 0..250 {
+            ms.insert_string(&format!("string:{}", i), "value")?;
+            ms.rpush(&format!("list:{}", i), &["value"])?;
+        }
+
+        for count in [1, 7, 100, 1000] {
+            let keys = scan_all(&ms, None, count)?;
+            let unique: HashSet<&String> = keys.iter().collect();
+
+            assert_eq!(keys.len(), 500);
+            assert_eq!(unique.len(), 500);
+        }
+
+        let keys = scan_all(&ms, Some("list:*"), 20)?;
+        assert_eq!(keys.len(), 250);
+        assert!(keys.iter().all(|key| key.starts_with("list:")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pages_are_bounded_by_count() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        for i in 0..100 {
+            ms.insert_string(&format!("key:{}", i), "value")?;
+        }
+
+        let (cursor, page) = ms.scan(0, None, 10)?;
+        assert_ne!(cursor, 0);
+        assert_eq!(page.len(), 10);
+
+        // Patterns are applied after the page is selected
+        let (_, page) = ms.scan(0, Some("missing:*"), 10)?;
+        assert!(page.is_empty());
+
+        let err = ms.scan(0, None, 0).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cursor_survives_modification() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        for i in 0..100 {
+            ms.insert_string(&format!("kept:{}", i), "value")?;
+        }
+
+        let mut keys = HashSet::new();
+        let mut cursor = 0;
+        let mut round = 0;
+        loop {
+            let (next, page) = ms.scan(cursor, None, 10)?;
+            keys.extend(page);
+
+            // Add and remove other keys between calls
+            ms.insert_string(&format!("added:{}", round), "value")?;
+            if round > 0 {
+                ms.remove_string(&format!("added:{}", round - 1))?;
+            }
+            round += 1;
+
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+
+        for i in 0..100 {
+            assert!(keys.contains(&format!("kept:{}", i)));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn scans_hashes_and_sets() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        let fields: Vec<(String, String)> = (0..50)
+            .map(|i| (format!("field:{}", i), i.to_string()))
+            .collect();
+        let pairs: Vec<(&str, &str)> = fields
+            .iter()
+            .map(|(field, value)| (field.as_str(), value.as_str()))
+            .collect();
+        ms.hset("hash", &pairs)?;
+        ms.sadd("set", &["a", "b", "c", "ab"])?;
+
+        let mut scanned = vec![];
+        let mut cursor = 0;
+        loop {
+            let (next, page) = ms.hscan("hash", cursor, None, 8)?;
+            scanned.extend(page);
+
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        scanned.sort();
+        let mut expected = fields.clone();
+        expected.sort();
+        assert_eq!(scanned, expected);
+
+        let (cursor, mut members) = ms.sscan("set", 0, Some("a*"), 10)?;
+        members.sort();
+        assert_eq!(cursor, 0);
+        assert_eq!(members, vec!["a", "ab"]);
+
+        assert_eq!(ms.hscan("missing", 0, None, 10)?, (0, vec![]));
+        assert_eq!(ms.sscan("missing", 0, None, 10)?, (0, vec![]));
+        assert!(ms.hscan("missing", 0, None, 0).is_err());
+
+        Ok(())
+    }
+}
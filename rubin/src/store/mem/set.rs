@@ -0,0 +1,390 @@
+//! Set operations for the [`MemStore`]
+//!
+//! Sets are unordered collections of unique strings, useful for membership checks
+//! (e.g. tags or feature flags) and for combining collections using set algebra.
+
+use std::collections::HashSet;
+use std::io;
+
+use crate::store::mem::{KeyType, MemStore};
+
+impl MemStore {
+    /// Adds one or more members to a set, creating the set if it does not exist.
+    ///
+    /// # Returns
+    ///
+    /// * The number of members that were newly added to the set
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// let added = ms.sadd("tags", &["rust", "cache", "rust"]).unwrap();
+    /// assert_eq!(added, 2);
+    /// ```
+    pub fn sadd(&mut self, key: &str, members: &[&str]) -> io::Result<usize> {
+        self.check_type(key, KeyType::Set)?;
+        self.reserve_memory()?;
+
+        let set = self.sets.entry(key);
+
+        let added = members
+            .iter()
+            .filter(|member| set.insert(member.to_string()))
+            .count();
+
+        Ok(added)
+    }
+
+    /// Removes one or more members from a set.
+    ///
+    /// The set is removed from the store once it is empty.
+    ///
+    /// # Returns
+    ///
+    /// * The number of members removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("tags", &["rust", "cache"]).unwrap();
+    ///
+    /// let removed = ms.srem("tags", &["cache", "missing"]).unwrap();
+    /// assert_eq!(removed, 1);
+    /// ```
+    pub fn srem(&mut self, key: &str, members: &[&str]) -> io::Result<usize> {
+        let Some(set) = self.sets.get_mut(key) else {
+            return Ok(0);
+        };
+
+        let removed = members.iter().filter(|member| set.remove(**member)).count();
+
+        if set.is_empty() {
+            self.sets.delete(key);
+        }
+
+        Ok(removed)
+    }
+
+    /// Checks if a value is a member of a set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("tags", &["rust"]).unwrap();
+    ///
+    /// assert!(ms.sismember("tags", "rust").unwrap());
+    /// assert!(!ms.sismember("tags", "python").unwrap());
+    /// ```
+    pub fn sismember(&self, key: &str, member: &str) -> io::Result<bool> {
+        Ok(self.sets.get(key).is_some_and(|set| set.contains(member)))
+    }
+
+    /// Retrieves all members of a set, empty if the set is not present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("tags", &["rust", "cache"]).unwrap();
+    ///
+    /// let members = ms.smembers("tags").unwrap();
+    /// assert!(members.contains("rust"));
+    /// assert!(members.contains("cache"));
+    /// ```
+    pub fn smembers(&self, key: &str) -> io::Result<HashSet<String>> {
+        Ok(self.sets.get(key).cloned().unwrap_or_default())
+    }
+
+    /// Returns the number of members in a set, zero if the set is not present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("tags", &["rust", "cache"]).unwrap();
+    ///
+    /// assert_eq!(ms.scard("tags").unwrap(), 2);
+    /// ```
+    pub fn scard(&self, key: &str) -> io::Result<usize> {
+        Ok(self.sets.get(key).map_or(0, |set| set.len()))
+    }
+
+    /// Returns the union of all of the given sets.
+    ///
+    /// Sets that are not present are treated as empty sets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("set-1", &["a", "b"]).unwrap();
+    /// ms.sadd("set-2", &["b", "c"]).unwrap();
+    ///
+    /// let union = ms.sunion(&["set-1", "set-2"]).unwrap();
+    /// assert_eq!(union.len(), 3);
+    /// ```
+    pub fn sunion(&self, keys: &[&str]) -> io::Result<HashSet<String>> {
+        let mut result = HashSet::new();
+        for key in keys {
+            if let Some(set) = self.sets.get(key) {
+                result.extend(set.iter().cloned());
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the intersection of all of the given sets.
+    ///
+    /// Sets that are not present are treated as empty sets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("set-1", &["a", "b"]).unwrap();
+    /// ms.sadd("set-2", &["b", "c"]).unwrap();
+    ///
+    /// let inter = ms.sinter(&["set-1", "set-2"]).unwrap();
+    /// assert!(inter.contains("b"));
+    /// assert_eq!(inter.len(), 1);
+    /// ```
+    pub fn sinter(&self, keys: &[&str]) -> io::Result<HashSet<String>> {
+        let Some((first, rest)) = keys.split_first() else {
+            return Ok(HashSet::new());
+        };
+
+        let Some(first) = self.sets.get(first) else {
+            return Ok(HashSet::new());
+        };
+
+        let result = first
+            .iter()
+            .filter(|member| {
+                rest.iter()
+                    .all(|key| self.sets.get(key).is_some_and(|set| set.contains(*member)))
+            })
+            .cloned()
+            .collect();
+
+        Ok(result)
+    }
+
+    /// Returns the members of the first set that are not present in any of the following sets.
+    ///
+    /// Sets that are not present are treated as empty sets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("set-1", &["a", "b"]).unwrap();
+    /// ms.sadd("set-2", &["b", "c"]).unwrap();
+    ///
+    /// let diff = ms.sdiff(&["set-1", "set-2"]).unwrap();
+    /// assert!(diff.contains("a"));
+    /// assert_eq!(diff.len(), 1);
+    /// ```
+    pub fn sdiff(&self, keys: &[&str]) -> io::Result<HashSet<String>> {
+        let Some((first, rest)) = keys.split_first() else {
+            return Ok(HashSet::new());
+        };
+
+        let Some(first) = self.sets.get(first) else {
+            return Ok(HashSet::new());
+        };
+
+        let result = first
+            .iter()
+            .filter(|member| {
+                !rest
+                    .iter()
+                    .any(|key| self.sets.get(key).is_some_and(|set| set.contains(*member)))
+            })
+            .cloned()
+            .collect();
+
+        Ok(result)
+    }
+
+    /// Stores the union of the given sets in the `destination` set, overwriting it.
+    ///
+    /// # Returns
+    ///
+    /// * The number of members in the resulting set
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("set-1", &["a", "b"]).unwrap();
+    /// ms.sadd("set-2", &["b", "c"]).unwrap();
+    ///
+    /// let count = ms.sunionstore("union", &["set-1", "set-2"]).unwrap();
+    /// assert_eq!(count, 3);
+    /// assert_eq!(ms.scard("union").unwrap(), 3);
+    /// ```
+    pub fn sunionstore(&mut self, destination: &str, keys: &[&str]) -> io::Result<usize> {
+        let result = self.sunion(keys)?;
+        self.store_set(destination, result)
+    }
+
+    /// Stores the intersection of the given sets in the `destination` set, overwriting it.
+    ///
+    /// # Returns
+    ///
+    /// * The number of members in the resulting set
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("set-1", &["a", "b"]).unwrap();
+    /// ms.sadd("set-2", &["b", "c"]).unwrap();
+    ///
+    /// let count = ms.sinterstore("inter", &["set-1", "set-2"]).unwrap();
+    /// assert_eq!(count, 1);
+    /// ```
+    pub fn sinterstore(&mut self, destination: &str, keys: &[&str]) -> io::Result<usize> {
+        let result = self.sinter(keys)?;
+        self.store_set(destination, result)
+    }
+
+    /// Stores the difference of the given sets in the `destination` set, overwriting it.
+    ///
+    /// # Returns
+    ///
+    /// * The number of members in the resulting set
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.sadd("set-1", &["a", "b"]).unwrap();
+    /// ms.sadd("set-2", &["b", "c"]).unwrap();
+    ///
+    /// let count = ms.sdiffstore("diff", &["set-1", "set-2"]).unwrap();
+    /// assert_eq!(count, 1);
+    /// ```
+    pub fn sdiffstore(&mut self, destination: &str, keys: &[&str]) -> io::Result<usize> {
+        let result = self.sdiff(keys)?;
+        self.store_set(destination, result)
+    }
+
+    /// Stores a set under the given key, removing the key if the set is empty
+    fn store_set(&mut self, key: &str, set: HashSet<String>) -> io::Result<usize> {
+        self.reserve_memory()?;
+        self.replace_type(key, KeyType::Set);
+
+        let count = set.len();
+        if set.is_empty() {
+            self.sets.delete(key);
+        } else {
+            self.sets.insert(key, set)?;
+        }
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod set_tests {
+    use super::*;
+
+    fn sorted(set: HashSet<String>) -> Vec<String> {
+        let mut values: Vec<String> = set.into_iter().collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn add_and_remove_members() -> io::Result<()> {
+        let mut ms = MemStore::new();
+
+        assert_eq!(ms.sadd("set", &["a", "b", "a"])?, 2);
+        assert_eq!(ms.sadd("set", &["b", "c"])?, 1);
+        assert_eq!(ms.scard("set")?, 3);
+
+        assert_eq!(ms.srem("set", &["a", "x"])?, 1);
+        assert!(!ms.sismember("set", "a")?);
+        assert!(ms.sismember("set", "b")?);
+
+        ms.srem("set", &["b", "c"])?;
+        assert_eq!(ms.sets.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_sets_are_empty() -> io::Result<()> {
+        let ms = MemStore::new();
+
+        assert!(ms.smembers("missing")?.is_empty());
+        assert_eq!(ms.scard("missing")?, 0);
+        assert!(!ms.sismember("missing", "a")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_algebra() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.sadd("set-1", &["a", "b", "c"])?;
+        ms.sadd("set-2", &["b", "c", "d"])?;
+        ms.sadd("set-3", &["c", "e"])?;
+
+        let keys = ["set-1", "set-2", "set-3"];
+        assert_eq!(sorted(ms.sunion(&keys)?), vec!["a", "b", "c", "d", "e"]);
+        assert_eq!(sorted(ms.sinter(&keys)?), vec!["c"]);
+        assert_eq!(sorted(ms.sdiff(&["set-1", "set-3"])?), vec!["a", "b"]);
+
+        assert!(ms.sinter(&["set-1", "missing"])?.is_empty());
+        assert_eq!(
+            sorted(ms.sdiff(&["set-1", "missing"])?),
+            vec!["a", "b", "c"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_algebra_store_variants() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.sadd("set-1", &["a", "b"])?;
+        ms.sadd("set-2", &["b", "c"])?;
+
+        assert_eq!(ms.sunionstore("dest", &["set-1", "set-2"])?, 3);
+        assert_eq!(ms.sinterstore("dest", &["set-1", "set-2"])?, 1);
+        assert_eq!(sorted(ms.smembers("dest")?), vec!["b"]);
+        assert_eq!(ms.sdiffstore("dest", &["set-1", "set-1"])?, 0);
+        assert!(!ms.sets.get_ref().contains_key("dest"));
+
+        Ok(())
+    }
+}
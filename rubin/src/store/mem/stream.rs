@@ -0,0 +1,1190 @@
+//! Stream operations for the [`MemStore`]
+//!
+//! Streams are append-only logs of entries, each made up of field-value pairs and identified
+//! by a [`StreamId`]. IDs only ever increase, so entries can be read back in the order they
+//! were added and a reader can resume from the last ID it saw.
+//!
+//! Consumer groups let a set of workers share the entries of a stream. Each entry is
+//! delivered to one consumer in the group and stays pending until it is acknowledged, so
+//! entries held by a worker which has failed can be claimed by another.
+//!
+//! Reads do not block waiting for new entries, callers should poll instead.
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+use std::mem::size_of;
+use std::str::FromStr;
+
+use crate::store::mem::{KeyType, MemStore};
+use crate::store::{now_millis, MemoryUsage};
+
+/// ID of an entry in a stream, written as `<milliseconds>-<sequence>`
+///
+/// Automatically generated IDs use the time the entry was added, with the sequence number
+/// distinguishing entries added in the same millisecond.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct StreamId {
+    /// Milliseconds since the Unix epoch
+    pub ms: u64,
+
+    /// Sequence number within the millisecond
+    pub seq: u64,
+}
+
+impl StreamId {
+    /// Lowest possible ID, `0-0`
+    pub const MIN: Self = Self { ms: 0, seq: 0 };
+
+    /// Highest possible ID
+    pub const MAX: Self = Self {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    /// Constructs an ID from its parts
+    pub fn new(ms: u64, seq: u64) -> Self {
+        Self { ms, seq }
+    }
+
+    /// Parses the start of a range, where `-` is the lowest ID and a missing sequence
+    /// number is treated as `0`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::StreamId;
+    ///
+    /// assert_eq!(StreamId::parse_start("-").unwrap(), StreamId::MIN);
+    /// assert_eq!(StreamId::parse_start("1000").unwrap(), StreamId::new(1000, 0));
+    /// ```
+    pub fn parse_start(s: &str) -> io::Result<Self> {
+        match s {
+            "-" => Ok(Self::MIN),
+            "+" => Ok(Self::MAX),
+            _ => s.parse(),
+        }
+    }
+
+    /// Parses the end of a range, where `+` is the highest ID and a missing sequence
+    /// number covers the whole millisecond
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::StreamId;
+    ///
+    /// assert_eq!(StreamId::parse_end("+").unwrap(), StreamId::MAX);
+    /// assert_eq!(StreamId::parse_end("1000").unwrap(), StreamId::new(1000, u64::MAX));
+    /// ```
+    pub fn parse_end(s: &str) -> io::Result<Self> {
+        match s {
+            "-" => Ok(Self::MIN),
+            "+" => Ok(Self::MAX),
+            _ if !s.contains('-') => Ok(Self::new(s.parse::<Self>()?.ms, u64::MAX)),
+            _ => s.parse(),
+        }
+    }
+
+    /// Gets the ID following this one, `None` if this is the highest ID
+    fn next(&self) -> Option<Self> {
+        match self.seq.checked_add(1) {
+            Some(seq) => Some(Self::new(self.ms, seq)),
+            None => self.ms.checked_add(1).map(|ms| Self::new(ms, 0)),
+        }
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+impl FromStr for StreamId {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid stream ID: {}", s),
+            )
+        };
+
+        let (ms, seq) = s.split_once('-').unwrap_or((s, "0"));
+        let ms = ms.parse().map_err(|_| invalid())?;
+        let seq = seq.parse().map_err(|_| invalid())?;
+
+        Ok(Self::new(ms, seq))
+    }
+}
+
+impl TryFrom<String> for StreamId {
+    type Error = io::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<StreamId> for String {
+    fn from(value: StreamId) -> Self {
+        value.to_string()
+    }
+}
+
+/// An entry in a stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamEntry {
+    /// ID of the entry
+    pub id: StreamId,
+
+    /// Field-value pairs of the entry, in the order they were added
+    pub fields: Vec<(String, String)>,
+}
+
+/// How entries are removed from a stream by [`MemStore::xtrim`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StreamTrim {
+    /// Remove the oldest entries until at most this many remain
+    MaxLen(usize),
+
+    /// Remove entries with an ID lower than this
+    MinId(StreamId),
+}
+
+/// An entry delivered to a consumer which has not been acknowledged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingEntry {
+    /// ID of the entry
+    pub id: StreamId,
+
+    /// Consumer the entry was last delivered to
+    pub consumer: String,
+
+    /// Milliseconds since the entry was last delivered
+    pub idle: u64,
+
+    /// Number of times the entry has been delivered
+    pub deliveries: u64,
+}
+
+/// Summary of the pending entries of a consumer group
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingSummary {
+    /// Number of pending entries
+    pub count: usize,
+
+    /// Lowest pending ID
+    pub first: Option<StreamId>,
+
+    /// Highest pending ID
+    pub last: Option<StreamId>,
+
+    /// Number of pending entries held by each consumer, ordered by consumer name
+    pub consumers: Vec<(String, usize)>,
+}
+
+/// Delivery state of a pending entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Delivery {
+    /// Consumer the entry was last delivered to
+    consumer: String,
+
+    /// Time of the last delivery in milliseconds since the Unix epoch
+    delivered_at: u64,
+
+    /// Number of times the entry has been delivered
+    deliveries: u64,
+}
+
+/// A named group of consumers sharing the entries of a stream
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct ConsumerGroup {
+    /// ID of the last entry delivered to any consumer in the group
+    last_delivered: StreamId,
+
+    /// Entries delivered but not yet acknowledged
+    pending: BTreeMap<StreamId, Delivery>,
+}
+
+/// Append-only log of entries along with its consumer groups
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Stream {
+    /// Entries ordered by ID
+    entries: BTreeMap<StreamId, Vec<(String, String)>>,
+
+    /// Highest ID added to the stream, which may since have been trimmed
+    last_id: StreamId,
+
+    /// Consumer groups by name
+    groups: HashMap<String, ConsumerGroup>,
+}
+
+impl Stream {
+    /// Get the total number of entries in the stream
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if the stream is currently empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets the entry with the given ID
+    fn entry(&self, id: StreamId) -> Option<StreamEntry> {
+        self.entries.get(&id).map(|fields| StreamEntry {
+            id,
+            fields: fields.clone(),
+        })
+    }
+
+    /// Gets the consumer group with the given name
+    fn group_mut(&mut self, group: &str) -> io::Result<&mut ConsumerGroup> {
+        self.groups.get_mut(group).ok_or_else(no_such_group)
+    }
+}
+
+impl MemoryUsage for Stream {
+    fn memory_usage(&self) -> usize {
+        let entries: usize = self
+            .entries
+            .values()
+            .map(|fields| {
+                size_of::<StreamId>()
+                    + fields
+                        .iter()
+                        .map(|(field, value)| field.memory_usage() + value.memory_usage())
+                        .sum::<usize>()
+            })
+            .sum();
+
+        let groups: usize = self
+            .groups
+            .iter()
+            .map(|(name, group)| {
+                name.memory_usage()
+                    + size_of::<ConsumerGroup>()
+                    + group
+                        .pending
+                        .values()
+                        .map(|delivery| {
+                            size_of::<StreamId>()
+                                + size_of::<Delivery>()
+                                + delivery.consumer.capacity()
+                        })
+                        .sum::<usize>()
+            })
+            .sum();
+
+        size_of::<Self>() + entries + groups
+    }
+}
+
+/// Error returned when a stream is not present
+fn no_such_key() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no such key")
+}
+
+/// Error returned when a consumer group is not present
+fn no_such_group() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no such consumer group")
+}
+
+impl MemStore {
+    /// Appends an entry to a stream, creating the stream if it does not exist
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: Key of the stream
+    /// * `id`: ID of the new entry, generated from the current time if `None`
+    /// * `fields`: Field-value pairs of the entry
+    ///
+    /// # Returns
+    ///
+    /// * The ID of the new entry
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if no fields are given or the ID is not greater
+    ///   than the last ID in the stream
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{MemStore, StreamId};
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// let first = ms.xadd("events", None, &[("type", "signup"), ("user", "1000")]).unwrap();
+    /// let second = ms.xadd("events", None, &[("type", "login"), ("user", "1000")]).unwrap();
+    /// assert!(second > first);
+    ///
+    /// ms.xadd("explicit", Some(StreamId::new(1, 1)), &[("field", "value")]).unwrap();
+    /// assert!(ms.xadd("explicit", Some(StreamId::new(1, 0)), &[("field", "value")]).is_err());
+    /// ```
+    pub fn xadd(
+        &mut self,
+        key: &str,
+        id: Option<StreamId>,
+        fields: &[(&str, &str)],
+    ) -> io::Result<StreamId> {
+        if fields.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stream entries must have at least one field",
+            ));
+        }
+
+        self.check_type(key, KeyType::Stream)?;
+
+        let last_id = self.streams.get(key).map(|stream| stream.last_id);
+        let id = match id {
+            Some(id) if id == StreamId::MIN || last_id.is_some_and(|last| id <= last) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "ID must be greater than the last ID in the stream",
+                ))
+            }
+            Some(id) => id,
+            None => {
+                let last = last_id.unwrap_or(StreamId::MIN);
+                match now_millis() {
+                    ms if ms > last.ms => StreamId::new(ms, 0),
+                    _ => last.next().ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, "stream has exhausted IDs")
+                    })?,
+                }
+            }
+        };
+
+        self.reserve_memory()?;
+
+        let stream = self.streams.entry(key);
+        let fields = fields
+            .iter()
+            .map(|(field, value)| (field.to_string(), value.to_string()))
+            .collect();
+        stream.entries.insert(id, fields);
+        stream.last_id = id;
+
+        Ok(id)
+    }
+
+    /// Gets the entries of a stream with IDs in an inclusive range, from lowest to highest
+    ///
+    /// Use [`StreamId::MIN`] and [`StreamId::MAX`] to cover the whole stream.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{MemStore, StreamId};
+    ///
+    /// let mut ms = MemStore::new();
+    /// for i in 1..=5 {
+    ///     ms.xadd("events", Some(StreamId::new(i, 0)), &[("n", &i.to_string())]).unwrap();
+    /// }
+    ///
+    /// let entries = ms.xrange("events", StreamId::new(2, 0), StreamId::MAX, Some(2)).unwrap();
+    /// let ids: Vec<String> = entries.iter().map(|entry| entry.id.to_string()).collect();
+    /// assert_eq!(ids, vec!["2-0", "3-0"]);
+    /// ```
+    pub fn xrange(
+        &self,
+        key: &str,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+    ) -> io::Result<Vec<StreamEntry>> {
+        let Some(stream) = self.streams.get(key) else {
+            return Ok(vec![]);
+        };
+        if start > end {
+            return Ok(vec![]);
+        }
+
+        let entries = stream
+            .entries
+            .range(start..=end)
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(id, fields)| StreamEntry {
+                id: *id,
+                fields: fields.clone(),
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Gets the entries of a stream with IDs in an inclusive range, from highest to lowest
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{MemStore, StreamId};
+    ///
+    /// let mut ms = MemStore::new();
+    /// for i in 1..=5 {
+    ///     ms.xadd("events", Some(StreamId::new(i, 0)), &[("n", &i.to_string())]).unwrap();
+    /// }
+    ///
+    /// // The latest entry in the stream
+    /// let entries = ms.xrevrange("events", StreamId::MAX, StreamId::MIN, Some(1)).unwrap();
+    /// assert_eq!(entries[0].id, StreamId::new(5, 0));
+    /// ```
+    pub fn xrevrange(
+        &self,
+        key: &str,
+        end: StreamId,
+        start: StreamId,
+        count: Option<usize>,
+    ) -> io::Result<Vec<StreamEntry>> {
+        let Some(stream) = self.streams.get(key) else {
+            return Ok(vec![]);
+        };
+        if start > end {
+            return Ok(vec![]);
+        }
+
+        let entries = stream
+            .entries
+            .range(start..=end)
+            .rev()
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(id, fields)| StreamEntry {
+                id: *id,
+                fields: fields.clone(),
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Gets the number of entries in a stream
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.xadd("events", None, &[("type", "signup")]).unwrap();
+    ///
+    /// assert_eq!(ms.xlen("events").unwrap(), 1);
+    /// assert_eq!(ms.xlen("missing").unwrap(), 0);
+    /// ```
+    pub fn xlen(&self, key: &str) -> io::Result<usize> {
+        Ok(self.streams.get(key).map_or(0, Stream::len))
+    }
+
+    /// Removes the oldest entries from a stream
+    ///
+    /// Entries which are pending in a consumer group stay pending until acknowledged.
+    ///
+    /// # Returns
+    ///
+    /// * The number of entries removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{MemStore, StreamTrim};
+    ///
+    /// let mut ms = MemStore::new();
+    /// for i in 0..10 {
+    ///     ms.xadd("events", None, &[("n", &i.to_string())]).unwrap();
+    /// }
+    ///
+    /// assert_eq!(ms.xtrim("events", StreamTrim::MaxLen(3)).unwrap(), 7);
+    /// assert_eq!(ms.xlen("events").unwrap(), 3);
+    /// ```
+    pub fn xtrim(&mut self, key: &str, trim: StreamTrim) -> io::Result<usize> {
+        self.check_type(key, KeyType::Stream)?;
+
+        let Some(stream) = self.streams.get_mut(key) else {
+            return Ok(0);
+        };
+
+        let before = stream.entries.len();
+        match trim {
+            StreamTrim::MaxLen(len) => {
+                while stream.entries.len() > len {
+                    stream.entries.pop_first();
+                }
+            }
+            StreamTrim::MinId(min) => {
+                stream.entries = stream.entries.split_off(&min);
+            }
+        }
+
+        Ok(before - stream.entries.len())
+    }
+
+    /// Creates a consumer group for a stream, creating the stream if it does not exist
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: Key of the stream
+    /// * `group`: Name of the group
+    /// * `last_delivered`: ID the group starts reading after, `None` to only deliver entries
+    ///   added after the group is created and [`StreamId::MIN`] to deliver every entry
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::AlreadyExists`] if the group already exists
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{MemStore, StreamId};
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.xgroup_create("events", "workers", Some(StreamId::MIN)).unwrap();
+    ///
+    /// assert!(ms.xgroup_create("events", "workers", None).is_err());
+    /// ```
+    pub fn xgroup_create(
+        &mut self,
+        key: &str,
+        group: &str,
+        last_delivered: Option<StreamId>,
+    ) -> io::Result<()> {
+        self.check_type(key, KeyType::Stream)?;
+        self.reserve_memory()?;
+
+        let stream = self.streams.entry(key);
+        if stream.groups.contains_key(group) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "consumer group already exists",
+            ));
+        }
+
+        let last_delivered = last_delivered.unwrap_or(stream.last_id);
+        stream.groups.insert(
+            group.to_string(),
+            ConsumerGroup {
+                last_delivered,
+                pending: BTreeMap::new(),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Removes a consumer group along with its pending entries
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the group was present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.xgroup_create("events", "workers", None).unwrap();
+    ///
+    /// assert!(ms.xgroup_destroy("events", "workers").unwrap());
+    /// assert!(!ms.xgroup_destroy("events", "workers").unwrap());
+    /// ```
+    pub fn xgroup_destroy(&mut self, key: &str, group: &str) -> io::Result<bool> {
+        self.check_type(key, KeyType::Stream)?;
+
+        let stream = self.streams.get_mut(key).ok_or_else(no_such_key)?;
+
+        Ok(stream.groups.remove(group).is_some())
+    }
+
+    /// Reads entries from a stream as a consumer in a group
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: Key of the stream
+    /// * `group`: Name of the group
+    /// * `consumer`: Name of the consumer, which does not need to be created beforehand
+    /// * `after`: `None` to read entries not yet delivered to any consumer in the group,
+    ///   marking them as pending for this consumer. Otherwise, re-reads this consumer's
+    ///   pending entries with IDs greater than the given ID
+    /// * `count`: Maximum number of entries to read, all if `None`
+    ///
+    /// Pending entries which have since been trimmed from the stream are returned without
+    /// any fields so they can still be acknowledged.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the stream or group is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{MemStore, StreamId};
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.xgroup_create("jobs", "workers", None).unwrap();
+    /// ms.xadd("jobs", None, &[("task", "resize")]).unwrap();
+    /// ms.xadd("jobs", None, &[("task", "upload")]).unwrap();
+    ///
+    /// // Each new entry is delivered to one consumer
+    /// let first = ms.xreadgroup("jobs", "workers", "worker-1", None, Some(1)).unwrap();
+    /// let second = ms.xreadgroup("jobs", "workers", "worker-2", None, Some(1)).unwrap();
+    /// assert_ne!(first[0].id, second[0].id);
+    ///
+    /// // Unacknowledged entries can be read again by the same consumer
+    /// let pending = ms.xreadgroup("jobs", "workers", "worker-1", Some(StreamId::MIN), None).unwrap();
+    /// assert_eq!(pending, first);
+    /// ```
+    pub fn xreadgroup(
+        &mut self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        after: Option<StreamId>,
+        count: Option<usize>,
+    ) -> io::Result<Vec<StreamEntry>> {
+        self.check_type(key, KeyType::Stream)?;
+
+        let stream = self.streams.get_mut(key).ok_or_else(no_such_key)?;
+        let count = count.unwrap_or(usize::MAX);
+
+        let Some(after) = after else {
+            let start = stream.group_mut(group)?.last_delivered.next();
+            let ids: Vec<StreamId> = match start {
+                Some(start) => stream
+                    .entries
+                    .range(start..)
+                    .take(count)
+                    .map(|(id, _)| *id)
+                    .collect(),
+                None => vec![],
+            };
+
+            let now = now_millis();
+            let group = stream.group_mut(group)?;
+            for id in &ids {
+                group.last_delivered = *id;
+                group.pending.insert(
+                    *id,
+                    Delivery {
+                        consumer: consumer.to_string(),
+                        delivered_at: now,
+                        deliveries: 1,
+                    },
+                );
+            }
+
+            return Ok(ids.into_iter().filter_map(|id| stream.entry(id)).collect());
+        };
+
+        let ids: Vec<StreamId> = stream
+            .group_mut(group)?
+            .pending
+            .range(after..)
+            .filter(|(id, delivery)| **id > after && delivery.consumer == consumer)
+            .take(count)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let entries = ids
+            .into_iter()
+            .map(|id| {
+                stream
+                    .entry(id)
+                    .unwrap_or(StreamEntry { id, fields: vec![] })
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Acknowledges entries delivered to a consumer group, removing them from its pending
+    /// entries
+    ///
+    /// # Returns
+    ///
+    /// * The number of entries acknowledged
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.xgroup_create("jobs", "workers", None).unwrap();
+    /// let id = ms.xadd("jobs", None, &[("task", "resize")]).unwrap();
+    ///
+    /// ms.xreadgroup("jobs", "workers", "worker-1", None, None).unwrap();
+    /// assert_eq!(ms.xack("jobs", "workers", &[id]).unwrap(), 1);
+    /// assert_eq!(ms.xack("jobs", "workers", &[id]).unwrap(), 0);
+    /// ```
+    pub fn xack(&mut self, key: &str, group: &str, ids: &[StreamId]) -> io::Result<usize> {
+        self.check_type(key, KeyType::Stream)?;
+
+        let Some(stream) = self.streams.get_mut(key) else {
+            return Ok(0);
+        };
+        let Some(group) = stream.groups.get_mut(group) else {
+            return Ok(0);
+        };
+
+        Ok(ids
+            .iter()
+            .filter(|id| group.pending.remove(id).is_some())
+            .count())
+    }
+
+    /// Summarises the pending entries of a consumer group
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the stream or group is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.xgroup_create("jobs", "workers", None).unwrap();
+    /// ms.xadd("jobs", None, &[("task", "resize")]).unwrap();
+    /// ms.xadd("jobs", None, &[("task", "upload")]).unwrap();
+    /// ms.xreadgroup("jobs", "workers", "worker-1", None, None).unwrap();
+    ///
+    /// let summary = ms.xpending("jobs", "workers").unwrap();
+    /// assert_eq!(summary.count, 2);
+    /// assert_eq!(summary.consumers, vec![("worker-1".to_string(), 2)]);
+    /// ```
+    pub fn xpending(&self, key: &str, group: &str) -> io::Result<PendingSummary> {
+        let stream = self.streams.get(key).ok_or_else(no_such_key)?;
+        let group = stream.groups.get(group).ok_or_else(no_such_group)?;
+
+        let mut consumers: BTreeMap<&str, usize> = BTreeMap::new();
+        for delivery in group.pending.values() {
+            *consumers.entry(&delivery.consumer).or_default() += 1;
+        }
+
+        Ok(PendingSummary {
+            count: group.pending.len(),
+            first: group.pending.keys().next().copied(),
+            last: group.pending.keys().next_back().copied(),
+            consumers: consumers
+                .into_iter()
+                .map(|(consumer, count)| (consumer.to_string(), count))
+                .collect(),
+        })
+    }
+
+    /// Gets the pending entries of a consumer group with IDs in an inclusive range
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: Key of the stream
+    /// * `group`: Name of the group
+    /// * `start` / `end`: Range of IDs to return
+    /// * `count`: Maximum number of entries to return
+    /// * `consumer`: Only return entries pending for this consumer if set
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the stream or group is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::{MemStore, StreamId};
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.xgroup_create("jobs", "workers", None).unwrap();
+    /// let id = ms.xadd("jobs", None, &[("task", "resize")]).unwrap();
+    /// ms.xreadgroup("jobs", "workers", "worker-1", None, None).unwrap();
+    ///
+    /// let pending = ms
+    ///     .xpending_range("jobs", "workers", StreamId::MIN, StreamId::MAX, 10, None)
+    ///     .unwrap();
+    /// assert_eq!(pending[0].id, id);
+    /// assert_eq!(pending[0].consumer, "worker-1");
+    /// assert_eq!(pending[0].deliveries, 1);
+    /// ```
+    pub fn xpending_range(
+        &self,
+        key: &str,
+        group: &str,
+        start: StreamId,
+        end: StreamId,
+        count: usize,
+        consumer: Option<&str>,
+    ) -> io::Result<Vec<PendingEntry>> {
+        let stream = self.streams.get(key).ok_or_else(no_such_key)?;
+        let group = stream.groups.get(group).ok_or_else(no_such_group)?;
+        if start > end {
+            return Ok(vec![]);
+        }
+
+        let now = now_millis();
+        let entries = group
+            .pending
+            .range(start..=end)
+            .filter(|(_, delivery)| consumer.is_none_or(|consumer| delivery.consumer == consumer))
+            .take(count)
+            .map(|(id, delivery)| PendingEntry {
+                id: *id,
+                consumer: delivery.consumer.clone(),
+                idle: now.saturating_sub(delivery.delivered_at),
+                deliveries: delivery.deliveries,
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// Transfers pending entries which have been idle for at least `min_idle` milliseconds
+    /// to another consumer
+    ///
+    /// Claiming an entry counts as a delivery. Pending entries which have since been trimmed
+    /// from the stream are acknowledged rather than claimed.
+    ///
+    /// # Returns
+    ///
+    /// * The entries claimed
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::NotFound`] if the stream or group is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.xgroup_create("jobs", "workers", None).unwrap();
+    /// let id = ms.xadd("jobs", None, &[("task", "resize")]).unwrap();
+    /// ms.xreadgroup("jobs", "workers", "worker-1", None, None).unwrap();
+    ///
+    /// // worker-1 has failed, hand its entry to worker-2
+    /// let claimed = ms.xclaim("jobs", "workers", "worker-2", 0, &[id]).unwrap();
+    /// assert_eq!(claimed[0].id, id);
+    ///
+    /// let summary = ms.xpending("jobs", "workers").unwrap();
+    /// assert_eq!(summary.consumers, vec![("worker-2".to_string(), 1)]);
+    /// ```
+    pub fn xclaim(
+        &mut self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        min_idle: u64,
+        ids: &[StreamId],
+    ) -> io::Result<Vec<StreamEntry>> {
+        self.check_type(key, KeyType::Stream)?;
+
+        let stream = self.streams.get_mut(key).ok_or_else(no_such_key)?;
+        let now = now_millis();
+
+        let mut claimed = vec![];
+        for id in ids {
+            let exists = stream.entries.contains_key(id);
+            let group = stream.group_mut(group)?;
+            let Some(delivery) = group.pending.get_mut(id) else {
+                continue;
+            };
+            if now.saturating_sub(delivery.delivered_at) < min_idle {
+                continue;
+            }
+
+            if !exists {
+                group.pending.remove(id);
+                continue;
+            }
+
+            delivery.consumer = consumer.to_string();
+            delivery.delivered_at = now;
+            delivery.deliveries += 1;
+            claimed.extend(stream.entry(*id));
+        }
+
+        Ok(claimed)
+    }
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use super::*;
+
+    fn add_entries(ms: &mut MemStore, key: &str, count: u64) -> io::Result<()> {
+        for i in 1..=count {
+            ms.xadd(key, Some(StreamId::new(i, 0)), &[("n", &i.to_string())])?;
+        }
+
+        Ok(())
+    }
+
+    fn ids(entries: &[StreamEntry]) -> Vec<String> {
+        entries.iter().map(|entry| entry.id.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_stream_ids() -> io::Result<()> {
+        assert_eq!(
+            "1526919030474-55".parse::<StreamId>()?,
+            StreamId::new(1526919030474, 55)
+        );
+        assert_eq!("1000".parse::<StreamId>()?, StreamId::new(1000, 0));
+        assert!("1000-".parse::<StreamId>().is_err());
+        assert!("abc-1".parse::<StreamId>().is_err());
+
+        assert_eq!(StreamId::parse_start("5")?, StreamId::new(5, 0));
+        assert_eq!(StreamId::parse_end("5")?, StreamId::new(5, u64::MAX));
+        assert_eq!(StreamId::parse_end("5-1")?, StreamId::new(5, 1));
+        assert_eq!(StreamId::new(5, 1).to_string(), "5-1");
+
+        assert_eq!(StreamId::new(1, u64::MAX).next(), Some(StreamId::new(2, 0)));
+        assert_eq!(StreamId::MAX.next(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn generates_monotonic_ids() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        let mut previous = StreamId::MIN;
+        for _ in 0..1000 {
+            let id = ms.xadd("events", None, &[("field", "value")])?;
+            assert!(id > previous);
+            previous = id;
+        }
+
+        // IDs keep increasing after an explicit ID ahead of the clock
+        let future = StreamId::new(u64::MAX - 1, 5);
+        ms.xadd("events", Some(future), &[("field", "value")])?;
+        assert_eq!(
+            ms.xadd("events", None, &[("field", "value")])?,
+            StreamId::new(u64::MAX - 1, 6)
+        );
+
+        let err = ms
+            .xadd("events", Some(future), &[("field", "value")])
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(ms
+            .xadd("other", Some(StreamId::MIN), &[("field", "value")])
+            .is_err());
+        assert!(ms.xadd("other", None, &[]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn reads_ranges() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        add_entries(&mut ms, "events", 5)?;
+
+        let all = ms.xrange("events", StreamId::MIN, StreamId::MAX, None)?;
+        assert_eq!(ids(&all), vec!["1-0", "2-0", "3-0", "4-0", "5-0"]);
+        assert_eq!(all[0].fields, vec![("n".to_string(), "1".to_string())]);
+
+        let some = ms.xrange(
+            "events",
+            StreamId::new(2, 0),
+            StreamId::parse_end("4")?,
+            Some(2),
+        )?;
+        assert_eq!(ids(&some), vec!["2-0", "3-0"]);
+
+        let reversed = ms.xrevrange("events", StreamId::new(4, 0), StreamId::new(2, 0), None)?;
+        assert_eq!(ids(&reversed), vec!["4-0", "3-0", "2-0"]);
+
+        assert!(ms
+            .xrange("events", StreamId::new(4, 0), StreamId::new(2, 0), None)?
+            .is_empty());
+        assert!(ms
+            .xrange("missing", StreamId::MIN, StreamId::MAX, None)?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn trims_streams() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        add_entries(&mut ms, "events", 10)?;
+
+        assert_eq!(
+            ms.xtrim("events", StreamTrim::MinId(StreamId::new(4, 0)))?,
+            3
+        );
+        assert_eq!(ms.xtrim("events", StreamTrim::MaxLen(5))?, 2);
+        assert_eq!(ms.xtrim("events", StreamTrim::MaxLen(5))?, 0);
+        assert_eq!(ms.xlen("events")?, 5);
+
+        let first = ms.xrange("events", StreamId::MIN, StreamId::MAX, Some(1))?;
+        assert_eq!(ids(&first), vec!["6-0"]);
+
+        // Trimming does not allow IDs to be reused
+        ms.xtrim("events", StreamTrim::MaxLen(0))?;
+        assert!(ms
+            .xadd("events", Some(StreamId::new(10, 0)), &[("n", "10")])
+            .is_err());
+        assert_eq!(ms.key_type("events")?, Some(KeyType::Stream));
+
+        Ok(())
+    }
+
+    #[test]
+    fn delivers_entries_to_consumer_groups() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        add_entries(&mut ms, "jobs", 2)?;
+        ms.xgroup_create("jobs", "all", Some(StreamId::MIN))?;
+        ms.xgroup_create("jobs", "new", None)?;
+        ms.xadd("jobs", Some(StreamId::new(3, 0)), &[("n", "3")])?;
+
+        let first = ms.xreadgroup("jobs", "all", "worker-1", None, Some(2))?;
+        let second = ms.xreadgroup("jobs", "all", "worker-2", None, None)?;
+        assert_eq!(ids(&first), vec!["1-0", "2-0"]);
+        assert_eq!(ids(&second), vec!["3-0"]);
+        assert!(ms
+            .xreadgroup("jobs", "all", "worker-1", None, None)?
+            .is_empty());
+
+        // Groups track deliveries separately
+        let other = ms.xreadgroup("jobs", "new", "worker-1", None, None)?;
+        assert_eq!(ids(&other), vec!["3-0"]);
+
+        let history = ms.xreadgroup("jobs", "all", "worker-1", Some(StreamId::new(1, 0)), None)?;
+        assert_eq!(ids(&history), vec!["2-0"]);
+
+        assert_eq!(
+            ms.xack("jobs", "all", &[StreamId::new(1, 0), StreamId::new(3, 0)])?,
+            2
+        );
+        let history = ms.xreadgroup("jobs", "all", "worker-1", Some(StreamId::MIN), None)?;
+        assert_eq!(ids(&history), vec!["2-0"]);
+
+        // Trimmed entries are returned without fields
+        ms.xtrim("jobs", StreamTrim::MaxLen(0))?;
+        let history = ms.xreadgroup("jobs", "all", "worker-1", Some(StreamId::MIN), None)?;
+        assert_eq!(
+            history,
+            vec![StreamEntry {
+                id: StreamId::new(2, 0),
+                fields: vec![]
+            }]
+        );
+
+        let err = ms
+            .xreadgroup("jobs", "missing", "worker-1", None, None)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(ms
+            .xreadgroup("missing", "all", "worker-1", None, None)
+            .is_err());
+        assert_eq!(
+            ms.xgroup_create("jobs", "all", None).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_and_claims_pending_entries() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.xgroup_create("jobs", "workers", None)?;
+        add_entries(&mut ms, "jobs", 4)?;
+        ms.xreadgroup("jobs", "workers", "worker-1", None, Some(3))?;
+        ms.xreadgroup("jobs", "workers", "worker-2", None, None)?;
+
+        let summary = ms.xpending("jobs", "workers")?;
+        assert_eq!(
+            summary,
+            PendingSummary {
+                count: 4,
+                first: Some(StreamId::new(1, 0)),
+                last: Some(StreamId::new(4, 0)),
+                consumers: vec![("worker-1".to_string(), 3), ("worker-2".to_string(), 1)],
+            }
+        );
+
+        let pending = ms.xpending_range(
+            "jobs",
+            "workers",
+            StreamId::MIN,
+            StreamId::MAX,
+            10,
+            Some("worker-1"),
+        )?;
+        assert_eq!(pending.len(), 3);
+        assert!(pending.iter().all(|entry| entry.deliveries == 1));
+
+        // Entries which have not been idle long enough are not claimed
+        assert!(ms
+            .xclaim(
+                "jobs",
+                "workers",
+                "worker-2",
+                60_000,
+                &[StreamId::new(1, 0)]
+            )?
+            .is_empty());
+
+        let claimed = ms.xclaim(
+            "jobs",
+            "workers",
+            "worker-2",
+            0,
+            &[
+                StreamId::new(1, 0),
+                StreamId::new(2, 0),
+                StreamId::new(9, 0),
+            ],
+        )?;
+        assert_eq!(ids(&claimed), vec!["1-0", "2-0"]);
+
+        let pending =
+            ms.xpending_range("jobs", "workers", StreamId::MIN, StreamId::MAX, 1, None)?;
+        assert_eq!(pending[0].consumer, "worker-2");
+        assert_eq!(pending[0].deliveries, 2);
+
+        // Claiming trimmed entries acknowledges them
+        ms.xtrim("jobs", StreamTrim::MinId(StreamId::new(3, 0)))?;
+        assert!(ms
+            .xclaim("jobs", "workers", "worker-1", 0, &[StreamId::new(1, 0)])?
+            .is_empty());
+        assert_eq!(ms.xpending("jobs", "workers")?.count, 3);
+
+        assert!(ms.xgroup_destroy("jobs", "workers")?);
+        assert!(ms.xpending("jobs", "workers").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn streams_serialise() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        add_entries(&mut ms, "jobs", 3)?;
+        ms.xgroup_create("jobs", "workers", Some(StreamId::MIN))?;
+        ms.xreadgroup("jobs", "workers", "worker-1", None, Some(1))?;
+
+        let contents = serde_json::to_string(&ms)?;
+        let mut loaded: MemStore = serde_json::from_str(&contents)?;
+
+        assert_eq!(
+            loaded.xrange("jobs", StreamId::MIN, StreamId::MAX, None)?,
+            ms.xrange("jobs", StreamId::MIN, StreamId::MAX, None)?
+        );
+        assert_eq!(loaded.xpending("jobs", "workers")?.count, 1);
+
+        let next = loaded.xreadgroup("jobs", "workers", "worker-1", None, None)?;
+        assert_eq!(ids(&next), vec!["2-0", "3-0"]);
+
+        Ok(())
+    }
+}
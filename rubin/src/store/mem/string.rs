@@ -0,0 +1,345 @@
+//! String manipulation operations for the [`MemStore`]
+//!
+//! Operations on parts of values in the string store and on many string keys at once. Byte
+//! ranges follow the same rules as list ranges, with negative indices counting from the end
+//! of the string.
+
+use std::io;
+
+use crate::store::mem::list::normalise_range;
+use crate::store::mem::{KeyType, MemStore};
+
+/// Longest string which can be built with [`MemStore::setrange`], matching the bitmap limit
+const MAX_STRING_LENGTH: usize = 512 * 1024 * 1024;
+
+impl MemStore {
+    /// Appends a value to the end of a string, creating the string if it is not present
+    ///
+    /// # Returns
+    ///
+    /// * The length of the string after the append
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// assert_eq!(ms.append("log", "started;").unwrap(), 8);
+    /// assert_eq!(ms.append("log", "stopped;").unwrap(), 16);
+    /// assert_eq!(ms.get_string("log").unwrap().as_deref(), Some("started;stopped;"));
+    /// ```
+    pub fn append(&mut self, key: &str, value: &str) -> io::Result<usize> {
+        self.check_type(key, KeyType::String)?;
+        self.reserve_memory()?;
+
+        let bytes = self.strings.entry(key);
+        bytes.extend_from_slice(value.as_bytes());
+
+        Ok(bytes.len())
+    }
+
+    /// Gets the part of a string between two byte indices (inclusive)
+    ///
+    /// # Returns
+    ///
+    /// * The part of the string within the range, empty if the string is not present or the
+    ///   range is outside of it. Invalid UTF-8 sequences are replaced with `U+FFFD`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("greeting", "Hello, World").unwrap();
+    ///
+    /// assert_eq!(ms.getrange("greeting", 0, 4).unwrap(), "Hello");
+    /// assert_eq!(ms.getrange("greeting", -5, -1).unwrap(), "World");
+    /// assert_eq!(ms.getrange("greeting", 20, 30).unwrap(), "");
+    /// ```
+    pub fn getrange(&self, key: &str, start: isize, end: isize) -> io::Result<String> {
+        let Some(bytes) = self.strings.get(key) else {
+            return Ok(String::new());
+        };
+
+        let range = match normalise_range(start, end, bytes.len()) {
+            Some((start, end)) => String::from_utf8_lossy(&bytes[start..=end]).into_owned(),
+            None => String::new(),
+        };
+
+        Ok(range)
+    }
+
+    /// Overwrites part of a string starting at a byte offset
+    ///
+    /// A string which is shorter than the offset is padded with zero bytes first, and a
+    /// missing string is created unless the value is empty.
+    ///
+    /// # Returns
+    ///
+    /// * The length of the string after it was overwritten
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the string would grow past 512MB
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("greeting", "Hello, World").unwrap();
+    ///
+    /// assert_eq!(ms.setrange("greeting", 7, "Rubin").unwrap(), 12);
+    /// assert_eq!(ms.get_string("greeting").unwrap().as_deref(), Some("Hello, Rubin"));
+    ///
+    /// assert_eq!(ms.setrange("padded", 2, "ab").unwrap(), 4);
+    /// assert_eq!(ms.get_bytes("padded").unwrap().as_deref(), Some(&b"\0\0ab"[..]));
+    /// ```
+    pub fn setrange(&mut self, key: &str, offset: usize, value: &str) -> io::Result<usize> {
+        self.check_type(key, KeyType::String)?;
+        if value.is_empty() {
+            return self.strlen(key);
+        }
+
+        let end = offset
+            .checked_add(value.len())
+            .filter(|end| *end <= MAX_STRING_LENGTH)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "string exceeds maximum allowed size",
+                )
+            })?;
+
+        self.reserve_memory()?;
+        let bytes = self.strings.entry(key);
+        if bytes.len() < end {
+            bytes.resize(end, 0);
+        }
+        bytes[offset..end].copy_from_slice(value.as_bytes());
+
+        Ok(bytes.len())
+    }
+
+    /// Gets the length of a string in bytes, zero if the string is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("greeting", "Hello, World").unwrap();
+    ///
+    /// assert_eq!(ms.strlen("greeting").unwrap(), 12);
+    /// assert_eq!(ms.strlen("missing").unwrap(), 0);
+    /// ```
+    pub fn strlen(&self, key: &str) -> io::Result<usize> {
+        Ok(self.strings.get(key).map_or(0, |bytes| bytes.len()))
+    }
+
+    /// Inserts a string if the key is not present, whatever type of value it could hold
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the string was inserted
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// assert!(ms.setnx("lock:orders", "worker-1").unwrap());
+    /// assert!(!ms.setnx("lock:orders", "worker-2").unwrap());
+    /// assert_eq!(ms.get_string("lock:orders").unwrap().as_deref(), Some("worker-1"));
+    /// ```
+    pub fn setnx(&mut self, key: &str, value: &str) -> io::Result<bool> {
+        if self.exists(&[key])? > 0 {
+            return Ok(false);
+        }
+
+        self.insert_string(key, value)?;
+        Ok(true)
+    }
+
+    /// Removes a string, returning the value it held
+    ///
+    /// Unlike [`MemStore::remove_string`], a key holding another type of value is an error
+    /// rather than being treated as missing.
+    ///
+    /// # Returns
+    ///
+    /// * The value that was removed, `None` if not present
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the key holds another type of value
+    /// * [`io::ErrorKind::InvalidData`] if the value is not valid UTF-8, in which case it is
+    ///   not removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("token:1000", "abc123").unwrap();
+    ///
+    /// assert_eq!(ms.getdel("token:1000").unwrap().as_deref(), Some("abc123"));
+    /// assert_eq!(ms.getdel("token:1000").unwrap(), None);
+    /// ```
+    pub fn getdel(&mut self, key: &str) -> io::Result<Option<String>> {
+        self.check_type(key, KeyType::String)?;
+
+        let value = self.get_string(key)?;
+        if value.is_some() {
+            self.strings.delete(key);
+        }
+
+        Ok(value)
+    }
+
+    /// Gets the strings held by many keys at once
+    ///
+    /// # Returns
+    ///
+    /// * The value of each key in order, `None` for keys which are missing or hold another
+    ///   type of value
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if any value is not valid UTF-8
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.mset(&[("user:1", "alice"), ("user:2", "bob")]).unwrap();
+    ///
+    /// let users = ms.mget(&["user:1", "user:3", "user:2"]).unwrap();
+    /// assert_eq!(users, vec![Some("alice".to_string()), None, Some("bob".to_string())]);
+    /// ```
+    pub fn mget(&self, keys: &[&str]) -> io::Result<Vec<Option<String>>> {
+        keys.iter().map(|key| self.get_string(key)).collect()
+    }
+
+    /// Inserts many strings at once, replacing whatever the keys held
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.mset(&[("user:1", "alice"), ("user:2", "bob")]).unwrap();
+    ///
+    /// assert_eq!(ms.get_string("user:2").unwrap().as_deref(), Some("bob"));
+    /// ```
+    pub fn mset(&mut self, pairs: &[(&str, &str)]) -> io::Result<()> {
+        for (key, value) in pairs {
+            self.insert_string(key, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Inserts many strings at once, only if none of the keys are present
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the strings were inserted, `false` if any key was present and nothing
+    ///   was inserted
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// assert!(ms.msetnx(&[("user:1", "alice"), ("user:2", "bob")]).unwrap());
+    /// assert!(!ms.msetnx(&[("user:2", "carol"), ("user:3", "dave")]).unwrap());
+    /// assert_eq!(ms.get_string("user:3").unwrap(), None);
+    /// ```
+    pub fn msetnx(&mut self, pairs: &[(&str, &str)]) -> io::Result<bool> {
+        let keys: Vec<&str> = pairs.iter().map(|(key, _)| *key).collect();
+        if self.exists(&keys)? > 0 {
+            return Ok(false);
+        }
+
+        self.mset(pairs)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod string_tests {
+    use super::*;
+
+    #[test]
+    fn edits_parts_of_strings() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_string_with_expiry("key", "Hello", std::time::Duration::from_secs(60))?;
+
+        assert_eq!(ms.append("key", " World")?, 11);
+        assert_eq!(ms.setrange("key", 6, "Rubin")?, 11);
+        assert_eq!(ms.get_string("key")?.as_deref(), Some("Hello Rubin"));
+        assert!(ms.ttl("key")? > 0);
+
+        assert_eq!(ms.getrange("key", 0, -7)?, "Hello");
+        assert_eq!(ms.getrange("key", -100, 100)?, "Hello Rubin");
+        assert_eq!(ms.getrange("key", 5, 2)?, "");
+        assert_eq!(ms.getrange("missing", 0, -1)?, "");
+
+        assert_eq!(ms.setrange("missing", 10, "")?, 0);
+        assert_eq!(ms.exists(&["missing"])?, 0);
+
+        let err = ms.setrange("key", MAX_STRING_LENGTH, "a").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(ms.strlen("key")?, 11);
+
+        ms.rpush("list", &["a"])?;
+        assert!(ms.append("list", "a").is_err());
+        assert!(ms.setrange("list", 0, "a").is_err());
+        assert!(ms.getdel("list").is_err());
+        assert_eq!(ms.strlen("list")?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sets_only_missing_keys() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.incr("counter")?;
+
+        assert!(!ms.setnx("counter", "value")?);
+        assert!(!ms.msetnx(&[("new", "value"), ("counter", "value")])?);
+        assert_eq!(ms.exists(&["new"])?, 0);
+
+        ms.mset(&[("counter", "replaced"), ("new", "value")])?;
+        assert_eq!(ms.key_type("counter")?, Some(KeyType::String));
+        assert_eq!(
+            ms.mget(&["counter", "new", "missing"])?,
+            vec![
+                Some("replaced".to_string()),
+                Some("value".to_string()),
+                None
+            ]
+        );
+
+        ms.insert_bytes("binary", &[0xff])?;
+        assert!(ms.mget(&["binary"]).is_err());
+        assert!(ms.getdel("binary").is_err());
+        assert_eq!(ms.strlen("binary")?, 1);
+
+        Ok(())
+    }
+}
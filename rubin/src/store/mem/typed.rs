@@ -0,0 +1,275 @@
+//! Typed value operations for the [`MemStore`]
+//!
+//! Any type implementing [`Serialize`] can be stored without converting it to a string by hand.
+//! Values are serialized to JSON and stored alongside the name of their type, so reading a
+//! value back as a different type is reported as an error rather than silently succeeding
+//! for structurally similar types.
+
+use std::any::type_name;
+use std::io;
+use std::mem::size_of;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::store::mem::{KeyType, MemStore};
+use crate::store::MemoryUsage;
+
+/// A serialized value tagged with the name of the type it was serialized from
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypedValue {
+    /// Name of the type the value was serialized from
+    pub type_name: String,
+
+    /// JSON representation of the value
+    pub value: String,
+}
+
+impl TypedValue {
+    /// Serializes a value, tagging it with the name of its type
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::TypedValue;
+    ///
+    /// let typed = TypedValue::new(&vec![1, 2, 3]).unwrap();
+    ///
+    /// assert!(typed.type_name.ends_with("Vec<i32>"));
+    /// assert_eq!(typed.value, "[1,2,3]");
+    /// ```
+    pub fn new<T: Serialize>(value: &T) -> io::Result<Self> {
+        Ok(Self {
+            type_name: type_tag::<T>(),
+            value: serde_json::to_string(value)?,
+        })
+    }
+
+    /// Deserializes the value, checking it was serialized from the requested type
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the value was serialized from a different type or
+    ///   could not be deserialized
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::TypedValue;
+    ///
+    /// let typed = TypedValue::new(&vec![1, 2, 3]).unwrap();
+    ///
+    /// assert_eq!(typed.deserialize::<Vec<i32>>().unwrap(), vec![1, 2, 3]);
+    /// assert!(typed.deserialize::<Vec<u8>>().is_err());
+    /// ```
+    pub fn deserialize<T: DeserializeOwned>(&self) -> io::Result<T> {
+        self.check_type(&type_tag::<T>())?;
+
+        serde_json::from_str(&self.value).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Checks the value was serialized from a type with the given name
+    fn check_type(&self, requested: &str) -> io::Result<()> {
+        if self.type_name != requested {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "stored value is of type {}, not {}",
+                    self.type_name, requested
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Name used to tag values of a type
+///
+/// Whitespace is removed so the tag can be sent as a single argument over the network.
+pub(crate) fn type_tag<T: ?Sized>() -> String {
+    type_name::<T>().replace(' ', "")
+}
+
+impl MemoryUsage for TypedValue {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.type_name.capacity() + self.value.capacity()
+    }
+}
+
+impl MemStore {
+    /// Serializes a value into the typed value store with a given key
+    ///
+    /// Any existing value under the key is replaced, regardless of its type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct User {
+    ///     name: String,
+    ///     age: u32,
+    /// }
+    ///
+    /// let mut ms = MemStore::new();
+    /// let user = User { name: "rubin".to_string(), age: 3 };
+    ///
+    /// ms.insert_typed("user:1000", &user).unwrap();
+    /// ```
+    pub fn insert_typed<T: Serialize>(&mut self, key: &str, value: &T) -> io::Result<()> {
+        let typed = TypedValue::new(value)?;
+
+        self.insert_tagged(key, typed)
+    }
+
+    /// Retrieves and deserializes a value from the typed value store
+    ///
+    /// # Returns
+    ///
+    /// * The deserialized value, `None` if the key is not present
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the value was stored as a different type or could
+    ///   not be deserialized
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// struct User {
+    ///     name: String,
+    ///     age: u32,
+    /// }
+    ///
+    /// let mut ms = MemStore::new();
+    /// let user = User { name: "rubin".to_string(), age: 3 };
+    /// ms.insert_typed("user:1000", &user).unwrap();
+    ///
+    /// let stored: Option<User> = ms.get_typed("user:1000").unwrap();
+    /// assert_eq!(stored, Some(user));
+    ///
+    /// // Reading the value as a different type is an error
+    /// assert!(ms.get_typed::<String>("user:1000").is_err());
+    /// ```
+    pub fn get_typed<T: DeserializeOwned>(&self, key: &str) -> io::Result<Option<T>> {
+        match self.typed.get(key) {
+            Some(typed) => typed.deserialize().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Inserts an already serialized value into the typed value store
+    ///
+    /// Used by the server, which receives values serialized by the client.
+    pub(crate) fn insert_tagged(&mut self, key: &str, typed: TypedValue) -> io::Result<()> {
+        self.reserve_memory()?;
+        self.replace_type(key, KeyType::Typed);
+
+        self.typed.insert(key, typed)
+    }
+
+    /// Retrieves the serialized form of a value from the typed value store, checking it was
+    /// stored as the type with the given name
+    pub(crate) fn get_tagged(&self, key: &str, type_name: &str) -> io::Result<Option<String>> {
+        match self.typed.get(key) {
+            Some(typed) => {
+                typed.check_type(type_name)?;
+                Ok(Some(typed.value.clone()))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod typed_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Admin {
+        name: String,
+        age: u32,
+    }
+
+    #[test]
+    fn stores_typed_values() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        let user = User {
+            name: "rubin".to_string(),
+            age: 3,
+        };
+
+        let mut scores = HashMap::new();
+        scores.insert("rubin".to_string(), 100);
+
+        ms.insert_typed("user", &user)?;
+        ms.insert_typed("scores", &scores)?;
+
+        assert_eq!(ms.get_typed("user")?, Some(user));
+        assert_eq!(ms.get_typed("scores")?, Some(scores));
+        assert_eq!(ms.get_typed::<User>("missing")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_mismatched_types() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        let user = User {
+            name: "rubin".to_string(),
+            age: 3,
+        };
+        ms.insert_typed("user", &user)?;
+
+        // Structurally identical types are still rejected
+        let err = ms.get_typed::<Admin>("user").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("User"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_data() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        let typed = TypedValue {
+            type_name: type_tag::<User>(),
+            value: "{\"name\":\"rubin\"}".to_string(),
+        };
+        ms.insert_tagged("user", typed)?;
+
+        let err = ms.get_typed::<User>("user").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
+    #[test]
+    fn typed_values_expire_and_serialise() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_typed("kept", &vec![1, 2, 3])?;
+        ms.insert_typed("expired", &vec![4, 5, 6])?;
+        assert!(ms.pexpire("expired", 0)?);
+
+        let contents = serde_json::to_string(&ms)?;
+        let loaded: MemStore = serde_json::from_str(&contents)?;
+
+        assert_eq!(loaded.get_typed("kept")?, Some(vec![1, 2, 3]));
+        assert_eq!(loaded.get_typed::<Vec<i32>>("expired")?, None);
+
+        Ok(())
+    }
+}
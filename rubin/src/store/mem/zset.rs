@@ -0,0 +1,562 @@
+//! Sorted set operations for the [`MemStore`]
+//!
+//! Sorted sets are collections of unique members, each associated with a score.
+//! Members are kept ordered by score (and then by member for equal scores) so range
+//! queries do not require sorting on each read.
+//!
+//! Indices follow the Redis conventions where negative values count backwards from
+//! the end of the set (i.e. `-1` is the member with the highest score).
+
+use serde::{Deserialize, Serialize};
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::mem::size_of;
+
+use crate::store::mem::list::normalise_range;
+use crate::store::mem::{KeyType, MemStore};
+use crate::store::MemoryUsage;
+
+/// Score of a member in a [`SortedSet`], ordered using [`f64::total_cmp`]
+#[derive(Debug, Clone, Copy)]
+struct Score(f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Collection of unique members ordered by score.
+///
+/// Holds a lookup of member to score alongside an ordered index of `(score, member)` pairs.
+/// Only the member to score lookup is serialized, the ordered index is rebuilt on load.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(from = "HashMap<String, f64>", into = "HashMap<String, f64>")]
+pub struct SortedSet {
+    /// Score lookup for each member
+    scores: HashMap<String, f64>,
+
+    /// Members ordered by score
+    ordered: BTreeSet<(Score, String)>,
+}
+
+impl SortedSet {
+    /// Get the total number of members in the set
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Checks if the set is currently empty
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Inserts a member with the given score, updating the score if the member already exists.
+    ///
+    /// Returns `true` if the member was newly added.
+    pub fn insert(&mut self, member: &str, score: f64) -> bool {
+        // Normalise negative zero so it orders alongside positive zero
+        let score = score + 0.0;
+
+        let previous = self.scores.insert(member.to_string(), score);
+        if let Some(previous) = previous {
+            self.ordered.remove(&(Score(previous), member.to_string()));
+        }
+        self.ordered.insert((Score(score), member.to_string()));
+
+        previous.is_none()
+    }
+
+    /// Removes a member from the set, returning `true` if it was present
+    pub fn remove(&mut self, member: &str) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => {
+                self.ordered.remove(&(Score(score), member.to_string()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gets the score of a member
+    pub fn score(&self, member: &str) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Gets the position of a member within the set, ordered from lowest to highest score
+    pub fn rank(&self, member: &str) -> Option<usize> {
+        let score = self.score(member)?;
+        Some(
+            self.ordered
+                .range(..(Score(score), member.to_string()))
+                .count(),
+        )
+    }
+
+    /// Iterates over the members and scores in order from lowest to highest score
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&str, f64)> {
+        self.ordered
+            .iter()
+            .map(|(score, member)| (member.as_str(), score.0))
+    }
+
+    /// Iterates over the members with a score between `min` and `max` (inclusive)
+    pub fn range_by_score(&self, min: f64, max: f64) -> impl Iterator<Item = (&str, f64)> {
+        self.ordered
+            .range((Score(min), String::new())..)
+            .take_while(move |(score, _)| score.0 <= max)
+            .map(|(score, member)| (member.as_str(), score.0))
+    }
+}
+
+impl From<HashMap<String, f64>> for SortedSet {
+    fn from(scores: HashMap<String, f64>) -> Self {
+        let mut set = Self::default();
+        for (member, score) in scores {
+            set.insert(&member, score);
+        }
+
+        set
+    }
+}
+
+impl From<SortedSet> for HashMap<String, f64> {
+    fn from(set: SortedSet) -> Self {
+        set.scores
+    }
+}
+
+impl MemoryUsage for SortedSet {
+    fn memory_usage(&self) -> usize {
+        // Each member is held in both the score lookup and the ordered index
+        size_of::<Self>()
+            + self
+                .scores
+                .keys()
+                .map(|member| 2 * (member.memory_usage() + size_of::<f64>()))
+                .sum::<usize>()
+    }
+}
+
+/// Checks that a score is a finite number
+fn validate_score(score: f64) -> io::Result<f64> {
+    // Infinite scores cannot be written to JSON, so a store holding one could not be loaded
+    if !score.is_finite() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "score is not a finite number",
+        ));
+    }
+
+    Ok(score)
+}
+
+impl MemStore {
+    /// Adds one or more members with scores to a sorted set, creating the set if it does not exist.
+    ///
+    /// Existing members have their score updated.
+    ///
+    /// # Returns
+    ///
+    /// * The number of members that were newly added to the set
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if any score is `NaN` or infinite
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    ///
+    /// let added = ms.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).unwrap();
+    /// assert_eq!(added, 2);
+    /// ```
+    pub fn zadd(&mut self, key: &str, members: &[(f64, &str)]) -> io::Result<usize> {
+        for (score, _) in members {
+            validate_score(*score)?;
+        }
+
+        self.check_type(key, KeyType::SortedSet)?;
+        self.reserve_memory()?;
+
+        let set = self.zsets.entry(key);
+        let added = members
+            .iter()
+            .filter(|(score, member)| set.insert(member, *score))
+            .count();
+
+        Ok(added)
+    }
+
+    /// Removes one or more members from a sorted set.
+    ///
+    /// The set is removed from the store once it is empty.
+    ///
+    /// # Returns
+    ///
+    /// * The number of members removed
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).unwrap();
+    ///
+    /// let removed = ms.zrem("leaderboard", &["bob", "carol"]).unwrap();
+    /// assert_eq!(removed, 1);
+    /// ```
+    pub fn zrem(&mut self, key: &str, members: &[&str]) -> io::Result<usize> {
+        let Some(set) = self.zsets.get_mut(key) else {
+            return Ok(0);
+        };
+
+        let removed = members.iter().filter(|member| set.remove(member)).count();
+
+        if set.is_empty() {
+            self.zsets.delete(key);
+        }
+
+        Ok(removed)
+    }
+
+    /// Retrieves the score of a member in a sorted set.
+    ///
+    /// # Returns
+    ///
+    /// * The score of the member, `None` if the member or set is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.zadd("leaderboard", &[(100.0, "alice")]).unwrap();
+    ///
+    /// assert_eq!(ms.zscore("leaderboard", "alice").unwrap(), Some(100.0));
+    /// assert_eq!(ms.zscore("leaderboard", "bob").unwrap(), None);
+    /// ```
+    pub fn zscore(&self, key: &str, member: &str) -> io::Result<Option<f64>> {
+        Ok(self.zsets.get(key).and_then(|set| set.score(member)))
+    }
+
+    /// Increments the score of a member in a sorted set by a given amount.
+    ///
+    /// If the member does not exist, it is added with the increment as its score.
+    ///
+    /// # Returns
+    ///
+    /// * The new score of the member
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the resulting score is `NaN` or infinite
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.zadd("leaderboard", &[(100.0, "alice")]).unwrap();
+    ///
+    /// let score = ms.zincrby("leaderboard", 5.0, "alice").unwrap();
+    /// assert_eq!(score, 105.0);
+    /// ```
+    pub fn zincrby(&mut self, key: &str, increment: f64, member: &str) -> io::Result<f64> {
+        self.check_type(key, KeyType::SortedSet)?;
+        self.reserve_memory()?;
+
+        let current = self.zscore(key, member)?.unwrap_or(0.0);
+        let score = validate_score(current + increment)?;
+
+        self.zsets.entry(key).insert(member, score);
+
+        Ok(score)
+    }
+
+    /// Retrieves the rank of a member in a sorted set, ordered from lowest to highest score.
+    ///
+    /// # Returns
+    ///
+    /// * The zero-based rank of the member, `None` if the member or set is not present
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).unwrap();
+    ///
+    /// assert_eq!(ms.zrank("leaderboard", "alice").unwrap(), Some(1));
+    /// assert_eq!(ms.zrank("leaderboard", "bob").unwrap(), Some(0));
+    /// ```
+    pub fn zrank(&self, key: &str, member: &str) -> io::Result<Option<usize>> {
+        Ok(self.zsets.get(key).and_then(|set| set.rank(member)))
+    }
+
+    /// Retrieves the members and scores of a sorted set between `start` and `stop` (inclusive),
+    /// ordered from lowest to highest score.
+    ///
+    /// Negative indices count backwards from the end of the set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).unwrap();
+    ///
+    /// let members = ms.zrange("leaderboard", 0, -1).unwrap();
+    /// assert_eq!(members, vec![("bob".to_string(), 75.0), ("alice".to_string(), 100.0)]);
+    /// ```
+    pub fn zrange(&self, key: &str, start: isize, stop: isize) -> io::Result<Vec<(String, f64)>> {
+        let Some(set) = self.zsets.get(key) else {
+            return Ok(vec![]);
+        };
+
+        let Some((start, stop)) = normalise_range(start, stop, set.len()) else {
+            return Ok(vec![]);
+        };
+
+        Ok(set
+            .iter()
+            .skip(start)
+            .take(stop - start + 1)
+            .map(|(member, score)| (member.to_string(), score))
+            .collect())
+    }
+
+    /// Retrieves the members and scores of a sorted set between `start` and `stop` (inclusive),
+    /// ordered from highest to lowest score.
+    ///
+    /// Negative indices count backwards from the end of the set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).unwrap();
+    ///
+    /// let top = ms.zrevrange("leaderboard", 0, 0).unwrap();
+    /// assert_eq!(top, vec![("alice".to_string(), 100.0)]);
+    /// ```
+    pub fn zrevrange(
+        &self,
+        key: &str,
+        start: isize,
+        stop: isize,
+    ) -> io::Result<Vec<(String, f64)>> {
+        let Some(set) = self.zsets.get(key) else {
+            return Ok(vec![]);
+        };
+
+        let Some((start, stop)) = normalise_range(start, stop, set.len()) else {
+            return Ok(vec![]);
+        };
+
+        Ok(set
+            .iter()
+            .rev()
+            .skip(start)
+            .take(stop - start + 1)
+            .map(|(member, score)| (member.to_string(), score))
+            .collect())
+    }
+
+    /// Retrieves the members and scores of a sorted set with a score between `min` and `max`
+    /// (inclusive), ordered from lowest to highest score.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob"), (50.0, "carol")]).unwrap();
+    ///
+    /// let members = ms.zrangebyscore("leaderboard", 60.0, f64::INFINITY).unwrap();
+    /// assert_eq!(members, vec![("bob".to_string(), 75.0), ("alice".to_string(), 100.0)]);
+    /// ```
+    pub fn zrangebyscore(&self, key: &str, min: f64, max: f64) -> io::Result<Vec<(String, f64)>> {
+        let Some(set) = self.zsets.get(key) else {
+            return Ok(vec![]);
+        };
+
+        Ok(set
+            .range_by_score(min, max)
+            .map(|(member, score)| (member.to_string(), score))
+            .collect())
+    }
+
+    /// Counts the members of a sorted set with a score between `min` and `max` (inclusive).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob"), (50.0, "carol")]).unwrap();
+    ///
+    /// assert_eq!(ms.zcount("leaderboard", 50.0, 75.0).unwrap(), 2);
+    /// ```
+    pub fn zcount(&self, key: &str, min: f64, max: f64) -> io::Result<usize> {
+        Ok(self
+            .zsets
+            .get(key)
+            .map_or(0, |set| set.range_by_score(min, max).count()))
+    }
+}
+
+#[cfg(test)]
+mod zset_tests {
+    use super::*;
+    use crate::store::persistence::file_handling::read_store_sync;
+
+    fn members(values: Vec<(String, f64)>) -> Vec<String> {
+        values.into_iter().map(|(member, _)| member).collect()
+    }
+
+    #[test]
+    fn add_and_update_members() -> io::Result<()> {
+        let mut ms = MemStore::new();
+
+        assert_eq!(ms.zadd("set", &[(1.0, "a"), (2.0, "b")])?, 2);
+        assert_eq!(ms.zadd("set", &[(3.0, "a"), (0.5, "c")])?, 1);
+
+        assert_eq!(ms.zscore("set", "a")?, Some(3.0));
+        assert_eq!(members(ms.zrange("set", 0, -1)?), vec!["c", "b", "a"]);
+
+        for score in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let err = ms.zadd("set", &[(score, "d")]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert_eq!(ms.zscore("set", "d")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn equal_scores_are_ordered_by_member() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.zadd("set", &[(1.0, "c"), (1.0, "a"), (1.0, "b")])?;
+
+        assert_eq!(members(ms.zrange("set", 0, -1)?), vec!["a", "b", "c"]);
+        assert_eq!(ms.zrank("set", "c")?, Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove_members() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.zadd("set", &[(1.0, "a"), (2.0, "b")])?;
+
+        assert_eq!(ms.zrem("set", &["a", "x"])?, 1);
+        assert_eq!(ms.zrank("set", "b")?, Some(0));
+        assert_eq!(ms.zrank("set", "a")?, None);
+
+        ms.zrem("set", &["b"])?;
+        assert_eq!(ms.zsets.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn increment_scores() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.zadd("set", &[(1.0, "a"), (2.0, "b")])?;
+
+        assert_eq!(ms.zincrby("set", 5.0, "a")?, 6.0);
+        assert_eq!(ms.zincrby("set", 1.5, "new")?, 1.5);
+        assert_eq!(members(ms.zrange("set", 0, -1)?), vec!["new", "b", "a"]);
+
+        ms.zadd("set", &[(f64::MAX, "max")])?;
+        let err = ms.zincrby("set", f64::MAX, "max").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(ms.zscore("set", "max")?, Some(f64::MAX));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ranges_by_index_and_score() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.zadd("set", &[(1.0, "a"), (2.0, "b"), (3.0, "c"), (4.0, "d")])?;
+
+        assert_eq!(members(ms.zrange("set", 1, 2)?), vec!["b", "c"]);
+        assert_eq!(members(ms.zrevrange("set", 0, 1)?), vec!["d", "c"]);
+        assert_eq!(members(ms.zrevrange("set", -1, -1)?), vec!["a"]);
+        assert!(ms.zrange("set", 10, 20)?.is_empty());
+
+        assert_eq!(members(ms.zrangebyscore("set", 2.0, 3.0)?), vec!["b", "c"]);
+        assert_eq!(
+            members(ms.zrangebyscore("set", f64::NEG_INFINITY, 1.5)?),
+            vec!["a"]
+        );
+        assert_eq!(ms.zcount("set", 1.5, f64::INFINITY)?, 3);
+        assert_eq!(ms.zcount("missing", 0.0, 1.0)?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dumps_and_loads_extreme_scores() -> io::Result<()> {
+        let td = tempdir::TempDir::new("zset")?;
+        let path = td.path().join("store.json");
+
+        let mut ms = MemStore::new();
+        ms.zadd("set", &[(f64::MAX, "max"), (f64::MIN, "min"), (0.5, "mid")])?;
+        assert!(ms.zincrby("set", f64::MAX, "max").is_err());
+        ms.dump_store(&path)?;
+
+        let (loaded, _, _) = read_store_sync(&path)?.unwrap();
+        assert_eq!(
+            members(loaded.zrange("set", 0, -1)?),
+            vec!["min", "mid", "max"]
+        );
+        assert_eq!(loaded.zscore("set", "max")?, Some(f64::MAX));
+
+        Ok(())
+    }
+
+    #[test]
+    fn serializes_and_rebuilds_the_index() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.zadd("set", &[(2.0, "b"), (1.0, "a")])?;
+
+        let raw = serde_json::to_string(&ms)?;
+        let other: MemStore = serde_json::from_str(&raw)?;
+
+        assert_eq!(members(other.zrange("set", 0, -1)?), vec!["a", "b"]);
+        assert_eq!(other.zrank("set", "b")?, Some(1));
+
+        Ok(())
+    }
+}
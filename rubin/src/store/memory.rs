@@ -0,0 +1,372 @@
+//! Approximate memory accounting for the inner stores
+//!
+//! Sizes are estimates based on the lengths of the stored keys and values plus a fixed
+//! overhead per entry. They do not account for allocator behaviour or spare capacity in
+//! the underlying hash tables, but are close enough to make a memory limit meaningful.
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use crate::store::now_millis;
+
+/// Approximate bookkeeping cost of a single entry in an inner store
+/// (hash table slot, expiry and usage tracking)
+pub(crate) const ENTRY_OVERHEAD: usize = 64;
+
+/// Logical clock used to order key accesses across all stores
+static ACCESS_CLOCK: AtomicU64 = AtomicU64::new(0);
+
+/// Time a key can go without being accessed before its access count is halved, so keys which
+/// were popular in the past do not stay ahead of those in use now
+const HIT_DECAY_PERIOD: u64 = 60_000;
+
+/// Types which can estimate the amount of memory they occupy
+pub trait MemoryUsage {
+    /// Approximate number of bytes used by the value, including any heap allocations
+    fn memory_usage(&self) -> usize;
+}
+
+impl MemoryUsage for String {
+    fn memory_usage(&self) -> usize {
+        size_of::<String>() + self.capacity()
+    }
+}
+
+impl MemoryUsage for isize {
+    fn memory_usage(&self) -> usize {
+        size_of::<isize>()
+    }
+}
+
+impl<T: MemoryUsage> MemoryUsage for VecDeque<T> {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+    }
+}
+
+impl<T: MemoryUsage> MemoryUsage for HashSet<T> {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+    }
+}
+
+impl<K: MemoryUsage, V: MemoryUsage> MemoryUsage for HashMap<K, V> {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self
+                .iter()
+                .map(|(key, value)| key.memory_usage() + value.memory_usage())
+                .sum::<usize>()
+    }
+}
+
+/// Usage information of a single key, used to select keys for eviction
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct KeyUsage {
+    /// Name of the key
+    pub key: String,
+
+    /// Approximate size of the entry in bytes
+    pub size: usize,
+
+    /// Logical time of the last access
+    pub last_access: u64,
+
+    /// Number of times the key has been accessed, halved for each [`HIT_DECAY_PERIOD`] since
+    /// it was last accessed
+    pub hits: u32,
+
+    /// Expiry deadline (milliseconds since the Unix epoch), if one is set
+    pub expiry: Option<u64>,
+}
+
+/// Size and access statistics of a single entry
+///
+/// Access statistics are atomics so they can be updated on reads through a shared reference.
+#[derive(Debug, Default)]
+struct EntryUsage {
+    size: usize,
+    last_access: AtomicU64,
+    hits: AtomicU32,
+
+    /// Time of the last access (milliseconds since the Unix epoch), used to decay `hits`
+    accessed_at: AtomicU64,
+
+    /// Position of the key in [`UsageTracker::keys`]
+    slot: usize,
+}
+
+impl Clone for EntryUsage {
+    fn clone(&self) -> Self {
+        Self {
+            size: self.size,
+            last_access: AtomicU64::new(self.last_access.load(Ordering::Relaxed)),
+            hits: AtomicU32::new(self.hits.load(Ordering::Relaxed)),
+            accessed_at: AtomicU64::new(self.accessed_at.load(Ordering::Relaxed)),
+            slot: self.slot,
+        }
+    }
+}
+
+impl EntryUsage {
+    fn touch(&self) {
+        let now = ACCESS_CLOCK.fetch_add(1, Ordering::Relaxed);
+        self.last_access.store(now, Ordering::Relaxed);
+
+        let accessed_at = now_millis();
+        let hits = self.decayed_hits(accessed_at);
+        self.hits.store(hits.saturating_add(1), Ordering::Relaxed);
+        self.accessed_at.store(accessed_at, Ordering::Relaxed);
+    }
+
+    /// Gets the number of hits, halved for each [`HIT_DECAY_PERIOD`] since the last access
+    fn decayed_hits(&self, now: u64) -> u32 {
+        let idle = now.saturating_sub(self.accessed_at.load(Ordering::Relaxed));
+        let periods = idle / HIT_DECAY_PERIOD;
+
+        self.hits
+            .load(Ordering::Relaxed)
+            .checked_shr(periods.try_into().unwrap_or(u32::MAX))
+            .unwrap_or(0)
+    }
+}
+
+/// Tracks the approximate size and access patterns of the entries in an inner store
+///
+/// Entries handed out mutably are marked as dirty and have their size recalculated the next
+/// time the total is requested, rather than on every modification.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct UsageTracker {
+    entries: HashMap<String, EntryUsage>,
+    dirty: HashSet<String>,
+    total: usize,
+
+    /// Every tracked key, so keys can be picked at random without walking the whole table
+    keys: Vec<String>,
+}
+
+impl UsageTracker {
+    /// Records an access to a key
+    pub fn touch(&self, key: &str) {
+        if let Some(entry) = self.entries.get(key) {
+            entry.touch();
+        }
+    }
+
+    /// Records an access to a key which may change its size
+    pub fn touch_mut(&mut self, key: &str) {
+        self.entry_mut(key).touch();
+        self.dirty.insert(key.to_string());
+    }
+
+    /// Records a new value for a key with the given size
+    pub fn record(&mut self, key: &str, size: usize) {
+        let entry = self.entry_mut(key);
+        let previous = std::mem::replace(&mut entry.size, size);
+        entry.touch();
+        self.total = self.total - previous + size;
+        self.dirty.remove(key);
+    }
+
+    /// Stops tracking a key
+    pub fn forget(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.total -= entry.size;
+            self.keys.swap_remove(entry.slot);
+            if let Some(moved) = self.keys.get(entry.slot) {
+                if let Some(moved) = self.entries.get_mut(moved) {
+                    moved.slot = entry.slot;
+                }
+            }
+        }
+        self.dirty.remove(key);
+    }
+
+    /// Stops tracking all keys
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.dirty.clear();
+        self.keys.clear();
+        self.total = 0;
+    }
+
+    /// Recalculates the size of any modified or untracked keys and returns the total size
+    pub fn refresh<T: MemoryUsage>(&mut self, values: &HashMap<String, T>) -> usize {
+        // Stores loaded from disk start with no usage information
+        if self.entries.len() != values.len() {
+            self.dirty.extend(
+                values
+                    .keys()
+                    .filter(|key| !self.entries.contains_key(*key))
+                    .cloned(),
+            );
+        }
+
+        for key in std::mem::take(&mut self.dirty) {
+            match values.get(&key) {
+                Some(value) => {
+                    let size = entry_size(&key, value);
+                    let previous = std::mem::replace(&mut self.entry_mut(&key).size, size);
+                    self.total = self.total - previous + size;
+                }
+                None => self.forget(&key),
+            }
+        }
+
+        self.total
+    }
+
+    /// Gets the size and access statistics of a key
+    pub fn usage(&self, key: &str) -> Option<(usize, u64, u32)> {
+        let now = now_millis();
+        self.entries.get(key).map(|entry| {
+            (
+                entry.size,
+                entry.last_access.load(Ordering::Relaxed),
+                entry.decayed_hits(now),
+            )
+        })
+    }
+
+    /// Picks up to `count` tracked keys at random, or every key if there are no more than
+    /// `count`
+    ///
+    /// Keys are picked independently, so the same key may be picked more than once.
+    pub fn sample(&self, count: usize) -> Vec<&str> {
+        if self.keys.len() <= count {
+            return self.keys.iter().map(String::as_str).collect();
+        }
+
+        let state = RandomState::new();
+        (0..count)
+            .map(|i| {
+                let slot = state.hash_one(i) % self.keys.len() as u64;
+                self.keys[slot as usize].as_str()
+            })
+            .collect()
+    }
+
+    /// Gets the usage of a key, tracking it if it is not already
+    fn entry_mut(&mut self, key: &str) -> &mut EntryUsage {
+        let keys = &mut self.keys;
+        self.entries.entry(key.to_string()).or_insert_with(|| {
+            keys.push(key.to_string());
+            EntryUsage {
+                slot: keys.len() - 1,
+                ..EntryUsage::default()
+            }
+        })
+    }
+}
+
+/// Approximate size of a key-value entry in an inner store
+pub(crate) fn entry_size<T: MemoryUsage>(key: &str, value: &T) -> usize {
+    ENTRY_OVERHEAD + key.len() + value.memory_usage()
+}
+
+#[cfg(test)]
+mod memory_tests {
+    use super::*;
+
+    #[test]
+    fn collection_sizes_grow_with_contents() {
+        let mut list = VecDeque::new();
+        let empty = list.memory_usage();
+        list.push_back("value".to_string());
+        assert!(list.memory_usage() > empty);
+
+        let mut hash = HashMap::new();
+        hash.insert("field".to_string(), "value".to_string());
+        assert_eq!(
+            hash.memory_usage(),
+            size_of::<HashMap<String, String>>() + 2 * size_of::<String>() + 10
+        );
+    }
+
+    #[test]
+    fn tracks_total_size() {
+        let mut values = HashMap::new();
+        let mut tracker = UsageTracker::default();
+
+        values.insert("key".to_string(), "value".to_string());
+        tracker.record("key", entry_size("key", &values["key"]));
+        let size = tracker.refresh(&values);
+        assert_eq!(size, entry_size("key", &values["key"]));
+
+        values.get_mut("key").unwrap().push_str("-longer");
+        tracker.touch_mut("key");
+        assert!(tracker.refresh(&values) > size);
+
+        values.remove("key");
+        tracker.forget("key");
+        assert_eq!(tracker.refresh(&values), 0);
+    }
+
+    #[test]
+    fn untracked_keys_are_sized_on_refresh() {
+        let mut values = HashMap::new();
+        values.insert("key".to_string(), 10isize);
+
+        let mut tracker = UsageTracker::default();
+        assert_eq!(tracker.refresh(&values), entry_size("key", &10isize));
+    }
+
+    #[test]
+    fn records_accesses() {
+        let mut tracker = UsageTracker::default();
+        tracker.record("first", 10);
+        tracker.record("second", 10);
+        tracker.touch("first");
+
+        let (_, first_access, first_hits) = tracker.usage("first").unwrap();
+        let (_, second_access, second_hits) = tracker.usage("second").unwrap();
+        assert!(first_access > second_access);
+        assert_eq!(first_hits, 2);
+        assert_eq!(second_hits, 1);
+    }
+
+    #[test]
+    fn decays_hits_of_idle_keys() {
+        let mut tracker = UsageTracker::default();
+        tracker.record("key", 10);
+        for _ in 0..7 {
+            tracker.touch("key");
+        }
+        assert_eq!(tracker.usage("key").unwrap().2, 8);
+
+        let entry = &tracker.entries["key"];
+        let idle = now_millis() - 2 * HIT_DECAY_PERIOD;
+        entry.accessed_at.store(idle, Ordering::Relaxed);
+        assert_eq!(tracker.usage("key").unwrap().2, 2);
+
+        tracker.touch("key");
+        assert_eq!(tracker.usage("key").unwrap().2, 3);
+    }
+
+    #[test]
+    fn samples_tracked_keys() {
+        let mut tracker = UsageTracker::default();
+        for i in 0..100 {
+            tracker.record(&format!("key-{}", i), 10);
+        }
+        for i in 0..50 {
+            tracker.forget(&format!("key-{}", i * 2));
+        }
+
+        let sample = tracker.sample(10);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.iter().all(|key| tracker.entries.contains_key(*key)));
+
+        let mut all = tracker.sample(50);
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), 50);
+
+        for (key, entry) in &tracker.entries {
+            assert_eq!(&tracker.keys[entry.slot], key);
+        }
+    }
+}
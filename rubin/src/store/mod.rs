@@ -5,28 +5,92 @@
 //!
 //! For examples, see [`mem`] and [`persistence`] module documentation.
 
+pub(crate) mod glob;
 pub mod mem;
+mod memory;
 pub mod persistence;
 
-use std::collections::HashMap;
+pub use memory::MemoryUsage;
+
+use std::collections::{BTreeSet, HashMap};
 use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::store::memory::{entry_size, KeyUsage, UsageTracker};
+
+/// Gets the current time as milliseconds since the Unix epoch
+///
+/// Expiry deadlines are stored as wall-clock times so they remain valid when a store is
+/// written to disk and loaded again later.
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
 /// Generic struct representing an inner store of a given type.
 ///
 /// This is intended to be used by the [`mem::MemStore`] as an internal type and is not directly
 /// used.
-#[derive(Default, Serialize, Deserialize)]
+///
+/// Keys can optionally be given an expiry deadline. Expired keys are treated as absent when
+/// accessed and are removed from the store on the next mutable access or when
+/// [`mem::MemStore::purge_expired()`] is called.
+///
+/// The approximate size and access pattern of each entry is tracked so that keys can be
+/// evicted when the [`mem::MemStore`] is over its memory limit.
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredInner<T>", bound(deserialize = "T: DeserializeOwned"))]
 pub struct InnerStore<T> {
     /// Storage for the generic type
     inner: HashMap<String, T>,
+
+    /// Expiry deadlines (milliseconds since the Unix epoch) for keys with a time-to-live
+    #[serde(default)]
+    expiry: HashMap<String, u64>,
+
+    /// Keys with a time-to-live ordered by their deadline, so expired keys can be found
+    /// without checking every key
+    #[serde(skip)]
+    deadlines: BTreeSet<(u64, String)>,
+
+    /// Size and access statistics for each key
+    #[serde(skip)]
+    usage: UsageTracker,
+}
+
+/// Fields of an [`InnerStore`] which are written to disk
+#[derive(Deserialize)]
+struct StoredInner<T> {
+    inner: HashMap<String, T>,
+
+    #[serde(default)]
+    expiry: HashMap<String, u64>,
+}
+
+impl<T> From<StoredInner<T>> for InnerStore<T> {
+    fn from(stored: StoredInner<T>) -> Self {
+        let deadlines = stored
+            .expiry
+            .iter()
+            .map(|(key, deadline)| (*deadline, key.clone()))
+            .collect();
+
+        Self {
+            inner: stored.inner,
+            expiry: stored.expiry,
+            deadlines,
+            usage: UsageTracker::default(),
+        }
+    }
 }
 
 impl<T> InnerStore<T>
 where
-    T: Default + Clone + Serialize + DeserializeOwned,
+    T: Default + Clone + Serialize + DeserializeOwned + MemoryUsage,
 {
     /// Get the total number of items in the store
     pub fn len(&self) -> usize {
@@ -39,7 +103,11 @@ where
     }
 
     /// Insert an item into the store
+    ///
+    /// Any expiry previously set on the key is cleared.
     pub fn insert(&mut self, key: &str, value: T) -> io::Result<()> {
+        self.remove_deadline(key);
+        self.usage.record(key, entry_size(key, &value));
         self.inner.insert(key.to_string(), value);
         Ok(())
     }
@@ -48,32 +116,278 @@ where
     ///
     /// * Returns:
     ///     * The value if it is present
-    ///     * Default value for the type if not (i.e. empty)
-    pub fn retrieve(&self, key: &str) -> io::Result<T> {
-        if let Some(value) = self.inner.get(key) {
-            return Ok(value.clone());
-        }
-
-        Ok(T::default())
+    ///     * `None` if the key is not present or has expired
+    pub fn retrieve(&self, key: &str) -> io::Result<Option<T>> {
+        Ok(self.get(key).cloned())
     }
 
     /// Remove an item from the store and return the removed value
-    pub fn remove(&mut self, key: &str) -> io::Result<T> {
-        if let Some(value) = self.inner.remove(key) {
-            return Ok(value);
-        }
-
-        Ok(T::default())
+    ///
+    /// Returns `None` if the key is not present or has expired.
+    pub fn remove(&mut self, key: &str) -> io::Result<Option<T>> {
+        self.expire_if_needed(key);
+        Ok(self.take(key))
     }
 
     /// Clear all items in the store
     pub fn clear(&mut self) -> io::Result<()> {
         self.inner.clear();
+        self.expiry.clear();
+        self.deadlines.clear();
+        self.usage.clear();
         Ok(())
     }
 
     /// Gets an immutable reference to the inner store type
+    ///
+    /// This is the raw storage and may contain keys which have expired but have not yet
+    /// been purged.
     pub fn get_ref(&self) -> &HashMap<String, T> {
         &self.inner
     }
+
+    /// Gets a reference to the value of a key if it is present and has not expired
+    pub fn get(&self, key: &str) -> Option<&T> {
+        let value = self.peek(key)?;
+        self.usage.touch(key);
+
+        Some(value)
+    }
+
+    /// Gets a mutable reference to the value of a key if it is present and has not expired
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut T> {
+        self.expire_if_needed(key);
+        let value = self.inner.get_mut(key)?;
+        self.usage.touch_mut(key);
+
+        Some(value)
+    }
+
+    /// Gets a mutable reference to the value of a key, inserting the default value if the key
+    /// is not present or has expired
+    pub fn entry(&mut self, key: &str) -> &mut T {
+        self.expire_if_needed(key);
+        self.usage.touch_mut(key);
+        self.inner.entry(key.to_string()).or_default()
+    }
+
+    /// Removes a key and its expiry from the store, returning `true` if the key was present
+    pub fn delete(&mut self, key: &str) -> bool {
+        self.expire_if_needed(key);
+        self.take(key).is_some()
+    }
+
+    /// Moves the value and expiry of a key into another store, replacing any value the other
+    /// store held under the key and returning `false` if the key is not present
+    pub(crate) fn transfer(&mut self, key: &str, target: &mut Self) -> bool {
+        self.expire_if_needed(key);
+        let expiry = self.expiry.get(key).copied();
+        let Some(value) = self.take(key) else {
+            return false;
+        };
+
+        target.take(key);
+        target.usage.record(key, entry_size(key, &value));
+        target.inner.insert(key.to_string(), value);
+        if let Some(deadline) = expiry {
+            target.set_deadline(key, deadline);
+        }
+
+        true
+    }
+
+    /// Gets a reference to the value of a key without recording an access
+    fn peek(&self, key: &str) -> Option<&T> {
+        if self.is_expired(key) {
+            return None;
+        }
+
+        self.inner.get(key)
+    }
+
+    /// Removes a key along with its expiry and usage statistics
+    fn take(&mut self, key: &str) -> Option<T> {
+        self.remove_deadline(key);
+        self.usage.forget(key);
+        self.inner.remove(key)
+    }
+
+    /// Removes a key if its expiry deadline has passed
+    fn expire_if_needed(&mut self, key: &str) {
+        if self.is_expired(key) {
+            self.take(key);
+        }
+    }
+
+    /// Sets the expiry deadline of a key, replacing any previous deadline
+    fn set_deadline(&mut self, key: &str, deadline: u64) {
+        self.remove_deadline(key);
+        self.expiry.insert(key.to_string(), deadline);
+        self.deadlines.insert((deadline, key.to_string()));
+    }
+
+    /// Removes the expiry deadline of a key, returning the deadline if one was set
+    fn remove_deadline(&mut self, key: &str) -> Option<u64> {
+        let deadline = self.expiry.remove(key)?;
+        self.deadlines.remove(&(deadline, key.to_string()));
+
+        Some(deadline)
+    }
+
+    /// Gets the size and access statistics of a key
+    fn key_usage(&self, key: &str) -> Option<KeyUsage> {
+        let (size, last_access, hits) = self.usage.usage(key)?;
+        Some(KeyUsage {
+            key: key.to_string(),
+            size,
+            last_access,
+            hits,
+            expiry: self.expiry.get(key).copied(),
+        })
+    }
+
+    /// Checks if a key has an expiry deadline which has passed
+    fn is_expired(&self, key: &str) -> bool {
+        self.expiry
+            .get(key)
+            .is_some_and(|deadline| *deadline <= now_millis())
+    }
+}
+
+/// Operations on the keys of an [`InnerStore`] that do not depend on the type of value held.
+///
+/// Used by the [`mem::MemStore`] to perform operations across all of its inner stores.
+pub(crate) trait KeyedStore {
+    /// Checks if a key is present and has not expired
+    fn contains_key(&self, key: &str) -> bool;
+
+    /// Removes a key from the store, returning `true` if the key was present
+    fn delete_key(&mut self, key: &str) -> bool;
+
+    /// Moves the value and expiry of a key to a new key, replacing any value already under
+    /// the new key and returning `false` if the key is not present
+    fn rename_key(&mut self, key: &str, new_key: &str) -> bool;
+
+    /// Gets every key in the store which has not expired
+    fn keys(&self) -> Box<dyn Iterator<Item = &str> + '_>;
+
+    /// Sets the expiry deadline (milliseconds since the Unix epoch) of a key,
+    /// returning `false` if the key is not present
+    fn set_expiry(&mut self, key: &str, deadline: u64) -> bool;
+
+    /// Gets the expiry deadline (milliseconds since the Unix epoch) of a key, if one is set
+    fn expiry(&self, key: &str) -> Option<u64>;
+
+    /// Removes the expiry of a key, returning `true` if an expiry was removed
+    fn clear_expiry(&mut self, key: &str) -> bool;
+
+    /// Removes up to `limit` keys whose expiry deadline has passed, those which expired
+    /// first being removed first, returning the number removed
+    fn purge_expired(&mut self, limit: usize) -> usize;
+
+    /// Gets the approximate number of bytes used by the store
+    fn used_memory(&mut self) -> usize;
+
+    /// Gets the usage statistics of up to `count` keys picked at random, or of every key if
+    /// there are no more than `count`
+    fn sample_usage(&self, count: usize) -> Vec<KeyUsage>;
+
+    /// Gets the usage statistics of the key with the earliest expiry deadline
+    fn soonest_expiring(&self) -> Option<KeyUsage>;
+}
+
+impl<T> KeyedStore for InnerStore<T>
+where
+    T: Default + Clone + Serialize + DeserializeOwned + MemoryUsage,
+{
+    fn contains_key(&self, key: &str) -> bool {
+        self.peek(key).is_some()
+    }
+
+    fn delete_key(&mut self, key: &str) -> bool {
+        self.delete(key)
+    }
+
+    fn rename_key(&mut self, key: &str, new_key: &str) -> bool {
+        self.expire_if_needed(key);
+        let expiry = self.expiry.get(key).copied();
+        let Some(value) = self.take(key) else {
+            return false;
+        };
+
+        self.take(new_key);
+        self.usage.record(new_key, entry_size(new_key, &value));
+        self.inner.insert(new_key.to_string(), value);
+        if let Some(deadline) = expiry {
+            self.set_deadline(new_key, deadline);
+        }
+
+        true
+    }
+
+    fn keys(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(
+            self.inner
+                .keys()
+                .filter(|key| !self.is_expired(key))
+                .map(|key| key.as_str()),
+        )
+    }
+
+    fn set_expiry(&mut self, key: &str, deadline: u64) -> bool {
+        self.expire_if_needed(key);
+        if !self.inner.contains_key(key) {
+            return false;
+        }
+
+        self.set_deadline(key, deadline);
+        true
+    }
+
+    fn expiry(&self, key: &str) -> Option<u64> {
+        if !self.contains_key(key) {
+            return None;
+        }
+
+        self.expiry.get(key).copied()
+    }
+
+    fn clear_expiry(&mut self, key: &str) -> bool {
+        self.expire_if_needed(key);
+        self.remove_deadline(key).is_some()
+    }
+
+    fn purge_expired(&mut self, limit: usize) -> usize {
+        let now = now_millis();
+        let mut removed = 0;
+        while removed < limit {
+            match self.deadlines.first() {
+                Some((deadline, key)) if *deadline <= now => {
+                    let key = key.clone();
+                    self.take(&key);
+                    removed += 1;
+                }
+                _ => break,
+            }
+        }
+
+        removed
+    }
+
+    fn used_memory(&mut self) -> usize {
+        self.usage.refresh(&self.inner)
+    }
+
+    fn sample_usage(&self, count: usize) -> Vec<KeyUsage> {
+        self.usage
+            .sample(count)
+            .into_iter()
+            .filter_map(|key| self.key_usage(key))
+            .collect()
+    }
+
+    fn soonest_expiring(&self) -> Option<KeyUsage> {
+        let (_, key) = self.deadlines.first()?;
+        self.key_usage(key)
+    }
 }
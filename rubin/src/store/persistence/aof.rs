@@ -0,0 +1,853 @@
+//! Append-only log of the updates made to a [`super::PersistentStore`]
+//!
+//! Rather than writing out the whole store after every update, each update can be appended
+//! to a log kept next to the store file. When the store is loaded, the log is replayed on top
+//! of the store file so no logged update is lost.
+//!
+//! The log always starts with a record of the full contents of the store, written when the
+//! log is enabled or rewritten. Rewriting replaces a log which has grown with a record of the
+//! current contents. It runs in the background while updates continue to be logged, and the
+//! new log is swapped in once it is complete.
+//!
+//! Each record is a line of JSON. How often the log is synced to disk is controlled by the
+//! [`FsyncPolicy`].
+
+use serde::{Deserialize, Serialize};
+
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::store::mem::{BitFieldOp, BitOp, MemStore, StreamId, StreamTrim, TypedValue};
+
+/// Size the log must reach before it is rewritten automatically
+const AUTO_REWRITE_MIN_SIZE: u64 = 64 * 1024 * 1024;
+
+/// How often the append-only log is synced to disk
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FsyncPolicy {
+    /// Sync after every update, so no logged update can be lost
+    Always,
+
+    /// Sync once per second in the background, so at most a second of updates can be lost
+    #[default]
+    EverySecond,
+
+    /// Never sync, leaving it to the operating system
+    Never,
+}
+
+impl FromStr for FsyncPolicy {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "always" => Ok(Self::Always),
+            "everysec" => Ok(Self::EverySecond),
+            "no" => Ok(Self::Never),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown fsync policy: {}", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FsyncPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::EverySecond => write!(f, "everysec"),
+            Self::Never => write!(f, "no"),
+        }
+    }
+}
+
+/// An update recorded in the log, named after the [`MemStore`] operation which made it
+///
+/// Updates which depend on the time they are made are recorded with their outcome, such as
+/// the ID given to a stream entry or the deadline of an expiry, so replaying them gives the
+/// same result.
+#[derive(Serialize, Deserialize)]
+pub(crate) enum Command {
+    /// Replace the contents of the store
+    Restore(Box<MemStore>),
+
+    /// Change how often the log is synced to disk, leaving the store as it is
+    SetPolicy(FsyncPolicy),
+
+    /// Move every key of another store into the store, along with their expiry
+    Import(Box<MemStore>),
+
+    InsertString(String, String),
+    InsertStringWithExpiry(String, String, Option<u64>),
+    RemoveString(String),
+    ClearStrings,
+    IncrBy(String, isize),
+    DecrBy(String, isize),
+    IncrByFloat(String, f64),
+    GetSet(String, isize),
+
+    /// Set the expiry deadline of a key, or clear it if `None`
+    SetExpiry(String, Option<u64>),
+
+    InsertBytes(String, Vec<u8>),
+    SetBit(String, usize, bool),
+    BitOp(BitOp, String, Vec<String>),
+    BitField(String, Vec<BitFieldOp>),
+    GeoAdd(String, Vec<(f64, f64, String)>),
+    HSet(String, Vec<(String, String)>),
+    HDel(String, Vec<String>),
+    HIncrBy(String, String, isize),
+    PfAdd(String, Vec<String>),
+    PfMerge(String, Vec<String>),
+    JsonSet(String, String, String),
+    JsonDel(String, String),
+    JsonArrAppend(String, String, Vec<String>),
+    JsonNumIncrBy(String, String, f64),
+    Del(Vec<String>),
+    Rename(String, String),
+    RenameNx(String, String),
+    Flush,
+    LPush(String, Vec<String>),
+    RPush(String, Vec<String>),
+    LPop(String),
+    RPop(String),
+    LSet(String, isize, String),
+    LTrim(String, isize, isize),
+    LRem(String, isize, String),
+    SAdd(String, Vec<String>),
+    SRem(String, Vec<String>),
+    SUnionStore(String, Vec<String>),
+    SInterStore(String, Vec<String>),
+    SDiffStore(String, Vec<String>),
+    XAdd(String, StreamId, Vec<(String, String)>),
+    XTrim(String, StreamTrim),
+    XGroupCreate(String, String, Option<StreamId>),
+    XGroupDestroy(String, String),
+    XReadGroup(String, String, String, Option<StreamId>, Option<usize>),
+    XAck(String, String, Vec<StreamId>),
+
+    /// Claim the entries which were claimed, whatever their idle time when replayed
+    XClaim(String, String, String, Vec<StreamId>),
+
+    Append(String, String),
+    SetRange(String, usize, String),
+    SetNx(String, String),
+    GetDel(String),
+    MSet(Vec<(String, String)>),
+    MSetNx(Vec<(String, String)>),
+    InsertTyped(String, TypedValue),
+    ZAdd(String, Vec<(f64, String)>),
+    ZRem(String, Vec<String>),
+    ZIncrBy(String, f64, String),
+}
+
+/// Copies borrowed values so they can be recorded
+pub(crate) fn owned(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+/// Copies borrowed pairs so they can be recorded
+pub(crate) fn owned_pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(first, second)| (first.to_string(), second.to_string()))
+        .collect()
+}
+
+/// Borrows recorded values to pass them to the store
+fn borrowed(values: &[String]) -> Vec<&str> {
+    values.iter().map(String::as_str).collect()
+}
+
+/// Borrows recorded pairs to pass them to the store
+fn borrowed_pairs(pairs: &[(String, String)]) -> Vec<(&str, &str)> {
+    pairs
+        .iter()
+        .map(|(first, second)| (first.as_str(), second.as_str()))
+        .collect()
+}
+
+impl Command {
+    /// Makes the recorded update to a store
+    fn apply(self, store: &mut MemStore) -> io::Result<()> {
+        match self {
+            Self::Restore(vault) => {
+                *store = MemStore {
+                    max_memory: store.max_memory,
+                    eviction_policy: store.eviction_policy,
+                    ..*vault
+                };
+            }
+            Self::SetPolicy(_) => {}
+            Self::Import(mut vault) => {
+                for key in vault.keys("*")? {
+                    vault.move_key(&key, store)?;
+                }
+            }
+            Self::InsertString(key, value) => store.insert_string(&key, &value)?,
+            Self::InsertStringWithExpiry(key, value, deadline) => {
+                store.insert_string(&key, &value)?;
+                if let Some(deadline) = deadline {
+                    store.expire_at(&key, deadline);
+                }
+            }
+            Self::RemoveString(key) => {
+                store.remove_string(&key)?;
+            }
+            Self::ClearStrings => store.clear_strings()?,
+            Self::IncrBy(key, increment) => {
+                store.incrby(key, increment)?;
+            }
+            Self::DecrBy(key, decrement) => {
+                store.decrby(key, decrement)?;
+            }
+            Self::IncrByFloat(key, increment) => {
+                store.incrbyfloat(&key, increment)?;
+            }
+            Self::GetSet(key, value) => {
+                store.getset(&key, value)?;
+            }
+            Self::SetExpiry(key, Some(deadline)) => {
+                store.expire_at(&key, deadline);
+            }
+            Self::SetExpiry(key, None) => {
+                store.persist(&key)?;
+            }
+            Self::InsertBytes(key, value) => store.insert_bytes(&key, &value)?,
+            Self::SetBit(key, offset, value) => {
+                store.setbit(&key, offset, value)?;
+            }
+            Self::BitOp(op, destination, keys) => {
+                store.bitop(op, &destination, &borrowed(&keys))?;
+            }
+            Self::BitField(key, ops) => {
+                store.bitfield(&key, &ops)?;
+            }
+            Self::GeoAdd(key, members) => {
+                let members: Vec<(f64, f64, &str)> = members
+                    .iter()
+                    .map(|(longitude, latitude, member)| (*longitude, *latitude, member.as_str()))
+                    .collect();
+                store.geoadd(&key, &members)?;
+            }
+            Self::HSet(key, fields) => {
+                store.hset(&key, &borrowed_pairs(&fields))?;
+            }
+            Self::HDel(key, fields) => {
+                store.hdel(&key, &borrowed(&fields))?;
+            }
+            Self::HIncrBy(key, field, increment) => {
+                store.hincrby(&key, &field, increment)?;
+            }
+            Self::PfAdd(key, elements) => {
+                store.pfadd(&key, &borrowed(&elements))?;
+            }
+            Self::PfMerge(destination, sources) => {
+                store.pfmerge(&destination, &borrowed(&sources))?;
+            }
+            Self::JsonSet(key, path, value) => {
+                store.json_set(&key, &path, &value)?;
+            }
+            Self::JsonDel(key, path) => {
+                store.json_del(&key, &path)?;
+            }
+            Self::JsonArrAppend(key, path, values) => {
+                store.json_arrappend(&key, &path, &borrowed(&values))?;
+            }
+            Self::JsonNumIncrBy(key, path, increment) => {
+                store.json_numincrby(&key, &path, increment)?;
+            }
+            Self::Del(keys) => {
+                store.del(&borrowed(&keys))?;
+            }
+            Self::Rename(key, new_key) => store.rename(&key, &new_key)?,
+            Self::RenameNx(key, new_key) => {
+                store.renamenx(&key, &new_key)?;
+            }
+            Self::Flush => store.flush(),
+            Self::LPush(key, values) => {
+                store.lpush(&key, &borrowed(&values))?;
+            }
+            Self::RPush(key, values) => {
+                store.rpush(&key, &borrowed(&values))?;
+            }
+            Self::LPop(key) => {
+                store.lpop(&key)?;
+            }
+            Self::RPop(key) => {
+                store.rpop(&key)?;
+            }
+            Self::LSet(key, index, value) => store.lset(&key, index, &value)?,
+            Self::LTrim(key, start, stop) => store.ltrim(&key, start, stop)?,
+            Self::LRem(key, count, value) => {
+                store.lrem(&key, count, &value)?;
+            }
+            Self::SAdd(key, members) => {
+                store.sadd(&key, &borrowed(&members))?;
+            }
+            Self::SRem(key, members) => {
+                store.srem(&key, &borrowed(&members))?;
+            }
+            Self::SUnionStore(destination, keys) => {
+                store.sunionstore(&destination, &borrowed(&keys))?;
+            }
+            Self::SInterStore(destination, keys) => {
+                store.sinterstore(&destination, &borrowed(&keys))?;
+            }
+            Self::SDiffStore(destination, keys) => {
+                store.sdiffstore(&destination, &borrowed(&keys))?;
+            }
+            Self::XAdd(key, id, fields) => {
+                store.xadd(&key, Some(id), &borrowed_pairs(&fields))?;
+            }
+            Self::XTrim(key, trim) => {
+                store.xtrim(&key, trim)?;
+            }
+            Self::XGroupCreate(key, group, last_delivered) => {
+                store.xgroup_create(&key, &group, last_delivered)?
+            }
+            Self::XGroupDestroy(key, group) => {
+                store.xgroup_destroy(&key, &group)?;
+            }
+            Self::XReadGroup(key, group, consumer, after, count) => {
+                store.xreadgroup(&key, &group, &consumer, after, count)?;
+            }
+            Self::XAck(key, group, ids) => {
+                store.xack(&key, &group, &ids)?;
+            }
+            Self::XClaim(key, group, consumer, ids) => {
+                store.xclaim(&key, &group, &consumer, 0, &ids)?;
+            }
+            Self::Append(key, value) => {
+                store.append(&key, &value)?;
+            }
+            Self::SetRange(key, offset, value) => {
+                store.setrange(&key, offset, &value)?;
+            }
+            Self::SetNx(key, value) => {
+                store.setnx(&key, &value)?;
+            }
+            Self::GetDel(key) => {
+                store.getdel(&key)?;
+            }
+            Self::MSet(pairs) => store.mset(&borrowed_pairs(&pairs))?,
+            Self::MSetNx(pairs) => {
+                store.msetnx(&borrowed_pairs(&pairs))?;
+            }
+            Self::InsertTyped(key, typed) => store.insert_tagged(&key, typed)?,
+            Self::ZAdd(key, members) => {
+                let members: Vec<(f64, &str)> = members
+                    .iter()
+                    .map(|(score, member)| (*score, member.as_str()))
+                    .collect();
+                store.zadd(&key, &members)?;
+            }
+            Self::ZRem(key, members) => {
+                store.zrem(&key, &borrowed(&members))?;
+            }
+            Self::ZIncrBy(key, increment, member) => {
+                store.zincrby(&key, increment, &member)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Path of the log kept next to a store file
+pub(crate) fn log_path(store_path: &Path) -> PathBuf {
+    let mut path = OsString::from(store_path);
+    path.push(".aof");
+    path.into()
+}
+
+/// Path a log is written to while it is being rewritten
+fn rewrite_path(log_path: &Path) -> PathBuf {
+    let mut path = OsString::from(log_path);
+    path.push(".rewrite");
+    path.into()
+}
+
+/// Serializes a record of the full contents of a store, followed by a record of the fsync
+/// policy of the log so it is kept when the log is opened again
+fn restore_record(store: &MemStore, policy: FsyncPolicy) -> io::Result<Vec<u8>> {
+    // Borrows the store rather than moving it into a `Command::Restore`
+    #[derive(Serialize)]
+    enum Record<'a> {
+        Restore(&'a MemStore),
+    }
+
+    let mut record = serde_json::to_vec(&Record::Restore(store))?;
+    record.push(b'\n');
+    serde_json::to_writer(&mut record, &Command::SetPolicy(policy))?;
+    record.push(b'\n');
+
+    Ok(record)
+}
+
+/// Writes the start of a new log and syncs it, ready to be renamed over the current log
+fn write_rewrite(path: &Path, contents: &[u8]) -> io::Result<File> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_data()?;
+
+    Ok(file)
+}
+
+/// A log file, shared with the task which syncs it in the background
+struct LogFile {
+    file: File,
+
+    /// Whether updates have been written since the file was last synced
+    dirty: AtomicBool,
+}
+
+/// Syncs a log file once per second while updates are being written to it
+fn spawn_sync(log: Arc<LogFile>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            if !log.dirty.swap(false, Ordering::AcqRel) {
+                continue;
+            }
+
+            let log = Arc::clone(&log);
+            if let Ok(Err(e)) = tokio::task::spawn_blocking(move || log.file.sync_data()).await {
+                tracing::error!("failed to sync append-only log - {}", e);
+            }
+        }
+    })
+}
+
+/// A rewrite of the log running in the background
+struct Rewrite {
+    /// Task writing the start of the new log
+    task: JoinHandle<io::Result<File>>,
+
+    /// Updates logged since the rewrite started, to be appended to the new log
+    buffer: Vec<u8>,
+}
+
+/// Append-only log of the updates made to a store
+pub(crate) struct AppendOnlyLog {
+    path: PathBuf,
+    log: Arc<LogFile>,
+    policy: FsyncPolicy,
+
+    /// Task syncing the log when the policy is [`FsyncPolicy::EverySecond`]
+    sync_task: Option<JoinHandle<()>>,
+
+    /// Current size of the log in bytes
+    size: u64,
+
+    /// Size of the log in bytes when it was last rewritten
+    base_size: u64,
+
+    rewrite: Option<Rewrite>,
+}
+
+impl AppendOnlyLog {
+    /// Starts a new log holding the contents of a store, replacing any existing log
+    pub(crate) fn create(path: PathBuf, policy: FsyncPolicy, store: &MemStore) -> io::Result<Self> {
+        let record = restore_record(store, policy)?;
+        let file = write_rewrite(&rewrite_path(&path), &record)?;
+        fs::rename(rewrite_path(&path), &path)?;
+
+        Ok(Self::with_file(path, file, policy, record.len() as u64))
+    }
+
+    /// Replays an existing log into a store and opens it to log further updates
+    ///
+    /// The log keeps the fsync policy it was last given, or [`FsyncPolicy::default()`] if it
+    /// has none. A final record which was only partly written, as happens if the process
+    /// stops while writing it, is discarded.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if a complete record cannot be read or cannot be
+    ///   applied to the replayed store
+    pub(crate) fn open(path: PathBuf, store: &mut MemStore) -> io::Result<Self> {
+        let contents = fs::read(&path)?;
+        let complete = contents
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |end| end + 1);
+
+        let mut policy = FsyncPolicy::default();
+        let lines = contents[..complete].split(|byte| *byte == b'\n');
+        for (number, line) in lines.enumerate().filter(|(_, line)| !line.is_empty()) {
+            let command: Command = serde_json::from_slice(line)?;
+            if let Command::SetPolicy(logged) = command {
+                policy = logged;
+            }
+
+            command.apply(store).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "unable to replay record {} of append-only log {}: {}",
+                        number + 1,
+                        path.display(),
+                        e
+                    ),
+                )
+            })?;
+        }
+
+        let file = OpenOptions::new().append(true).open(&path)?;
+        file.set_len(complete as u64)?;
+
+        Ok(Self::with_file(path, file, policy, complete as u64))
+    }
+
+    fn with_file(path: PathBuf, file: File, policy: FsyncPolicy, size: u64) -> Self {
+        let mut aof = Self {
+            path,
+            log: Arc::new(LogFile {
+                file,
+                dirty: AtomicBool::new(false),
+            }),
+            policy,
+            sync_task: None,
+            size,
+            base_size: size,
+            rewrite: None,
+        };
+        aof.restart_sync();
+
+        aof
+    }
+
+    /// Starts syncing the current log file in the background if the policy needs it
+    fn restart_sync(&mut self) {
+        if let Some(task) = self.sync_task.take() {
+            task.abort();
+        }
+
+        if self.policy == FsyncPolicy::EverySecond {
+            self.sync_task = Some(spawn_sync(Arc::clone(&self.log)));
+        }
+    }
+
+    /// Changes how often the log is synced to disk, recording the change in the log
+    pub(crate) async fn set_policy(&mut self, policy: FsyncPolicy) -> io::Result<()> {
+        self.append(&Command::SetPolicy(policy)).await?;
+        self.policy = policy;
+        self.restart_sync();
+
+        Ok(())
+    }
+
+    /// Appends an update to the log, writing it without blocking the runtime
+    pub(crate) async fn append(&mut self, command: &Command) -> io::Result<()> {
+        let mut line = serde_json::to_vec(command)?;
+        line.push(b'\n');
+
+        let log = Arc::clone(&self.log);
+        let policy = self.policy;
+        let line = tokio::task::spawn_blocking(move || {
+            (&log.file).write_all(&line)?;
+            match policy {
+                FsyncPolicy::Always => log.file.sync_data()?,
+                FsyncPolicy::EverySecond => log.dirty.store(true, Ordering::Release),
+                FsyncPolicy::Never => {}
+            }
+
+            Ok::<_, io::Error>(line)
+        })
+        .await
+        .unwrap_or_else(|e| Err(io::Error::other(e)))?;
+        self.size += line.len() as u64;
+
+        if let Some(rewrite) = &mut self.rewrite {
+            rewrite.buffer.extend_from_slice(&line);
+            if rewrite.task.is_finished() {
+                self.finish_rewrite().await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks if the log has doubled in size since it was last rewritten
+    pub(crate) fn should_rewrite(&self) -> bool {
+        self.rewrite.is_none()
+            && self.size >= AUTO_REWRITE_MIN_SIZE
+            && self.size >= self.base_size.saturating_mul(2)
+    }
+
+    /// Starts rewriting the log in the background from the contents of a store
+    ///
+    /// The store is copied as it is now, then serialized and written by a separate task so
+    /// the caller is not held up while the new log is written.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the rewrite was started, `false` if one is already running
+    pub(crate) fn rewrite(&mut self, store: &MemStore) -> io::Result<bool> {
+        if self.rewrite.is_some() {
+            return Ok(false);
+        }
+
+        let snapshot = store.clone();
+        let policy = self.policy;
+        let path = rewrite_path(&self.path);
+        let task = tokio::task::spawn_blocking(move || {
+            write_rewrite(&path, &restore_record(&snapshot, policy)?)
+        });
+
+        self.rewrite = Some(Rewrite {
+            task,
+            buffer: Vec::new(),
+        });
+
+        Ok(true)
+    }
+
+    /// Waits for a running rewrite and swaps the new log in place of the current one
+    async fn finish_rewrite(&mut self) -> io::Result<()> {
+        let Some(rewrite) = self.rewrite.take() else {
+            return Ok(());
+        };
+
+        let temporary = rewrite_path(&self.path);
+        let result = match rewrite.task.await {
+            Ok(result) => result,
+            Err(e) => Err(io::Error::other(e)),
+        };
+
+        let mut file = match result {
+            Ok(file) => file,
+            Err(e) => {
+                let _ = fs::remove_file(&temporary);
+                return Err(e);
+            }
+        };
+
+        let path = self.path.clone();
+        let file = tokio::task::spawn_blocking(move || {
+            file.write_all(&rewrite.buffer)?;
+            file.sync_data()?;
+            fs::rename(&temporary, &path)?;
+
+            Ok::<_, io::Error>(file)
+        })
+        .await
+        .unwrap_or_else(|e| Err(io::Error::other(e)))?;
+
+        self.size = file.metadata()?.len();
+        self.base_size = self.size;
+        self.log = Arc::new(LogFile {
+            file,
+            dirty: AtomicBool::new(false),
+        });
+        self.restart_sync();
+
+        Ok(())
+    }
+
+    /// Waits for a running rewrite to finish and syncs the log to disk
+    pub(crate) async fn sync(&mut self) -> io::Result<()> {
+        self.finish_rewrite().await?;
+
+        let log = Arc::clone(&self.log);
+        tokio::task::spawn_blocking(move || log.file.sync_data())
+            .await
+            .unwrap_or_else(|e| Err(io::Error::other(e)))?;
+        self.log.dirty.store(false, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Deletes the log, abandoning a running rewrite
+    pub(crate) async fn remove(mut self) -> io::Result<()> {
+        if let Some(rewrite) = self.rewrite.take() {
+            let _ = rewrite.task.await;
+            let _ = fs::remove_file(rewrite_path(&self.path));
+        }
+
+        fs::remove_file(&self.path)
+    }
+}
+
+impl Drop for AppendOnlyLog {
+    fn drop(&mut self) {
+        if let Some(task) = self.sync_task.take() {
+            task.abort();
+        }
+
+        // Sync anything the background task has not got to yet
+        if self.log.dirty.load(Ordering::Acquire) {
+            let _ = self.log.file.sync_data();
+        }
+    }
+}
+
+#[cfg(test)]
+mod aof_tests {
+    use super::*;
+    use crate::store::now_millis;
+    use tempdir::TempDir;
+
+    #[tokio::test]
+    async fn replays_logged_updates() -> io::Result<()> {
+        let td = TempDir::new("aof")?;
+        let path = td.path().join("rubinstore.json.aof");
+
+        let mut ms = MemStore::new();
+        ms.insert_string("before", "value")?;
+        let mut aof = AppendOnlyLog::create(path.clone(), FsyncPolicy::Always, &ms)?;
+
+        ms.incr("counter")?;
+        aof.append(&Command::IncrBy("counter".into(), 1)).await?;
+        aof.append(&Command::RPush("list".into(), owned(&["a", "b"])))
+            .await?;
+        let deadline = now_millis() + 60_000;
+        aof.append(&Command::SetExpiry("list".into(), Some(deadline)))
+            .await?;
+        drop(aof);
+
+        let mut loaded = MemStore::new();
+        AppendOnlyLog::open(path, &mut loaded)?;
+
+        assert_eq!(loaded.get_string("before")?.as_deref(), Some("value"));
+        assert_eq!(loaded.counters.retrieve("counter")?, Some(1));
+        assert_eq!(loaded.lrange("list", 0, -1)?, vec!["a", "b"]);
+        assert!(loaded.ttl("list")? > 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn discards_partly_written_records() -> io::Result<()> {
+        let td = TempDir::new("aof")?;
+        let path = td.path().join("rubinstore.json.aof");
+
+        let mut aof = AppendOnlyLog::create(path.clone(), FsyncPolicy::Never, &MemStore::new())?;
+        aof.append(&Command::IncrBy("counter".into(), 5)).await?;
+        drop(aof);
+
+        let mut file = OpenOptions::new().append(true).open(&path)?;
+        file.write_all(br#"{"IncrBy":["coun"#)?;
+
+        let mut loaded = MemStore::new();
+        let mut aof = AppendOnlyLog::open(path.clone(), &mut loaded)?;
+        assert_eq!(loaded.counters.retrieve("counter")?, Some(5));
+
+        aof.append(&Command::IncrBy("counter".into(), 1)).await?;
+        drop(aof);
+
+        let mut loaded = MemStore::new();
+        AppendOnlyLog::open(path, &mut loaded)?;
+        assert_eq!(loaded.counters.retrieve("counter")?, Some(6));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fails_on_records_which_cannot_be_applied() -> io::Result<()> {
+        let td = TempDir::new("aof")?;
+        let path = td.path().join("rubinstore.json.aof");
+
+        let mut aof = AppendOnlyLog::create(path.clone(), FsyncPolicy::Never, &MemStore::new())?;
+        aof.append(&Command::InsertString("key".into(), "value".into()))
+            .await?;
+        aof.append(&Command::RPush("key".into(), owned(&["a"])))
+            .await?;
+        aof.append(&Command::IncrBy("counter".into(), 2)).await?;
+        drop(aof);
+
+        let err = AppendOnlyLog::open(path, &mut MemStore::new())
+            .err()
+            .expect("replaying a record which cannot be applied should fail");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rewrites_in_the_background() -> io::Result<()> {
+        let td = TempDir::new("aof")?;
+        let path = td.path().join("rubinstore.json.aof");
+
+        let mut ms = MemStore::new();
+        let mut aof = AppendOnlyLog::create(path.clone(), FsyncPolicy::EverySecond, &ms)?;
+        for _ in 0..100 {
+            ms.incr("counter")?;
+            aof.append(&Command::IncrBy("counter".into(), 1)).await?;
+        }
+        let before = fs::metadata(&path)?.len();
+
+        assert!(aof.rewrite(&ms)?);
+        assert!(!aof.rewrite(&ms)?);
+
+        // Updates made while rewriting are kept in the new log
+        ms.incr("counter")?;
+        aof.append(&Command::IncrBy("counter".into(), 1)).await?;
+        aof.sync().await?;
+
+        assert!(fs::metadata(&path)?.len() < before);
+        assert!(!rewrite_path(&path).exists());
+        drop(aof);
+
+        let mut loaded = MemStore::new();
+        AppendOnlyLog::open(path, &mut loaded)?;
+        assert_eq!(loaded.counters.retrieve("counter")?, Some(101));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_the_fsync_policy_when_opened() -> io::Result<()> {
+        let td = TempDir::new("aof")?;
+        let path = td.path().join("rubinstore.json.aof");
+
+        let aof = AppendOnlyLog::create(path.clone(), FsyncPolicy::Always, &MemStore::new())?;
+        drop(aof);
+
+        let mut aof = AppendOnlyLog::open(path.clone(), &mut MemStore::new())?;
+        assert_eq!(aof.policy, FsyncPolicy::Always);
+
+        aof.set_policy(FsyncPolicy::Never).await?;
+        drop(aof);
+
+        let mut aof = AppendOnlyLog::open(path.clone(), &mut MemStore::new())?;
+        assert_eq!(aof.policy, FsyncPolicy::Never);
+
+        // The policy is part of the new log after a rewrite
+        aof.rewrite(&MemStore::new())?;
+        aof.sync().await?;
+        drop(aof);
+
+        let aof = AppendOnlyLog::open(path, &mut MemStore::new())?;
+        assert_eq!(aof.policy, FsyncPolicy::Never);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_fsync_policies() -> io::Result<()> {
+        for policy in [
+            FsyncPolicy::Always,
+            FsyncPolicy::EverySecond,
+            FsyncPolicy::Never,
+        ] {
+            assert_eq!(policy.to_string().parse::<FsyncPolicy>()?, policy);
+        }
+
+        assert!("sometimes".parse::<FsyncPolicy>().is_err());
+
+        Ok(())
+    }
+}
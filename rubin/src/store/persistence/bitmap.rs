@@ -0,0 +1,191 @@
+//! Binary string and bitmap operations for the [`PersistentStore`]
+//!
+//! Wraps the binary string and bitmap operations of the [`crate::store::mem::MemStore`],
+//! logging each update if the append-only log is enabled and writing to disk after each
+//! update if `write_on_update` is set.
+
+use std::io;
+
+use crate::store::mem::{BitFieldOp, BitOp};
+use crate::store::persistence::aof::{owned, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Inserts a binary value into the string store with a given key
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_bytes("image:1000", &[0x89, 0x50, 0x4e, 0x47]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn insert_bytes(&mut self, key: &str, value: &[u8]) -> io::Result<()> {
+        self.store.insert_bytes(key, value)?;
+        self.updated(|_| Command::InsertBytes(key.into(), value.to_vec()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves the raw bytes of a value from the string store
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_bytes("image:1000", &[0x89, 0x50]).await?;
+    ///
+    ///     assert_eq!(ps.get_bytes("image:1000")?, Some(vec![0x89, 0x50]));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_bytes(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        self.store.get_bytes(key)
+    }
+
+    /// Sets or clears the bit at an offset in a string, returning the previous bit
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.setbit("active:2024-01-01", 1000, true).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn setbit(&mut self, key: &str, offset: usize, value: bool) -> io::Result<bool> {
+        let result = self.store.setbit(key, offset, value)?;
+        self.updated(|_| Command::SetBit(key.into(), offset, value))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Gets the bit at an offset in a string
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.setbit("active:2024-01-01", 1000, true).await?;
+    ///
+    ///     assert!(ps.getbit("active:2024-01-01", 1000)?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn getbit(&self, key: &str, offset: usize) -> io::Result<bool> {
+        self.store.getbit(key, offset)
+    }
+
+    /// Counts the set bits in a string, optionally within an inclusive range of bytes
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("key", "foobar").await?;
+    ///
+    ///     assert_eq!(ps.bitcount("key", None)?, 26);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn bitcount(&self, key: &str, range: Option<(isize, isize)>) -> io::Result<usize> {
+        self.store.bitcount(key, range)
+    }
+
+    /// Finds the position of the first bit set to `bit` in a string, optionally within an
+    /// inclusive range of bytes
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.setbit("key", 12, true).await?;
+    ///
+    ///     assert_eq!(ps.bitpos("key", true, None, None)?, Some(12));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn bitpos(
+        &self,
+        key: &str,
+        bit: bool,
+        start: Option<isize>,
+        end: Option<isize>,
+    ) -> io::Result<Option<usize>> {
+        self.store.bitpos(key, bit, start, end)
+    }
+
+    /// Performs a bitwise operation between strings, storing the result in `destination`
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::{mem::BitOp, persistence::PersistentStore};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.bitop(BitOp::Or, "active:week", &["active:monday", "active:tuesday"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bitop(
+        &mut self,
+        op: BitOp,
+        destination: &str,
+        keys: &[&str],
+    ) -> io::Result<usize> {
+        let result = self.store.bitop(op, destination, keys)?;
+        self.updated(|_| Command::BitOp(op, destination.into(), owned(keys)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Reads, writes and increments integer fields at bit offsets in a string
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::{mem::BitFieldOp, persistence::PersistentStore};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let ops = BitFieldOp::parse(&["INCRBY", "u8", "#0", "1"])?;
+    ///
+    ///     assert_eq!(ps.bitfield("counters", &ops).await?, vec![Some(1)]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bitfield(
+        &mut self,
+        key: &str,
+        ops: &[BitFieldOp],
+    ) -> io::Result<Vec<Option<i64>>> {
+        let result = self.store.bitfield(key, ops)?;
+        self.updated(|_| Command::BitField(key.into(), ops.to_vec()))
+            .await?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,174 @@
+//! Optional compression of store files
+//!
+//! Store files are mostly made up of repeated keys and field names, which compress well.
+//! Compression is applied on top of the [`super::StoreFormat`] of a store, so either format
+//! can be compressed. Compressed files are detected by their magic bytes when loaded, so
+//! compressed and uncompressed files can be read back regardless of the compression being
+//! written.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+/// Magic bytes at the start of every zstd frame
+const ZSTD_MAGIC: &[u8; 4] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression level used for zstd, the default level of the zstd library
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compression applied to a store file
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Written as is
+    #[default]
+    None,
+
+    /// Compressed as a zstd frame with a checksum of the contents
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression of the contents of a store file
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::persistence::Compression;
+    ///
+    /// let compressed = Compression::Zstd.compress(b"{}").unwrap();
+    ///
+    /// assert_eq!(Compression::detect(&compressed), Compression::Zstd);
+    /// assert_eq!(Compression::detect(b"{}"), Compression::None);
+    /// ```
+    pub fn detect(contents: &[u8]) -> Self {
+        if contents.starts_with(ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+
+    /// Compresses the contents of a store file
+    pub fn compress(self, contents: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(contents.to_vec()),
+            Self::Zstd => {
+                let mut encoder = zstd::Encoder::new(Vec::new(), ZSTD_LEVEL)?;
+                encoder.include_checksum(true)?;
+                encoder.write_all(contents)?;
+                encoder.finish()
+            }
+        }
+    }
+
+    /// Decompresses the contents of a store file, detecting the compression
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the contents are compressed but are truncated or
+    ///   fail their checksum
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::persistence::Compression;
+    ///
+    /// let compressed = Compression::Zstd.compress(b"{}").unwrap();
+    ///
+    /// assert_eq!(Compression::decompress(&compressed).unwrap().as_ref(), b"{}");
+    /// assert_eq!(Compression::decompress(b"{}").unwrap().as_ref(), b"{}");
+    /// ```
+    pub fn decompress(contents: &[u8]) -> io::Result<Cow<'_, [u8]>> {
+        match Self::detect(contents) {
+            Self::None => Ok(Cow::Borrowed(contents)),
+            Self::Zstd => zstd::decode_all(contents).map(Cow::Owned).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unable to decompress store: {}", err),
+                )
+            }),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown compression: {}", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+/// Size of a store written to disk, before and after compression
+///
+/// Both sizes are the same if the store is not compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotSize {
+    /// Number of bytes in the encoded store
+    pub uncompressed: usize,
+
+    /// Number of bytes written to disk
+    pub compressed: usize,
+}
+
+#[cfg(test)]
+mod compression_tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_compressed_contents() -> io::Result<()> {
+        let contents = br#"{"user:1000":"value","user:1001":"value"}"#.repeat(100);
+
+        let compressed = Compression::Zstd.compress(&contents)?;
+        assert!(compressed.len() < contents.len());
+        assert_eq!(Compression::decompress(&compressed)?.as_ref(), contents);
+
+        let uncompressed = Compression::None.compress(&contents)?;
+        assert_eq!(uncompressed, contents);
+        assert!(matches!(
+            Compression::decompress(&uncompressed)?,
+            Cow::Borrowed(_)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_damaged_contents() -> io::Result<()> {
+        let compressed = Compression::Zstd.compress(&b"value".repeat(100))?;
+
+        let truncated = &compressed[..compressed.len() - 4];
+        let err = Compression::decompress(truncated).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut corrupted = compressed.clone();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        assert!(Compression::decompress(&corrupted).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parses_compression_names() {
+        assert_eq!("ZSTD".parse::<Compression>().unwrap(), Compression::Zstd);
+        assert_eq!(Compression::None.to_string(), "none");
+        assert!("gzip".parse::<Compression>().is_err());
+    }
+}
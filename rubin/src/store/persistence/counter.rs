@@ -0,0 +1,103 @@
+//! Counter arithmetic operations for the [`PersistentStore`]
+//!
+//! Wraps the counter operations of the [`crate::store::mem::MemStore`], logging each update
+//! if the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use crate::store::persistence::aof::Command;
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Increments a value in the store by an amount, returning an error on overflow
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let value = ps.incrby("requests", 50).await?;
+    ///
+    ///     assert_eq!(value, 50);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn incrby(&mut self, key: impl AsRef<str>, increment: isize) -> io::Result<isize> {
+        let result = self.store.incrby(key.as_ref(), increment)?;
+        self.updated(|_| Command::IncrBy(key.as_ref().into(), increment))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Decrements a value in the store by an amount, returning an error on overflow
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let value = ps.decrby("stock", 30).await?;
+    ///
+    ///     assert_eq!(value, -30);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn decrby(&mut self, key: impl AsRef<str>, decrement: isize) -> io::Result<isize> {
+        let result = self.store.decrby(key.as_ref(), decrement)?;
+        self.updated(|_| Command::DecrBy(key.as_ref().into(), decrement))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Increments the floating point value of a string by an amount
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let value = ps.incrbyfloat("temperature", 0.5).await?;
+    ///
+    ///     assert_eq!(value, 0.5);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn incrbyfloat(&mut self, key: &str, increment: f64) -> io::Result<f64> {
+        let result = self.store.incrbyfloat(key, increment)?;
+        self.updated(|_| Command::IncrByFloat(key.into(), increment))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Sets the value of a counter, returning the previous value if present
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.incrby("requests", 120).await?;
+    ///
+    ///     assert_eq!(ps.getset("requests", 0).await?, Some(120));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn getset(&mut self, key: &str, value: isize) -> io::Result<Option<isize>> {
+        let result = self.store.getset(key, value)?;
+        self.updated(|_| Command::GetSet(key.into(), value)).await?;
+
+        Ok(result)
+    }
+}
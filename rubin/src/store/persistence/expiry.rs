@@ -0,0 +1,155 @@
+//! Key expiry operations for the [`PersistentStore`]
+//!
+//! Wraps the expiry operations of the [`crate::store::mem::MemStore`], logging each update
+//! if the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+use std::time::Duration;
+
+use crate::store::persistence::aof::Command;
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Inserts a string into the string store which expires after the given duration
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string_with_expiry("session:1000", "token", Duration::from_secs(60)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn insert_string_with_expiry(
+        &mut self,
+        key: &str,
+        value: &str,
+        expiry: Duration,
+    ) -> io::Result<()> {
+        self.store.insert_string_with_expiry(key, value, expiry)?;
+        self.updated(|store| {
+            Command::InsertStringWithExpiry(key.into(), value.into(), store.expiry_deadline(key))
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sets a key to expire after the given number of seconds, returning `true` if the key was present
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("session:1000", "token").await?;
+    ///
+    ///     assert!(ps.expire("session:1000", 60).await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn expire(&mut self, key: &str, seconds: u64) -> io::Result<bool> {
+        let result = self.store.expire(key, seconds)?;
+        self.updated(|store| Command::SetExpiry(key.into(), store.expiry_deadline(key)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Sets a key to expire after the given number of milliseconds, returning `true` if the key was present
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("session:1000", "token").await?;
+    ///
+    ///     assert!(ps.pexpire("session:1000", 1500).await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pexpire(&mut self, key: &str, milliseconds: u64) -> io::Result<bool> {
+        let result = self.store.pexpire(key, milliseconds)?;
+        self.updated(|store| Command::SetExpiry(key.into(), store.expiry_deadline(key)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Gets the remaining time-to-live of a key in seconds
+    ///
+    /// Returns `-2` if the key is not present and `-1` if the key has no expiry.
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("session:1000", "token").await?;
+    ///     ps.expire("session:1000", 60).await?;
+    ///
+    ///     assert_eq!(ps.ttl("session:1000")?, 60);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn ttl(&self, key: &str) -> io::Result<i64> {
+        self.store.ttl(key)
+    }
+
+    /// Gets the remaining time-to-live of a key in milliseconds
+    ///
+    /// Returns `-2` if the key is not present and `-1` if the key has no expiry.
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("session:1000", "token").await?;
+    ///
+    ///     assert_eq!(ps.pttl("session:1000")?, -1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn pttl(&self, key: &str) -> io::Result<i64> {
+        self.store.pttl(key)
+    }
+
+    /// Removes the expiry from a key, returning `true` if an expiry was removed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("session:1000", "token").await?;
+    ///     ps.expire("session:1000", 60).await?;
+    ///
+    ///     assert!(ps.persist("session:1000").await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn persist(&mut self, key: &str) -> io::Result<bool> {
+        let result = self.store.persist(key)?;
+        self.updated(|store| Command::SetExpiry(key.into(), store.expiry_deadline(key)))
+            .await?;
+
+        Ok(result)
+    }
+}
@@ -1,13 +1,56 @@
 //! File-handling operations used by the [`super::PersistentStore`]
 //!
 //! Just a collection of File I/O helpers, nothing more, nothing less
+//!
+//! Store files are never written in place. A store is written to a temporary file which is
+//! synced and renamed over the store file, so a crash part way through a write leaves the
+//! previous file intact. The previous file is kept with a `.bak` extension and is loaded
+//! instead if the store file turns out to be empty, truncated or corrupt.
 
-use std::io::{Result, Write};
+use std::ffi::OsString;
+use std::io::{ErrorKind, Result, Write};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 use crate::store::mem::MemStore;
+use crate::store::persistence::{Compression, SnapshotSize, StoreFormat};
+
+/// A deserialized store with the format and compression it was written in
+type DecodedStore = (MemStore, StoreFormat, Compression);
+
+/// Appends an extension to a path, keeping any existing extension
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    path.into()
+}
+
+/// Path of the previous store file, kept as a fallback when the store file is replaced
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+/// Path a store is written to before being renamed over the store file
+fn temporary_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".tmp")
+}
+
+/// Syncs the directory holding a file so a rename within it survives a crash
+fn sync_directory(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::File::open(parent)?.sync_all()?;
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
 
 /// Creates a directory at the given location
 pub async fn create_directory<P: AsRef<Path>>(location: P) -> Result<PathBuf> {
@@ -18,18 +61,19 @@ pub async fn create_directory<P: AsRef<Path>>(location: P) -> Result<PathBuf> {
 
 /// Loads a store file from disk.
 ///
-/// Will read the contents of the file and return it as a String
+/// Will read the contents of the file and return the raw bytes
 /// If nothing is in the file, it will write an empty string to the file
-pub async fn load_store(path: &Path) -> Result<String> {
+pub async fn load_store(path: &Path) -> Result<Vec<u8>> {
     let mut file = fs::OpenOptions::new()
         .create(true)
+        .truncate(false)
         .read(true)
         .write(true)
         .open(path)
         .await?;
 
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).await?;
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).await?;
 
     if contents.is_empty() {
         file.write_all(b"").await?;
@@ -38,22 +82,174 @@ pub async fn load_store(path: &Path) -> Result<String> {
     Ok(contents)
 }
 
-/// Serializes a [`MemStore`] and saves it out to disk
-pub async fn write_store(path: &Path, store: &MemStore) -> Result<()> {
-    let raw = serde_json::to_string_pretty(&store)?;
-    let mut file = fs::File::create(&path).await?;
-    file.write_all(raw.as_bytes()).await?;
+/// Decompresses and deserializes the contents of a store file, `None` if it is empty
+fn decode_store(contents: &[u8]) -> Result<Option<DecodedStore>> {
+    if contents.is_empty() {
+        return Ok(None);
+    }
 
-    Ok(())
+    let decompressed = Compression::decompress(contents)?;
+    let store = StoreFormat::decode(&decompressed)?;
+    Ok(Some((
+        store,
+        StoreFormat::detect(&decompressed),
+        Compression::detect(contents),
+    )))
 }
 
-/// Serializes a [`MemStore`] and saves it to disk without async functionality
-pub fn write_store_sync(path: impl AsRef<Path>, store: &MemStore) -> Result<()> {
-    let raw = serde_json::to_string_pretty(&store)?;
-    let mut file = std::fs::File::create(&path)?;
-    file.write_all(raw.as_bytes())?;
+/// Serializes a [`MemStore`] in the given format and compresses it
+pub fn encode_store(
+    store: &MemStore,
+    format: StoreFormat,
+    compression: Compression,
+) -> Result<(Vec<u8>, SnapshotSize)> {
+    let encoded = format.encode(store)?;
+    let contents = compression.compress(&encoded)?;
+    let size = SnapshotSize {
+        uncompressed: encoded.len(),
+        compressed: contents.len(),
+    };
+
+    Ok((contents, size))
+}
 
-    Ok(())
+/// Decides whether to fall back to the previous store file after decoding the current one
+///
+/// Returns the decoded store if it can be used, otherwise the error to return if there is
+/// no previous store file either.
+fn check_store(
+    path: &Path,
+    decoded: Result<Option<DecodedStore>>,
+) -> std::result::Result<DecodedStore, Option<std::io::Error>> {
+    match decoded {
+        Ok(Some(loaded)) => Ok(loaded),
+        Ok(None) => Err(None),
+        Err(e) => {
+            tracing::warn!(
+                "unable to read store file {}, using the previous store file - {}",
+                path.display(),
+                e
+            );
+            Err(Some(e))
+        }
+    }
+}
+
+/// Reads and deserializes a store file, detecting its format and compression
+///
+/// If the store file is empty, truncated or corrupt, the previous store file (see
+/// [`backup_path`]) is read instead.
+///
+/// # Returns
+///
+/// * The store with the format and compression it was written in, `None` if there is no
+///   store to load
+///
+/// # Errors
+///
+/// * [`ErrorKind::InvalidData`] if the store file is corrupt and there is no usable
+///   previous store file, or [`ErrorKind::UnexpectedEof`] if it is truncated JSON
+pub async fn read_store(path: &Path) -> Result<Option<DecodedStore>> {
+    let error = match check_store(path, decode_store(&load_store(path).await?)) {
+        Ok(loaded) => return Ok(Some(loaded)),
+        Err(error) => error,
+    };
+
+    let backup = backup_path(path);
+    if !backup.exists() {
+        return error.map_or(Ok(None), Err);
+    }
+
+    decode_store(&fs::read(&backup).await?)
+}
+
+/// Reads and deserializes a store file without async functionality, see [`read_store`]
+///
+/// Unlike [`read_store`], a missing store file is not created.
+pub fn read_store_sync(path: impl AsRef<Path>) -> Result<Option<DecodedStore>> {
+    let path = path.as_ref();
+    let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+
+    let error = match check_store(path, decode_store(&contents)) {
+        Ok(loaded) => return Ok(Some(loaded)),
+        Err(error) => error,
+    };
+
+    let backup = backup_path(path);
+    if !backup.exists() {
+        return error.map_or(Ok(None), Err);
+    }
+
+    decode_store(&std::fs::read(&backup)?)
+}
+
+/// Moves the current store file to its backup and renames the temporary file in its place
+fn replace_store(path: &Path, temporary: &Path) -> Result<()> {
+    if path.exists() {
+        std::fs::rename(path, backup_path(path))?;
+    }
+
+    std::fs::rename(temporary, path)?;
+    sync_directory(path)
+}
+
+/// Saves the contents of an encoded store out to disk
+///
+/// The contents are written to a temporary file which is synced and renamed over the store
+/// file, keeping the previous store file as a backup.
+pub async fn write_encoded(path: &Path, contents: &[u8]) -> Result<()> {
+    let temporary = temporary_path(path);
+
+    let mut file = fs::File::create(&temporary).await?;
+    file.write_all(contents).await?;
+    file.sync_all().await?;
+
+    replace_store(path, &temporary)
+}
+
+/// Saves the contents of an encoded store to disk without async functionality, see
+/// [`write_encoded`]
+pub fn write_encoded_sync(path: impl AsRef<Path>, contents: &[u8]) -> Result<()> {
+    let path = path.as_ref();
+    let temporary = temporary_path(path);
+
+    let mut file = std::fs::File::create(&temporary)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    replace_store(path, &temporary)
+}
+
+/// Serializes a [`MemStore`] in the given format and compression and saves it out to disk,
+/// see [`write_encoded`]
+pub async fn write_store(
+    path: &Path,
+    store: &MemStore,
+    format: StoreFormat,
+    compression: Compression,
+) -> Result<SnapshotSize> {
+    let (contents, size) = encode_store(store, format, compression)?;
+    write_encoded(path, &contents).await?;
+
+    Ok(size)
+}
+
+/// Serializes a [`MemStore`] in the given format and compression and saves it to disk
+/// without async functionality, see [`write_encoded`]
+pub fn write_store_sync(
+    path: impl AsRef<Path>,
+    store: &MemStore,
+    format: StoreFormat,
+    compression: Compression,
+) -> Result<SnapshotSize> {
+    let (contents, size) = encode_store(store, format, compression)?;
+    write_encoded_sync(path, &contents)?;
+
+    Ok(size)
 }
 
 #[cfg(test)]
@@ -89,7 +285,7 @@ mod fh_tests {
         create_directory(&td).await?;
 
         let result = load_store(&rubinstore).await?;
-        assert!(result.len() == 0);
+        assert!(result.is_empty());
         assert!(rubinstore.exists());
         Ok(())
     }
@@ -104,8 +300,8 @@ mod fh_tests {
         f.write_all(b"some_content").await?;
 
         let result = load_store(&rubinstore).await?;
-        assert!(result.len() != 0);
-        assert_eq!(result, "some_content");
+        assert!(!result.is_empty());
+        assert_eq!(result, b"some_content");
 
         Ok(())
     }
@@ -119,7 +315,7 @@ mod fh_tests {
         let mut ms = MemStore::new();
         ms.insert_string("key1", "value1")?;
 
-        write_store(&rubinstore, &ms).await?;
+        write_store(&rubinstore, &ms, StoreFormat::Json, Compression::None).await?;
 
         assert!(rubinstore.exists());
 
@@ -135,12 +331,12 @@ mod fh_tests {
         let mut ms = MemStore::new();
         ms.insert_string("key1", "value1")?;
 
-        write_store(&rubinstore, &ms).await?;
+        write_store(&rubinstore, &ms, StoreFormat::Json, Compression::None).await?;
 
         assert!(rubinstore.exists());
 
         let contents = load_store(&rubinstore).await?;
-        let other: MemStore = serde_json::from_str(&contents)?;
+        let other: MemStore = serde_json::from_slice(&contents)?;
         assert!(ms.strings.inner == other.strings.inner);
 
         Ok(())
@@ -155,10 +351,94 @@ mod fh_tests {
         let mut ms = MemStore::new();
         ms.insert_string("key1", "value1")?;
 
-        write_store_sync(&rubinstore, &ms)?;
+        write_store_sync(&rubinstore, &ms, StoreFormat::Json, Compression::None)?;
 
         assert!(rubinstore.exists());
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn writing_keeps_the_previous_store() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let rubinstore = td.join(STORAGE_FILE);
+        create_directory(&td).await?;
+
+        let mut ms = MemStore::new();
+        ms.insert_string("key1", "value1")?;
+        write_store(&rubinstore, &ms, StoreFormat::Json, Compression::None).await?;
+        assert!(!backup_path(&rubinstore).exists());
+
+        ms.insert_string("key1", "value2")?;
+        let size = write_store(&rubinstore, &ms, StoreFormat::Binary, Compression::Zstd).await?;
+        assert_eq!(
+            size.compressed as u64,
+            std::fs::metadata(&rubinstore)?.len()
+        );
+        assert!(!temporary_path(&rubinstore).exists());
+
+        let previous: MemStore =
+            serde_json::from_slice(&fs::read(backup_path(&rubinstore)).await?)?;
+        assert_eq!(previous.get_string("key1")?.as_deref(), Some("value1"));
+
+        let (current, format, compression) = read_store(&rubinstore).await?.unwrap();
+        assert_eq!(current.get_string("key1")?.as_deref(), Some("value2"));
+        assert_eq!(format, StoreFormat::Binary);
+        assert_eq!(compression, Compression::Zstd);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reading_a_damaged_store_uses_the_previous_store() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let rubinstore = td.join(STORAGE_FILE);
+        create_directory(&td).await?;
+
+        let mut ms = MemStore::new();
+        ms.insert_string("key1", "value1")?;
+        write_store_sync(&rubinstore, &ms, StoreFormat::Binary, Compression::Zstd)?;
+        ms.insert_string("key1", "value2")?;
+        write_store_sync(&rubinstore, &ms, StoreFormat::Binary, Compression::Zstd)?;
+
+        // Truncated part way through a write
+        let contents = std::fs::read(&rubinstore)?;
+        std::fs::write(&rubinstore, &contents[..contents.len() / 2])?;
+
+        let (loaded, _, _) = read_store(&rubinstore).await?.unwrap();
+        assert_eq!(loaded.get_string("key1")?.as_deref(), Some("value1"));
+
+        // Left empty after a crash
+        std::fs::write(&rubinstore, b"")?;
+        let (loaded, _, _) = read_store_sync(&rubinstore)?.unwrap();
+        assert_eq!(loaded.get_string("key1")?.as_deref(), Some("value1"));
+
+        // Removed between replacing the previous store and renaming the new one
+        std::fs::remove_file(&rubinstore)?;
+        let (loaded, _, _) = read_store_sync(&rubinstore)?.unwrap();
+        assert_eq!(loaded.get_string("key1")?.as_deref(), Some("value1"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reading_a_damaged_store_without_a_previous_store() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let rubinstore = td.join(STORAGE_FILE);
+        create_directory(&td).await?;
+
+        assert!(read_store(&rubinstore).await?.is_none());
+        assert!(read_store_sync(td.join("missing.json"))?.is_none());
+
+        std::fs::write(&rubinstore, b"{\"strings\":")?;
+        let err = read_store(&rubinstore).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        std::fs::write(&rubinstore, b"not a store")?;
+        let err = read_store(&rubinstore).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(read_store_sync(&rubinstore).is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,267 @@
+//! On-disk formats used to write a [`MemStore`] to disk
+//!
+//! Stores are written either as pretty-printed JSON, which is easy to read when debugging, or
+//! as a compact binary snapshot for large stores. Loading detects the format from the
+//! contents of the file so either can be read back regardless of the format being written.
+//!
+//! A binary snapshot is laid out as:
+//!
+//! | Bytes   | Contents                                        |
+//! |---------|-------------------------------------------------|
+//! | 0..5    | Magic bytes, `RUBIN`                            |
+//! | 5       | Format version                                  |
+//! | 6..14   | Length of the payload (little-endian `u64`)     |
+//! | 14..18  | CRC-32 checksum of the payload (little-endian)  |
+//! | 18..    | Payload, the store encoded as MessagePack       |
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use crate::store::mem::MemStore;
+
+/// Magic bytes at the start of every binary snapshot
+const MAGIC: &[u8; 5] = b"RUBIN";
+
+/// Current version of the binary snapshot layout
+const VERSION: u8 = 1;
+
+/// Length of the binary snapshot header
+const HEADER_LENGTH: usize = MAGIC.len() + 1 + 8 + 4;
+
+/// Lookup table for the CRC-32 (IEEE) checksum, built at compile time
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+};
+
+/// Calculates the CRC-32 checksum of some bytes
+fn checksum(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| {
+        CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Builds an error for a binary snapshot which cannot be read
+fn invalid_snapshot(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Format used to write a store to disk
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StoreFormat {
+    /// Pretty-printed JSON
+    #[default]
+    Json,
+
+    /// Compact binary snapshot with a versioned header and checksum
+    Binary,
+}
+
+impl StoreFormat {
+    /// Detects the format of the contents of a store file
+    ///
+    /// Anything which does not start with the binary snapshot magic bytes is treated as JSON.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    /// use rubin::store::persistence::StoreFormat;
+    ///
+    /// let contents = StoreFormat::Binary.encode(&MemStore::new()).unwrap();
+    ///
+    /// assert_eq!(StoreFormat::detect(&contents), StoreFormat::Binary);
+    /// assert_eq!(StoreFormat::detect(b"{}"), StoreFormat::Json);
+    /// ```
+    pub fn detect(contents: &[u8]) -> Self {
+        if contents.starts_with(MAGIC) {
+            Self::Binary
+        } else {
+            Self::Json
+        }
+    }
+
+    /// Serializes a store into this format
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    /// use rubin::store::persistence::StoreFormat;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    ///
+    /// let json = StoreFormat::Json.encode(&ms).unwrap();
+    /// let binary = StoreFormat::Binary.encode(&ms).unwrap();
+    /// assert!(binary.len() < json.len());
+    /// ```
+    pub fn encode(self, store: &MemStore) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Json => Ok(serde_json::to_vec_pretty(store)?),
+            Self::Binary => {
+                let payload = rmp_serde::to_vec_named(store)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+                let mut contents = Vec::with_capacity(HEADER_LENGTH + payload.len());
+                contents.extend_from_slice(MAGIC);
+                contents.push(VERSION);
+                contents.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+                contents.extend_from_slice(&checksum(&payload).to_le_bytes());
+                contents.extend_from_slice(&payload);
+
+                Ok(contents)
+            }
+        }
+    }
+
+    /// Deserializes a store, detecting the format from the contents
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] if the contents are not a valid store, or are a
+    ///   binary snapshot which is truncated, has an unsupported version or fails its checksum
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rubin::store::mem::MemStore;
+    /// use rubin::store::persistence::StoreFormat;
+    ///
+    /// let mut ms = MemStore::new();
+    /// ms.insert_string("user:1000", "value").unwrap();
+    /// let contents = StoreFormat::Binary.encode(&ms).unwrap();
+    ///
+    /// let loaded = StoreFormat::decode(&contents).unwrap();
+    /// assert_eq!(loaded.get_string("user:1000").unwrap().as_deref(), Some("value"));
+    /// ```
+    pub fn decode(contents: &[u8]) -> io::Result<MemStore> {
+        match Self::detect(contents) {
+            Self::Json => Ok(serde_json::from_slice(contents)?),
+            Self::Binary => {
+                if contents.len() < HEADER_LENGTH {
+                    return Err(invalid_snapshot("snapshot is truncated"));
+                }
+
+                let (header, payload) = contents.split_at(HEADER_LENGTH);
+                if header[MAGIC.len()] != VERSION {
+                    return Err(invalid_snapshot("unsupported snapshot version"));
+                }
+
+                let length = u64::from_le_bytes(header[6..14].try_into().unwrap());
+                if payload.len() as u64 != length {
+                    return Err(invalid_snapshot("snapshot is truncated"));
+                }
+
+                let expected = u32::from_le_bytes(header[14..18].try_into().unwrap());
+                if checksum(payload) != expected {
+                    return Err(invalid_snapshot("snapshot checksum does not match"));
+                }
+
+                rmp_serde::from_slice(payload)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            }
+        }
+    }
+}
+
+impl FromStr for StoreFormat {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "binary" => Ok(Self::Binary),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown store format: {}", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for StoreFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod format_tests {
+    use super::*;
+
+    #[test]
+    fn checksum_matches_crc32() {
+        assert_eq!(checksum(b""), 0);
+        assert_eq!(checksum(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn round_trips_binary_snapshots() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_bytes("binary", &[0x00, 0xff])?;
+        ms.incr("counter")?;
+        ms.json_set("document", "$", r#"{"a":[1,2.5,null]}"#)?;
+        ms.expire("counter", 60)?;
+
+        let contents = StoreFormat::Binary.encode(&ms)?;
+        assert_eq!(&contents[..MAGIC.len()], MAGIC);
+
+        let loaded = StoreFormat::decode(&contents)?;
+        assert_eq!(loaded.get_bytes("binary")?, Some(vec![0x00, 0xff]));
+        assert_eq!(loaded.counters.retrieve("counter")?, Some(1));
+        assert!(loaded.ttl("counter")? > 0);
+        assert_eq!(
+            loaded.json_get("document", "$.a")?.as_deref(),
+            Some("[[1,2.5,null]]")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_damaged_snapshots() -> io::Result<()> {
+        let mut ms = MemStore::new();
+        ms.insert_string("key", "value")?;
+        let contents = StoreFormat::Binary.encode(&ms)?;
+
+        let mut corrupted = contents.clone();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        let err = StoreFormat::decode(&corrupted).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "snapshot checksum does not match");
+
+        let err = StoreFormat::decode(&contents[..contents.len() - 1])
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "snapshot is truncated");
+        assert!(StoreFormat::decode(&contents[..8]).is_err());
+
+        let mut future = contents.clone();
+        future[MAGIC.len()] = VERSION + 1;
+        let err = StoreFormat::decode(&future).err().unwrap();
+        assert_eq!(err.to_string(), "unsupported snapshot version");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Geospatial operations for the [`PersistentStore`]
+//!
+//! Wraps the geo operations of the [`crate::store::mem::MemStore`], logging each update if
+//! the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use crate::store::mem::{GeoMatch, GeoSearch, GeoUnit};
+use crate::store::persistence::aof::Command;
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Adds one or more members with positions to a geo set, returning the number of members
+    /// newly added
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.geoadd("drivers", &[(-0.1276, 51.5072, "driver:1")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn geoadd(&mut self, key: &str, members: &[(f64, f64, &str)]) -> io::Result<usize> {
+        let result = self.store.geoadd(key, members)?;
+        self.updated(|_| {
+            let members = members
+                .iter()
+                .map(|(longitude, latitude, member)| (*longitude, *latitude, member.to_string()))
+                .collect();
+            Command::GeoAdd(key.into(), members)
+        })
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Gets the positions of members of a geo set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let positions = ps.geopos("drivers", &["driver:1"])?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn geopos(&self, key: &str, members: &[&str]) -> io::Result<Vec<Option<(f64, f64)>>> {
+        self.store.geopos(key, members)
+    }
+
+    /// Gets the distance between two members of a geo set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    /// use rubin::store::mem::GeoUnit;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let distance = ps.geodist("drivers", "driver:1", "driver:2", GeoUnit::Kilometers)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn geodist(
+        &self,
+        key: &str,
+        member1: &str,
+        member2: &str,
+        unit: GeoUnit,
+    ) -> io::Result<Option<f64>> {
+        self.store.geodist(key, member1, member2, unit)
+    }
+
+    /// Finds the members of a geo set within an area, nearest first
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    /// use rubin::store::mem::{GeoOrigin, GeoSearch, GeoShape, GeoUnit};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///
+    ///     let search = GeoSearch {
+    ///         origin: GeoOrigin::LonLat(-0.1276, 51.5072),
+    ///         shape: GeoShape::Radius(5.0),
+    ///         unit: GeoUnit::Kilometers,
+    ///         count: Some(10),
+    ///     };
+    ///     let nearest = ps.geosearch("drivers", &search)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn geosearch(&self, key: &str, search: &GeoSearch) -> io::Result<Vec<GeoMatch>> {
+        self.store.geosearch(key, search)
+    }
+}
@@ -0,0 +1,198 @@
+//! Hash operations for the [`PersistentStore`]
+//!
+//! Wraps the hash operations of the [`crate::store::mem::MemStore`], logging each update if
+//! the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::collections::HashMap;
+use std::io;
+
+use crate::store::persistence::aof::{owned, owned_pairs, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Sets one or more fields in a hash, returning the number of fields added
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let added = ps.hset("user:1000", &[("name", "rubin"), ("age", "3")]).await?;
+    ///
+    ///     assert_eq!(added, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hset(&mut self, key: &str, fields: &[(&str, &str)]) -> io::Result<usize> {
+        let result = self.store.hset(key, fields)?;
+        self.updated(|_| Command::HSet(key.into(), owned_pairs(fields)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Retrieves the value of a field in a hash
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     assert_eq!(ps.hget("user:1000", "name")?.as_deref(), Some("rubin"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn hget(&self, key: &str, field: &str) -> io::Result<Option<String>> {
+        self.store.hget(key, field)
+    }
+
+    /// Removes one or more fields from a hash, returning the number of fields removed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     let removed = ps.hdel("user:1000", &["name"]).await?;
+    ///     assert_eq!(removed, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hdel(&mut self, key: &str, fields: &[&str]) -> io::Result<usize> {
+        let result = self.store.hdel(key, fields)?;
+        self.updated(|_| Command::HDel(key.into(), owned(fields)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Retrieves all field-value pairs of a hash
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     let user = ps.hgetall("user:1000")?;
+    ///     assert_eq!(user.get("name").unwrap(), "rubin");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn hgetall(&self, key: &str) -> io::Result<HashMap<String, String>> {
+        self.store.hgetall(key)
+    }
+
+    /// Retrieves all field names of a hash
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     assert_eq!(ps.hkeys("user:1000")?, vec!["name"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn hkeys(&self, key: &str) -> io::Result<Vec<String>> {
+        self.store.hkeys(key)
+    }
+
+    /// Retrieves all values of a hash
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     assert_eq!(ps.hvals("user:1000")?, vec!["rubin"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn hvals(&self, key: &str) -> io::Result<Vec<String>> {
+        self.store.hvals(key)
+    }
+
+    /// Returns the number of fields in a hash
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     assert_eq!(ps.hlen("user:1000")?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn hlen(&self, key: &str) -> io::Result<usize> {
+        self.store.hlen(key)
+    }
+
+    /// Checks if a field exists in a hash
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     assert!(ps.hexists("user:1000", "name")?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn hexists(&self, key: &str, field: &str) -> io::Result<bool> {
+        self.store.hexists(key, field)
+    }
+
+    /// Increments the integer value of a field in a hash by a given amount
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///
+    ///     let visits = ps.hincrby("user:1000", "visits", 5).await?;
+    ///     assert_eq!(visits, 5);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn hincrby(&mut self, key: &str, field: &str, increment: isize) -> io::Result<isize> {
+        let result = self.store.hincrby(key, field, increment)?;
+        self.updated(|_| Command::HIncrBy(key.into(), field.into(), increment))
+            .await?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,74 @@
+//! HyperLogLog operations for the [`PersistentStore`]
+//!
+//! Wraps the HyperLogLog operations of the [`crate::store::mem::MemStore`], logging each
+//! update if the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use crate::store::persistence::aof::{owned, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Adds elements to a HyperLogLog, returning `true` if it was created or its estimate
+    /// may have changed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.pfadd("visitors", &["user:1", "user:2"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pfadd(&mut self, key: &str, elements: &[&str]) -> io::Result<bool> {
+        let result = self.store.pfadd(key, elements)?;
+        self.updated(|_| Command::PfAdd(key.into(), owned(elements)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Estimates the number of unique elements added to the HyperLogLogs at the given keys
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.pfadd("visitors", &["user:1", "user:2"]).await?;
+    ///
+    ///     assert_eq!(ps.pfcount(&["visitors"])?, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn pfcount(&self, keys: &[&str]) -> io::Result<u64> {
+        self.store.pfcount(keys)
+    }
+
+    /// Merges the HyperLogLogs at the source keys into the HyperLogLog at `destination`
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.pfmerge("visitors:week", &["visitors:monday", "visitors:tuesday"]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn pfmerge(&mut self, destination: &str, sources: &[&str]) -> io::Result<()> {
+        self.store.pfmerge(destination, sources)?;
+        self.updated(|_| Command::PfMerge(destination.into(), owned(sources)))
+            .await?;
+
+        Ok(())
+    }
+}
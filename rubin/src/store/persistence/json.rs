@@ -0,0 +1,124 @@
+//! JSON document operations for the [`PersistentStore`]
+//!
+//! Wraps the JSON operations of the [`crate::store::mem::MemStore`], logging each update if
+//! the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use crate::store::persistence::aof::{owned, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Sets the value at a path in a JSON document, creating the document at the root `$`
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.json_set("user:1000", "$", r#"{"name": "rubin"}"#).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_set(&mut self, key: &str, path: &str, value: &str) -> io::Result<bool> {
+        let result = self.store.json_set(key, path, value)?;
+        self.updated(|_| Command::JsonSet(key.into(), path.into(), value.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Gets the values at a path in a JSON document as a JSON array
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let name = ps.json_get("user:1000", "$.name")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn json_get(&self, key: &str, path: &str) -> io::Result<Option<String>> {
+        self.store.json_get(key, path)
+    }
+
+    /// Removes the values at a path in a JSON document, returning the number removed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.json_del("user:1000", "$.address").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_del(&mut self, key: &str, path: &str) -> io::Result<usize> {
+        let result = self.store.json_del(key, path)?;
+        self.updated(|_| Command::JsonDel(key.into(), path.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Appends values to the arrays at a path in a JSON document, returning their new lengths
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.json_arrappend("user:1000", "$.tags", &[r#""rust""#]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_arrappend(
+        &mut self,
+        key: &str,
+        path: &str,
+        values: &[&str],
+    ) -> io::Result<Vec<Option<usize>>> {
+        let result = self.store.json_arrappend(key, path, values)?;
+        self.updated(|_| Command::JsonArrAppend(key.into(), path.into(), owned(values)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Increments the numbers at a path in a JSON document, returning the new values as a
+    /// JSON array
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.json_numincrby("user:1000", "$.visits", 1.0).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn json_numincrby(
+        &mut self,
+        key: &str,
+        path: &str,
+        increment: f64,
+    ) -> io::Result<String> {
+        let result = self.store.json_numincrby(key, path, increment)?;
+        self.updated(|_| Command::JsonNumIncrBy(key.into(), path.into(), increment))
+            .await?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,242 @@
+//! Keyspace operations for the [`PersistentStore`]
+//!
+//! Wraps the keyspace operations of the [`crate::store::mem::MemStore`], logging each
+//! update if the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use crate::store::mem::{KeyType, MemStore};
+use crate::store::persistence::aof::{owned, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Gets the type of value held by a key, `None` if the key is not present
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::{mem::KeyType, persistence::PersistentStore};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1"]).await?;
+    ///
+    ///     assert_eq!(ps.key_type("queue")?, Some(KeyType::List));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn key_type(&self, key: &str) -> io::Result<Option<KeyType>> {
+        self.store.key_type(key)
+    }
+
+    /// Counts how many of the given keys are present
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("user:1000", "value").await?;
+    ///
+    ///     assert_eq!(ps.exists(&["user:1000", "missing"])?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn exists(&self, keys: &[&str]) -> io::Result<usize> {
+        self.store.exists(keys)
+    }
+
+    /// Removes keys regardless of the type of value they hold, returning the number removed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("user:1000", "value").await?;
+    ///
+    ///     assert_eq!(ps.del(&["user:1000", "missing"]).await?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn del(&mut self, keys: &[&str]) -> io::Result<usize> {
+        let result = self.store.del(keys)?;
+        self.updated(|_| Command::Del(owned(keys))).await?;
+
+        Ok(result)
+    }
+
+    /// Renames a key, replacing any value held by the new key
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("jobs:pending", &["job-1"]).await?;
+    ///
+    ///     ps.rename("jobs:pending", "jobs:active").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rename(&mut self, key: &str, new_key: &str) -> io::Result<()> {
+        self.store.rename(key, new_key)?;
+        self.updated(|_| Command::Rename(key.into(), new_key.into()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Renames a key if the new key is not present, returning `true` if the key was renamed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("jobs:pending", &["job-1"]).await?;
+    ///
+    ///     assert!(ps.renamenx("jobs:pending", "jobs:active").await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn renamenx(&mut self, key: &str, new_key: &str) -> io::Result<bool> {
+        let result = self.store.renamenx(key, new_key)?;
+        self.updated(|_| Command::RenameNx(key.into(), new_key.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Moves a key with its value and expiry into another store
+    ///
+    /// The key is logged as removed from this store and added to the other store, so the
+    /// move survives a restart of either.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the key was moved, `false` if it is not present or the other store already
+    ///   holds the key
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut staging = PersistentStore::new("./storage/staging.json").await?;
+    ///     let mut production = PersistentStore::new("./storage/production.json").await?;
+    ///     staging.hset("config", &[("timeout", "30")]).await?;
+    ///
+    ///     assert!(staging.move_key("config", &mut production).await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn move_key(&mut self, key: &str, target: &mut PersistentStore) -> io::Result<bool> {
+        if self.store.exists(&[key])? == 0 || target.store.exists(&[key])? > 0 {
+            return Ok(false);
+        }
+
+        // Move the key through a store of its own so it can be logged for the target
+        let mut moving = MemStore::new();
+        target.store.reserve_memory()?;
+        self.store.move_key(key, &mut moving)?;
+        let record = moving.clone();
+        moving.move_key(key, &mut target.store)?;
+
+        self.updated(|_| Command::Del(vec![key.into()])).await?;
+        target
+            .updated(|_| Command::Import(Box::new(record)))
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Swaps the contents of two stores, each keeping its own memory limit and eviction
+    /// policy
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut current = PersistentStore::new("./storage/current.json").await?;
+    ///     let mut next = PersistentStore::new("./storage/next.json").await?;
+    ///     next.insert_string("release", "v2").await?;
+    ///
+    ///     current.swap(&mut next).await?;
+    ///     assert_eq!(current.get_string("release")?.as_deref(), Some("v2"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn swap(&mut self, other: &mut PersistentStore) -> io::Result<()> {
+        std::mem::swap(&mut self.store, &mut other.store);
+
+        // Swap the memory settings back, which belong to the store rather than its contents
+        std::mem::swap(&mut self.store.max_memory, &mut other.store.max_memory);
+        std::mem::swap(
+            &mut self.store.eviction_policy,
+            &mut other.store.eviction_policy,
+        );
+
+        self.updated(|store| Command::Restore(Box::new(store.clone())))
+            .await?;
+        other
+            .updated(|store| Command::Restore(Box::new(store.clone())))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Gets every key matching a glob-style pattern, sorted alphabetically
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("user:1000", "value").await?;
+    ///
+    ///     assert_eq!(ps.keys("user:*")?, vec!["user:1000"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn keys(&self, pattern: &str) -> io::Result<Vec<String>> {
+        self.store.keys(pattern)
+    }
+
+    /// Removes every key from the store
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("user:1000", "value").await?;
+    ///
+    ///     ps.flush().await?;
+    ///     assert!(ps.keys("*")?.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn flush(&mut self) -> io::Result<()> {
+        self.store.flush();
+        self.updated(|_| Command::Flush).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+//! List operations for the [`PersistentStore`]
+//!
+//! Wraps the list operations of the [`crate::store::mem::MemStore`], logging each update if
+//! the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use crate::store::persistence::aof::{owned, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Pushes values onto the head of a list, returning the length of the list
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let length = ps.lpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     assert_eq!(length, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lpush(&mut self, key: &str, values: &[&str]) -> io::Result<usize> {
+        let result = self.store.lpush(key, values)?;
+        self.updated(|_| Command::LPush(key.into(), owned(values)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Pushes values onto the tail of a list, returning the length of the list
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let length = ps.rpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     assert_eq!(length, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rpush(&mut self, key: &str, values: &[&str]) -> io::Result<usize> {
+        let result = self.store.rpush(key, values)?;
+        self.updated(|_| Command::RPush(key.into(), owned(values)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Removes and returns the value at the head of a list
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     let job = ps.lpop("queue").await?;
+    ///     assert_eq!(job.as_deref(), Some("job-1"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lpop(&mut self, key: &str) -> io::Result<Option<String>> {
+        let result = self.store.lpop(key)?;
+        self.updated(|_| Command::LPop(key.into())).await?;
+
+        Ok(result)
+    }
+
+    /// Removes and returns the value at the tail of a list
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     let job = ps.rpop("queue").await?;
+    ///     assert_eq!(job.as_deref(), Some("job-2"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn rpop(&mut self, key: &str) -> io::Result<Option<String>> {
+        let result = self.store.rpop(key)?;
+        self.updated(|_| Command::RPop(key.into())).await?;
+
+        Ok(result)
+    }
+
+    /// Returns the values of a list between `start` and `stop` (inclusive)
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     let jobs = ps.lrange("queue", 0, -1)?;
+    ///     assert_eq!(jobs, vec!["job-1", "job-2"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn lrange(&self, key: &str, start: isize, stop: isize) -> io::Result<Vec<String>> {
+        self.store.lrange(key, start, stop)
+    }
+
+    /// Returns the length of a list
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     assert_eq!(ps.llen("queue")?, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn llen(&self, key: &str) -> io::Result<usize> {
+        self.store.llen(key)
+    }
+
+    /// Returns the value at a given index of a list
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     assert_eq!(ps.lindex("queue", -1)?.as_deref(), Some("job-2"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn lindex(&self, key: &str, index: isize) -> io::Result<Option<String>> {
+        self.store.lindex(key, index)
+    }
+
+    /// Sets the value at a given index of a list
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1", "job-2"]).await?;
+    ///
+    ///     ps.lset("queue", 0, "job-3").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lset(&mut self, key: &str, index: isize, value: &str) -> io::Result<()> {
+        self.store.lset(key, index, value)?;
+        self.updated(|_| Command::LSet(key.into(), index, value.into()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Trims a list to the values between `start` and `stop` (inclusive)
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1", "job-2", "job-3"]).await?;
+    ///
+    ///     ps.ltrim("queue", 0, 1).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ltrim(&mut self, key: &str, start: isize, stop: isize) -> io::Result<()> {
+        self.store.ltrim(key, start, stop)?;
+        self.updated(|_| Command::LTrim(key.into(), start, stop))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes occurrences of a value from a list, returning the number removed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.rpush("queue", &["job-1", "job-2", "job-1"]).await?;
+    ///
+    ///     let removed = ps.lrem("queue", 0, "job-1").await?;
+    ///     assert_eq!(removed, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn lrem(&mut self, key: &str, count: isize, value: &str) -> io::Result<usize> {
+        let result = self.store.lrem(key, count, value)?;
+        self.updated(|_| Command::LRem(key.into(), count, value.into()))
+            .await?;
+
+        Ok(result)
+    }
+}
@@ -1,7 +1,7 @@
 //! An asynchronus in-memory store with on-disk persistence
 //!
 //! Functions as a wrapper around the [`MemStore`] struct with the option to write
-//! to disk when needed in JSON format.
+//! to disk when needed, either as JSON or as a compact binary snapshot (see [`StoreFormat`]).
 //!
 //! A [`PersistentStore`] can be created in three ways:
 //!
@@ -27,11 +27,49 @@
 //!
 //! ```
 //!
+//! ## Choosing a format
+//!
+//! Stores are written as pretty-printed JSON by default. Large stores can be written as
+//! compact binary snapshots instead by choosing the format when the store is created.
+//!
+//! ```no_run
+//! use rubin::store::persistence::{PersistentStore, StoreFormat};
+//!
+//! #[tokio::main]
+//! async fn main() -> std::io::Result<()> {
+//!     let ps = PersistentStore::with_format("some/storage/rubinstore.bin", StoreFormat::Binary).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Either format can also be compressed (see [`Compression`]), which shrinks stores made up of
+//! many similar keys considerably.
+//!
+//! ```no_run
+//! use rubin::store::persistence::{Compression, PersistentStore};
+//!
+//! #[tokio::main]
+//! async fn main() -> std::io::Result<()> {
+//!     let mut ps = PersistentStore::new("some/storage/rubinstore.json.zst").await?;
+//!     ps.set_compression(Compression::Zstd);
+//!
+//!     let size = ps.write().await?;
+//!     println!("{} bytes compressed to {}", size.uncompressed, size.compressed);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ## Loading an existing store
 //!
 //! An already existing store file can be loaded to create a [`PersistentStore`]
 //!
-//! This will deserialize the contents into the inner [`MemStore`].
+//! This will deserialize the contents into the inner [`MemStore`], detecting whether the
+//! file holds JSON or a binary snapshot and whether it is compressed.
+//!
+//! Store files are replaced atomically when written and the previous file is kept alongside
+//! with a `.bak` extension. If the store file is left truncated or corrupt by a crash, the
+//! previous file is loaded instead.
 //!
 //! ```no_run
 //! use rubin::store::persistence::PersistentStore;
@@ -63,18 +101,97 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## Logging updates
+//!
+//! Writing the whole store after every update is slow for large stores. Instead, each update
+//! can be appended to a log kept next to the store file, which is replayed when the store is
+//! loaded. The log is rewritten in the background once it has grown, see
+//! [`PersistentStore::enable_aof()`].
+//!
+//! ```no_run
+//! use rubin::store::persistence::{FsyncPolicy, PersistentStore};
+//!
+//! #[tokio::main]
+//! async fn main() -> std::io::Result<()> {
+//!     let mut ps = PersistentStore::new("some/storage/rubinstore.json").await?;
+//!     ps.enable_aof(FsyncPolicy::EverySecond).await?;
+//!
+//!     ps.incr("page:views").await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ## Saving in the background
+//!
+//! Rather than writing on every update or calling [`PersistentStore::write()`] by hand, the
+//! store can be written periodically by a background task once enough changes have been made,
+//! using rules such as "at least 100 changes in 5 minutes" (see [`SaveRule`]).
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use tokio::sync::Mutex;
+//! use rubin::store::persistence::{PersistentStore, DEFAULT_SAVE_RULES};
+//!
+//! #[tokio::main]
+//! async fn main() -> std::io::Result<()> {
+//!     let mut ps = PersistentStore::new("some/storage/rubinstore.json").await?;
+//!     ps.set_save_rules(DEFAULT_SAVE_RULES.to_vec());
+//!
+//!     let ps = Arc::new(Mutex::new(ps));
+//!     PersistentStore::schedule_saves(&ps);
+//!
+//!     ps.lock().await.incr("page:views").await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+mod aof;
+mod bitmap;
+mod compression;
+mod counter;
+mod expiry;
 pub(crate) mod file_handling;
-
-use crate::store::mem::MemStore;
+mod format;
+mod geo;
+mod hash;
+mod hyperloglog;
+mod json;
+mod keyspace;
+mod list;
+mod save;
+mod scan;
+mod set;
+mod stream;
+mod string;
+mod typed;
+mod zset;
+
+pub use aof::FsyncPolicy;
+pub use compression::{Compression, SnapshotSize};
+pub use format::StoreFormat;
+pub use save::{SaveRule, SaveSchedule, DEFAULT_SAVE_RULES};
+
+use crate::store::mem::{Bytes, MemStore};
+use crate::store::persistence::aof::{log_path, AppendOnlyLog, Command};
 use crate::store::persistence::file_handling::*;
 
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::interval;
+
+/// How often the background task checks whether the save rules are met
+const SAVE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
 /// In-memory key-value store with persistence
 ///
 /// A wrapper around the [`MemStore`] with the option for on-disk persistence
-/// in JSON format
+/// in JSON or binary format
 pub struct PersistentStore {
     /// Directory which holds the store
     pub path: PathBuf,
@@ -87,6 +204,18 @@ pub struct PersistentStore {
 
     /// Whether to write to disk after each update or not
     pub write_on_update: bool,
+
+    /// Format the store is written to disk in
+    pub format: StoreFormat,
+
+    /// Compression applied to the store when written to disk
+    pub compression: Compression,
+
+    /// Log of updates, if enabled
+    aof: Option<AppendOnlyLog>,
+
+    /// Changes made since the store was last written and the rules for saving them
+    saves: SaveSchedule,
 }
 
 impl PersistentStore {
@@ -108,6 +237,26 @@ impl PersistentStore {
     /// }
     /// ```
     pub async fn new<P: AsRef<Path>>(storage_loc: P) -> io::Result<Self> {
+        Self::with_format(storage_loc, StoreFormat::default()).await
+    }
+
+    /// Create a fresh PersistentStore which is written to disk in the given format
+    ///
+    /// Behaves the same as [`Self::new()`], which writes the store as JSON.
+    ///
+    /// ```no_run
+    /// use rubin::store::persistence::{PersistentStore, StoreFormat};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::with_format("some/storage/file.bin", StoreFormat::Binary).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn with_format<P: AsRef<Path>>(
+        storage_loc: P,
+        format: StoreFormat,
+    ) -> io::Result<Self> {
         let folder = storage_loc
             .as_ref()
             .parent()
@@ -123,14 +272,44 @@ impl PersistentStore {
         Ok(Self {
             path,
             filename: filename.into(),
+            format,
+            ..Self::in_memory()
+        })
+    }
+
+    /// Create a PersistentStore which is kept in memory only
+    ///
+    /// The store has no file, so writing it out or enabling the append-only log fails.
+    /// Used by the server when it has no data directory.
+    pub(crate) fn in_memory() -> Self {
+        Self {
+            path: PathBuf::new(),
+            filename: PathBuf::new(),
             store: MemStore::new(),
             write_on_update: false,
-        })
+            format: StoreFormat::default(),
+            compression: Compression::default(),
+            aof: None,
+            saves: SaveSchedule::default(),
+        }
     }
 
     /// Create a Persistent Store from an already existing store file.
     ///
-    /// This will deserialize the JSON into the inner [`MemStore`] type.
+    /// This will deserialize the contents into the inner [`MemStore`] type. The format and
+    /// compression of the file are detected when loading and the store keeps writing with them.
+    ///
+    /// If an append-only log exists next to the store file, it is replayed on top of the
+    /// contents and further updates are logged to it with the fsync policy it was last
+    /// enabled with (see [`Self::enable_aof()`]).
+    ///
+    /// If the store file is empty, truncated or corrupt, for example after a crash while it
+    /// was being written, the previous store file is loaded instead.
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidData`] or [`io::ErrorKind::UnexpectedEof`] if neither the
+    ///   store file nor the previous store file hold a valid store
     ///
     /// ```no_run
     /// use rubin::store::persistence::PersistentStore;
@@ -144,7 +323,7 @@ impl PersistentStore {
     /// ```
     pub async fn from_existing<P: AsRef<Path>>(storage_loc: P) -> io::Result<Self> {
         let mut store = Self::new(storage_loc).await?;
-        store.load().await.expect("unable to load store");
+        store.load().await?;
         Ok(store)
     }
 
@@ -195,18 +374,16 @@ impl PersistentStore {
     /// }
     /// ```
     pub async fn insert_string(&mut self, key: &str, value: &str) -> io::Result<()> {
-        let result = self.store.insert_string(key, value);
+        self.store.insert_string(key, value)?;
+        self.updated(|_| Command::InsertString(key.into(), value.into()))
+            .await?;
 
-        if self.write_on_update {
-            self.write().await?;
-        }
-
-        result
+        Ok(())
     }
 
     /// Retrieve a value from the string store denoted by the given key
     ///
-    /// If no value is present, it will return an empty string
+    /// If no value is present, it will return `None`
     ///
     /// ```no_run
     /// use rubin::store::persistence::PersistentStore;
@@ -219,18 +396,18 @@ impl PersistentStore {
     ///     // ...
     ///
     ///     let result = ps.get_string("user:1000")?;
-    ///     assert_eq!(&result, "value");
+    ///     assert_eq!(result.as_deref(), Some("value"));
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub fn get_string(&self, key: &str) -> io::Result<String> {
+    pub fn get_string(&self, key: &str) -> io::Result<Option<String>> {
         self.store.get_string(key)
     }
 
     /// Remove a value from the string store denoted by its key
     ///
-    /// If no key is present, will return an empty string
+    /// If no key is present, will return `None`
     ///
     /// ```rust,no_run
     /// use rubin::store::persistence::PersistentStore;
@@ -243,17 +420,14 @@ impl PersistentStore {
     ///
     ///     let value = ps.remove_string("user:1000").await?;
     ///
-    ///     assert_eq!(&value, "value");
+    ///     assert_eq!(value.as_deref(), Some("value"));
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn remove_string(&mut self, key: &str) -> io::Result<String> {
+    pub async fn remove_string(&mut self, key: &str) -> io::Result<Option<String>> {
         let result = self.store.remove_string(key)?;
-
-        if self.write_on_update {
-            self.write().await?;
-        }
+        self.updated(|_| Command::RemoveString(key.into())).await?;
 
         Ok(result)
     }
@@ -283,10 +457,7 @@ impl PersistentStore {
     /// ```
     pub async fn clear_strings(&mut self) -> io::Result<()> {
         self.store.clear_strings()?;
-
-        if self.write_on_update {
-            self.write().await?;
-        }
+        self.updated(|_| Command::ClearStrings).await?;
 
         Ok(())
     }
@@ -311,13 +482,11 @@ impl PersistentStore {
     /// }
     /// ```
     pub async fn incr(&mut self, key: impl AsRef<str>) -> io::Result<isize> {
-        let result = self.store.incr(key);
-
-        if self.write_on_update {
-            self.write().await?;
-        }
+        let result = self.store.incr(key.as_ref())?;
+        self.updated(|_| Command::IncrBy(key.as_ref().into(), 1))
+            .await?;
 
-        result
+        Ok(result)
     }
 
     /// Decrements a value in the store by 1
@@ -340,13 +509,11 @@ impl PersistentStore {
     /// }
     /// ```
     pub async fn decr(&mut self, key: impl AsRef<str>) -> io::Result<isize> {
-        let result = self.store.decr(key);
+        let result = self.store.decr(key.as_ref())?;
+        self.updated(|_| Command::DecrBy(key.as_ref().into(), 1))
+            .await?;
 
-        if self.write_on_update {
-            self.write().await?;
-        }
-
-        result
+        Ok(result)
     }
 
     /// Gets a reference to the inner string store.
@@ -373,7 +540,7 @@ impl PersistentStore {
     ///     Ok(())
     /// }
     /// ```
-    pub fn get_string_store_ref(&self) -> &std::collections::HashMap<String, String> {
+    pub fn get_string_store_ref(&self) -> &std::collections::HashMap<String, Bytes> {
         self.store.get_string_store_ref()
     }
 
@@ -400,20 +567,74 @@ impl PersistentStore {
         self.write_on_update = set;
     }
 
+    /// Sets the compression applied to the store when it is next written to disk
+    ///
+    /// Compressed and uncompressed store files can both be loaded, whatever the compression.
+    ///
+    /// ```no_run
+    /// use rubin::store::persistence::{Compression, PersistentStore};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json.zst").await?;
+    ///     ps.set_compression(Compression::Zstd);
+    ///
+    ///     ps.insert_string("user:1000", "value").await?;
+    ///     ps.write().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.compression = compression;
+    }
+
+    /// Gets the path of the store file
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the store is kept in memory only, see
+    ///   [`Self::in_memory()`]
+    fn file_path(&self) -> io::Result<PathBuf> {
+        if self.filename.as_os_str().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "store is kept in memory only",
+            ));
+        }
+
+        Ok(self.path.join(&self.filename))
+    }
+
     /// Loads the store file from disk
     ///
-    /// Parses the contents of the store file and deserializes it into
-    /// a [`MemStore`]
+    /// Parses the contents of the store file, or the previous store file if it cannot be
+    /// read, and deserializes it into a [`MemStore`], replacing the contents of every inner
+    /// store, replaying the append-only log if there is one and removing any keys which have
+    /// expired
     async fn load(&mut self) -> io::Result<()> {
-        let path = self.path.join(&self.filename);
-        let contents = load_store(&path).await?;
-        if contents.is_empty() {
-            return Ok(());
+        let path = self.file_path()?;
+        if let Some((vault, format, compression)) = read_store(&path).await? {
+            self.format = format;
+            self.compression = compression;
+
+            // Replace every store at once so none can be missed, keeping the settings
+            // which are not written to disk
+            self.store = MemStore {
+                max_memory: self.store.max_memory,
+                eviction_policy: self.store.eviction_policy,
+                ..vault
+            };
         }
 
-        let vault: MemStore = serde_json::from_str(&contents)?;
+        let log = log_path(&path);
+        if log.exists() {
+            let aof = AppendOnlyLog::open(log, &mut self.store)?;
+            self.aof = Some(aof);
+        }
 
-        self.store.strings = vault.strings;
+        // Drop any keys which expired while the store was on disk
+        self.store.purge_expired();
 
         Ok(())
     }
@@ -425,6 +646,10 @@ impl PersistentStore {
     ///
     /// This best suited for frequent updates when snapshotting each time is expensive.
     ///
+    /// # Returns
+    ///
+    /// * The size of the store before and after compression, the same if not compressed
+    ///
     /// ```no_run
     /// use rubin::store::persistence::PersistentStore;
     ///
@@ -439,14 +664,227 @@ impl PersistentStore {
     ///     }
     ///
     ///     // Manually write to disk
-    ///     ps.write().await?;
+    ///     let size = ps.write().await?;
+    ///     println!("Wrote {} bytes", size.compressed);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn write(&self) -> io::Result<SnapshotSize> {
+        let changes = self.saves.changes();
+        let path = self.file_path()?;
+        let size = write_store(&path, &self.store, self.format, self.compression).await?;
+        self.saves.saved(changes);
+
+        Ok(size)
+    }
+
+    /// Sets the rules for writing the store in the background, see [`Self::schedule_saves()`]
+    ///
+    /// No rules, the default, disables writing in the background.
+    pub fn set_save_rules(&mut self, rules: Vec<SaveRule>) {
+        self.saves.set_rules(rules);
+    }
+
+    /// Gets the number of updates made since the store was last written
+    ///
+    /// Updates made directly to the inner [`MemStore`] are not counted.
+    pub fn changes_since_save(&self) -> u64 {
+        self.saves.changes()
+    }
+
+    /// Gets the time the store was last written as seconds since the Unix epoch
+    ///
+    /// This is the time the store was created or loaded if it has not been written since.
+    pub fn last_save(&self) -> u64 {
+        self.saves.last_save()
+    }
+
+    /// Starts writing the store out to disk in the background
+    ///
+    /// The store is encoded while locked, then written without holding the lock so updates
+    /// can carry on. Updates made while the store is being written are counted towards the
+    /// next save.
+    ///
+    /// # Returns
+    ///
+    /// * A handle to the task writing the store, resolving to the size of the store written
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::AlreadyExists`] if a background save is already in progress
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use tokio::sync::Mutex;
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let ps = Arc::new(Mutex::new(ps));
+    ///
+    ///     let saving = PersistentStore::background_save(&ps).await?;
+    ///     ps.lock().await.insert_string("user:1000", "value").await?;
+    ///
+    ///     saving.await??;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn background_save(
+        store: &Arc<Mutex<Self>>,
+    ) -> io::Result<JoinHandle<io::Result<SnapshotSize>>> {
+        let (path, contents, size, changes) = {
+            let ps = store.lock().await;
+            let path = ps.file_path()?;
+            let changes = ps.saves.begin_background_save()?;
+            let (contents, size) = encode_store(&ps.store, ps.format, ps.compression)
+                .inspect_err(|_| ps.saves.end_background_save(None))?;
+
+            (path, contents, size, changes)
+        };
+
+        let store = Arc::clone(store);
+        Ok(tokio::spawn(async move {
+            let result = write_encoded(&path, &contents).await;
+            let saved = result.as_ref().ok().map(|_| changes);
+            store.lock().await.saves.end_background_save(saved);
+
+            result.map(|_| size)
+        }))
+    }
+
+    /// Spawns a task which writes the store in the background whenever one of its save
+    /// rules is met (see [`Self::set_save_rules()`])
+    ///
+    /// The rules are checked every second. The task ends once the store has been dropped.
+    pub fn schedule_saves(store: &Arc<Mutex<Self>>) -> JoinHandle<()> {
+        let store = Arc::downgrade(store);
+
+        tokio::spawn(async move {
+            let mut ticker = interval(SAVE_CHECK_INTERVAL);
+            loop {
+                ticker.tick().await;
+
+                let Some(store) = store.upgrade() else {
+                    return;
+                };
+
+                if !store.lock().await.saves.is_due() {
+                    continue;
+                }
+
+                let saved = match Self::background_save(&store).await {
+                    Ok(saving) => saving.await.unwrap_or_else(|e| Err(e.into())),
+                    Err(e) => Err(e),
+                };
+
+                if let Err(e) = saved {
+                    tracing::error!("unable to save store in the background - {}", e);
+                }
+            }
+        })
+    }
+
+    /// Enables the append-only log, or changes its fsync policy if already enabled
+    ///
+    /// Each update is appended to a log named after the store file with an `.aof`
+    /// extension, rather than writing out the whole store. The log starts with the
+    /// current contents of the store and is replayed by [`Self::from_existing()`], which
+    /// keeps logging with the same fsync policy.
+    ///
+    /// The log is rewritten in the background once it has doubled in size since it was
+    /// last rewritten, or when [`Self::rewrite_aof()`] is called.
+    ///
+    /// Updates made directly to the inner [`MemStore`] are not logged.
+    ///
+    /// ```no_run
+    /// use rubin::store::persistence::{FsyncPolicy, PersistentStore};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.enable_aof(FsyncPolicy::Always).await?;
+    ///
+    ///     // Appended to `./storage/file.json.aof`
+    ///     ps.insert_string("user:1000", "value").await?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn write(&self) -> io::Result<()> {
-        let path = self.path.join(&self.filename);
-        write_store(&path, &self.store).await?;
+    pub async fn enable_aof(&mut self, policy: FsyncPolicy) -> io::Result<()> {
+        match &mut self.aof {
+            Some(aof) => aof.set_policy(policy).await?,
+            None => {
+                let log = log_path(&self.file_path()?);
+                self.aof = Some(AppendOnlyLog::create(log, policy, &self.store)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Disables the append-only log
+    ///
+    /// The store is written out to disk before the log is deleted so no updates are lost.
+    pub async fn disable_aof(&mut self) -> io::Result<()> {
+        if let Some(aof) = self.aof.take() {
+            self.write().await?;
+            aof.remove().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Starts rewriting the append-only log in the background
+    ///
+    /// The log is replaced by the current contents of the store plus any updates made
+    /// while the rewrite runs. The new log is swapped in by the next update once the
+    /// rewrite is complete, or by [`Self::sync_aof()`].
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the rewrite was started, `false` if one is already running
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::InvalidInput`] if the append-only log is not enabled
+    pub fn rewrite_aof(&mut self) -> io::Result<bool> {
+        match &mut self.aof {
+            Some(aof) => aof.rewrite(&self.store),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "append-only log is not enabled",
+            )),
+        }
+    }
+
+    /// Waits for a running rewrite of the append-only log to finish and syncs the log
+    /// to disk, whatever the fsync policy
+    pub async fn sync_aof(&mut self) -> io::Result<()> {
+        if let Some(aof) = &mut self.aof {
+            aof.sync().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Records an update in the append-only log, if enabled, counts it towards the
+    /// save rules and writes the store out to disk if writing on update
+    async fn updated(&mut self, command: impl FnOnce(&MemStore) -> Command) -> io::Result<()> {
+        self.saves.record_change();
+
+        if let Some(aof) = &mut self.aof {
+            aof.append(&command(&self.store)).await?;
+            if aof.should_rewrite() {
+                aof.rewrite(&self.store)?;
+            }
+        }
+
+        if self.write_on_update {
+            self.write().await?;
+        }
 
         Ok(())
     }
@@ -562,7 +1000,10 @@ mod persistent_store {
             ps.incr("view-counter").await?;
         }
 
-        assert_eq!(ps.store.counters.retrieve("view-counter").unwrap(), 10_000);
+        assert_eq!(
+            ps.store.counters.retrieve("view-counter").unwrap(),
+            Some(10_000)
+        );
         assert!(ps.store.strings.len() == 100_000);
 
         ps.write().await?;
@@ -588,7 +1029,10 @@ mod persistent_store {
             ps.decr("view-counter").await?;
         }
 
-        assert_eq!(ps.store.counters.retrieve("view-counter").unwrap(), -10_000);
+        assert_eq!(
+            ps.store.counters.retrieve("view-counter").unwrap(),
+            Some(-10_000)
+        );
         assert!(ps.store.strings.len() == 100_000);
 
         ps.write().await?;
@@ -611,7 +1055,134 @@ mod persistent_store {
         assert_eq!(ps.store.strings.len(), 1);
 
         let result = ps.get_string("key1")?;
-        assert_eq!(result, "value1");
+        assert_eq!(result.as_deref(), Some("value1"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_existing_store_with_lists() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let path = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.rpush("queue", &["job-1", "job-2"]).await?;
+        ps.write().await?;
+
+        drop(ps);
+
+        let ps = PersistentStore::from_existing(path).await?;
+        assert_eq!(ps.lrange("queue", 0, -1)?, vec!["job-1", "job-2"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_existing_store_with_hashes() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let path = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.hset("user:1000", &[("name", "rubin")]).await?;
+        ps.write().await?;
+
+        drop(ps);
+
+        let ps = PersistentStore::from_existing(path).await?;
+        assert_eq!(ps.hget("user:1000", "name")?.as_deref(), Some("rubin"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_existing_store_with_sets() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let path = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.sadd("flags", &["dark-mode", "beta"]).await?;
+        ps.write().await?;
+
+        drop(ps);
+
+        let ps = PersistentStore::from_existing(path).await?;
+        assert_eq!(ps.scard("flags")?, 2);
+        assert!(ps.sismember("flags", "beta")?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_existing_store_with_sorted_sets() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let path = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")])
+            .await?;
+        ps.write().await?;
+
+        drop(ps);
+
+        let ps = PersistentStore::from_existing(path).await?;
+        assert_eq!(ps.zrank("leaderboard", "alice")?, Some(1));
+        assert_eq!(ps.zscore("leaderboard", "bob")?, Some(75.0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_existing_store_drops_expired_keys() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let path = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.insert_string("session:1", "token").await?;
+        ps.insert_string("session:2", "token").await?;
+        ps.expire("session:1", 60).await?;
+        ps.pexpire("session:2", 0).await?;
+        ps.write().await?;
+
+        drop(ps);
+
+        let ps = PersistentStore::from_existing(path).await?;
+        assert_eq!(ps.ttl("session:1")?, 60);
+        assert_eq!(ps.ttl("session:2")?, -2);
+        assert_eq!(ps.store.strings.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_existing_store_with_typed_values() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let path = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.set_write_on_update(true);
+        ps.insert_typed("scores", &vec![10, 20, 30]).await?;
+
+        drop(ps);
+
+        let ps = PersistentStore::from_existing(path).await?;
+        assert_eq!(ps.get_typed("scores")?, Some(vec![10, 20, 30]));
+        assert!(ps.get_typed::<Vec<String>>("scores").is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_existing_store_with_hyperloglogs() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let path = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        for i in 0..1_000 {
+            ps.store.pfadd("visitors", &[&format!("user:{}", i)])?;
+        }
+        let estimate = ps.pfcount(&["visitors"])?;
+        ps.write().await?;
+
+        drop(ps);
+
+        let mut ps = PersistentStore::from_existing(path).await?;
+        assert_eq!(ps.pfcount(&["visitors"])?, estimate);
+
+        ps.pfadd("visitors", &["user:0"]).await?;
+        assert_eq!(ps.pfcount(&["visitors"])?, estimate);
 
         Ok(())
     }
@@ -632,11 +1203,409 @@ mod persistent_store {
         ps.set_write_on_update(true);
         assert_eq!(ps.store.strings.len(), 10);
 
-        let _ = ps.insert_string("key-11", "value-11").await?;
+        ps.insert_string("key-11", "value-11").await?;
         assert_eq!(ps.store.strings.len(), 11);
 
         assert!(rubinstore.exists());
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn counting_changes_since_save() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let rubinstore = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&rubinstore).await?;
+
+        ps.insert_string("key", "value").await?;
+        ps.incr("counter").await?;
+        assert_eq!(ps.changes_since_save(), 2);
+
+        ps.write().await?;
+        assert_eq!(ps.changes_since_save(), 0);
+
+        let ps = Arc::new(Mutex::new(ps));
+        ps.lock().await.incr("counter").await?;
+
+        let saving = PersistentStore::background_save(&ps).await?;
+        let err = PersistentStore::background_save(&ps).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+        ps.lock().await.incr("counter").await?;
+        saving.await??;
+        assert_eq!(ps.lock().await.changes_since_save(), 1);
+
+        let saved: MemStore = serde_json::from_slice(&std::fs::read(&rubinstore)?)?;
+        assert_eq!(saved.counters.retrieve("counter")?, Some(2));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn saving_when_a_rule_is_met() -> io::Result<()> {
+        let td = create_test_directory()?;
+        let rubinstore = td.join("rubinstore.json");
+        let mut ps = PersistentStore::new(&rubinstore).await?;
+        ps.set_save_rules(vec![SaveRule::new(Duration::ZERO, 2)]);
+
+        let ps = Arc::new(Mutex::new(ps));
+        let task = PersistentStore::schedule_saves(&ps);
+
+        ps.lock().await.insert_string("key", "value").await?;
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!rubinstore.exists());
+
+        ps.lock().await.insert_string("other", "value").await?;
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(rubinstore.exists());
+        assert_eq!(ps.lock().await.changes_since_save(), 0);
+
+        drop(ps);
+        tokio::time::timeout(Duration::from_secs(2), task).await??;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod round_trip {
+    use super::*;
+
+    use crate::store::mem::{EvictionPolicy, KeyType, StreamId};
+    use std::path::Path;
+    use tempdir::TempDir;
+
+    /// Writes the store out and loads it back from the same file
+    async fn reload(ps: PersistentStore, path: &Path) -> io::Result<PersistentStore> {
+        ps.write().await?;
+        drop(ps);
+
+        PersistentStore::from_existing(path).await
+    }
+
+    #[tokio::test]
+    async fn counters_survive_a_restart() -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let path = td.path().join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.incrby("views:home", 42).await?;
+        ps.decr("stock:widgets").await?;
+
+        let mut ps = reload(ps, &path).await?;
+        assert_eq!(ps.store.counters.retrieve("views:home")?, Some(42));
+        assert_eq!(ps.store.counters.retrieve("stock:widgets")?, Some(-1));
+
+        assert_eq!(ps.incr("views:home").await?, 43);
+
+        Ok(())
+    }
+
+    /// Writes a key of every type in the given format and compression and checks they are
+    /// all loaded back
+    async fn check_every_data_type(
+        format: StoreFormat,
+        compression: Compression,
+    ) -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let path = td.path().join("rubinstore.json");
+        let mut ps = PersistentStore::with_format(&path, format).await?;
+        ps.set_compression(compression);
+
+        ps.insert_string("string", "value").await?;
+        ps.insert_bytes("bytes", &[0x00, 0xff]).await?;
+        ps.incrby("counter", 7).await?;
+        ps.pfadd("hyperloglog", &["a", "b", "c"]).await?;
+        ps.rpush("list", &["one", "two"]).await?;
+        ps.hset("hash", &[("field", "value")]).await?;
+        ps.sadd("set", &["member"]).await?;
+        ps.zadd("zset", &[(1.5, "member")]).await?;
+        ps.geoadd("geo", &[(13.361389, 38.115556, "Palermo")])
+            .await?;
+        ps.xadd("stream", Some(StreamId::new(1, 0)), &[("field", "value")])
+            .await?;
+        ps.xgroup_create("stream", "group", Some(StreamId::MIN))
+            .await?;
+        ps.xreadgroup("stream", "group", "consumer", None, None)
+            .await?;
+        ps.json_set("json", "$", r#"{"tags":["a"]}"#).await?;
+        ps.insert_typed("typed", &(1, "one".to_string())).await?;
+
+        let keys = [
+            ("string", KeyType::String),
+            ("bytes", KeyType::String),
+            ("counter", KeyType::Counter),
+            ("hyperloglog", KeyType::HyperLogLog),
+            ("list", KeyType::List),
+            ("hash", KeyType::Hash),
+            ("set", KeyType::Set),
+            ("zset", KeyType::SortedSet),
+            ("geo", KeyType::SortedSet),
+            ("stream", KeyType::Stream),
+            ("json", KeyType::Json),
+            ("typed", KeyType::Typed),
+        ];
+
+        let ps = reload(ps, &path).await?;
+        assert_eq!(ps.format, format);
+        assert_eq!(ps.compression, compression);
+        for (key, key_type) in keys {
+            assert_eq!(ps.store.key_type(key)?, Some(key_type), "{}", key);
+        }
+
+        assert_eq!(ps.get_string("string")?.as_deref(), Some("value"));
+        assert_eq!(ps.store.get_bytes("bytes")?, Some(vec![0x00, 0xff]));
+        assert_eq!(ps.store.counters.retrieve("counter")?, Some(7));
+        assert_eq!(ps.pfcount(&["hyperloglog"])?, 3);
+        assert_eq!(ps.lrange("list", 0, -1)?, vec!["one", "two"]);
+        assert_eq!(ps.hget("hash", "field")?.as_deref(), Some("value"));
+        assert!(ps.sismember("set", "member")?);
+        assert_eq!(ps.zscore("zset", "member")?, Some(1.5));
+
+        let (longitude, latitude) = ps.store.geopos("geo", &["Palermo"])?[0].unwrap();
+        assert!((longitude - 13.361389).abs() < 1e-5);
+        assert!((latitude - 38.115556).abs() < 1e-5);
+
+        assert_eq!(ps.store.xlen("stream")?, 1);
+        let pending = ps.store.xpending("stream", "group")?;
+        assert_eq!(pending.count, 1);
+        assert_eq!(pending.consumers, vec![("consumer".to_string(), 1)]);
+
+        assert_eq!(
+            ps.store.json_get("json", "$.tags")?.as_deref(),
+            Some(r#"[["a"]]"#)
+        );
+        assert_eq!(
+            ps.get_typed::<(i32, String)>("typed")?,
+            Some((1, "one".to_string()))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn every_data_type_survives_a_restart_as_json() -> io::Result<()> {
+        check_every_data_type(StoreFormat::Json, Compression::None).await
+    }
+
+    #[tokio::test]
+    async fn every_data_type_survives_a_restart_as_binary() -> io::Result<()> {
+        check_every_data_type(StoreFormat::Binary, Compression::None).await
+    }
+
+    #[tokio::test]
+    async fn every_data_type_survives_a_restart_compressed() -> io::Result<()> {
+        check_every_data_type(StoreFormat::Json, Compression::Zstd).await?;
+        check_every_data_type(StoreFormat::Binary, Compression::Zstd).await
+    }
+
+    #[tokio::test]
+    async fn writing_reports_the_size_of_the_store() -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let path = td.path().join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        for i in 0..100 {
+            ps.insert_string(&format!("user:{}", i), "value").await?;
+        }
+
+        let size = ps.write().await?;
+        assert_eq!(size.uncompressed, size.compressed);
+        assert_eq!(size.compressed as u64, std::fs::metadata(&path)?.len());
+
+        ps.set_compression(Compression::Zstd);
+        let compressed = ps.write().await?;
+        assert_eq!(compressed.uncompressed, size.uncompressed);
+        assert!(compressed.compressed < size.compressed / 2);
+        assert_eq!(
+            compressed.compressed as u64,
+            std::fs::metadata(&path)?.len()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn expiry_survives_a_restart_for_every_store() -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let path = td.path().join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+
+        ps.incr("counter").await?;
+        ps.rpush("list", &["one"]).await?;
+        ps.json_set("json", "$", "1").await?;
+        for key in ["counter", "list", "json"] {
+            ps.expire(key, 60).await?;
+        }
+
+        let ps = reload(ps, &path).await?;
+        for key in ["counter", "list", "json"] {
+            let ttl = ps.ttl(key)?;
+            assert!(ttl > 0 && ttl <= 60, "{}", key);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn updates_survive_a_restart_from_the_log() -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let path = td.path().join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.insert_string("before", "value").await?;
+        ps.enable_aof(FsyncPolicy::Always).await?;
+
+        ps.incrby("views", 41).await?;
+        ps.incr("views").await?;
+        ps.rpush("queue", &["a", "b"]).await?;
+        ps.lpop("queue").await?;
+        ps.expire("queue", 60).await?;
+        let id = ps.xadd("events", None, &[("type", "signup")]).await?;
+        ps.xgroup_create("events", "group", Some(StreamId::MIN))
+            .await?;
+        ps.xreadgroup("events", "group", "alice", None, None)
+            .await?;
+        ps.xclaim("events", "group", "bob", 0, &[id]).await?;
+        ps.insert_typed("typed", &vec![1, 2]).await?;
+
+        // Never written out as a whole
+        drop(ps);
+
+        let mut ps = PersistentStore::from_existing(&path).await?;
+        assert_eq!(ps.get_string("before")?.as_deref(), Some("value"));
+        assert_eq!(ps.store.counters.retrieve("views")?, Some(42));
+        assert_eq!(ps.lrange("queue", 0, -1)?, vec!["b"]);
+        let ttl = ps.ttl("queue")?;
+        assert!(ttl > 0 && ttl <= 60);
+        assert_eq!(
+            ps.store
+                .xrange("events", StreamId::MIN, StreamId::MAX, None)?[0]
+                .id,
+            id
+        );
+        let pending = ps.store.xpending("events", "group")?;
+        assert_eq!(pending.consumers, vec![("bob".to_string(), 1)]);
+        assert_eq!(ps.get_typed("typed")?, Some(vec![1, 2]));
+
+        // Loading keeps logging to the same log
+        ps.incr("views").await?;
+        drop(ps);
+
+        let ps = PersistentStore::from_existing(&path).await?;
+        assert_eq!(ps.store.counters.retrieve("views")?, Some(43));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn moves_and_swaps_survive_a_restart_from_the_log() -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let (first_path, second_path) = (td.path().join("first"), td.path().join("second"));
+        let mut first = PersistentStore::new(&first_path).await?;
+        let mut second = PersistentStore::new(&second_path).await?;
+        first.enable_aof(FsyncPolicy::Always).await?;
+        second.enable_aof(FsyncPolicy::Always).await?;
+        second
+            .store
+            .set_max_memory(Some(1024 * 1024), EvictionPolicy::AllKeysLru);
+
+        first.insert_string("user:1000", "value").await?;
+        first.expire("user:1000", 60).await?;
+        first.rpush("queue", &["job-1"]).await?;
+        assert!(first.move_key("user:1000", &mut second).await?);
+        assert!(!first.move_key("missing", &mut second).await?);
+        first.swap(&mut second).await?;
+        assert_eq!(first.store.max_memory, None);
+        assert_eq!(second.store.max_memory, Some(1024 * 1024));
+        drop((first, second));
+
+        let first = PersistentStore::from_existing(&first_path).await?;
+        let second = PersistentStore::from_existing(&second_path).await?;
+        assert_eq!(first.get_string("user:1000")?.as_deref(), Some("value"));
+        let ttl = first.ttl("user:1000")?;
+        assert!(ttl > 0 && ttl <= 60);
+        assert_eq!(first.exists(&["queue"])?, 0);
+        assert_eq!(second.lrange("queue", 0, -1)?, vec!["job-1"]);
+        assert_eq!(second.exists(&["user:1000"])?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn in_memory_stores_cannot_be_written() -> io::Result<()> {
+        let mut ps = PersistentStore::in_memory();
+        ps.insert_string("user:1000", "value").await?;
+
+        let error = ps.write().await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let error = ps.enable_aof(FsyncPolicy::Always).await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn disabling_the_log_writes_the_store() -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let path = td.path().join("rubinstore.json");
+        let log = td.path().join("rubinstore.json.aof");
+
+        let mut ps = PersistentStore::new(&path).await?;
+        assert!(ps.rewrite_aof().is_err());
+
+        ps.enable_aof(FsyncPolicy::Never).await?;
+        assert!(log.exists());
+        ps.incr("views").await?;
+        assert!(ps.rewrite_aof()?);
+        ps.incr("views").await?;
+
+        ps.disable_aof().await?;
+        assert!(!log.exists());
+        drop(ps);
+
+        let ps = PersistentStore::from_existing(&path).await?;
+        assert_eq!(ps.store.counters.retrieve("views")?, Some(2));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn loading_keeps_memory_settings() -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let path = td.path().join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.insert_string("key", "value").await?;
+        ps.write().await?;
+
+        ps.store
+            .set_max_memory(Some(1024 * 1024), EvictionPolicy::AllKeysLru);
+        ps.load().await?;
+
+        assert_eq!(ps.store.max_memory, Some(1024 * 1024));
+        assert_eq!(ps.store.eviction_policy, EvictionPolicy::AllKeysLru);
+        assert_eq!(ps.get_string("key")?.as_deref(), Some("value"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn loading_a_corrupt_store_falls_back_or_errors() -> io::Result<()> {
+        let td = TempDir::new("roundtrip")?;
+        let path = td.path().join("rubinstore.json");
+        let mut ps = PersistentStore::new(&path).await?;
+        ps.insert_string("key", "first").await?;
+        ps.write().await?;
+        ps.insert_string("key", "second").await?;
+        ps.write().await?;
+        drop(ps);
+
+        std::fs::write(&path, b"{\"strings\": {")?;
+        let ps = PersistentStore::from_existing(&path).await?;
+        assert_eq!(ps.get_string("key")?.as_deref(), Some("first"));
+        drop(ps);
+
+        std::fs::remove_file(backup_path(&path))?;
+        let err = PersistentStore::from_existing(&path).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,286 @@
+//! Rules for writing a store to disk periodically in the background
+//!
+//! A [`SaveRule`] asks for the store to be written once at least a number of changes have been
+//! made within a period, such as "at least 100 changes in 5 minutes". A [`SaveSchedule`]
+//! counts the changes made since the store was last written and checks them against its
+//! rules, so a background task only writes the store when it is worth doing.
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::store::now_millis;
+
+/// Save rules used when none are given, matching the defaults of Redis
+pub const DEFAULT_SAVE_RULES: [SaveRule; 3] = [
+    SaveRule::new(Duration::from_secs(3600), 1),
+    SaveRule::new(Duration::from_secs(300), 100),
+    SaveRule::new(Duration::from_secs(60), 10000),
+];
+
+/// Writes the store once at least `changes` changes have been made within `period`
+///
+/// Written as `<seconds> <changes>` when parsed from a string.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rubin::store::persistence::SaveRule;
+///
+/// let rule: SaveRule = "300 100".parse().unwrap();
+///
+/// assert_eq!(rule, SaveRule::new(Duration::from_secs(300), 100));
+/// assert_eq!(rule.to_string(), "300 100");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveRule {
+    /// Time since the store was last written
+    pub period: Duration,
+
+    /// Number of changes made since the store was last written
+    pub changes: u64,
+}
+
+impl SaveRule {
+    /// Creates a rule which writes the store after `changes` changes within `period`
+    pub const fn new(period: Duration, changes: u64) -> Self {
+        Self { period, changes }
+    }
+
+    /// Checks if the rule is met by a number of changes made over an elapsed time
+    fn is_met(&self, changes: u64, elapsed: Duration) -> bool {
+        changes > 0 && changes >= self.changes && elapsed >= self.period
+    }
+}
+
+impl FromStr for SaveRule {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid save rule, expected <seconds> <changes>: {}", s),
+            )
+        };
+
+        let mut parts = s.split_whitespace();
+        let (Some(seconds), Some(changes), None) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        let seconds = seconds.parse().map_err(|_| invalid())?;
+        let changes = changes.parse().map_err(|_| invalid())?;
+        Ok(Self::new(Duration::from_secs(seconds), changes))
+    }
+}
+
+impl fmt::Display for SaveRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.period.as_secs(), self.changes)
+    }
+}
+
+/// Tracks changes made to a store since it was last written against a set of [`SaveRule`]s
+///
+/// Counters are atomic so a store can be written, and the schedule updated, through a shared
+/// reference.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rubin::store::persistence::{SaveRule, SaveSchedule};
+///
+/// let schedule = SaveSchedule::new(vec![SaveRule::new(Duration::ZERO, 2)]);
+///
+/// schedule.record_change();
+/// assert!(!schedule.is_due());
+///
+/// schedule.record_change();
+/// assert!(schedule.is_due());
+///
+/// schedule.saved(schedule.changes());
+/// assert_eq!(schedule.changes(), 0);
+/// ```
+#[derive(Debug)]
+pub struct SaveSchedule {
+    /// Rules checked to decide whether the store should be written
+    rules: Vec<SaveRule>,
+
+    /// Number of changes made since the store was last written
+    changes: AtomicU64,
+
+    /// Time the store was last written, in milliseconds since the Unix epoch
+    last_save: AtomicU64,
+
+    /// Whether a background save is in progress
+    saving: AtomicBool,
+}
+
+impl SaveSchedule {
+    /// Creates a schedule with the given rules, treating the store as just written
+    pub fn new(rules: Vec<SaveRule>) -> Self {
+        Self {
+            rules,
+            changes: AtomicU64::new(0),
+            last_save: AtomicU64::new(now_millis()),
+            saving: AtomicBool::new(false),
+        }
+    }
+
+    /// Gets the rules of the schedule
+    pub fn rules(&self) -> &[SaveRule] {
+        &self.rules
+    }
+
+    /// Replaces the rules of the schedule, no rules disables saving in the background
+    pub fn set_rules(&mut self, rules: Vec<SaveRule>) {
+        self.rules = rules;
+    }
+
+    /// Records a change made to the store
+    pub fn record_change(&self) {
+        self.changes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Gets the number of changes made since the store was last written
+    pub fn changes(&self) -> u64 {
+        self.changes.load(Ordering::Relaxed)
+    }
+
+    /// Gets the time the store was last written as seconds since the Unix epoch
+    ///
+    /// This is the time the schedule was created if the store has not been written since.
+    pub fn last_save(&self) -> u64 {
+        self.last_save.load(Ordering::Relaxed) / 1000
+    }
+
+    /// Checks if any rule is met and no background save is in progress
+    pub fn is_due(&self) -> bool {
+        if self.saving.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let changes = self.changes();
+        let elapsed = now_millis().saturating_sub(self.last_save.load(Ordering::Relaxed));
+        self.rules
+            .iter()
+            .any(|rule| rule.is_met(changes, Duration::from_millis(elapsed)))
+    }
+
+    /// Records that the store was written, including the given number of changes
+    ///
+    /// Changes made while the store was being written are kept for the next save.
+    pub fn saved(&self, changes: u64) {
+        // Saturate as another save may have already included some of the changes
+        let _ = self
+            .changes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                Some(current.saturating_sub(changes))
+            });
+        self.last_save.store(now_millis(), Ordering::Relaxed);
+    }
+
+    /// Marks a background save as started
+    ///
+    /// # Returns
+    ///
+    /// * The number of changes the save will include
+    ///
+    /// # Errors
+    ///
+    /// * [`io::ErrorKind::AlreadyExists`] if a background save is already in progress
+    pub fn begin_background_save(&self) -> io::Result<u64> {
+        if self.saving.swap(true, Ordering::AcqRel) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "background save already in progress",
+            ));
+        }
+
+        Ok(self.changes())
+    }
+
+    /// Marks a background save as finished
+    ///
+    /// `saved` is the number of changes the save included if it succeeded, `None` if it failed.
+    pub fn end_background_save(&self, saved: Option<u64>) {
+        if let Some(changes) = saved {
+            self.saved(changes);
+        }
+
+        self.saving.store(false, Ordering::Release);
+    }
+
+    /// Checks if a background save is in progress
+    pub fn is_saving(&self) -> bool {
+        self.saving.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for SaveSchedule {
+    /// Creates a schedule without any rules, never saving in the background
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod save_tests {
+    use super::*;
+
+    #[test]
+    fn parses_save_rules() {
+        let rule: SaveRule = " 60   10000 ".parse().unwrap();
+        assert_eq!(rule, DEFAULT_SAVE_RULES[2]);
+
+        for invalid in ["", "60", "60 10 1", "sixty 10", "60 -1"] {
+            let err = invalid.parse::<SaveRule>().unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn waits_for_changes_and_time() {
+        let schedule = SaveSchedule::new(vec![SaveRule::new(Duration::from_secs(60), 1)]);
+        schedule.record_change();
+        assert!(!schedule.is_due());
+
+        schedule
+            .last_save
+            .store(now_millis() - 61_000, Ordering::Relaxed);
+        assert!(schedule.is_due());
+
+        // A rule of zero changes still needs something to write
+        let schedule = SaveSchedule::new(vec![SaveRule::new(Duration::ZERO, 0)]);
+        assert!(!schedule.is_due());
+        assert!(!SaveSchedule::default().is_due());
+    }
+
+    #[test]
+    fn keeps_changes_made_during_a_background_save() {
+        let schedule = SaveSchedule::new(vec![SaveRule::new(Duration::ZERO, 1)]);
+        schedule.record_change();
+        schedule.record_change();
+
+        let changes = schedule.begin_background_save().unwrap();
+        assert_eq!(changes, 2);
+        assert!(!schedule.is_due());
+        assert!(schedule.begin_background_save().is_err());
+
+        schedule.record_change();
+        schedule.end_background_save(Some(changes));
+        assert_eq!(schedule.changes(), 1);
+        assert!(schedule.is_due());
+
+        let changes = schedule.begin_background_save().unwrap();
+        schedule.end_background_save(None);
+        assert_eq!(schedule.changes(), changes);
+        assert!(!schedule.is_saving());
+    }
+}
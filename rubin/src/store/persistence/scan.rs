@@ -0,0 +1,90 @@
+//! Cursor-based iteration for the [`PersistentStore`]
+//!
+//! Wraps the scan operations of the [`crate::store::mem::MemStore`]. Scanning does not
+//! modify the store, so nothing is written to disk.
+
+use std::io;
+
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Iterates over the keys in the store, a page at a time
+    ///
+    /// See [`crate::store::mem::MemStore::scan`] for details of the cursor and arguments.
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_string("user:1000", "value").await?;
+    ///
+    ///     let (cursor, keys) = ps.scan(0, Some("user:*"), 10)?;
+    ///     assert_eq!(cursor, 0);
+    ///     assert_eq!(keys, vec!["user:1000"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn scan(
+        &self,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> io::Result<(u64, Vec<String>)> {
+        self.store.scan(cursor, pattern, count)
+    }
+
+    /// Iterates over the field-value pairs of a hash, a page at a time
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.hset("user:1000", &[("name", "rubin")]).await?;
+    ///
+    ///     let (_, fields) = ps.hscan("user:1000", 0, None, 10)?;
+    ///     assert_eq!(fields, vec![("name".to_string(), "rubin".to_string())]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn hscan(
+        &self,
+        key: &str,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> io::Result<(u64, Vec<(String, String)>)> {
+        self.store.hscan(key, cursor, pattern, count)
+    }
+
+    /// Iterates over the members of a set, a page at a time
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("tags", &["rust"]).await?;
+    ///
+    ///     let (_, members) = ps.sscan("tags", 0, None, 10)?;
+    ///     assert_eq!(members, vec!["rust"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sscan(
+        &self,
+        key: &str,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> io::Result<(u64, Vec<String>)> {
+        self.store.sscan(key, cursor, pattern, count)
+    }
+}
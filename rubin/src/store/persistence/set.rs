@@ -0,0 +1,256 @@
+//! Set operations for the [`PersistentStore`]
+//!
+//! Wraps the set operations of the [`crate::store::mem::MemStore`], logging each update if
+//! the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::collections::HashSet;
+use std::io;
+
+use crate::store::persistence::aof::{owned, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Adds one or more members to a set, returning the number of members added
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let added = ps.sadd("flags", &["dark-mode", "beta"]).await?;
+    ///
+    ///     assert_eq!(added, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sadd(&mut self, key: &str, members: &[&str]) -> io::Result<usize> {
+        let result = self.store.sadd(key, members)?;
+        self.updated(|_| Command::SAdd(key.into(), owned(members)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Removes one or more members from a set, returning the number of members removed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags", &["dark-mode", "beta"]).await?;
+    ///
+    ///     let removed = ps.srem("flags", &["beta"]).await?;
+    ///     assert_eq!(removed, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn srem(&mut self, key: &str, members: &[&str]) -> io::Result<usize> {
+        let result = self.store.srem(key, members)?;
+        self.updated(|_| Command::SRem(key.into(), owned(members)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Checks if a value is a member of a set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags", &["dark-mode"]).await?;
+    ///
+    ///     assert!(ps.sismember("flags", "dark-mode")?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sismember(&self, key: &str, member: &str) -> io::Result<bool> {
+        self.store.sismember(key, member)
+    }
+
+    /// Retrieves all members of a set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags", &["dark-mode"]).await?;
+    ///
+    ///     let flags = ps.smembers("flags")?;
+    ///     assert!(flags.contains("dark-mode"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn smembers(&self, key: &str) -> io::Result<HashSet<String>> {
+        self.store.smembers(key)
+    }
+
+    /// Returns the number of members in a set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags", &["dark-mode"]).await?;
+    ///
+    ///     assert_eq!(ps.scard("flags")?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn scard(&self, key: &str) -> io::Result<usize> {
+        self.store.scard(key)
+    }
+
+    /// Returns the union of all of the given sets
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags:a", &["dark-mode"]).await?;
+    ///     ps.sadd("flags:b", &["beta"]).await?;
+    ///
+    ///     let flags = ps.sunion(&["flags:a", "flags:b"])?;
+    ///     assert_eq!(flags.len(), 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sunion(&self, keys: &[&str]) -> io::Result<HashSet<String>> {
+        self.store.sunion(keys)
+    }
+
+    /// Returns the intersection of all of the given sets
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags:a", &["dark-mode", "beta"]).await?;
+    ///     ps.sadd("flags:b", &["beta"]).await?;
+    ///
+    ///     let flags = ps.sinter(&["flags:a", "flags:b"])?;
+    ///     assert_eq!(flags.len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sinter(&self, keys: &[&str]) -> io::Result<HashSet<String>> {
+        self.store.sinter(keys)
+    }
+
+    /// Returns the members of the first set not present in any of the following sets
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags:a", &["dark-mode", "beta"]).await?;
+    ///     ps.sadd("flags:b", &["beta"]).await?;
+    ///
+    ///     let flags = ps.sdiff(&["flags:a", "flags:b"])?;
+    ///     assert!(flags.contains("dark-mode"));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sdiff(&self, keys: &[&str]) -> io::Result<HashSet<String>> {
+        self.store.sdiff(keys)
+    }
+
+    /// Stores the union of the given sets in the `destination` set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags:a", &["dark-mode"]).await?;
+    ///     ps.sadd("flags:b", &["beta"]).await?;
+    ///
+    ///     let count = ps.sunionstore("flags:all", &["flags:a", "flags:b"]).await?;
+    ///     assert_eq!(count, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sunionstore(&mut self, destination: &str, keys: &[&str]) -> io::Result<usize> {
+        let result = self.store.sunionstore(destination, keys)?;
+        self.updated(|_| Command::SUnionStore(destination.into(), owned(keys)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Stores the intersection of the given sets in the `destination` set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags:a", &["dark-mode", "beta"]).await?;
+    ///     ps.sadd("flags:b", &["beta"]).await?;
+    ///
+    ///     let count = ps.sinterstore("flags:common", &["flags:a", "flags:b"]).await?;
+    ///     assert_eq!(count, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sinterstore(&mut self, destination: &str, keys: &[&str]) -> io::Result<usize> {
+        let result = self.store.sinterstore(destination, keys)?;
+        self.updated(|_| Command::SInterStore(destination.into(), owned(keys)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Stores the difference of the given sets in the `destination` set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.sadd("flags:a", &["dark-mode", "beta"]).await?;
+    ///     ps.sadd("flags:b", &["beta"]).await?;
+    ///
+    ///     let count = ps.sdiffstore("flags:only-a", &["flags:a", "flags:b"]).await?;
+    ///     assert_eq!(count, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn sdiffstore(&mut self, destination: &str, keys: &[&str]) -> io::Result<usize> {
+        let result = self.store.sdiffstore(destination, keys)?;
+        self.updated(|_| Command::SDiffStore(destination.into(), owned(keys)))
+            .await?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,308 @@
+//! Stream operations for the [`PersistentStore`]
+//!
+//! Wraps the stream operations of the [`crate::store::mem::MemStore`], logging each update
+//! if the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set. Reading as part of a consumer group updates the group's
+//! pending entries, so is also written.
+
+use std::io;
+
+use crate::store::mem::{PendingEntry, PendingSummary, StreamEntry, StreamId, StreamTrim};
+use crate::store::persistence::aof::{owned_pairs, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Appends an entry to a stream, generating an ID from the current time if `id` is `None`
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let id = ps.xadd("events", None, &[("type", "signup")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xadd(
+        &mut self,
+        key: &str,
+        id: Option<StreamId>,
+        fields: &[(&str, &str)],
+    ) -> io::Result<StreamId> {
+        let result = self.store.xadd(key, id, fields)?;
+        self.updated(|_| Command::XAdd(key.into(), result, owned_pairs(fields)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Gets the entries of a stream with IDs in an inclusive range, from lowest to highest
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    /// use rubin::store::mem::StreamId;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let entries = ps.xrange("events", StreamId::MIN, StreamId::MAX, None)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn xrange(
+        &self,
+        key: &str,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+    ) -> io::Result<Vec<StreamEntry>> {
+        self.store.xrange(key, start, end, count)
+    }
+
+    /// Gets the entries of a stream with IDs in an inclusive range, from highest to lowest
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    /// use rubin::store::mem::StreamId;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let latest = ps.xrevrange("events", StreamId::MAX, StreamId::MIN, Some(1))?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn xrevrange(
+        &self,
+        key: &str,
+        end: StreamId,
+        start: StreamId,
+        count: Option<usize>,
+    ) -> io::Result<Vec<StreamEntry>> {
+        self.store.xrevrange(key, end, start, count)
+    }
+
+    /// Gets the number of entries in a stream
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.xadd("events", None, &[("type", "signup")]).await?;
+    ///
+    ///     assert_eq!(ps.xlen("events")?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn xlen(&self, key: &str) -> io::Result<usize> {
+        self.store.xlen(key)
+    }
+
+    /// Removes the oldest entries from a stream, returning the number removed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    /// use rubin::store::mem::StreamTrim;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.xtrim("events", StreamTrim::MaxLen(1000)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xtrim(&mut self, key: &str, trim: StreamTrim) -> io::Result<usize> {
+        let result = self.store.xtrim(key, trim)?;
+        self.updated(|_| Command::XTrim(key.into(), trim)).await?;
+
+        Ok(result)
+    }
+
+    /// Creates a consumer group for a stream, reading entries after `last_delivered` or only
+    /// new entries if `None`
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.xgroup_create("jobs", "workers", None).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xgroup_create(
+        &mut self,
+        key: &str,
+        group: &str,
+        last_delivered: Option<StreamId>,
+    ) -> io::Result<()> {
+        self.store.xgroup_create(key, group, last_delivered)?;
+        self.updated(|_| Command::XGroupCreate(key.into(), group.into(), last_delivered))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes a consumer group, returning `true` if it was present
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.xgroup_destroy("jobs", "workers").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xgroup_destroy(&mut self, key: &str, group: &str) -> io::Result<bool> {
+        let result = self.store.xgroup_destroy(key, group)?;
+        self.updated(|_| Command::XGroupDestroy(key.into(), group.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Reads entries from a stream as a consumer in a group
+    ///
+    /// New entries are read if `after` is `None`, otherwise the consumer's pending entries
+    /// after the ID are read again
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let jobs = ps.xreadgroup("jobs", "workers", "worker-1", None, Some(10)).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xreadgroup(
+        &mut self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        after: Option<StreamId>,
+        count: Option<usize>,
+    ) -> io::Result<Vec<StreamEntry>> {
+        let result = self.store.xreadgroup(key, group, consumer, after, count)?;
+        self.updated(|_| {
+            Command::XReadGroup(key.into(), group.into(), consumer.into(), after, count)
+        })
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Acknowledges entries delivered to a consumer group, returning the number acknowledged
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    /// use rubin::store::mem::StreamId;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.xack("jobs", "workers", &[StreamId::new(1526919030474, 0)]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xack(&mut self, key: &str, group: &str, ids: &[StreamId]) -> io::Result<usize> {
+        let result = self.store.xack(key, group, ids)?;
+        self.updated(|_| Command::XAck(key.into(), group.into(), ids.to_vec()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Summarises the pending entries of a consumer group
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let summary = ps.xpending("jobs", "workers")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn xpending(&self, key: &str, group: &str) -> io::Result<PendingSummary> {
+        self.store.xpending(key, group)
+    }
+
+    /// Gets the pending entries of a consumer group with IDs in an inclusive range
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    /// use rubin::store::mem::StreamId;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let pending = ps.xpending_range("jobs", "workers", StreamId::MIN, StreamId::MAX, 10, None)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn xpending_range(
+        &self,
+        key: &str,
+        group: &str,
+        start: StreamId,
+        end: StreamId,
+        count: usize,
+        consumer: Option<&str>,
+    ) -> io::Result<Vec<PendingEntry>> {
+        self.store
+            .xpending_range(key, group, start, end, count, consumer)
+    }
+
+    /// Transfers pending entries idle for at least `min_idle` milliseconds to `consumer`,
+    /// returning the entries claimed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    /// use rubin::store::mem::StreamId;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.xclaim("jobs", "workers", "worker-2", 60_000, &[StreamId::new(1526919030474, 0)]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn xclaim(
+        &mut self,
+        key: &str,
+        group: &str,
+        consumer: &str,
+        min_idle: u64,
+        ids: &[StreamId],
+    ) -> io::Result<Vec<StreamEntry>> {
+        let result = self.store.xclaim(key, group, consumer, min_idle, ids)?;
+        self.updated(|_| {
+            let ids = result.iter().map(|entry| entry.id).collect();
+            Command::XClaim(key.into(), group.into(), consumer.into(), ids)
+        })
+        .await?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,188 @@
+//! String manipulation operations for the [`PersistentStore`]
+//!
+//! Wraps the string operations of the [`crate::store::mem::MemStore`], logging each update
+//! if the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use crate::store::persistence::aof::{owned_pairs, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Appends a value to a string, returning the length of the string after the append
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.append("log", "started;").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn append(&mut self, key: &str, value: &str) -> io::Result<usize> {
+        let result = self.store.append(key, value)?;
+        self.updated(|_| Command::Append(key.into(), value.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Gets part of a string between two byte indices (inclusive)
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let prefix = ps.getrange("greeting", 0, 4)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn getrange(&self, key: &str, start: isize, end: isize) -> io::Result<String> {
+        self.store.getrange(key, start, end)
+    }
+
+    /// Overwrites part of a string from a byte offset, returning the length of the string
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.setrange("greeting", 7, "Rubin").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn setrange(&mut self, key: &str, offset: usize, value: &str) -> io::Result<usize> {
+        let result = self.store.setrange(key, offset, value)?;
+        self.updated(|_| Command::SetRange(key.into(), offset, value.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Gets the length of a string in bytes
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let len = ps.strlen("greeting")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn strlen(&self, key: &str) -> io::Result<usize> {
+        self.store.strlen(key)
+    }
+
+    /// Inserts a string if the key is not present, returning `true` if it was inserted
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.setnx("lock:orders", "worker-1").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn setnx(&mut self, key: &str, value: &str) -> io::Result<bool> {
+        let result = self.store.setnx(key, value)?;
+        self.updated(|_| Command::SetNx(key.into(), value.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Removes a string, returning the value it held
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let token = ps.getdel("token:1000").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn getdel(&mut self, key: &str) -> io::Result<Option<String>> {
+        let result = self.store.getdel(key)?;
+        self.updated(|_| Command::GetDel(key.into())).await?;
+
+        Ok(result)
+    }
+
+    /// Gets the strings held by many keys at once
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let users = ps.mget(&["user:1", "user:2"])?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn mget(&self, keys: &[&str]) -> io::Result<Vec<Option<String>>> {
+        self.store.mget(keys)
+    }
+
+    /// Inserts many strings at once
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.mset(&[("user:1", "alice"), ("user:2", "bob")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn mset(&mut self, pairs: &[(&str, &str)]) -> io::Result<()> {
+        self.store.mset(pairs)?;
+        self.updated(|_| Command::MSet(owned_pairs(pairs))).await?;
+
+        Ok(())
+    }
+
+    /// Inserts many strings at once if none of the keys are present, returning `true` if
+    /// they were inserted
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.msetnx(&[("user:1", "alice"), ("user:2", "bob")]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn msetnx(&mut self, pairs: &[(&str, &str)]) -> io::Result<bool> {
+        let result = self.store.msetnx(pairs)?;
+        self.updated(|_| Command::MSetNx(owned_pairs(pairs)))
+            .await?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,71 @@
+//! Typed value operations for the [`PersistentStore`]
+//!
+//! Wraps the typed value operations of the [`crate::store::mem::MemStore`], logging each
+//! update if the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::store::mem::TypedValue;
+use crate::store::persistence::aof::Command;
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Serializes a value into the typed value store with a given key
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_typed("scores", &vec![10, 20, 30]).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn insert_typed<T: Serialize>(&mut self, key: &str, value: &T) -> io::Result<()> {
+        self.store.insert_typed(key, value)?;
+        self.updated(|store| {
+            let typed = store.typed.get(key).cloned().unwrap_or_default();
+            Command::InsertTyped(key.into(), typed)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Inserts an already serialized value into the typed value store
+    ///
+    /// Used by the server, which receives values serialized by the client.
+    pub(crate) async fn insert_tagged(&mut self, key: &str, typed: TypedValue) -> io::Result<()> {
+        self.store.insert_tagged(key, typed.clone())?;
+        self.updated(|_| Command::InsertTyped(key.into(), typed))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves and deserializes a value from the typed value store, returning an error if
+    /// it was stored as a different type
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.insert_typed("scores", &vec![10, 20, 30]).await?;
+    ///
+    ///     let scores: Option<Vec<i32>> = ps.get_typed("scores")?;
+    ///     assert_eq!(scores, Some(vec![10, 20, 30]));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_typed<T: DeserializeOwned>(&self, key: &str) -> io::Result<Option<T>> {
+        self.store.get_typed(key)
+    }
+}
@@ -0,0 +1,211 @@
+//! Sorted set operations for the [`PersistentStore`]
+//!
+//! Wraps the sorted set operations of the [`crate::store::mem::MemStore`], logging each
+//! update if the append-only log is enabled and writing to disk after each update if
+//! `write_on_update` is set.
+
+use std::io;
+
+use crate::store::persistence::aof::{owned, Command};
+use crate::store::persistence::PersistentStore;
+
+impl PersistentStore {
+    /// Adds one or more members with scores to a sorted set, returning the number of members added
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     let added = ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///     assert_eq!(added, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zadd(&mut self, key: &str, members: &[(f64, &str)]) -> io::Result<usize> {
+        let result = self.store.zadd(key, members)?;
+        self.updated(|_| {
+            let members = members
+                .iter()
+                .map(|(score, member)| (*score, member.to_string()))
+                .collect();
+            Command::ZAdd(key.into(), members)
+        })
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Removes one or more members from a sorted set, returning the number of members removed
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///
+    ///     let removed = ps.zrem("leaderboard", &["bob"]).await?;
+    ///     assert_eq!(removed, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zrem(&mut self, key: &str, members: &[&str]) -> io::Result<usize> {
+        let result = self.store.zrem(key, members)?;
+        self.updated(|_| Command::ZRem(key.into(), owned(members)))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Retrieves the score of a member in a sorted set
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///
+    ///     assert_eq!(ps.zscore("leaderboard", "alice")?, Some(100.0));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn zscore(&self, key: &str, member: &str) -> io::Result<Option<f64>> {
+        self.store.zscore(key, member)
+    }
+
+    /// Increments the score of a member in a sorted set, returning the new score
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///
+    ///     let score = ps.zincrby("leaderboard", 10.0, "bob").await?;
+    ///     assert_eq!(score, 85.0);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn zincrby(&mut self, key: &str, increment: f64, member: &str) -> io::Result<f64> {
+        let result = self.store.zincrby(key, increment, member)?;
+        self.updated(|_| Command::ZIncrBy(key.into(), increment, member.into()))
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Retrieves the rank of a member in a sorted set, ordered from lowest to highest score
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///
+    ///     assert_eq!(ps.zrank("leaderboard", "bob")?, Some(0));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn zrank(&self, key: &str, member: &str) -> io::Result<Option<usize>> {
+        self.store.zrank(key, member)
+    }
+
+    /// Retrieves the members and scores of a sorted set between `start` and `stop` (inclusive)
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///
+    ///     let members = ps.zrange("leaderboard", 0, -1)?;
+    ///     assert_eq!(members.len(), 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn zrange(&self, key: &str, start: isize, stop: isize) -> io::Result<Vec<(String, f64)>> {
+        self.store.zrange(key, start, stop)
+    }
+
+    /// Retrieves the members and scores of a sorted set between `start` and `stop` (inclusive)
+    /// ordered from highest to lowest score
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///
+    ///     let top = ps.zrevrange("leaderboard", 0, 0)?;
+    ///     assert_eq!(top[0].0, "alice");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn zrevrange(
+        &self,
+        key: &str,
+        start: isize,
+        stop: isize,
+    ) -> io::Result<Vec<(String, f64)>> {
+        self.store.zrevrange(key, start, stop)
+    }
+
+    /// Retrieves the members and scores of a sorted set with a score between `min` and `max` (inclusive)
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///
+    ///     let members = ps.zrangebyscore("leaderboard", 80.0, f64::INFINITY)?;
+    ///     assert_eq!(members.len(), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn zrangebyscore(&self, key: &str, min: f64, max: f64) -> io::Result<Vec<(String, f64)>> {
+        self.store.zrangebyscore(key, min, max)
+    }
+
+    /// Counts the members of a sorted set with a score between `min` and `max` (inclusive)
+    ///
+    /// ```rust,no_run
+    /// use rubin::store::persistence::PersistentStore;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let mut ps = PersistentStore::new("./storage/file.json").await?;
+    ///     ps.zadd("leaderboard", &[(100.0, "alice"), (75.0, "bob")]).await?;
+    ///
+    ///     assert_eq!(ps.zcount("leaderboard", 0.0, 100.0)?, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn zcount(&self, key: &str, min: f64, max: f64) -> io::Result<usize> {
+        self.store.zcount(key, min, max)
+    }
+}
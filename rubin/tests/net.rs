@@ -1,7 +1,15 @@
 #[cfg(test)]
 mod net_integration_tests {
     use rubin::net::client::RubinClient;
-    use rubin::net::server::start;
+    use rubin::net::parser::{create_request, Operation};
+    use rubin::net::server::{start, start_with_config, start_with_shutdown, ServerConfig};
+    use rubin::store::mem::{
+        BitOp, EvictionPolicy, GeoOrigin, GeoSearch, GeoShape, GeoUnit, StreamTrim,
+    };
+    use rubin::store::persistence::{FsyncPolicy, PersistentStore, SaveRule};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+    use std::time::Duration;
 
     async fn sleep(duration: u64) {
         tokio::time::sleep(tokio::time::Duration::from_millis(duration)).await;
@@ -29,7 +37,7 @@ mod net_integration_tests {
 
         sleep(500).await;
         let response = client.get_string("user:1000").await.unwrap();
-        assert_eq!(&response, "value1");
+        assert_eq!(response.as_deref(), Some("value1"));
 
         server.abort();
     }
@@ -46,4 +54,1023 @@ mod net_integration_tests {
 
         server.abort();
     }
+
+    #[tokio::test]
+    async fn gets_an_error_from_a_request_which_may_not_return_a_value() {
+        let server = tokio::spawn(start("127.0.0.1", 9904));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9904);
+
+        let msg = create_request(Operation::ListIndex, vec!["queue".into(), "one".into()]);
+        let err = client.request_optional(&msg).await.unwrap_err();
+        assert_eq!(err.to_string(), "invalid argument: one");
+        let missing = client.lindex("queue", 0).await.unwrap();
+        assert_eq!(missing, None);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn performs_list_operations_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9879));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9879);
+        let length = client
+            .rpush("queue", &["job-1", "job-2", "job-3"])
+            .await
+            .unwrap();
+        assert_eq!(&length, "3");
+
+        let job = client.lpop("queue").await.unwrap();
+        assert_eq!(job.as_deref(), Some("job-1"));
+
+        let jobs = client.lrange("queue", 0, -1).await.unwrap();
+        assert_eq!(jobs, vec!["job-2", "job-3"]);
+
+        let response = client.lset("queue", 10, "job-4").await.unwrap();
+        assert_eq!(&response, "index out of range");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn pushes_large_lists_of_values_containing_spaces() {
+        let server = tokio::spawn(start("127.0.0.1", 9902));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9902);
+        let length = client
+            .rpush("queue", &["send email", "resize image"])
+            .await
+            .unwrap();
+        assert_eq!(&length, "2");
+
+        let length = client.lpush("queue", &["urgent job"]).await.unwrap();
+        assert_eq!(&length, "3");
+
+        let jobs = client.lrange("queue", 0, -1).await.unwrap();
+        assert_eq!(jobs, vec!["urgent job", "send email", "resize image"]);
+
+        // Spans several reads on the server
+        let values = (0..2000).map(|n| format!("job {}", n)).collect::<Vec<_>>();
+        let values = values.iter().map(String::as_str).collect::<Vec<_>>();
+        let length = client.rpush("large", &values).await.unwrap();
+        assert_eq!(&length, "2000");
+
+        let last = client.lindex("large", -1).await.unwrap();
+        assert_eq!(last.as_deref(), Some("job 1999"));
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn performs_hash_operations_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9880));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9880);
+        let added = client
+            .hset("user:1000", &[("name", "rubin"), ("visits", "1")])
+            .await
+            .unwrap();
+        assert_eq!(&added, "2");
+
+        let name = client.hget("user:1000", "name").await.unwrap();
+        assert_eq!(name.as_deref(), Some("rubin"));
+
+        let visits = client.hincrby("user:1000", "visits", 4).await.unwrap();
+        assert_eq!(&visits, "5");
+
+        let user = client.hgetall("user:1000").await.unwrap();
+        assert_eq!(user.get("visits").unwrap(), "5");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn keeps_spaces_within_hash_fields_and_values() {
+        let server = tokio::spawn(start("127.0.0.1", 9901));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9901);
+        let added = client
+            .hset(
+                "user:1000",
+                &[("full name", "John Q Smith"), ("path", r"C:\\data")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(&added, "2");
+
+        let name = client.hget("user:1000", "full name").await.unwrap();
+        assert_eq!(name.as_deref(), Some("John Q Smith"));
+
+        let user = client.hgetall("user:1000").await.unwrap();
+        assert_eq!(user.len(), 2);
+        assert_eq!(user.get("path").unwrap(), r"C:\\data");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn performs_set_operations_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9881));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9881);
+        let added = client
+            .sadd("flags:a", &["dark-mode", "beta"])
+            .await
+            .unwrap();
+        assert_eq!(&added, "2");
+        client.sadd("flags:b", &["beta"]).await.unwrap();
+
+        let member = client.sismember("flags:a", "beta").await.unwrap();
+        assert_eq!(&member, "1");
+
+        let common = client.sinter(&["flags:a", "flags:b"]).await.unwrap();
+        assert!(common.contains("beta"));
+        assert_eq!(common.len(), 1);
+
+        let count = client
+            .sunionstore("flags:all", &["flags:a", "flags:b"])
+            .await
+            .unwrap();
+        assert_eq!(&count, "2");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn performs_sorted_set_operations_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9882));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9882);
+        let added = client
+            .zadd(
+                "leaderboard",
+                &[(100.0, "alice"), (75.0, "bob"), (50.0, "carol")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(&added, "3");
+
+        let score = client.zincrby("leaderboard", 30.0, "bob").await.unwrap();
+        assert_eq!(&score, "105");
+
+        let top = client.zrevrange("leaderboard", 0, 0).await.unwrap();
+        assert_eq!(top, vec![("bob".to_string(), 105.0)]);
+
+        let members = client
+            .zrangebyscore("leaderboard", 60.0, f64::INFINITY)
+            .await
+            .unwrap();
+        assert_eq!(members.len(), 2);
+
+        let rank = client.zrank("leaderboard", "missing").await.unwrap();
+        assert_eq!(rank, None);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn expires_keys_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9883));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9883);
+        client
+            .insert_string_with_expiry("session", "token", Duration::from_millis(200))
+            .await
+            .unwrap();
+        client.insert_string("user", "rubin").await.unwrap();
+
+        let updated = client.expire("user", 60).await.unwrap();
+        assert_eq!(&updated, "1");
+        let ttl = client.ttl("user").await.unwrap();
+        assert_eq!(&ttl, "60");
+
+        let removed = client.persist("user").await.unwrap();
+        assert_eq!(&removed, "1");
+        let ttl = client.ttl("user").await.unwrap();
+        assert_eq!(&ttl, "-1");
+
+        // Only a trailing expiry option sets a time-to-live, not a value ending like one
+        client
+            .insert_string("note", "call back PX 100")
+            .await
+            .unwrap();
+        let ttl = client.ttl("note").await.unwrap();
+        assert_eq!(&ttl, "-1");
+
+        // An expiry too far in the future to be stored is rejected rather than wrapping
+        let response = client
+            .insert_string_with_expiry("forever", "token", Duration::from_millis(u64::MAX))
+            .await
+            .unwrap();
+        assert!(
+            response.starts_with("expiry is out of range"),
+            "{}",
+            response
+        );
+        let value = client.get_string("forever").await.unwrap();
+        assert_eq!(value, None);
+        for response in [
+            client.expire("user", u64::MAX).await.unwrap(),
+            client.pexpire("user", u64::MAX).await.unwrap(),
+        ] {
+            assert!(
+                response.starts_with("expiry is out of range"),
+                "{}",
+                response
+            );
+        }
+        let ttl = client.pttl("user").await.unwrap();
+        assert_eq!(&ttl, "-1");
+
+        sleep(300).await;
+        let value = client.get_string("session").await.unwrap();
+        assert_eq!(value, None);
+        let ttl = client.ttl("session").await.unwrap();
+        assert_eq!(&ttl, "-2");
+        let note = client.get_string("note").await.unwrap();
+        assert_eq!(note.as_deref(), Some("call back PX 100"));
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn rejects_writes_over_max_memory() {
+        let config = ServerConfig {
+            max_memory: Some(1024),
+            eviction_policy: EvictionPolicy::NoEviction,
+            ..ServerConfig::default()
+        };
+        let server = tokio::spawn(start_with_config("127.0.0.1", 9884, config));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9884);
+        let mut response = String::new();
+        for i in 0..100 {
+            response = client
+                .insert_string(&format!("key-{}", i), "value")
+                .await
+                .unwrap();
+
+            if response != "OK" {
+                break;
+            }
+        }
+
+        assert_eq!(
+            &response,
+            "command not allowed when used memory exceeds max memory"
+        );
+
+        let value = client.get_string("key-0").await.unwrap();
+        assert_eq!(value.as_deref(), Some("value"));
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn gets_nil_for_missing_values_from_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9885));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9885);
+        let value = client.get_string("missing").await.unwrap();
+        assert_eq!(value, None);
+
+        client.rpush("queue", &["job-1"]).await.unwrap();
+        let job = client.lindex("queue", 5).await.unwrap();
+        assert_eq!(job, None);
+        let job = client.lpop("queue").await.unwrap();
+        assert_eq!(job.as_deref(), Some("job-1"));
+        let job = client.lpop("queue").await.unwrap();
+        assert_eq!(job, None);
+
+        let raw = client.request("GET::missing").await.unwrap();
+        assert_eq!(&raw, "");
+
+        server.abort();
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+        bio: String,
+        tags: HashMap<String, u32>,
+    }
+
+    #[tokio::test]
+    async fn stores_typed_values_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9886));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9886);
+        let user = User {
+            name: "rubin".to_string(),
+            bio: "a  key-value store :: in rust".to_string(),
+            tags: HashMap::from([("stars".to_string(), 3)]),
+        };
+
+        let response = client.insert_typed("user:1000", &user).await.unwrap();
+        assert_eq!(&response, "OK");
+
+        let stored: Option<User> = client.get_typed("user:1000").await.unwrap();
+        assert_eq!(stored, Some(user));
+
+        let missing: Option<User> = client.get_typed("user:2000").await.unwrap();
+        assert_eq!(missing, None);
+
+        let err = client.get_typed::<String>("user:1000").await.unwrap_err();
+        assert!(
+            err.to_string().starts_with("stored value is of type"),
+            "{}",
+            err
+        );
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn performs_counter_arithmetic_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9887));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9887);
+        assert_eq!(&client.incrby("requests", 50).await.unwrap(), "50");
+        assert_eq!(&client.decrby("requests", 20).await.unwrap(), "30");
+        assert_eq!(&client.incrbyfloat("load", 1.25).await.unwrap(), "1.25");
+
+        let previous = client.getset("requests", 0).await.unwrap();
+        assert_eq!(previous.as_deref(), Some("30"));
+        let previous = client.getset("missing", 0).await.unwrap();
+        assert_eq!(previous, None);
+
+        client.incrby("requests", isize::MAX).await.unwrap();
+        let response = client.incr("requests").await.unwrap();
+        assert_eq!(&response, "increment or decrement would overflow");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn performs_keyspace_operations_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9888));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9888);
+        client.insert_string("user:1000", "value").await.unwrap();
+        client.rpush("user:2000", &["a"]).await.unwrap();
+        client.incr("visits").await.unwrap();
+
+        assert_eq!(
+            &client
+                .exists(&["user:1000", "visits", "missing"])
+                .await
+                .unwrap(),
+            "2"
+        );
+        assert_eq!(&client.key_type("user:2000").await.unwrap(), "list");
+        assert_eq!(&client.key_type("missing").await.unwrap(), "none");
+        assert_eq!(
+            client.keys("user:*").await.unwrap(),
+            vec!["user:1000", "user:2000"]
+        );
+
+        let response = client.sadd("user:2000", &["a"]).await.unwrap();
+        assert!(response.contains("wrong kind of value"));
+
+        assert_eq!(
+            &client.rename("user:2000", "user:3000").await.unwrap(),
+            "OK"
+        );
+        assert_eq!(
+            &client.renamenx("user:3000", "user:1000").await.unwrap(),
+            "0"
+        );
+        assert_eq!(&client.del(&["user:1000", "user:3000"]).await.unwrap(), "2");
+        assert_eq!(client.keys("*").await.unwrap(), vec!["visits"]);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn scans_keys_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9889));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9889);
+        for i in 0..25 {
+            client
+                .insert_string(&format!("user:{}", i), "value")
+                .await
+                .unwrap();
+        }
+        client.incr("visits").await.unwrap();
+        client
+            .hset("user:info", &[("name", "rubin"), ("age", "3")])
+            .await
+            .unwrap();
+        client.sadd("tags", &["rust", "redis"]).await.unwrap();
+
+        let mut keys = vec![];
+        let mut cursor = 0;
+        loop {
+            let (next, page) = client.scan(cursor, Some("user:*"), 5).await.unwrap();
+            keys.extend(page);
+
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), 26);
+        assert!(keys.iter().all(|key| key.starts_with("user:")));
+
+        let (cursor, fields) = client.hscan("user:info", 0, Some("n*"), 10).await.unwrap();
+        assert_eq!(cursor, 0);
+        assert_eq!(fields, vec![("name".to_string(), "rubin".to_string())]);
+
+        let (cursor, mut members) = client.sscan("tags", 0, None, 10).await.unwrap();
+        members.sort();
+        assert_eq!(cursor, 0);
+        assert_eq!(members, vec!["redis", "rust"]);
+
+        assert!(client.scan(0, None, 0).await.is_err());
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn performs_bitmap_operations_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9890));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9890);
+        assert_eq!(&client.setbit("monday", 7, true).await.unwrap(), "0");
+        assert_eq!(&client.setbit("monday", 7, true).await.unwrap(), "1");
+        client.setbit("monday", 1000, true).await.unwrap();
+        client.setbit("tuesday", 7, true).await.unwrap();
+
+        assert_eq!(&client.getbit("monday", 1000).await.unwrap(), "1");
+        assert_eq!(&client.getbit("monday", 8).await.unwrap(), "0");
+        assert_eq!(&client.bitcount("monday", None).await.unwrap(), "2");
+        assert_eq!(&client.bitcount("monday", Some((0, 0))).await.unwrap(), "1");
+        assert_eq!(
+            &client.bitpos("monday", true, Some(1), None).await.unwrap(),
+            "1000"
+        );
+        assert_eq!(
+            &client.bitpos("tuesday", true, Some(1), None).await.unwrap(),
+            "-1"
+        );
+
+        assert_eq!(
+            &client
+                .bitop(BitOp::And, "both", &["monday", "tuesday"])
+                .await
+                .unwrap(),
+            "126"
+        );
+        assert_eq!(&client.bitcount("both", None).await.unwrap(), "1");
+
+        let values = client
+            .bitfield(
+                "stats",
+                &[
+                    "INCRBY", "u8", "#0", "255", "OVERFLOW", "FAIL", "INCRBY", "u8", "#0", "1",
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(values, vec![Some(255), None]);
+
+        let response = client
+            .bitop(BitOp::Not, "both", &["monday", "tuesday"])
+            .await;
+        assert!(response.unwrap().contains("wrong number of keys"));
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn estimates_cardinality_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9891));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9891);
+        let monday: Vec<String> = (0..100).map(|i| format!("user:{}", i)).collect();
+        let tuesday: Vec<String> = (50..150).map(|i| format!("user:{}", i)).collect();
+        let monday: Vec<&str> = monday.iter().map(|user| user.as_str()).collect();
+        let tuesday: Vec<&str> = tuesday.iter().map(|user| user.as_str()).collect();
+
+        assert_eq!(&client.pfadd("monday", &monday).await.unwrap(), "1");
+        assert_eq!(&client.pfadd("monday", &["user:0"]).await.unwrap(), "0");
+        client.pfadd("tuesday", &tuesday).await.unwrap();
+
+        // Estimates are within a few percent of the actual number of unique elements
+        let estimate = |count: String| count.parse::<i64>().unwrap();
+        let monday_count = estimate(client.pfcount(&["monday"]).await.unwrap());
+        let union_count = estimate(client.pfcount(&["monday", "tuesday"]).await.unwrap());
+        assert!((monday_count - 100).abs() <= 3);
+        assert!((union_count - 150).abs() <= 5);
+
+        assert_eq!(
+            &client
+                .pfmerge("week", &["monday", "tuesday"])
+                .await
+                .unwrap(),
+            "OK"
+        );
+        assert_eq!(
+            estimate(client.pfcount(&["week"]).await.unwrap()),
+            union_count
+        );
+        assert_eq!(&client.key_type("week").await.unwrap(), "hyperloglog");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn shares_stream_entries_between_consumers() {
+        let server = tokio::spawn(start("127.0.0.1", 9892));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9892);
+        assert_eq!(
+            &client.xgroup_create("jobs", "workers", "$").await.unwrap(),
+            "OK"
+        );
+        for task in ["resize", "upload", "notify"] {
+            client.xadd("jobs", "*", &[("task", task)]).await.unwrap();
+        }
+        assert_eq!(
+            &client
+                .xadd("jobs", "1-0", &[("task", "old")])
+                .await
+                .unwrap(),
+            "ID must be greater than the last ID in the stream"
+        );
+        assert_eq!(&client.xlen("jobs").await.unwrap(), "3");
+
+        let entries = client.xrange("jobs", "-", "+", None).await.unwrap();
+        let latest = client.xrevrange("jobs", "+", "-", Some(1)).await.unwrap();
+        assert_eq!(
+            entries[0].fields,
+            vec![("task".to_string(), "resize".to_string())]
+        );
+        assert_eq!(latest[0], entries[2]);
+
+        let first = client
+            .xreadgroup("jobs", "workers", "worker-1", ">", Some(2))
+            .await
+            .unwrap();
+        let second = client
+            .xreadgroup("jobs", "workers", "worker-2", ">", None)
+            .await
+            .unwrap();
+        assert_eq!(first, entries[..2]);
+        assert_eq!(second, entries[2..]);
+
+        let id = entries[0].id.to_string();
+        assert_eq!(&client.xack("jobs", "workers", &[&id]).await.unwrap(), "1");
+
+        let summary = client.xpending("jobs", "workers").await.unwrap();
+        assert_eq!(summary.count, 2);
+        assert_eq!(
+            summary.consumers,
+            vec![("worker-1".to_string(), 1), ("worker-2".to_string(), 1)]
+        );
+
+        let id = entries[1].id.to_string();
+        let claimed = client
+            .xclaim("jobs", "workers", "worker-2", 0, &[&id])
+            .await
+            .unwrap();
+        assert_eq!(claimed, entries[1..2]);
+
+        let pending = client
+            .xpending_range("jobs", "workers", "-", "+", 10, Some("worker-2"))
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].deliveries, 2);
+
+        assert_eq!(
+            &client.xtrim("jobs", StreamTrim::MaxLen(1)).await.unwrap(),
+            "2"
+        );
+        assert_eq!(&client.key_type("jobs").await.unwrap(), "stream");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn searches_positions_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9893));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9893);
+        let drivers = [
+            (13.361389, 38.115556, "driver:1"),
+            (15.087269, 37.502669, "driver:2"),
+            (12.758489, 38.788135, "driver:3"),
+        ];
+        assert_eq!(&client.geoadd("drivers", &drivers).await.unwrap(), "3");
+
+        let positions = client
+            .geopos("drivers", &["driver:1", "driver:9"])
+            .await
+            .unwrap();
+        let (lon, lat) = positions[0].unwrap();
+        assert!((lon - 13.361389).abs() < 1e-5 && (lat - 38.115556).abs() < 1e-5);
+        assert_eq!(positions[1], None);
+
+        let distance = client
+            .geodist("drivers", "driver:1", "driver:2", GeoUnit::Kilometers)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!((distance.parse::<f64>().unwrap() - 166.274).abs() < 0.01);
+        assert_eq!(
+            client
+                .geodist("drivers", "driver:1", "driver:9", GeoUnit::Meters)
+                .await
+                .unwrap(),
+            None
+        );
+
+        let search = GeoSearch {
+            origin: GeoOrigin::LonLat(15.0, 37.0),
+            shape: GeoShape::Radius(200.0),
+            unit: GeoUnit::Kilometers,
+            count: Some(1),
+        };
+        let nearest = client.geosearch("drivers", &search).await.unwrap();
+        assert_eq!(nearest.len(), 1);
+        assert_eq!(nearest[0].member, "driver:2");
+
+        let search = GeoSearch {
+            origin: GeoOrigin::Member("driver:1".to_string()),
+            shape: GeoShape::Box(200.0, 200.0),
+            unit: GeoUnit::Kilometers,
+            count: None,
+        };
+        let found = client.geosearch("drivers", &search).await.unwrap();
+        let members: Vec<&str> = found.iter().map(|found| found.member.as_str()).collect();
+        assert_eq!(members, vec!["driver:1", "driver:3"]);
+        assert_eq!(&client.key_type("drivers").await.unwrap(), "zset");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn updates_json_documents_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9894));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9894);
+        let user = r#"{"name": "Rubin Store", "tags": ["cache"], "visits": 1}"#;
+        assert_eq!(&client.json_set("user:1", "$", user).await.unwrap(), "1");
+        assert_eq!(
+            &client
+                .json_set("user:1", "$.city", r#""New York""#)
+                .await
+                .unwrap(),
+            "1"
+        );
+
+        let lengths = client
+            .json_arrappend("user:1", "$.tags", &[r#""in memory""#, "2"])
+            .await
+            .unwrap();
+        assert_eq!(lengths, vec![Some(3)]);
+        assert_eq!(
+            &client
+                .json_numincrby("user:1", "$.visits", 2.0)
+                .await
+                .unwrap(),
+            "[3]"
+        );
+
+        let invalid = client.json_get("user:1", "$.tags[1:]").await.unwrap_err();
+        assert!(invalid.to_string().starts_with("invalid path"));
+        let tags = client.json_get("user:1", "$.tags").await.unwrap();
+        assert_eq!(tags.as_deref(), Some(r#"[["cache","in memory",2]]"#));
+        let name = client.json_get("user:1", "$..name").await.unwrap();
+        assert_eq!(name.as_deref(), Some(r#"["Rubin Store"]"#));
+
+        assert_eq!(&client.json_del("user:1", "$.tags[0]").await.unwrap(), "1");
+        assert_eq!(&client.key_type("user:1").await.unwrap(), "json");
+        assert_eq!(&client.json_del("user:1", "$").await.unwrap(), "1");
+        assert_eq!(client.json_get("user:1", "$").await.unwrap(), None);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn manipulates_strings_on_the_server() {
+        let server = tokio::spawn(start("127.0.0.1", 9895));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9895);
+        assert_eq!(&client.append("greeting", "Hello").await.unwrap(), "5");
+        assert_eq!(&client.append("greeting", " World").await.unwrap(), "11");
+        assert_eq!(
+            &client.setrange("greeting", 6, "Rubin").await.unwrap(),
+            "11"
+        );
+        assert_eq!(&client.getrange("greeting", -5, -1).await.unwrap(), "Rubin");
+        assert_eq!(&client.strlen("greeting").await.unwrap(), "11");
+
+        assert_eq!(&client.setnx("lock", "worker-1").await.unwrap(), "1");
+        assert_eq!(&client.setnx("lock", "worker-2").await.unwrap(), "0");
+        assert_eq!(
+            client.getdel("lock").await.unwrap().as_deref(),
+            Some("worker-1")
+        );
+        assert_eq!(client.getdel("lock").await.unwrap(), None);
+
+        let users = [("user:1", "alice"), ("user:2", "bob")];
+        assert_eq!(&client.mset(&users).await.unwrap(), "OK");
+        assert_eq!(
+            &client
+                .msetnx(&[("user:2", "carol"), ("user:3", "dave")])
+                .await
+                .unwrap(),
+            "0"
+        );
+        let values = client
+            .mget(&["user:1", "user:3", "user:2", "greeting"])
+            .await
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                Some("alice".to_string()),
+                None,
+                Some("bob".to_string()),
+                Some("Hello Rubin".to_string())
+            ]
+        );
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn sets_multiple_values_containing_spaces() {
+        let server = tokio::spawn(start("127.0.0.1", 9903));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9903);
+        let users = [("user:1", "Alice Smith"), ("user:2", "Bob J Jones")];
+        assert_eq!(&client.mset(&users).await.unwrap(), "OK");
+        assert_eq!(
+            &client
+                .msetnx(&[("user:3", "Carol White"), ("user:4", "Dave Brown")])
+                .await
+                .unwrap(),
+            "1"
+        );
+
+        let values = client
+            .mget(&["user:1", "user:2", "user:3", "user:4", "Smith"])
+            .await
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                Some("Alice Smith".to_string()),
+                Some("Bob J Jones".to_string()),
+                Some("Carol White".to_string()),
+                Some("Dave Brown".to_string()),
+                None
+            ]
+        );
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn separates_keys_between_databases() {
+        let config = ServerConfig {
+            databases: 3,
+            ..ServerConfig::default()
+        };
+        let server = tokio::spawn(start_with_config("127.0.0.1", 9896, config));
+        sleep(1000).await;
+
+        let test = RubinClient::new("127.0.0.1", 9896);
+        let staging = RubinClient::new("127.0.0.1", 9896);
+        assert_eq!(&staging.select(1).await.unwrap(), "OK");
+        assert_eq!(staging.database(), 1);
+        assert!(staging.select(3).await.unwrap().contains("out of range"));
+        assert_eq!(staging.database(), 1);
+
+        test.insert_string("tenant", "test").await.unwrap();
+        staging.insert_string("tenant", "staging").await.unwrap();
+        assert_eq!(
+            test.get_string("tenant").await.unwrap().as_deref(),
+            Some("test")
+        );
+        assert_eq!(
+            staging.get_string("tenant").await.unwrap().as_deref(),
+            Some("staging")
+        );
+
+        assert_eq!(&test.swapdb(0, 1).await.unwrap(), "OK");
+        assert_eq!(
+            test.get_string("tenant").await.unwrap().as_deref(),
+            Some("staging")
+        );
+
+        test.rpush("jobs", &["job-1"]).await.unwrap();
+        assert_eq!(&test.move_key("jobs", 2).await.unwrap(), "1");
+        assert_eq!(&test.move_key("tenant", 1).await.unwrap(), "0");
+        staging.select(2).await.unwrap();
+        assert_eq!(staging.lrange("jobs", 0, -1).await.unwrap(), vec!["job-1"]);
+
+        assert_eq!(&staging.flushdb().await.unwrap(), "OK");
+        assert_eq!(&staging.exists(&["jobs"]).await.unwrap(), "0");
+        assert_eq!(&test.exists(&["tenant"]).await.unwrap(), "1");
+        assert_eq!(&staging.flushall().await.unwrap(), "OK");
+        assert_eq!(&test.exists(&["tenant"]).await.unwrap(), "0");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn saves_databases_in_the_background() {
+        let server = tokio::spawn(start("127.0.0.1", 9897));
+        let td = tempdir::TempDir::new("bgsave").unwrap();
+        let config = ServerConfig {
+            data_dir: Some(td.path().to_path_buf()),
+            save_rules: vec![],
+            ..ServerConfig::default()
+        };
+        let saving = tokio::spawn(start_with_config("127.0.0.1", 9905, config));
+        sleep(1000).await;
+
+        // Without a data directory there is nowhere to save to
+        let client = RubinClient::new("127.0.0.1", 9897);
+        assert!(client.bgsave().await.unwrap().contains("no data directory"));
+        assert!(client.save().await.unwrap().contains("no data directory"));
+
+        let client = RubinClient::new("127.0.0.1", 9905);
+        let started: u64 = client.lastsave().await.unwrap().parse().unwrap();
+
+        client.insert_string("user:1000", "value").await.unwrap();
+        client.select(2).await.unwrap();
+        client.incr("visits").await.unwrap();
+        sleep(1000).await;
+
+        assert_eq!(&client.bgsave().await.unwrap(), "Background saving started");
+        sleep(500).await;
+
+        let saved: u64 = client.lastsave().await.unwrap().parse().unwrap();
+        assert!(saved > started);
+
+        let loaded = PersistentStore::from_existing(td.path().join("db-0.rubin"))
+            .await
+            .unwrap();
+        assert_eq!(
+            loaded.get_string("user:1000").unwrap().as_deref(),
+            Some("value")
+        );
+        let loaded = PersistentStore::from_existing(td.path().join("db-2.rubin"))
+            .await
+            .unwrap();
+        assert_eq!(loaded.store.counters.retrieve("visits").unwrap(), Some(1));
+
+        server.abort();
+        saving.abort();
+    }
+
+    #[tokio::test]
+    async fn persists_databases_in_the_data_directory() {
+        let td = tempdir::TempDir::new("datadir").unwrap();
+        let config = ServerConfig {
+            data_dir: Some(td.path().to_path_buf()),
+            save_rules: vec![],
+            ..ServerConfig::default()
+        };
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(start_with_shutdown(
+            "127.0.0.1",
+            9898,
+            config.clone(),
+            async {
+                stopped.await.ok();
+            },
+        ));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9898);
+        client.insert_string("user:1000", "saved").await.unwrap();
+        assert_eq!(&client.save().await.unwrap(), "OK");
+        assert!(td.path().join("db-0.rubin").exists());
+
+        client.select(3).await.unwrap();
+        client.rpush("jobs", &["job-1"]).await.unwrap();
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+
+        let server = tokio::spawn(start_with_config("127.0.0.1", 9899, config));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9899);
+        assert_eq!(
+            client.get_string("user:1000").await.unwrap().as_deref(),
+            Some("saved")
+        );
+        client.select(3).await.unwrap();
+        assert_eq!(client.lrange("jobs", 0, -1).await.unwrap(), vec!["job-1"]);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn saves_the_data_directory_on_a_schedule() {
+        let td = tempdir::TempDir::new("datadir").unwrap();
+        let config = ServerConfig {
+            data_dir: Some(td.path().to_path_buf()),
+            save_rules: vec![SaveRule::new(Duration::ZERO, 2)],
+            ..ServerConfig::default()
+        };
+        let server = tokio::spawn(start_with_config("127.0.0.1", 9900, config));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9900);
+        client.insert_string("user:1000", "value").await.unwrap();
+        client.get_string("user:1000").await.unwrap();
+        sleep(1500).await;
+        let written = std::fs::metadata(td.path().join("db-0.rubin")).unwrap();
+        assert_eq!(written.len(), 0);
+
+        client.incr("visits").await.unwrap();
+        sleep(1500).await;
+
+        let loaded = PersistentStore::from_existing(td.path().join("db-0.rubin"))
+            .await
+            .unwrap();
+        assert_eq!(loaded.store.counters.retrieve("visits").unwrap(), Some(1));
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn replays_the_append_only_log_of_each_database() {
+        let td = tempdir::TempDir::new("datadir").unwrap();
+        let config = ServerConfig {
+            data_dir: Some(td.path().to_path_buf()),
+            save_rules: vec![],
+            append_fsync: Some(FsyncPolicy::Always),
+            ..ServerConfig::default()
+        };
+        let server = tokio::spawn(start_with_config("127.0.0.1", 9906, config.clone()));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9906);
+        client.insert_string("user:1000", "logged").await.unwrap();
+        client.insert_string("session:1000", "moved").await.unwrap();
+        assert_eq!(&client.move_key("session:1000", 1).await.unwrap(), "1");
+        client.select(2).await.unwrap();
+        client.rpush("jobs", &["job-1"]).await.unwrap();
+        assert_eq!(&client.swapdb(2, 3).await.unwrap(), "OK");
+
+        // Stopped without saving, so the databases can only be restored from their logs
+        server.abort();
+        sleep(100).await;
+        let written = std::fs::metadata(td.path().join("db-0.rubin")).unwrap();
+        assert_eq!(written.len(), 0);
+
+        let server = tokio::spawn(start_with_config("127.0.0.1", 9907, config));
+        sleep(1000).await;
+
+        let client = RubinClient::new("127.0.0.1", 9907);
+        assert_eq!(
+            client.get_string("user:1000").await.unwrap().as_deref(),
+            Some("logged")
+        );
+        assert_eq!(&client.exists(&["session:1000"]).await.unwrap(), "0");
+        client.select(1).await.unwrap();
+        assert_eq!(
+            client.get_string("session:1000").await.unwrap().as_deref(),
+            Some("moved")
+        );
+        client.select(2).await.unwrap();
+        assert_eq!(&client.exists(&["jobs"]).await.unwrap(), "0");
+        client.select(3).await.unwrap();
+        assert_eq!(client.lrange("jobs", 0, -1).await.unwrap(), vec!["job-1"]);
+
+        server.abort();
+    }
 }